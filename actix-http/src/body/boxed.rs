@@ -8,7 +8,7 @@ use std::{
 use bytes::Bytes;
 
 use super::{BodySize, MessageBody, MessageBodyMapErr};
-use crate::body;
+use crate::{body, header::HeaderMap};
 
 /// A boxed message body with boxed errors.
 #[derive(Debug)]
@@ -83,6 +83,14 @@ impl MessageBody for BoxBody {
         }
     }
 
+    #[inline]
+    fn poll_trailers(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        match &mut self.0 {
+            BoxBodyInner::Stream(body) => Pin::new(body).poll_trailers(cx),
+            _ => Poll::Ready(None),
+        }
+    }
+
     #[inline]
     fn try_into_bytes(self) -> Result<Bytes, Self> {
         match self.0 {
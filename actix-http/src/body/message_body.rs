@@ -13,6 +13,7 @@ use futures_core::ready;
 use pin_project_lite::pin_project;
 
 use super::{BodySize, BoxBody};
+use crate::header::HeaderMap;
 
 /// An interface for types that can be used as a response body.
 ///
@@ -86,6 +87,20 @@ pub trait MessageBody {
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Bytes, Self::Error>>>;
 
+    /// Attempt to pull out the trailer fields sent after the body.
+    ///
+    /// Only called once `poll_next` has returned `Poll::Ready(None)`. Trailers are sent using the
+    /// chunked transfer encoding on HTTP/1.1 and as a trailing `HEADERS` frame on HTTP/2; they
+    /// are dropped on HTTP/1.0 and when a body is sent with a known size. Bodies that send trailers
+    /// should therefore return [`BodySize::Stream`] from `size`.
+    ///
+    /// The default implementation returns `Poll::Ready(None)`, meaning there are no trailers.
+    #[inline]
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        let _ = cx;
+        Poll::Ready(None)
+    }
+
     /// Try to convert into the complete chunk of body bytes.
     ///
     /// Override this method if the complete body can be trivially extracted. This is useful for
@@ -140,6 +155,13 @@ mod foreign_impls {
         ) -> Poll<Option<Result<Bytes, Self::Error>>> {
             Pin::new(&mut **self).poll_next(cx)
         }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<HeaderMap>> {
+            Pin::new(&mut **self).poll_trailers(cx)
+        }
     }
 
     impl MessageBody for Infallible {
@@ -197,6 +219,11 @@ mod foreign_impls {
         ) -> Poll<Option<Result<Bytes, Self::Error>>> {
             Pin::new(self.get_mut().as_mut()).poll_next(cx)
         }
+
+        #[inline]
+        fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+            Pin::new(self.get_mut().as_mut()).poll_trailers(cx)
+        }
     }
 
     impl<T, B> MessageBody for Pin<T>
@@ -218,6 +245,11 @@ mod foreign_impls {
         ) -> Poll<Option<Result<Bytes, Self::Error>>> {
             self.get_mut().as_mut().poll_next(cx)
         }
+
+        #[inline]
+        fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+            self.get_mut().as_mut().poll_trailers(cx)
+        }
     }
 
     impl MessageBody for &'static [u8] {
@@ -520,6 +552,11 @@ where
         }
     }
 
+    #[inline]
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        self.project().body.poll_trailers(cx)
+    }
+
     #[inline]
     fn try_into_bytes(self) -> Result<Bytes, Self> {
         let Self { body, mapper } = self;
@@ -1,11 +1,12 @@
-use std::{fmt, marker::PhantomData, net, rc::Rc, time::Duration};
+use std::{fmt, future::Future, marker::PhantomData, net, rc::Rc, time::Duration};
 
 use actix_codec::Framed;
 use actix_service::{IntoServiceFactory, Service, ServiceFactory};
 
 use crate::{
     body::{BoxBody, MessageBody},
-    h1::{self, ExpectHandler, H1Service, UpgradeHandler},
+    config::ShutdownSignal,
+    h1::{self, ExpectHandler, H1Limits, H1Service, UpgradeHandler},
     service::HttpService,
     ConnectCallback, Extensions, KeepAlive, Request, Response, ServiceConfig,
 };
@@ -19,6 +20,11 @@ pub struct HttpServiceBuilder<T, S, X = ExpectHandler, U = UpgradeHandler> {
     client_disconnect_timeout: Duration,
     secure: bool,
     local_addr: Option<net::SocketAddr>,
+    h1_limits: H1Limits,
+    #[cfg(feature = "http2")]
+    h2_config: crate::h2::H2Config,
+    shutdown_signal: Option<ShutdownSignal>,
+    proxy_protocol: bool,
     expect: X,
     upgrade: Option<U>,
     on_connect_ext: Option<Rc<ConnectCallback<T>>>,
@@ -40,6 +46,11 @@ where
             client_disconnect_timeout: Duration::ZERO,
             secure: false,
             local_addr: None,
+            h1_limits: H1Limits::default(),
+            #[cfg(feature = "http2")]
+            h2_config: crate::h2::H2Config::default(),
+            shutdown_signal: None,
+            proxy_protocol: false,
 
             // dispatcher parts
             expect: ExpectHandler,
@@ -50,6 +61,26 @@ where
     }
 }
 
+impl<T, S, X, U> HttpServiceBuilder<T, S, X, U> {
+    fn service_config(&self) -> ServiceConfig {
+        let cfg = ServiceConfig::new(
+            self.keep_alive,
+            self.client_request_timeout,
+            self.client_disconnect_timeout,
+            self.secure,
+            self.local_addr,
+        )
+        .with_h1_limits(self.h1_limits)
+        .with_shutdown_signal(self.shutdown_signal.clone())
+        .with_proxy_protocol(self.proxy_protocol);
+
+        #[cfg(feature = "http2")]
+        let cfg = cfg.with_h2_config(self.h2_config.clone());
+
+        cfg
+    }
+}
+
 impl<T, S, X, U> HttpServiceBuilder<T, S, X, U>
 where
     S: ServiceFactory<Request, Config = ()>,
@@ -124,6 +155,58 @@ where
         self.client_disconnect_timeout(dur)
     }
 
+    /// Set HTTP/1 request head parsing limits.
+    ///
+    /// Requests exceeding the limits are responded to with a `414 URI Too Long` or
+    /// `431 Request Header Fields Too Large` error.
+    ///
+    /// By default, up to 96 header fields and 128 KiB of request head are accepted.
+    pub fn h1_limits(mut self, limits: H1Limits) -> Self {
+        self.h1_limits = limits;
+        self
+    }
+
+    /// Set HTTP/2 connection settings.
+    ///
+    /// By default, the defaults of the `h2` crate are used.
+    #[cfg(feature = "http2")]
+    pub fn h2_config(mut self, config: crate::h2::H2Config) -> Self {
+        self.h2_config = config;
+        self
+    }
+
+    /// Set a function that creates futures resolving when the server starts a graceful shutdown.
+    ///
+    /// The function is called once for each connection. When its future resolves, the connection
+    /// starts draining: idle HTTP/1 keep-alive connections are closed, further HTTP/1 responses are
+    /// sent with `Connection: close`, and HTTP/2 connections send a `GOAWAY` frame and close once
+    /// their in-flight streams are complete.
+    ///
+    /// By default, connections are not drained.
+    pub fn shutdown_signal<F, Fut>(mut self, signal: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.shutdown_signal = Some(ShutdownSignal::new(signal));
+        self
+    }
+
+    /// Require connections to start with a PROXY protocol (v1 or v2) header.
+    ///
+    /// The header is read before the TLS handshake and HTTP dispatch. The client address it
+    /// carries is used as the connection's peer address and the parsed
+    /// [`ProxyHeader`](crate::proxy_protocol::ProxyHeader) is added to the connection data.
+    /// Connections without a valid header are closed, so this should only be enabled for
+    /// listeners that are reachable exclusively through a proxy sending the header.
+    ///
+    /// Applies to the TCP and TLS transports of services created using [`finish()`](Self::finish).
+    /// By default, PROXY protocol headers are not expected.
+    pub fn proxy_protocol(mut self, enabled: bool) -> Self {
+        self.proxy_protocol = enabled;
+        self
+    }
+
     /// Provide service for `EXPECT: 100-Continue` support.
     ///
     /// Service get called with request that contains `EXPECT` header.
@@ -142,6 +225,11 @@ where
             client_disconnect_timeout: self.client_disconnect_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            h1_limits: self.h1_limits,
+            #[cfg(feature = "http2")]
+            h2_config: self.h2_config,
+            shutdown_signal: self.shutdown_signal,
+            proxy_protocol: self.proxy_protocol,
             expect: expect.into_factory(),
             upgrade: self.upgrade,
             on_connect_ext: self.on_connect_ext,
@@ -166,6 +254,11 @@ where
             client_disconnect_timeout: self.client_disconnect_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            h1_limits: self.h1_limits,
+            #[cfg(feature = "http2")]
+            h2_config: self.h2_config,
+            shutdown_signal: self.shutdown_signal,
+            proxy_protocol: self.proxy_protocol,
             expect: self.expect,
             upgrade: Some(upgrade.into_factory()),
             on_connect_ext: self.on_connect_ext,
@@ -195,13 +288,7 @@ where
         S::InitError: fmt::Debug,
         S::Response: Into<Response<B>>,
     {
-        let cfg = ServiceConfig::new(
-            self.keep_alive,
-            self.client_request_timeout,
-            self.client_disconnect_timeout,
-            self.secure,
-            self.local_addr,
-        );
+        let cfg = self.service_config();
 
         H1Service::with_config(cfg, service.into_factory())
             .expect(self.expect)
@@ -220,13 +307,7 @@ where
 
         B: MessageBody + 'static,
     {
-        let cfg = ServiceConfig::new(
-            self.keep_alive,
-            self.client_request_timeout,
-            self.client_disconnect_timeout,
-            self.secure,
-            self.local_addr,
-        );
+        let cfg = self.service_config();
 
         crate::h2::H2Service::with_config(cfg, service.into_factory())
             .on_connect_ext(self.on_connect_ext)
@@ -242,13 +323,7 @@ where
 
         B: MessageBody + 'static,
     {
-        let cfg = ServiceConfig::new(
-            self.keep_alive,
-            self.client_request_timeout,
-            self.client_disconnect_timeout,
-            self.secure,
-            self.local_addr,
-        );
+        let cfg = self.service_config();
 
         HttpService::with_config(cfg, service.into_factory())
             .expect(self.expect)
@@ -256,3 +331,28 @@ where
             .on_connect_ext(self.on_connect_ext)
     }
 }
+
+#[cfg(feature = "http3")]
+impl<S, X, U> HttpServiceBuilder<quinn::Connection, S, X, U>
+where
+    S: ServiceFactory<Request, Config = ()>,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::InitError: fmt::Debug,
+    <S::Service as Service<Request>>::Future: 'static,
+{
+    /// Finish service configuration and create a service for the HTTP/3 protocol.
+    ///
+    /// The returned service factory handles established QUIC connections; see [`crate::h3`].
+    pub fn h3<F, B>(self, service: F) -> crate::h3::H3Service<S, B>
+    where
+        F: IntoServiceFactory<S, Request>,
+        S::Response: Into<Response<B>> + 'static,
+
+        B: MessageBody + 'static,
+    {
+        let cfg = self.service_config();
+
+        crate::h3::H3Service::with_config(cfg, service.into_factory())
+            .on_connect_ext(self.on_connect_ext)
+    }
+}
@@ -1,12 +1,15 @@
 use std::{
+    fmt,
+    future::Future,
     net,
     rc::Rc,
     time::{Duration, Instant},
 };
 
 use bytes::BytesMut;
+use futures_core::future::LocalBoxFuture;
 
-use crate::{date::DateService, KeepAlive};
+use crate::{date::DateService, h1::H1Limits, KeepAlive};
 
 /// HTTP service configuration.
 #[derive(Debug, Clone)]
@@ -20,6 +23,31 @@ struct Inner {
     secure: bool,
     local_addr: Option<std::net::SocketAddr>,
     date_service: DateService,
+    h1_limits: H1Limits,
+    #[cfg(feature = "http2")]
+    h2: crate::h2::H2Config,
+    shutdown_signal: Option<ShutdownSignal>,
+    proxy_protocol: bool,
+}
+
+/// Creates futures that resolve when the server starts a graceful shutdown.
+#[derive(Clone)]
+pub(crate) struct ShutdownSignal(Rc<dyn Fn() -> LocalBoxFuture<'static, ()>>);
+
+impl ShutdownSignal {
+    pub(crate) fn new<F, Fut>(signal: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        Self(Rc::new(move || Box::pin(signal())))
+    }
+}
+
+impl fmt::Debug for ShutdownSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownSignal").finish_non_exhaustive()
+    }
 }
 
 impl Default for ServiceConfig {
@@ -50,9 +78,47 @@ impl ServiceConfig {
             secure,
             local_addr,
             date_service: DateService::new(),
+            h1_limits: H1Limits::default(),
+            #[cfg(feature = "http2")]
+            h2: crate::h2::H2Config::default(),
+            shutdown_signal: None,
+            proxy_protocol: false,
         }))
     }
 
+    /// Sets the HTTP/1 request head parsing limits of a newly created config.
+    pub(crate) fn with_h1_limits(mut self, limits: H1Limits) -> Self {
+        Rc::get_mut(&mut self.0)
+            .expect("ServiceConfig should not be shared before it is built")
+            .h1_limits = limits;
+        self
+    }
+
+    /// Sets the HTTP/2 connection settings of a newly created config.
+    #[cfg(feature = "http2")]
+    pub(crate) fn with_h2_config(mut self, h2: crate::h2::H2Config) -> Self {
+        Rc::get_mut(&mut self.0)
+            .expect("ServiceConfig should not be shared before it is built")
+            .h2 = h2;
+        self
+    }
+
+    /// Sets the graceful shutdown signal of a newly created config.
+    pub(crate) fn with_shutdown_signal(mut self, signal: Option<ShutdownSignal>) -> Self {
+        Rc::get_mut(&mut self.0)
+            .expect("ServiceConfig should not be shared before it is built")
+            .shutdown_signal = signal;
+        self
+    }
+
+    /// Sets whether connections of a newly created config start with a PROXY protocol header.
+    pub(crate) fn with_proxy_protocol(mut self, enabled: bool) -> Self {
+        Rc::get_mut(&mut self.0)
+            .expect("ServiceConfig should not be shared before it is built")
+            .proxy_protocol = enabled;
+        self
+    }
+
     /// Returns `true` if connection is secure (i.e., using TLS / HTTPS).
     #[inline]
     pub fn secure(&self) -> bool {
@@ -73,6 +139,31 @@ impl ServiceConfig {
         self.0.keep_alive
     }
 
+    /// HTTP/1 request head parsing limits.
+    #[inline]
+    pub fn h1_limits(&self) -> H1Limits {
+        self.0.h1_limits
+    }
+
+    /// HTTP/2 connection settings.
+    #[cfg(feature = "http2")]
+    #[inline]
+    pub fn h2_config(&self) -> &crate::h2::H2Config {
+        &self.0.h2
+    }
+
+    /// Returns `true` if connections are expected to start with a PROXY protocol header.
+    #[inline]
+    pub fn proxy_protocol(&self) -> bool {
+        self.0.proxy_protocol
+    }
+
+    /// Creates a future that resolves when the server starts a graceful shutdown, if a shutdown
+    /// signal is set.
+    pub(crate) fn shutdown_signal(&self) -> Option<LocalBoxFuture<'static, ()>> {
+        self.0.shutdown_signal.as_ref().map(|signal| (signal.0)())
+    }
+
     /// Creates a time object representing the deadline for this connection's keep-alive period, if
     /// enabled.
     ///
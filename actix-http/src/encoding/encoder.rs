@@ -22,7 +22,7 @@ use zstd::stream::write::Encoder as ZstdEncoder;
 use super::Writer;
 use crate::{
     body::{self, BodySize, MessageBody},
-    header::{self, ContentEncoding, HeaderValue, CONTENT_ENCODING},
+    header::{self, ContentEncoding, HeaderMap, HeaderValue, CONTENT_ENCODING},
     ResponseHead, StatusCode,
 };
 
@@ -98,6 +98,75 @@ impl<B: MessageBody> Encoder<B> {
             eof: false,
         }
     }
+
+    /// Wraps a request body in an encoder, setting the `Content-Encoding` header in `headers`.
+    ///
+    /// Bodies that can be converted into [`Bytes`] and are smaller than 1 KiB are compressed
+    /// immediately, so the returned encoder can still be converted into bytes with
+    /// [`MessageBody::try_into_bytes`]. Larger and streaming bodies are compressed as they are
+    /// polled, with chunks of 1 KiB or more compressed on a blocking thread, as for responses.
+    ///
+    /// Empty bodies, bodies whose headers already set `Content-Encoding`, and encodings that are
+    /// not enabled by crate features are left unencoded.
+    pub fn request(encoding: ContentEncoding, headers: &mut HeaderMap, body: B) -> Self {
+        // no need to compress empty bodies
+        match body.size() {
+            BodySize::None => return Self::none(),
+            BodySize::Sized(0) => return Self::empty(),
+            _ => {}
+        }
+
+        let should_encode =
+            !(headers.contains_key(&CONTENT_ENCODING) || encoding == ContentEncoding::Identity);
+
+        let body = match body.try_into_bytes() {
+            Ok(body) => EncoderBody::Full { body },
+            Err(body) => EncoderBody::Stream { body },
+        };
+
+        if should_encode {
+            // wrap body only if encoder is feature-enabled
+            if let Some(mut enc) = ContentEncoder::select(encoding) {
+                // small full bodies are compressed up front so they can be sent again, e.g. on
+                // retries; larger ones are compressed on a blocking thread once polled
+                let (body, encoder) = match body {
+                    EncoderBody::Full { body } if body.len() < MAX_CHUNK_SIZE_ENCODE_IN_PLACE => {
+                        match enc.write(&body).and_then(|()| enc.finish()) {
+                            Ok(body) => (EncoderBody::Full { body }, None),
+
+                            // send the body unencoded if it could not be compressed
+                            Err(_) => {
+                                return Encoder {
+                                    body: EncoderBody::Full { body },
+                                    encoder: None,
+                                    fut: None,
+                                    eof: false,
+                                }
+                            }
+                        }
+                    }
+                    body => (body, Some(enc)),
+                };
+
+                headers.insert(CONTENT_ENCODING, encoding.to_header_value());
+                headers.remove(header::CONTENT_LENGTH);
+
+                return Encoder {
+                    body,
+                    encoder,
+                    fut: None,
+                    eof: false,
+                };
+            }
+        }
+
+        Encoder {
+            body,
+            encoder: None,
+            fut: None,
+            eof: false,
+        }
+    }
 }
 
 pin_project! {
@@ -141,6 +210,14 @@ where
         }
     }
 
+    #[inline]
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        match self.project() {
+            EncoderBodyProj::Stream { body } => body.poll_trailers(cx),
+            _ => Poll::Ready(None),
+        }
+    }
+
     #[inline]
     fn try_into_bytes(self) -> Result<Bytes, Self>
     where
@@ -243,6 +320,11 @@ where
         }
     }
 
+    #[inline]
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        self.project().body.poll_trailers(cx)
+    }
+
     #[inline]
     fn try_into_bytes(mut self) -> Result<Bytes, Self>
     where
@@ -179,6 +179,10 @@ pub enum ParseError {
     #[display("message head is too large")]
     TooLarge,
 
+    /// A request URI is longer than allowed.
+    #[display("request URI is too long")]
+    UriTooLong,
+
     /// A message reached EOF, but is not complete.
     #[display("message is incomplete")]
     Incomplete,
@@ -276,6 +280,11 @@ pub enum PayloadError {
     #[display("{}", _0)]
     Http2Payload(::h2::Error),
 
+    /// HTTP/3 payload error.
+    #[cfg(feature = "http3")]
+    #[display("{}", _0)]
+    Http3Payload(::h3::error::StreamError),
+
     /// Generic I/O error.
     #[display("{}", _0)]
     Io(io::Error),
@@ -291,6 +300,8 @@ impl std::error::Error for PayloadError {
             PayloadError::UnknownLength => None,
             #[cfg(feature = "http2")]
             PayloadError::Http2Payload(err) => Some(err),
+            #[cfg(feature = "http3")]
+            PayloadError::Http3Payload(err) => Some(err),
             PayloadError::Io(err) => Some(err),
         }
     }
@@ -303,6 +314,13 @@ impl From<::h2::Error> for PayloadError {
     }
 }
 
+#[cfg(feature = "http3")]
+impl From<::h3::error::StreamError> for PayloadError {
+    fn from(err: ::h3::error::StreamError) -> Self {
+        PayloadError::Http3Payload(err)
+    }
+}
+
 impl From<Option<io::Error>> for PayloadError {
     fn from(err: Option<io::Error>) -> Self {
         PayloadError::Incomplete(err)
@@ -350,6 +368,11 @@ pub enum DispatchError {
     #[cfg(feature = "http2")]
     H2(h2::Error),
 
+    /// HTTP/3 connection error.
+    #[display("{}", _0)]
+    #[cfg(feature = "http3")]
+    H3(h3::error::ConnectionError),
+
     /// The first request did not complete within the specified timeout.
     #[display("request did not complete within the specified timeout")]
     SlowRequestTimeout,
@@ -378,6 +401,9 @@ impl StdError for DispatchError {
             #[cfg(feature = "http2")]
             DispatchError::H2(err) => Some(err),
 
+            #[cfg(feature = "http3")]
+            DispatchError::H3(err) => Some(err),
+
             _ => None,
         }
     }
@@ -3,6 +3,9 @@ use std::{io, task::Poll};
 use bytes::{Buf as _, Bytes, BytesMut};
 use tracing::{debug, trace};
 
+use super::decoder::{MAX_BUFFER_SIZE, MAX_HEADERS};
+use crate::header::{HeaderMap, HeaderName, HeaderValue};
+
 macro_rules! byte (
     ($rdr:ident) => ({
         if $rdr.len() > 0 {
@@ -26,6 +29,7 @@ pub(super) enum ChunkedState {
     BodyLf,
     EndCr,
     EndLf,
+    Trailers,
     End,
 }
 
@@ -35,6 +39,7 @@ impl ChunkedState {
         body: &mut BytesMut,
         size: &mut u64,
         buf: &mut Option<Bytes>,
+        trailers: &mut Option<HeaderMap>,
     ) -> Poll<Result<ChunkedState, io::Error>> {
         use self::ChunkedState::*;
         match *self {
@@ -47,6 +52,7 @@ impl ChunkedState {
             BodyLf => ChunkedState::read_body_lf(body),
             EndCr => ChunkedState::read_end_cr(body),
             EndLf => ChunkedState::read_end_lf(body),
+            Trailers => ChunkedState::read_trailers(body, trailers),
             End => Poll::Ready(Ok(ChunkedState::End)),
         }
     }
@@ -167,12 +173,14 @@ impl ChunkedState {
         }
     }
     fn read_end_cr(rdr: &mut BytesMut) -> Poll<Result<ChunkedState, io::Error>> {
-        match byte!(rdr) {
-            b'\r' => Poll::Ready(Ok(ChunkedState::EndLf)),
-            _ => Poll::Ready(Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Invalid chunk end CR",
-            ))),
+        match rdr.first() {
+            Some(b'\r') => {
+                rdr.advance(1);
+                Poll::Ready(Ok(ChunkedState::EndLf))
+            }
+            // anything else starts the trailer section
+            Some(_) => Poll::Ready(Ok(ChunkedState::Trailers)),
+            None => Poll::Pending,
         }
     }
     fn read_end_lf(rdr: &mut BytesMut) -> Poll<Result<ChunkedState, io::Error>> {
@@ -184,6 +192,47 @@ impl ChunkedState {
             ))),
         }
     }
+
+    /// Reads the complete trailer section, including the terminating empty line.
+    fn read_trailers(
+        rdr: &mut BytesMut,
+        trailers: &mut Option<HeaderMap>,
+    ) -> Poll<Result<ChunkedState, io::Error>> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Invalid chunked trailer section",
+            )
+        };
+
+        let mut parsed = [httparse::EMPTY_HEADER; MAX_HEADERS];
+
+        let (len, fields) = match httparse::parse_headers(rdr, &mut parsed) {
+            Ok(httparse::Status::Complete(res)) => res,
+
+            Ok(httparse::Status::Partial) if rdr.len() > MAX_BUFFER_SIZE => {
+                debug!("chunked trailer section is too large");
+                return Poll::Ready(Err(invalid()));
+            }
+
+            Ok(httparse::Status::Partial) => return Poll::Pending,
+
+            Err(_) => return Poll::Ready(Err(invalid())),
+        };
+
+        let mut map = HeaderMap::with_capacity(fields.len());
+
+        for field in fields {
+            let name = HeaderName::from_bytes(field.name.as_bytes()).map_err(|_| invalid())?;
+            let value = HeaderValue::from_bytes(field.value).map_err(|_| invalid())?;
+            map.append(name, value);
+        }
+
+        rdr.advance(len);
+        *trailers = Some(map);
+
+        Poll::Ready(Ok(ChunkedState::End))
+    }
 }
 
 #[cfg(test)]
@@ -362,6 +411,51 @@ mod tests {
         assert!(pl.decode(&mut buf).unwrap().unwrap().eof());
     }
 
+    #[test]
+    fn chunked_payload_trailers() {
+        let mut buf = BytesMut::from(
+            "POST /test HTTP/1.1\r\n\
+             transfer-encoding: chunked\r\n\r\n",
+        );
+        let mut reader = MessageDecoder::<Request>::default();
+        let (_req, pl) = reader.decode(&mut buf).unwrap().unwrap();
+        let mut pl = pl.unwrap();
+
+        buf.extend(b"4\r\ndata\r\n0\r\nx-checksum: abc\r\nx-");
+        let msg = pl.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(msg.chunk().as_ref(), b"data");
+
+        // trailer section is incomplete
+        assert!(pl.decode(&mut buf).unwrap().is_none());
+        assert!(pl.take_trailers().is_none());
+
+        buf.extend(b"count: 1\r\n\r\nGET /next HTTP/1.1\r\n\r\n");
+        assert!(pl.decode(&mut buf).unwrap().unwrap().eof());
+
+        let trailers = pl.take_trailers().unwrap();
+        assert_eq!(trailers.len(), 2);
+        assert_eq!(trailers.get("x-checksum").unwrap(), "abc");
+        assert_eq!(trailers.get("x-count").unwrap(), "1");
+
+        let (req, _) = reader.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(req.path(), "/next");
+    }
+
+    #[test]
+    fn chunked_payload_trailers_invalid() {
+        let mut buf = BytesMut::from(
+            "POST /test HTTP/1.1\r\n\
+             transfer-encoding: chunked\r\n\r\n",
+        );
+        let mut reader = MessageDecoder::<Request>::default();
+        let (_req, pl) = reader.decode(&mut buf).unwrap().unwrap();
+        let mut pl = pl.unwrap();
+
+        buf.extend(b"0\r\nx checksum: abc\r\n\r\n");
+        let err = pl.decode(&mut buf).unwrap_err();
+        assert!(err.to_string().contains("Invalid chunked trailer section"));
+    }
+
     #[test]
     fn chunk_extension_quoted() {
         let mut buf = BytesMut::from(
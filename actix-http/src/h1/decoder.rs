@@ -8,14 +8,17 @@ use http::{
 };
 use tracing::{debug, error, trace};
 
-use super::chunked::ChunkedState;
+use super::{chunked::ChunkedState, H1Limits};
 use crate::{error::ParseError, header::HeaderMap, ConnectionType, Request, ResponseHead};
 
 pub(crate) const MAX_BUFFER_SIZE: usize = 131_072;
-const MAX_HEADERS: usize = 96;
+pub(crate) const MAX_HEADERS: usize = 96;
 
 /// Incoming message decoder
-pub(crate) struct MessageDecoder<T: MessageType>(PhantomData<T>);
+pub(crate) struct MessageDecoder<T: MessageType> {
+    limits: H1Limits,
+    _phantom: PhantomData<T>,
+}
 
 #[derive(Debug)]
 /// Incoming request type
@@ -27,7 +30,17 @@ pub(crate) enum PayloadType {
 
 impl<T: MessageType> Default for MessageDecoder<T> {
     fn default() -> Self {
-        MessageDecoder(PhantomData)
+        MessageDecoder::new(H1Limits::default())
+    }
+}
+
+impl<T: MessageType> MessageDecoder<T> {
+    /// Constructs a message decoder that enforces the given head parsing limits.
+    pub(crate) fn new(limits: H1Limits) -> Self {
+        MessageDecoder {
+            limits,
+            _phantom: PhantomData,
+        }
     }
 }
 
@@ -36,7 +49,7 @@ impl<T: MessageType> Decoder for MessageDecoder<T> {
     type Error = ParseError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        T::decode(src)
+        T::decode(src, &self.limits)
     }
 }
 
@@ -57,7 +70,8 @@ impl PayloadLength {
         matches!(
             self,
             PayloadLength::Payload(PayloadType::Payload(PayloadDecoder {
-                kind: Kind::Length(0)
+                kind: Kind::Length(0),
+                ..
             }))
         )
     }
@@ -70,7 +84,10 @@ pub(crate) trait MessageType: Sized {
 
     fn headers_mut(&mut self) -> &mut HeaderMap;
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError>;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &H1Limits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError>;
 
     fn set_headers(
         &mut self,
@@ -228,9 +245,15 @@ impl MessageType for Request {
         &mut self.head_mut().headers
     }
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError> {
+    fn decode(
+        src: &mut BytesMut,
+        limits: &H1Limits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError> {
         let mut headers: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
 
+        // only used when more headers than fit on the stack are allowed
+        let mut heap_headers = Vec::new();
+
         let (len, method, uri, ver, h_len) = {
             // SAFETY:
             // Create an uninitialized array of `MaybeUninit`. The `assume_init` is safe because the
@@ -241,26 +264,67 @@ impl MessageType for Request {
                     .assume_init()
             };
 
+            let mut heap_parsed = Vec::new();
+
+            let parsed = if limits.max_headers <= MAX_HEADERS {
+                &mut parsed[..limits.max_headers]
+            } else {
+                heap_parsed.resize_with(limits.max_headers, MaybeUninit::uninit);
+                &mut heap_parsed[..]
+            };
+
             let mut req = httparse::Request::new(&mut []);
 
-            match req.parse_with_uninit_headers(src, &mut parsed)? {
+            match req.parse_with_uninit_headers(src, parsed)? {
                 httparse::Status::Complete(len) => {
+                    let path = req.path.unwrap();
+
+                    if limits.max_uri_length.is_some_and(|max| path.len() > max) {
+                        trace!("request URI exceeds maximum length");
+                        return Err(ParseError::UriTooLong);
+                    }
+
+                    if len > limits.max_head_size {
+                        trace!("request head exceeds maximum size");
+                        return Err(ParseError::TooLarge);
+                    }
+
+                    if let Some(max) = limits.max_header_size {
+                        if req
+                            .headers
+                            .iter()
+                            .any(|header| header.name.len() + header.value.len() > max)
+                        {
+                            trace!("request header field exceeds maximum size");
+                            return Err(ParseError::TooLarge);
+                        }
+                    }
+
                     let method = Method::from_bytes(req.method.unwrap().as_bytes())
                         .map_err(|_| ParseError::Method)?;
-                    let uri = Uri::try_from(req.path.unwrap())?;
+                    let uri = Uri::try_from(path)?;
                     let version = if req.version.unwrap() == 1 {
                         Version::HTTP_11
                     } else {
                         Version::HTTP_10
                     };
-                    HeaderIndex::record(src, req.headers, &mut headers);
+
+                    if req.headers.len() <= MAX_HEADERS {
+                        HeaderIndex::record(src, req.headers, &mut headers);
+                    } else {
+                        heap_headers.resize(req.headers.len(), EMPTY_HEADER_INDEX);
+                        HeaderIndex::record(src, req.headers, &mut heap_headers);
+                    }
 
                     (len, method, uri, version, req.headers.len())
                 }
 
                 httparse::Status::Partial => {
-                    return if src.len() >= MAX_BUFFER_SIZE {
-                        trace!("MAX_BUFFER_SIZE unprocessed data reached, closing");
+                    return if limits.partial_uri_too_long(src) {
+                        trace!("request URI exceeds maximum length");
+                        Err(ParseError::UriTooLong)
+                    } else if src.len() >= limits.max_head_size {
+                        trace!("maximum head size of unprocessed data reached, closing");
                         Err(ParseError::TooLarge)
                     } else {
                         // Return None to notify more read are needed for parsing request
@@ -270,10 +334,16 @@ impl MessageType for Request {
             }
         };
 
+        let headers = if heap_headers.is_empty() {
+            &headers[..h_len]
+        } else {
+            &heap_headers[..]
+        };
+
         let mut msg = Request::new();
 
         // convert headers
-        let mut length = msg.set_headers(&src.split_to(len).freeze(), &headers[..h_len], ver)?;
+        let mut length = msg.set_headers(&src.split_to(len).freeze(), headers, ver)?;
 
         // disallow HTTP/1.0 POST requests that do not contain a Content-Length headers
         // see https://datatracker.ietf.org/doc/html/rfc1945#section-7.2.2
@@ -327,7 +397,10 @@ impl MessageType for ResponseHead {
         &mut self.headers
     }
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError> {
+    fn decode(
+        src: &mut BytesMut,
+        _limits: &H1Limits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError> {
         let mut headers: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
 
         let (len, ver, status, h_len) = {
@@ -446,9 +519,10 @@ pub enum PayloadItem {
 /// Decoder that can handle different payload types.
 ///
 /// If a message body does not use `Transfer-Encoding`, it should include a `Content-Length`.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct PayloadDecoder {
     kind: Kind,
+    trailers: Option<HeaderMap>,
 }
 
 impl PayloadDecoder {
@@ -456,6 +530,7 @@ impl PayloadDecoder {
     pub fn length(x: u64) -> PayloadDecoder {
         PayloadDecoder {
             kind: Kind::Length(x),
+            trailers: None,
         }
     }
 
@@ -463,12 +538,21 @@ impl PayloadDecoder {
     pub fn chunked() -> PayloadDecoder {
         PayloadDecoder {
             kind: Kind::Chunked(ChunkedState::Size, 0),
+            trailers: None,
         }
     }
 
     /// Creates an decoder that yields chunks until the stream returns EOF.
     pub fn eof() -> PayloadDecoder {
-        PayloadDecoder { kind: Kind::Eof }
+        PayloadDecoder {
+            kind: Kind::Eof,
+            trailers: None,
+        }
+    }
+
+    /// Takes the trailer fields of a chunked payload, once it has been decoded to completion.
+    pub(crate) fn take_trailers(&mut self) -> Option<HeaderMap> {
+        self.trailers.take()
     }
 }
 
@@ -529,7 +613,7 @@ impl Decoder for PayloadDecoder {
                     let mut buf = None;
 
                     // advances the chunked state
-                    *state = match state.step(src, size, &mut buf) {
+                    *state = match state.step(src, size, &mut buf, &mut self.trailers) {
                         Poll::Pending => return Ok(None),
                         Poll::Ready(Ok(state)) => state,
                         Poll::Ready(Err(err)) => return Err(err),
@@ -965,7 +1049,7 @@ mod tests {
         let (_req, pl) = reader.decode(&mut buf).unwrap().unwrap();
         assert!(matches!(
             pl,
-            PayloadType::Payload(pl) if pl == PayloadDecoder::length(11)
+            PayloadType::Payload(pl) if pl.kind == Kind::Length(11)
         ));
     }
 
@@ -1054,6 +1138,52 @@ mod tests {
         expect_parse_err!(&mut BytesMut::from("GET //get HT/11\r\n\r\n"));
     }
 
+    #[test]
+    fn test_http_request_parser_limits() {
+        let headers = (0..100)
+            .map(|idx| format!("x-header-{idx}: {idx}\r\n"))
+            .collect::<String>();
+        let many_headers = format!("GET /test HTTP/1.1\r\n{headers}\r\n");
+
+        let mut buf = BytesMut::from(many_headers.as_str());
+        let mut reader = MessageDecoder::<Request>::default();
+        assert!(matches!(reader.decode(&mut buf), Err(ParseError::TooLarge)));
+
+        let mut buf = BytesMut::from(many_headers.as_str());
+        let mut reader = MessageDecoder::<Request>::new(H1Limits::default().max_headers(128));
+        let req = reader.decode(&mut buf).unwrap().unwrap().0;
+        assert_eq!(req.headers().len(), 100);
+        assert_eq!(req.headers().get("x-header-99").unwrap(), "99");
+
+        let limits = H1Limits::default()
+            .max_uri_length(8)
+            .max_header_size(16)
+            .max_head_size(64);
+        let mut reader = MessageDecoder::<Request>::new(limits);
+
+        let mut buf = BytesMut::from("GET /12345678 HTTP/1.1\r\n\r\n");
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(ParseError::UriTooLong)
+        ));
+
+        let mut buf = BytesMut::from("GET /12345678");
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(ParseError::UriTooLong)
+        ));
+
+        let mut buf = BytesMut::from("GET /1234567 HTTP/1.1\r\nname: value\r\n\r\n");
+        assert!(reader.decode(&mut buf).unwrap().is_some());
+
+        let mut buf = BytesMut::from("GET / HTTP/1.1\r\nname: long-value-1234\r\n\r\n");
+        assert!(matches!(reader.decode(&mut buf), Err(ParseError::TooLarge)));
+
+        let partial_head = format!("GET / HTTP/1.1\r\n{}", "name: value\r\n".repeat(5));
+        let mut buf = BytesMut::from(partial_head.as_str());
+        assert!(matches!(reader.decode(&mut buf), Err(ParseError::TooLarge)));
+    }
+
     #[test]
     fn test_response_http10_read_until_eof() {
         let mut buf = BytesMut::from("HTTP/1.0 200 Ok\r\n\r\ntest data");
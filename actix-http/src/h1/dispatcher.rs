@@ -13,7 +13,7 @@ use actix_rt::time::sleep_until;
 use actix_service::Service;
 use bitflags::bitflags;
 use bytes::{Buf, BytesMut};
-use futures_core::ready;
+use futures_core::{future::LocalBoxFuture, ready};
 use pin_project_lite::pin_project;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_util::codec::{Decoder as _, Encoder as _};
@@ -31,7 +31,7 @@ use crate::{
     config::ServiceConfig,
     error::{DispatchError, ParseError, PayloadError},
     service::HttpFlow,
-    Error, Extensions, OnConnectData, Request, Response, StatusCode,
+    ConnectionType, Error, Extensions, OnConnectData, Request, Response, StatusCode,
 };
 
 const LW_BUFFER_SIZE: usize = 1024;
@@ -58,6 +58,12 @@ bitflags! {
 
         /// Set if write-half is disconnected.
         const WRITE_DISCONNECT = 0b0010_0000;
+
+        /// Set when response body is complete and its trailers are being polled.
+        const TRAILERS         = 0b0100_0000;
+
+        /// Set when the server has started a graceful shutdown.
+        const DRAINING         = 0b1000_0000;
     }
 }
 
@@ -162,6 +168,7 @@ pin_project! {
         head_timer: TimerState,
         ka_timer: TimerState,
         shutdown_timer: TimerState,
+        shutdown_signal: Option<LocalBoxFuture<'static, ()>>,
 
         pub(super) io: Option<T>,
         read_buf: BytesMut,
@@ -274,6 +281,7 @@ where
                     head_timer: TimerState::new(config.client_request_deadline().is_some()),
                     ka_timer: TimerState::new(config.keep_alive().enabled()),
                     shutdown_timer: TimerState::new(config.client_disconnect_deadline().is_some()),
+                    shutdown_signal: config.shutdown_signal(),
 
                     io: Some(io),
                     read_buf: BytesMut::with_capacity(HW_BUFFER_SIZE),
@@ -357,13 +365,18 @@ where
 
     fn send_response_inner(
         self: Pin<&mut Self>,
-        res: Response<()>,
+        mut res: Response<()>,
         body: &impl MessageBody,
     ) -> Result<BodySize, DispatchError> {
         let this = self.project();
 
         let size = body.size();
 
+        // ask client to reconnect elsewhere once the server is shutting down
+        if this.flags.contains(Flags::DRAINING) && !res.head().upgrade() {
+            res.head_mut().set_connection_type(ConnectionType::Close);
+        }
+
         this.codec
             .encode(Message::Item((res, size)), this.write_buf)
             .map_err(|err| {
@@ -494,22 +507,36 @@ where
                     // keep populate writer buffer until buffer size limit hit,
                     // get blocked or finished.
                     while this.write_buf.len() < super::payload::MAX_BUFFER_SIZE {
+                        if this.flags.contains(Flags::TRAILERS) {
+                            let trailers = match body.as_mut().poll_trailers(cx) {
+                                Poll::Ready(trailers) => trailers,
+                                Poll::Pending => return Ok(PollResponse::DoNothing),
+                            };
+
+                            match trailers {
+                                Some(trailers) => {
+                                    this.codec.encode_trailers(&trailers, this.write_buf)?
+                                }
+                                None => this.codec.encode(Message::Chunk(None), this.write_buf)?,
+                            }
+
+                            // payload stream finished.
+                            // set state to None and handle next message
+                            this.state.set(State::None);
+                            this.flags.remove(Flags::TRAILERS);
+                            this.flags.insert(Flags::FINISHED);
+
+                            continue 'res;
+                        }
+
                         match body.as_mut().poll_next(cx) {
                             Poll::Ready(Some(Ok(item))) => {
                                 this.codec
                                     .encode(Message::Chunk(Some(item)), this.write_buf)?;
                             }
 
-                            Poll::Ready(None) => {
-                                this.codec.encode(Message::Chunk(None), this.write_buf)?;
-
-                                // payload stream finished.
-                                // set state to None and handle next message
-                                this.state.set(State::None);
-                                this.flags.insert(Flags::FINISHED);
-
-                                continue 'res;
-                            }
+                            // body data is complete; poll trailers before finishing
+                            Poll::Ready(None) => this.flags.insert(Flags::TRAILERS),
 
                             Poll::Ready(Some(Err(err))) => {
                                 let err = err.into();
@@ -758,6 +785,9 @@ where
 
                         Message::Chunk(None) => {
                             if let Some(mut payload) = this.payload.take() {
+                                if let Some(trailers) = this.codec.take_trailers() {
+                                    payload.feed_trailers(trailers);
+                                }
                                 payload.feed_eof();
                             } else {
                                 error!("Internal server error: unexpected eof");
@@ -804,6 +834,21 @@ where
                     break;
                 }
 
+                Err(ParseError::UriTooLong) => {
+                    trace!("request URI was too long; returning 414 response");
+
+                    this.messages
+                        .push_back(DispatcherMessage::Error(Response::with_body(
+                            StatusCode::URI_TOO_LONG,
+                            (),
+                        )));
+
+                    this.flags.insert(Flags::READ_DISCONNECT);
+                    *this.error = Some(ParseError::UriTooLong.into());
+
+                    break;
+                }
+
                 Err(err) => {
                     trace!("parse error {}", &err);
 
@@ -914,6 +959,21 @@ where
         Ok(())
     }
 
+    /// Starts draining the connection once the server has started a graceful shutdown.
+    ///
+    /// A draining connection is closed once it becomes idle instead of being kept alive.
+    fn poll_shutdown_signal(self: Pin<&mut Self>, cx: &mut Context<'_>) {
+        let this = self.project();
+
+        if let Some(signal) = this.shutdown_signal {
+            if signal.as_mut().poll(cx).is_ready() {
+                trace!("server is shutting down; draining connection");
+                *this.shutdown_signal = None;
+                this.flags.insert(Flags::DRAINING);
+            }
+        }
+    }
+
     /// Poll head, keep-alive, and disconnect timer.
     fn poll_timers(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Result<(), DispatchError> {
         self.as_mut().poll_head_timer(cx)?;
@@ -940,9 +1000,12 @@ where
 
         let mut read_some = false;
 
+        // request heads may be configured to be larger than the default buffer size
+        let max_buffer_size = MAX_BUFFER_SIZE.max(this.codec.config().h1_limits().max_head_size);
+
         loop {
             // Return early when read buf exceed decoder's max buffer size.
-            if this.read_buf.len() >= MAX_BUFFER_SIZE {
+            if this.read_buf.len() >= max_buffer_size {
                 // At this point it's not known IO stream is still scheduled to be waked up so
                 // force wake up dispatcher just in case.
                 //
@@ -1077,6 +1140,7 @@ where
                 );
 
                 inner.as_mut().poll_timers(cx)?;
+                inner.as_mut().poll_shutdown_signal(cx);
 
                 let poll = if inner.flags.contains(Flags::SHUTDOWN) {
                     if inner.flags.contains(Flags::WRITE_DISCONNECT) {
@@ -1132,7 +1196,16 @@ where
                                 // KEEP_ALIVE is set in send_response_inner if client allows it
                                 // FINISHED is set after writing last chunk of response
                                 if inner.flags.contains(Flags::KEEP_ALIVE | Flags::FINISHED) {
-                                    if let Some(timer) = inner.config.keep_alive_deadline() {
+                                    if inner.flags.contains(Flags::DRAINING) {
+                                        // server is shutting down; close idle connection
+                                        // instead of keeping it alive
+                                        let inner = inner.as_mut().project();
+                                        inner.flags.remove(Flags::KEEP_ALIVE);
+
+                                        if let TimerState::Active { .. } = inner.ka_timer {
+                                            inner.ka_timer.clear(line!());
+                                        }
+                                    } else if let Some(timer) = inner.config.keep_alive_deadline() {
                                         inner.as_mut().project().ka_timer.set_and_init(
                                             cx,
                                             sleep_until(timer.into()),
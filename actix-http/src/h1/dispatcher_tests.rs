@@ -1,17 +1,25 @@
-use std::{future::Future, str, task::Poll, time::Duration};
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    str,
+    task::{Context, Poll},
+    time::Duration,
+};
 
 use actix_codec::Framed;
 use actix_rt::{pin, time::sleep};
 use actix_service::{fn_service, Service};
-use actix_utils::future::{ready, Ready};
+use actix_utils::future::{poll_fn, ready, Ready};
 use bytes::{Buf, Bytes, BytesMut};
 use futures_util::future::lazy;
 
 use super::dispatcher::{Dispatcher, DispatcherState, DispatcherStateProj, Flags};
 use crate::{
-    body::MessageBody,
+    body::{BodySize, MessageBody},
     config::ServiceConfig,
-    h1::{Codec, ExpectHandler, UpgradeHandler},
+    h1::{Codec, ExpectHandler, H1Limits, UpgradeHandler},
+    header::HeaderMap,
     service::HttpFlow,
     test::{TestBuffer, TestSeqBuffer},
     Error, HttpMessage, KeepAlive, Method, OnConnectData, Request, Response, StatusCode,
@@ -439,6 +447,49 @@ async fn req_parse_err() {
     .await;
 }
 
+#[actix_rt::test]
+async fn req_limits_err() {
+    lazy(|cx| {
+        let limits = H1Limits::default().max_uri_length(8).max_head_size(24);
+
+        for (req, status_line) in [
+            (
+                "GET /123456789 HTTP/1.1\r\n\r\n",
+                &b"HTTP/1.1 414 URI Too Long\r\n"[..],
+            ),
+            (
+                "GET / HTTP/1.1\r\nname: value\r\n",
+                &b"HTTP/1.1 431 Request Header Fields Too Large\r\n"[..],
+            ),
+        ] {
+            let buf = TestBuffer::new(req);
+
+            let services = HttpFlow::new(ok_service(), ExpectHandler, None);
+
+            let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+                buf.clone(),
+                services,
+                ServiceConfig::default().with_h1_limits(limits),
+                None,
+                OnConnectData::default(),
+            );
+
+            pin!(h1);
+
+            match h1.as_mut().poll(cx) {
+                Poll::Pending => panic!(),
+                Poll::Ready(res) => assert!(res.is_err()),
+            }
+
+            if let DispatcherStateProj::Normal { inner } = h1.project().inner.project() {
+                assert!(inner.flags.contains(Flags::READ_DISCONNECT));
+                assert_eq!(&buf.write_buf_slice()[..status_line.len()], status_line);
+            }
+        }
+    })
+    .await;
+}
+
 #[actix_rt::test]
 async fn pipelining_ok_then_ok() {
     lazy(|cx| {
@@ -924,6 +975,106 @@ async fn handler_drop_payload() {
     .await;
 }
 
+#[actix_rt::test]
+async fn trailers() {
+    struct TrailersBody {
+        data: Option<Bytes>,
+        trailers: Option<HeaderMap>,
+    }
+
+    impl MessageBody for TrailersBody {
+        type Error = Infallible;
+
+        fn size(&self) -> BodySize {
+            BodySize::Stream
+        }
+
+        fn poll_next(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+            Poll::Ready(self.data.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<HeaderMap>> {
+            Poll::Ready(self.trailers.take())
+        }
+    }
+
+    // echoes request body and trailers
+    let echo_trailers_service = fn_service(|mut req: Request| async move {
+        use futures_util::StreamExt as _;
+
+        let mut pl = req.take_payload();
+        let mut body = BytesMut::new();
+        while let Some(chunk) = pl.next().await {
+            body.extend_from_slice(&chunk?);
+        }
+
+        let trailers = poll_fn(|cx| pl.poll_trailers(cx)).await?;
+
+        Ok::<_, Error>(Response::ok().set_body(TrailersBody {
+            data: Some(body.freeze()),
+            trailers,
+        }))
+    });
+
+    let buf = TestBuffer::new(
+        "POST / HTTP/1.1\r\n\
+        Transfer-Encoding: chunked\r\n\
+        \r\n\
+        5\r\n\
+        hello\r\n\
+        0\r\n\
+        x-checksum: abc\r\n\
+        \r\n",
+    );
+
+    let services = HttpFlow::new(echo_trailers_service, ExpectHandler, None);
+
+    let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+        buf.clone(),
+        services,
+        ServiceConfig::default(),
+        None,
+        OnConnectData::default(),
+    );
+    pin!(h1);
+
+    lazy(|cx| {
+        assert!(h1.as_mut().poll(cx).is_pending());
+
+        let mut res = buf.take_write_buf().to_vec();
+        stabilize_date_header(&mut res);
+        let res = &res[..];
+
+        let exp = b"\
+            HTTP/1.1 200 OK\r\n\
+            transfer-encoding: chunked\r\n\
+            date: Thu, 01 Jan 1970 12:34:56 UTC\r\n\
+            \r\n\
+            5\r\n\
+            hello\r\n\
+            0\r\n\
+            x-checksum: abc\r\n\
+            \r\n";
+
+        assert_eq!(
+            res,
+            exp,
+            "\nexpected response not in write buffer:\n\
+               response: {:?}\n\
+               expected: {:?}",
+            String::from_utf8_lossy(res),
+            String::from_utf8_lossy(exp)
+        );
+    })
+    .await;
+}
+
 fn http_msg(msg: impl AsRef<str>) -> BytesMut {
     let mut msg = msg
         .as_ref()
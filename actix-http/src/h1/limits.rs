@@ -0,0 +1,91 @@
+use super::decoder::{MAX_BUFFER_SIZE, MAX_HEADERS};
+
+/// Limits applied when parsing HTTP/1 request heads.
+///
+/// Requests exceeding the URI length limit are responded to with `414 URI Too Long`; requests
+/// exceeding any other limit are responded to with `431 Request Header Fields Too Large`.
+///
+/// # Examples
+/// ```
+/// use actix_http::h1::H1Limits;
+///
+/// let limits = H1Limits::default()
+///     .max_headers(128)
+///     .max_uri_length(8 * 1024)
+///     .max_header_size(16 * 1024);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct H1Limits {
+    pub(crate) max_headers: usize,
+    pub(crate) max_head_size: usize,
+    pub(crate) max_uri_length: Option<usize>,
+    pub(crate) max_header_size: Option<usize>,
+}
+
+impl Default for H1Limits {
+    fn default() -> Self {
+        Self {
+            max_headers: MAX_HEADERS,
+            max_head_size: MAX_BUFFER_SIZE,
+            max_uri_length: None,
+            max_header_size: None,
+        }
+    }
+}
+
+impl H1Limits {
+    /// Sets the maximum number of header fields in a request head.
+    ///
+    /// By default, up to 96 header fields are accepted.
+    pub fn max_headers(mut self, max: usize) -> Self {
+        self.max_headers = max;
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of a request head, including the request line.
+    ///
+    /// By default, request heads up to 128 KiB are accepted.
+    pub fn max_head_size(mut self, size: usize) -> Self {
+        self.max_head_size = size;
+        self
+    }
+
+    /// Sets the maximum length (in bytes) of a request's URI, as it appears in the request line.
+    ///
+    /// By default, the URI length is only limited by the [maximum head size](Self::max_head_size).
+    pub fn max_uri_length(mut self, len: usize) -> Self {
+        self.max_uri_length = Some(len);
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of a single header field's name and value combined.
+    ///
+    /// By default, header field size is only limited by the
+    /// [maximum head size](Self::max_head_size).
+    pub fn max_header_size(mut self, size: usize) -> Self {
+        self.max_header_size = Some(size);
+        self
+    }
+
+    /// Returns `true` if the URI in an incomplete request line is already longer than allowed.
+    pub(crate) fn partial_uri_too_long(&self, src: &[u8]) -> bool {
+        let max = match self.max_uri_length {
+            Some(max) => max,
+            None => return false,
+        };
+
+        // skip the method
+        let start = match src.iter().position(|&b| b == b' ') {
+            Some(idx) => idx + 1,
+            None => return false,
+        };
+
+        let uri = &src[start..];
+        let len = uri
+            .iter()
+            .position(|&b| b == b' ' || b == b'\r' || b == b'\n')
+            .unwrap_or(uri.len());
+
+        len > max
+    }
+}
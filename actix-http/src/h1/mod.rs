@@ -11,6 +11,7 @@ mod dispatcher;
 mod dispatcher_tests;
 mod encoder;
 mod expect;
+mod limits;
 mod payload;
 mod service;
 mod timer;
@@ -22,6 +23,7 @@ pub use self::{
     codec::Codec,
     dispatcher::Dispatcher,
     expect::ExpectHandler,
+    limits::H1Limits,
     payload::Payload,
     service::{H1Service, H1ServiceHandler},
     upgrade::UpgradeHandler,
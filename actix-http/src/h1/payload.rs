@@ -11,7 +11,7 @@ use std::{
 use bytes::Bytes;
 use futures_core::Stream;
 
-use crate::error::PayloadError;
+use crate::{error::PayloadError, header::HeaderMap};
 
 /// max buffer size 32k
 pub(crate) const MAX_BUFFER_SIZE: usize = 32_768;
@@ -73,6 +73,17 @@ impl Payload {
     pub fn unread_data(&mut self, data: Bytes) {
         self.inner.borrow_mut().unread_data(data);
     }
+
+    /// Attempt to pull out the trailer fields sent after a chunked payload.
+    ///
+    /// Resolves once all payload data has been read, with `None` if no trailers were sent.
+    #[inline]
+    pub fn poll_trailers(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, PayloadError>> {
+        self.inner.borrow_mut().poll_trailers(cx)
+    }
 }
 
 impl Stream for Payload {
@@ -117,6 +128,13 @@ impl PayloadSender {
         }
     }
 
+    #[inline]
+    pub fn feed_trailers(&mut self, trailers: HeaderMap) {
+        if let Some(shared) = self.inner.upgrade() {
+            shared.borrow_mut().feed_trailers(trailers)
+        }
+    }
+
     #[allow(clippy::needless_pass_by_ref_mut)]
     #[inline]
     pub fn need_read(&self, cx: &mut Context<'_>) -> PayloadStatus {
@@ -142,6 +160,7 @@ struct Inner {
     err: Option<PayloadError>,
     need_read: bool,
     items: VecDeque<Bytes>,
+    trailers: Option<HeaderMap>,
     task: Option<Waker>,
     io_task: Option<Waker>,
 }
@@ -153,6 +172,7 @@ impl Inner {
             len: 0,
             err: None,
             items: VecDeque::new(),
+            trailers: None,
             need_read: true,
             task: None,
             io_task: None,
@@ -207,6 +227,11 @@ impl Inner {
         self.eof = true;
     }
 
+    #[inline]
+    fn feed_trailers(&mut self, trailers: HeaderMap) {
+        self.trailers = Some(trailers);
+    }
+
     #[inline]
     fn feed_data(&mut self, data: Bytes) {
         self.len += data.len();
@@ -245,6 +270,23 @@ impl Inner {
         }
     }
 
+    fn poll_trailers(&mut self, cx: &Context<'_>) -> Poll<Result<Option<HeaderMap>, PayloadError>> {
+        if !self.items.is_empty() {
+            // trailers are only available once payload data has been read
+            self.register(cx);
+            Poll::Pending
+        } else if let Some(err) = self.err.take() {
+            Poll::Ready(Err(err))
+        } else if self.eof {
+            Poll::Ready(Ok(self.trailers.take()))
+        } else {
+            self.need_read = true;
+            self.register(cx);
+            self.wake_io();
+            Poll::Pending
+        }
+    }
+
     fn unread_data(&mut self, data: Bytes) {
         self.len += data.len();
         self.items.push_front(data);
@@ -263,6 +305,39 @@ mod tests {
 
     assert_impl_all!(Inner: Unpin, Send, Sync);
 
+    #[actix_rt::test]
+    async fn trailers() {
+        let (mut sender, mut payload) = Payload::create(false);
+
+        let mut trailers = HeaderMap::new();
+        trailers.insert(
+            crate::header::HeaderName::from_static("x-checksum"),
+            crate::header::HeaderValue::from_static("abc"),
+        );
+
+        sender.feed_data(Bytes::from("data"));
+        sender.feed_trailers(trailers);
+        sender.feed_eof();
+
+        // payload data has not been read yet
+        let res = poll_fn(|cx| Poll::Ready(payload.poll_trailers(cx))).await;
+        assert!(res.is_pending());
+
+        assert_eq!(
+            Bytes::from("data"),
+            poll_fn(|cx| Pin::new(&mut payload).poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap()
+        );
+
+        let trailers = poll_fn(|cx| payload.poll_trailers(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(trailers.get("x-checksum").unwrap(), "abc");
+    }
+
     #[actix_rt::test]
     async fn test_unread_data() {
         let (_, mut payload) = Payload::create(false);
@@ -0,0 +1,140 @@
+use h2::server::Builder;
+
+/// HTTP/2 server connection settings.
+///
+/// Settings that are not set use the defaults of the [`h2`] crate.
+///
+/// # Examples
+/// ```
+/// use actix_http::h2::H2Config;
+///
+/// let config = H2Config::default()
+///     .max_concurrent_streams(256)
+///     .initial_window_size(1024 * 1024)
+///     .enable_connect_protocol();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct H2Config {
+    max_concurrent_streams: Option<u32>,
+    initial_window_size: Option<u32>,
+    initial_connection_window_size: Option<u32>,
+    max_frame_size: Option<u32>,
+    max_header_list_size: Option<u32>,
+    enable_connect_protocol: bool,
+}
+
+impl H2Config {
+    /// Sets the maximum number of concurrent streams a client may open on one connection.
+    ///
+    /// By default, the number of concurrent streams is not limited.
+    pub fn max_concurrent_streams(mut self, max: u32) -> Self {
+        self.max_concurrent_streams = Some(max);
+        self
+    }
+
+    /// Indicates the initial window size (in octets) for stream-level flow control for
+    /// received data.
+    ///
+    /// The default value is 65,535 and is good for APIs, but not for big objects.
+    pub fn initial_window_size(mut self, size: u32) -> Self {
+        self.initial_window_size = Some(size);
+        self
+    }
+
+    /// Indicates the initial window size (in octets) for connection-level flow control for
+    /// received data.
+    ///
+    /// The default value is 65,535 and is good for APIs, but not for big objects.
+    pub fn initial_connection_window_size(mut self, size: u32) -> Self {
+        self.initial_connection_window_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum frame size (in octets) the server is willing to receive.
+    ///
+    /// The value must be between 16,384 and 16,777,215. By default, it is 16,384.
+    ///
+    /// # Panics
+    /// Panics if `size` is outside the allowed range.
+    pub fn max_frame_size(mut self, size: u32) -> Self {
+        assert!(
+            (16_384..=16_777_215).contains(&size),
+            "max frame size must be between 16,384 and 16,777,215"
+        );
+
+        self.max_frame_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum size (in octets) of the header list the server is willing to accept.
+    ///
+    /// By default, it is 16 MiB.
+    pub fn max_header_list_size(mut self, size: u32) -> Self {
+        self.max_header_list_size = Some(size);
+        self
+    }
+
+    /// Enables the extended CONNECT protocol (RFC 8441), used to bootstrap WebSockets over HTTP/2.
+    ///
+    /// By default, the extended CONNECT protocol is disabled.
+    pub fn enable_connect_protocol(mut self) -> Self {
+        self.enable_connect_protocol = true;
+        self
+    }
+
+    /// Creates a handshake builder with these settings applied.
+    pub(crate) fn builder(&self) -> Builder {
+        let mut builder = Builder::new();
+
+        if let Some(max) = self.max_concurrent_streams {
+            builder.max_concurrent_streams(max);
+        }
+
+        if let Some(size) = self.initial_window_size {
+            builder.initial_window_size(size);
+        }
+
+        if let Some(size) = self.initial_connection_window_size {
+            builder.initial_connection_window_size(size);
+        }
+
+        if let Some(size) = self.max_frame_size {
+            builder.max_frame_size(size);
+        }
+
+        if let Some(size) = self.max_header_list_size {
+            builder.max_header_list_size(size);
+        }
+
+        if self.enable_connect_protocol {
+            builder.enable_connect_protocol();
+        }
+
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_frame_size_bounds() {
+        H2Config::default()
+            .max_frame_size(16_384)
+            .max_frame_size(16_777_215)
+            .builder();
+    }
+
+    #[test]
+    #[should_panic]
+    fn max_frame_size_too_small() {
+        H2Config::default().max_frame_size(16_383);
+    }
+
+    #[test]
+    #[should_panic]
+    fn max_frame_size_too_large() {
+        H2Config::default().max_frame_size(16_777_216);
+    }
+}
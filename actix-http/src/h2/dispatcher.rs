@@ -14,7 +14,7 @@ use actix_rt::time::{sleep, Sleep};
 use actix_service::Service;
 use actix_utils::future::poll_fn;
 use bytes::{Bytes, BytesMut};
-use futures_core::ready;
+use futures_core::{future::LocalBoxFuture, ready};
 use h2::{
     server::{Connection, SendResponse},
     Ping, PingPong,
@@ -42,6 +42,7 @@ pin_project! {
         config: ServiceConfig,
         peer_addr: Option<net::SocketAddr>,
         ping_pong: Option<H2PingPong>,
+        shutdown_signal: Option<LocalBoxFuture<'static, ()>>,
         _phantom: PhantomData<B>
     }
 }
@@ -72,6 +73,7 @@ where
 
         Self {
             flow,
+            shutdown_signal: config.shutdown_signal(),
             config,
             peer_addr,
             connection: conn,
@@ -110,6 +112,16 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        if let Some(signal) = this.shutdown_signal.as_mut() {
+            if signal.as_mut().poll(cx).is_ready() {
+                tracing::trace!("server is shutting down; draining connection");
+                this.shutdown_signal = None;
+
+                // sends GOAWAY; connection is closed once in-flight streams are complete
+                this.connection.graceful_shutdown();
+            }
+        }
+
         loop {
             match Pin::new(&mut this.connection).poll_accept(cx)? {
                 Poll::Ready(Some((req, tx))) => {
@@ -265,10 +277,16 @@ where
         }
     }
 
-    // response body streaming finished. send end of stream and return.
-    stream
-        .send_data(Bytes::new(), true)
-        .map_err(DispatchError::SendData)?;
+    // response body streaming finished. send trailers or end of stream and return.
+    match poll_fn(|cx| body.as_mut().poll_trailers(cx)).await {
+        Some(trailers) => stream
+            .send_trailers(trailers.into())
+            .map_err(DispatchError::SendData)?,
+
+        None => stream
+            .send_data(Bytes::new(), true)
+            .map_err(DispatchError::SendData)?,
+    }
 
     Ok(())
 }
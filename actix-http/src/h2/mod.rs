@@ -11,19 +11,21 @@ use actix_rt::time::{sleep_until, Sleep};
 use bytes::Bytes;
 use futures_core::{ready, Stream};
 use h2::{
-    server::{handshake, Connection, Handshake},
+    server::{Connection, Handshake},
     RecvStream,
 };
 
 use crate::{
     config::ServiceConfig,
     error::{DispatchError, PayloadError},
+    header::HeaderMap,
 };
 
+mod config;
 mod dispatcher;
 mod service;
 
-pub use self::{dispatcher::Dispatcher, service::H2Service};
+pub use self::{config::H2Config, dispatcher::Dispatcher, service::H2Service};
 
 /// HTTP/2 peer stream.
 pub struct Payload {
@@ -34,6 +36,19 @@ impl Payload {
     pub(crate) fn new(stream: RecvStream) -> Self {
         Self { stream }
     }
+
+    /// Attempt to pull out the trailer fields sent after the stream's data.
+    ///
+    /// Resolves once all payload data has been read, with `None` if no trailers were sent.
+    pub fn poll_trailers(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, PayloadError>> {
+        match ready!(self.stream.poll_trailers(cx)) {
+            Ok(trailers) => Poll::Ready(Ok(trailers.map(HeaderMap::from))),
+            Err(err) => Poll::Ready(Err(err.into())),
+        }
+    }
 }
 
 impl Stream for Payload {
@@ -62,7 +77,7 @@ where
     T: AsyncRead + AsyncWrite + Unpin,
 {
     HandshakeWithTimeout {
-        handshake: handshake(io),
+        handshake: config.h2_config().builder().handshake(io),
         timer: config
             .client_request_deadline()
             .map(|deadline| Box::pin(sleep_until(deadline.into()))),
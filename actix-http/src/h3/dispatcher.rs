@@ -0,0 +1,504 @@
+use std::{
+    cell::Cell,
+    error::Error as StdError,
+    future::Future as _,
+    net,
+    pin::pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::Instant,
+};
+
+use actix_rt::time::sleep_until;
+use actix_service::Service;
+use actix_utils::future::poll_fn;
+use bytes::{Bytes, BytesMut};
+use h3::{
+    error::{ConnectionError, StreamError},
+    server::{RequestResolver, RequestStream},
+};
+use tracing::trace;
+
+use crate::{
+    body::{BodySize, BoxBody, MessageBody},
+    config::ServiceConfig,
+    header::{
+        HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING,
+        UPGRADE,
+    },
+    service::HttpFlow,
+    BoxedPayloadStream, Extensions, KeepAlive, Method, OnConnectData, Payload, Request, Response,
+    ResponseHead, StatusCode, Uri, Version,
+};
+
+type H3Connection = h3::server::Connection<h3_quinn::Connection, Bytes>;
+
+/// Drives an HTTP/3 connection, handling each request stream on its own task.
+///
+/// The connection is closed with a GOAWAY when the server starts a graceful shutdown or when it
+/// has been idle for longer than the configured timeouts:
+/// - before its first request, the client request timeout applies;
+/// - between requests, the keep-alive setting applies;
+/// - once a GOAWAY has been sent, the client disconnect timeout applies.
+pub(crate) async fn dispatch<S, B>(
+    conn: quinn::Connection,
+    flow: Rc<HttpFlow<S, (), ()>>,
+    config: ServiceConfig,
+    conn_data: OnConnectData,
+) -> Result<(), crate::error::DispatchError>
+where
+    S: Service<Request>,
+    S::Error: Into<Response<BoxBody>>,
+    S::Future: 'static,
+    S::Response: Into<Response<B>>,
+
+    B: MessageBody,
+    S: 'static,
+{
+    let peer_addr = Some(conn.remote_address());
+    let conn_data = conn_data.0.map(Rc::new);
+
+    let mut conn = H3Connection::new(h3_quinn::Connection::new(conn)).await?;
+
+    let in_flight = Rc::new(InFlight::default());
+    let mut shutdown_signal = config.shutdown_signal();
+    let mut state = ConnState::FirstRequest;
+
+    // idle timer; only armed while there are no in-flight requests
+    let mut timer = None;
+    let mut timer_armed = false;
+
+    loop {
+        let event = {
+            // polling `accept` is cancel-safe until a request stream has been returned
+            let mut accept = pin!(conn.accept());
+
+            poll_fn(|cx| {
+                if let Some(signal) = shutdown_signal.as_mut() {
+                    if signal.as_mut().poll(cx).is_ready() {
+                        shutdown_signal = None;
+                        return Poll::Ready(Event::Close);
+                    }
+                }
+
+                if let Poll::Ready(res) = accept.as_mut().poll(cx) {
+                    return Poll::Ready(Event::Accept(res));
+                }
+
+                if in_flight.is_busy(cx) {
+                    timer_armed = false;
+                    return Poll::Pending;
+                }
+
+                if !timer_armed {
+                    timer_armed = true;
+                    timer = idle_deadline(&config, state)
+                        .map(|deadline| Box::pin(sleep_until(deadline.into())));
+                }
+
+                match timer.as_mut() {
+                    Some(timer) => timer.as_mut().poll(cx).map(|()| Event::Close),
+                    None => Poll::Pending,
+                }
+            })
+            .await
+        };
+
+        match event {
+            Event::Accept(Ok(Some(resolver))) => {
+                if state == ConnState::FirstRequest {
+                    state = ConnState::KeepAlive;
+                }
+
+                let flow = Rc::clone(&flow);
+                let config = config.clone();
+                let conn_data = conn_data.clone();
+                let guard = InFlight::enter(&in_flight);
+
+                // multiplex request handling with spawn task
+                actix_rt::spawn(async move {
+                    if let Err(err) =
+                        handle_request(resolver, flow, config, peer_addr, conn_data).await
+                    {
+                        match err {
+                            DispatchError::ResolveRequest(err) => {
+                                trace!("Error reading request head: {err}");
+                            }
+                            DispatchError::RequestTimeout => {
+                                trace!("Timed out reading request head");
+                            }
+                            DispatchError::SendResponse(err) => {
+                                trace!("Error sending response: {err}");
+                            }
+                            DispatchError::SendData(err) => {
+                                tracing::warn!("Send data error: {err}");
+                            }
+                            DispatchError::ResponseBody(err) => {
+                                tracing::error!("Response payload stream error: {err:?}");
+                            }
+                        }
+                    }
+
+                    drop(guard);
+                });
+            }
+
+            Event::Accept(Ok(None)) => return Ok(()),
+
+            // peer closed the connection gracefully
+            Event::Accept(Err(err)) if err.is_h3_no_error() => return Ok(()),
+
+            Event::Accept(Err(err)) => return Err(err.into()),
+
+            // client did not close the connection in time after being sent a GOAWAY
+            Event::Close if state == ConnState::Closing => {
+                trace!("timed out waiting for client to disconnect");
+                return Ok(());
+            }
+
+            Event::Close => {
+                trace!("closing HTTP/3 connection");
+                state = ConnState::Closing;
+                timer_armed = false;
+
+                // sends GOAWAY; `accept` yields `None` once in-flight requests are complete
+                conn.shutdown(0).await?;
+            }
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+enum Event {
+    Accept(Result<Option<RequestResolver<h3_quinn::Connection, Bytes>>, ConnectionError>),
+
+    /// Connection should be closed, either because of a graceful server shutdown or because one
+    /// of the idle timeouts elapsed.
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnState {
+    /// No request has been received on the connection yet.
+    FirstRequest,
+
+    /// At least one request has been received on the connection.
+    KeepAlive,
+
+    /// A GOAWAY has been sent to the client.
+    Closing,
+}
+
+/// Returns the deadline after which an idle connection should be closed, if any.
+fn idle_deadline(config: &ServiceConfig, state: ConnState) -> Option<Instant> {
+    match state {
+        ConnState::FirstRequest => config.client_request_deadline(),
+
+        ConnState::KeepAlive => match config.keep_alive() {
+            KeepAlive::Timeout(_) => config.keep_alive_deadline(),
+            KeepAlive::Os => None,
+            KeepAlive::Disabled => Some(config.now()),
+        },
+
+        ConnState::Closing => Some(
+            config
+                .client_disconnect_deadline()
+                .unwrap_or_else(|| config.now()),
+        ),
+    }
+}
+
+/// Tracks the number of requests on a connection that are still being handled.
+#[derive(Default)]
+struct InFlight {
+    count: Cell<usize>,
+    waker: Cell<Option<Waker>>,
+}
+
+impl InFlight {
+    /// Registers a new in-flight request, which is complete when the returned guard is dropped.
+    fn enter(this: &Rc<Self>) -> InFlightGuard {
+        this.count.set(this.count.get() + 1);
+        InFlightGuard(Rc::clone(this))
+    }
+
+    /// Returns `true` if any request is in-flight, registering the task to be woken when the last
+    /// one completes.
+    fn is_busy(&self, cx: &mut Context<'_>) -> bool {
+        if self.count.get() == 0 {
+            return false;
+        }
+
+        self.waker.set(Some(cx.waker().clone()));
+        true
+    }
+}
+
+struct InFlightGuard(Rc<InFlight>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let count = self.0.count.get() - 1;
+        self.0.count.set(count);
+
+        if count == 0 {
+            if let Some(waker) = self.0.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+enum DispatchError {
+    ResolveRequest(StreamError),
+    RequestTimeout,
+    SendResponse(StreamError),
+    SendData(StreamError),
+    ResponseBody(Box<dyn StdError>),
+}
+
+async fn handle_request<S, B>(
+    resolver: RequestResolver<h3_quinn::Connection, Bytes>,
+    flow: Rc<HttpFlow<S, (), ()>>,
+    config: ServiceConfig,
+    peer_addr: Option<net::SocketAddr>,
+    conn_data: Option<Rc<Extensions>>,
+) -> Result<(), DispatchError>
+where
+    S: Service<Request>,
+    S::Error: Into<Response<BoxBody>>,
+    S::Response: Into<Response<B>>,
+
+    B: MessageBody,
+{
+    let mut resolve = pin!(resolver.resolve_request());
+    let mut timer = config
+        .client_request_deadline()
+        .map(|deadline| Box::pin(sleep_until(deadline.into())));
+
+    let (req, stream) = poll_fn(|cx| {
+        if let Some(timer) = timer.as_mut() {
+            if timer.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(DispatchError::RequestTimeout));
+            }
+        }
+
+        resolve
+            .as_mut()
+            .poll(cx)
+            .map_err(DispatchError::ResolveRequest)
+    })
+    .await?;
+
+    let (mut tx, rx) = stream.split();
+    let (parts, ()) = req.into_parts();
+
+    let Some((method, uri, headers)) = convert_request_head(parts) else {
+        let res = Response::bad_request();
+        return handle_response(res, &mut tx, config, false).await;
+    };
+
+    let payload: BoxedPayloadStream = Box::pin(crate::h3::Payload::new(rx));
+    let pl = Payload::from(payload);
+    let mut req = Request::with_payload(pl);
+    let head_req = method == Method::HEAD;
+
+    let head = req.head_mut();
+    head.uri = uri;
+    head.method = method;
+    head.version = Version::HTTP_3;
+    head.headers = headers;
+    head.peer_addr = peer_addr;
+
+    req.conn_data = conn_data;
+
+    match flow.service.call(req).await {
+        Ok(res) => handle_response(res.into(), &mut tx, config, head_req).await,
+        Err(err) => {
+            let res: Response<BoxBody> = err.into();
+            handle_response(res, &mut tx, config, head_req).await
+        }
+    }
+}
+
+async fn handle_response<B>(
+    res: Response<B>,
+    tx: &mut RequestStream<h3_quinn::SendStream<Bytes>, Bytes>,
+    config: ServiceConfig,
+    head_req: bool,
+) -> Result<(), DispatchError>
+where
+    B: MessageBody,
+{
+    let (res, body) = res.replace_body(());
+
+    // prepare response.
+    let mut size = body.size();
+    let res = prepare_response(config, res.head(), &mut size);
+    let eof_or_head = size.is_eof() || head_req;
+
+    tx.send_response(res)
+        .await
+        .map_err(DispatchError::SendResponse)?;
+
+    if !eof_or_head {
+        let mut body = pin!(body);
+
+        // poll response body and send chunks to client
+        while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = res.map_err(|err| DispatchError::ResponseBody(err.into()))?;
+
+            if !chunk.is_empty() {
+                tx.send_data(chunk).await.map_err(DispatchError::SendData)?;
+            }
+        }
+    }
+
+    // response finished. send end of stream and return.
+    tx.finish().await.map_err(DispatchError::SendData)
+}
+
+/// Converts request head parts from `http` v1 types, as used by `h3`, to those used in this crate.
+///
+/// Returns `None` if any part cannot be represented, which should not happen for heads that `h3`
+/// has already validated.
+fn convert_request_head(parts: http_1::request::Parts) -> Option<(Method, Uri, HeaderMap)> {
+    let method = Method::from_bytes(parts.method.as_str().as_bytes()).ok()?;
+    let uri = Uri::try_from(parts.uri.to_string()).ok()?;
+
+    let mut headers = HeaderMap::with_capacity(parts.headers.len());
+
+    for (name, value) in parts.headers.iter() {
+        let name = HeaderName::from_bytes(name.as_str().as_bytes()).ok()?;
+        let value = HeaderValue::from_bytes(value.as_bytes()).ok()?;
+        headers.append(name, value);
+    }
+
+    Some((method, uri, headers))
+}
+
+fn prepare_response(
+    config: ServiceConfig,
+    head: &ResponseHead,
+    size: &mut BodySize,
+) -> http_1::Response<()> {
+    let mut has_date = false;
+    let mut skip_len = size != &BodySize::Stream;
+
+    let mut res = http_1::Response::new(());
+    *res.status_mut() = http_1::StatusCode::from_u16(head.status.as_u16())
+        .expect("actix and http v1 status code ranges are the same");
+    *res.version_mut() = http_1::Version::HTTP_3;
+
+    // Content length
+    match head.status {
+        StatusCode::NO_CONTENT | StatusCode::CONTINUE | StatusCode::PROCESSING => {
+            *size = BodySize::None
+        }
+        StatusCode::SWITCHING_PROTOCOLS => {
+            skip_len = true;
+            *size = BodySize::Stream;
+        }
+        _ => {}
+    }
+
+    match size {
+        BodySize::None | BodySize::Stream => {}
+
+        BodySize::Sized(len) => {
+            let mut buf = itoa::Buffer::new();
+
+            res.headers_mut().insert(
+                http_1::header::CONTENT_LENGTH,
+                http_1::HeaderValue::from_str(buf.format(*len)).unwrap(),
+            );
+        }
+    };
+
+    // copy headers
+    for (key, value) in head.headers.iter() {
+        match key {
+            // omit connection-specific headers according to:
+            // https://datatracker.ietf.org/doc/html/rfc9114#section-4.2
+            &CONNECTION | &TRANSFER_ENCODING | &UPGRADE => continue,
+
+            &CONTENT_LENGTH if skip_len => continue,
+            &DATE => has_date = true,
+
+            hdr if hdr == HeaderName::from_static("keep-alive")
+                || hdr == HeaderName::from_static("proxy-connection") =>
+            {
+                continue
+            }
+
+            _ => {}
+        }
+
+        // names and values were validated when they were created so conversion cannot fail
+        let (Ok(key), Ok(value)) = (
+            http_1::HeaderName::from_bytes(key.as_str().as_bytes()),
+            http_1::HeaderValue::from_bytes(value.as_bytes()),
+        ) else {
+            continue;
+        };
+
+        res.headers_mut().append(key, value);
+    }
+
+    // set date header
+    if !has_date {
+        let mut bytes = BytesMut::with_capacity(29);
+        config.write_date_header_value(&mut bytes);
+        res.headers_mut().insert(
+            http_1::header::DATE,
+            // SAFETY: serialized date-times are known ASCII strings
+            unsafe { http_1::HeaderValue::from_maybe_shared_unchecked(bytes.freeze()) },
+        );
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_head_conversion() {
+        let req = http_1::Request::get("https://example.com/path?query=1")
+            .header("x-test", "value")
+            .header("x-test", "other")
+            .body(())
+            .unwrap();
+        let (parts, ()) = req.into_parts();
+
+        let (method, uri, headers) = convert_request_head(parts).unwrap();
+        assert_eq!(method, Method::GET);
+        assert_eq!(uri.path(), "/path");
+        assert_eq!(uri.query(), Some("query=1"));
+        assert_eq!(headers.get_all("x-test").count(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn response_strips_connection_headers() {
+        let mut res = Response::ok();
+        res.headers_mut()
+            .insert(CONNECTION, HeaderValue::from_static("keep-alive"));
+        res.headers_mut()
+            .insert(TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+        res.headers_mut().insert(
+            HeaderName::from_static("x-test"),
+            HeaderValue::from_static("1"),
+        );
+
+        let mut size = BodySize::Sized(5);
+        let res = prepare_response(ServiceConfig::default(), res.head(), &mut size);
+
+        assert_eq!(res.version(), http_1::Version::HTTP_3);
+        assert_eq!(res.headers()["content-length"], "5");
+        assert_eq!(res.headers()["x-test"], "1");
+        assert!(res.headers().contains_key("date"));
+        assert!(!res.headers().contains_key("connection"));
+        assert!(!res.headers().contains_key("transfer-encoding"));
+    }
+}
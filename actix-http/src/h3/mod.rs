@@ -0,0 +1,59 @@
+//! HTTP/3 protocol.
+//!
+//! HTTP/3 runs over QUIC rather than TCP, so connections are not accepted by the usual
+//! `actix-server` TCP listeners. [`H3Service`] is a service factory over established
+//! [`quinn::Connection`]s; the caller is responsible for running the QUIC endpoint and passing
+//! accepted connections to it.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf as _, Bytes};
+use futures_core::{ready, Stream};
+use h3::server::RequestStream;
+
+use crate::error::PayloadError;
+
+mod dispatcher;
+mod service;
+
+pub use self::service::{H3Service, H3ServiceHandler};
+
+/// ALPN protocol identifier for HTTP/3.
+pub const ALPN: &[u8] = b"h3";
+
+/// HTTP/3 peer stream.
+pub struct Payload {
+    stream: RequestStream<h3_quinn::RecvStream, Bytes>,
+}
+
+impl Payload {
+    pub(crate) fn new(stream: RequestStream<h3_quinn::RecvStream, Bytes>) -> Self {
+        Self { stream }
+    }
+}
+
+impl Stream for Payload {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        match ready!(this.stream.poll_recv_data(cx)) {
+            Ok(Some(mut chunk)) => Poll::Ready(Some(Ok(chunk.copy_to_bytes(chunk.remaining())))),
+            Ok(None) => Poll::Ready(None),
+            Err(err) => Poll::Ready(Some(Err(err.into()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+
+    assert_impl_all!(Payload: Unpin);
+}
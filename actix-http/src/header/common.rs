@@ -48,6 +48,13 @@ pub const CROSS_ORIGIN_RESOURCE_POLICY: HeaderName =
 /// document or within any `<iframe>` elements in the document.
 pub const PERMISSIONS_POLICY: HeaderName = HeaderName::from_static("permissions-policy");
 
+/// Request header field that identifies the incoming request in a distributed tracing system.
+///
+/// See the [W3C Trace Context spec] for full semantics.
+///
+/// [W3C Trace Context spec]: https://www.w3.org/TR/trace-context/#traceparent-header
+pub const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+
 /// Request header (de-facto standard) for identifying the originating IP address of a client
 /// connecting to a web server through a proxy server.
 pub const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
@@ -10,16 +10,10 @@
 //! - Browser conformance tests at: <http://greenbytes.de/tech/tc2231/>
 //! - IANA assignment: <http://www.iana.org/assignments/cont-disp/cont-disp.xhtml>
 
-use std::fmt::{self, Write};
+use std::fmt::{self, Write as _};
 
-use once_cell::sync::Lazy;
-#[cfg(feature = "unicode")]
-use regex::Regex;
-#[cfg(not(feature = "unicode"))]
-use regex_lite::Regex;
-
-use super::{ExtendedValue, Header, TryIntoHeaderValue, Writer};
-use crate::http::header;
+use super::{ExtendedValue, Header, TryIntoHeaderValue};
+use crate::header;
 
 /// Split at the index of the first `needle` if it exists or at the end.
 fn split_once(haystack: &str, needle: char) -> (&str, &str) {
@@ -76,7 +70,7 @@ impl<'a> From<&'a str> for DispositionType {
 ///
 /// # Examples
 /// ```
-/// use actix_web::http::header::DispositionParam;
+/// use actix_http::header::DispositionParam;
 ///
 /// let param = DispositionParam::Filename(String::from("sample.txt"));
 /// assert!(param.is_filename());
@@ -257,7 +251,7 @@ impl DispositionParam {
 ///
 /// # Examples
 /// ```
-/// use actix_web::http::header::{
+/// use actix_http::header::{
 ///     Charset, ContentDisposition, DispositionParam, DispositionType,
 ///     ExtendedValue,
 /// };
@@ -324,7 +318,7 @@ impl ContentDisposition {
     ///
     /// # Examples
     /// ```
-    /// use actix_web::http::header::{ContentDisposition, TryIntoHeaderValue as _};
+    /// use actix_http::header::{ContentDisposition, TryIntoHeaderValue as _};
     ///
     /// let cd = ContentDisposition::attachment("files.zip");
     ///
@@ -486,9 +480,7 @@ impl TryIntoHeaderValue for ContentDisposition {
     type Error = header::InvalidHeaderValue;
 
     fn try_into_value(self) -> Result<header::HeaderValue, Self::Error> {
-        let mut writer = Writer::new();
-        let _ = write!(&mut writer, "{}", self);
-        header::HeaderValue::from_maybe_shared(writer.take())
+        header::HeaderValue::from_maybe_shared(self.to_string())
     }
 }
 
@@ -554,22 +546,23 @@ impl fmt::Display for DispositionParam {
         //
         // See also comments in test_from_raw_unnecessary_percent_decode.
 
-        static RE: Lazy<Regex> =
-            Lazy::new(|| Regex::new("[\x00-\x08\x10-\x1F\x7F\"\\\\]").unwrap());
-
         match self {
-            DispositionParam::Name(ref value) => write!(f, "name={}", value),
+            DispositionParam::Name(ref value) if is_token(value) => write!(f, "name={}", value),
+
+            DispositionParam::Name(ref value) => {
+                f.write_str("name=")?;
+                write_quoted(f, value)
+            }
 
             DispositionParam::Filename(ref value) => {
-                write!(f, "filename=\"{}\"", RE.replace_all(value, "\\$0").as_ref())
+                f.write_str("filename=")?;
+                write_quoted(f, value)
             }
 
-            DispositionParam::Unknown(ref name, ref value) => write!(
-                f,
-                "{}=\"{}\"",
-                name,
-                &RE.replace_all(value, "\\$0").as_ref()
-            ),
+            DispositionParam::Unknown(ref name, ref value) => {
+                write!(f, "{}=", name)?;
+                write_quoted(f, value)
+            }
 
             DispositionParam::FilenameExt(ref ext_value) => {
                 write!(f, "filename*={}", ext_value)
@@ -582,6 +575,46 @@ impl fmt::Display for DispositionParam {
     }
 }
 
+/// Returns true if `value` can be written as a token instead of a quoted-string.
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value.bytes().all(|b| {
+            b.is_ascii_graphic()
+                && !matches!(
+                    b,
+                    b'(' | b')'
+                        | b'<'
+                        | b'>'
+                        | b'@'
+                        | b','
+                        | b';'
+                        | b':'
+                        | b'\\'
+                        | b'"'
+                        | b'/'
+                        | b'['
+                        | b']'
+                        | b'?'
+                        | b'='
+                )
+        })
+}
+
+/// Writes `value` as a quoted-string, escaping characters that cannot appear unescaped in one.
+fn write_quoted(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_char('"')?;
+
+    for ch in value.chars() {
+        if matches!(ch, '\x00'..='\x08' | '\x10'..='\x1F' | '\x7F' | '"' | '\\') {
+            f.write_char('\\')?;
+        }
+
+        f.write_char(ch)?;
+    }
+
+    f.write_char('"')
+}
+
 impl fmt::Display for ContentDisposition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.disposition)?;
@@ -594,7 +627,7 @@ impl fmt::Display for ContentDisposition {
 #[cfg(test)]
 mod tests {
     use super::{ContentDisposition, DispositionParam, DispositionType};
-    use crate::http::header::{Charset, ExtendedValue, HeaderValue};
+    use crate::header::{Charset, ExtendedValue, HeaderValue};
 
     #[test]
     fn test_from_raw_basic() {
@@ -962,6 +995,23 @@ mod tests {
         assert_eq!("inline; filename=\"Tab\x09here.png\"", display_rendered);
     }
 
+    #[test]
+    fn test_display_name() {
+        let as_string = "form-data; name=\"field name\"; filename=\"a.txt\"";
+        let a = HeaderValue::from_static(as_string);
+        let a: ContentDisposition = ContentDisposition::from_raw(&a).unwrap();
+        assert_eq!(a.get_name(), Some("field name"));
+        let display_rendered = format!("{}", a);
+        assert_eq!(as_string, display_rendered);
+
+        let a: ContentDisposition = ContentDisposition {
+            disposition: DispositionType::FormData,
+            parameters: vec![DispositionParam::Name(String::from("a\"b"))],
+        };
+        let display_rendered = format!("{}", a);
+        assert_eq!("form-data; name=\"a\\\"b\"", display_rendered);
+    }
+
     #[test]
     fn test_display_control_characters() {
         /* let a = "attachment; filename=\"carriage\rreturn.png\"";
@@ -32,6 +32,7 @@ use crate::{error::ParseError, HttpMessage};
 
 mod as_name;
 mod common;
+mod content_disposition;
 mod into_pair;
 mod into_value;
 pub mod map;
@@ -43,9 +44,10 @@ pub use self::{
     // re-export list is explicit so that any updates to `http` do not conflict with this set
     common::{
         CACHE_STATUS, CDN_CACHE_CONTROL, CLEAR_SITE_DATA, CROSS_ORIGIN_EMBEDDER_POLICY,
-        CROSS_ORIGIN_OPENER_POLICY, CROSS_ORIGIN_RESOURCE_POLICY, PERMISSIONS_POLICY,
+        CROSS_ORIGIN_OPENER_POLICY, CROSS_ORIGIN_RESOURCE_POLICY, PERMISSIONS_POLICY, TRACEPARENT,
         X_FORWARDED_FOR, X_FORWARDED_HOST, X_FORWARDED_PROTO,
     },
+    content_disposition::{ContentDisposition, DispositionParam, DispositionType},
     into_pair::TryIntoHeaderPair,
     into_value::TryIntoHeaderValue,
     map::HeaderMap,
@@ -5,6 +5,7 @@
 //! | Feature             | Functionality                               |
 //! | ------------------- | ------------------------------------------- |
 //! | `http2`             | HTTP/2 support via [h2].                    |
+//! | `http3`             | HTTP/3 support via [h3] and [quinn].        |
 //! | `openssl`           | TLS support via [OpenSSL].                  |
 //! | `rustls-0_20`       | TLS support via rustls 0.20.                |
 //! | `rustls-0_21`       | TLS support via rustls 0.21.                |
@@ -16,6 +17,8 @@
 //! | `trust-dns`         | Use [trust-dns] as the client DNS resolver. |
 //!
 //! [h2]: https://crates.io/crates/h2
+//! [h3]: https://crates.io/crates/h3
+//! [quinn]: https://crates.io/crates/quinn
 //! [OpenSSL]: https://crates.io/crates/openssl
 //! [rustls]: https://crates.io/crates/rustls
 //! [trust-dns]: https://crates.io/crates/trust-dns
@@ -42,6 +45,8 @@ mod extensions;
 pub mod h1;
 #[cfg(feature = "http2")]
 pub mod h2;
+#[cfg(feature = "http3")]
+pub mod h3;
 pub mod header;
 mod helpers;
 mod http_message;
@@ -50,10 +55,12 @@ mod message;
 #[cfg(test)]
 mod notify_on_drop;
 mod payload;
+pub mod proxy_protocol;
 mod requests;
 mod responses;
 mod service;
 pub mod test;
+pub mod trace;
 #[cfg(feature = "ws")]
 pub mod ws;
 
@@ -105,4 +112,9 @@ impl OnConnectData {
 
         Self(ext)
     }
+
+    /// Adds a value to the connection data.
+    pub(crate) fn insert<T: 'static>(&mut self, val: T) {
+        self.0.get_or_insert_with(Extensions::default).insert(val);
+    }
 }
@@ -18,12 +18,13 @@ pub enum ConnectionType {
 bitflags! {
     #[derive(Debug, Clone, Copy)]
     pub(crate) struct Flags: u8 {
-        const CLOSE       = 0b0000_0001;
-        const KEEP_ALIVE  = 0b0000_0010;
-        const UPGRADE     = 0b0000_0100;
-        const EXPECT      = 0b0000_1000;
-        const NO_CHUNKING = 0b0001_0000;
-        const CAMEL_CASE  = 0b0010_0000;
+        const CLOSE         = 0b0000_0001;
+        const KEEP_ALIVE    = 0b0000_0010;
+        const UPGRADE       = 0b0000_0100;
+        const EXPECT        = 0b0000_1000;
+        const NO_CHUNKING   = 0b0001_0000;
+        const CAMEL_CASE    = 0b0010_0000;
+        const ABSOLUTE_FORM = 0b0100_0000;
     }
 }
 
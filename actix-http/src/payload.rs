@@ -8,7 +8,7 @@ use bytes::Bytes;
 use futures_core::Stream;
 use pin_project_lite::pin_project;
 
-use crate::error::PayloadError;
+use crate::{error::PayloadError, header::HeaderMap};
 
 /// A boxed payload stream.
 pub type BoxedPayloadStream = Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>>;
@@ -73,6 +73,25 @@ impl<S> Payload<S> {
     pub fn take(&mut self) -> Payload<S> {
         mem::replace(self, Payload::None)
     }
+
+    /// Attempt to pull out the trailer fields sent after the payload.
+    ///
+    /// Trailers can be sent after chunked HTTP/1.1 payloads and after HTTP/2 payloads. Resolves
+    /// once all payload data has been read, with `None` if no trailers were sent or the payload
+    /// type does not support them.
+    pub fn poll_trailers(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, PayloadError>> {
+        match self {
+            Payload::H1 { payload } => payload.poll_trailers(cx),
+
+            #[cfg(feature = "http2")]
+            Payload::H2 { payload } => payload.poll_trailers(cx),
+
+            Payload::None | Payload::Stream { .. } => Poll::Ready(Ok(None)),
+        }
+    }
 }
 
 impl<S> Stream for Payload<S>
@@ -0,0 +1,500 @@
+//! PROXY protocol (v1 and v2) support.
+//!
+//! Proxies and load balancers that forward TCP connections can use the [PROXY protocol] to send
+//! the original client and destination addresses at the start of each connection. When enabled
+//! using [`HttpServiceBuilder::proxy_protocol()`](crate::HttpServiceBuilder::proxy_protocol), the
+//! header is read before the TLS handshake or HTTP dispatch; the client address it carries is used
+//! as the connection's peer address and the parsed [`ProxyHeader`] is added to the connection data.
+//!
+//! [PROXY protocol]: https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt
+
+use std::{
+    future::poll_fn,
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+};
+
+use actix_codec::{AsyncRead, ReadBuf};
+use actix_rt::net::TcpStream;
+use bytes::Bytes;
+
+use crate::ServiceConfig;
+
+/// Signature that starts every PROXY protocol v2 header.
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// Prefix of every PROXY protocol v1 header.
+const V1_PREFIX: &[u8; 6] = b"PROXY ";
+
+/// Maximum length of a PROXY protocol v1 header, including the trailing CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// PROXY protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyVersion {
+    /// Human-readable version 1.
+    V1,
+
+    /// Binary version 2.
+    V2,
+}
+
+/// A PROXY protocol header received at the start of a connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyHeader {
+    version: ProxyVersion,
+    source: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    tlvs: Vec<Tlv>,
+}
+
+impl ProxyHeader {
+    /// Returns the protocol version of the header.
+    pub fn version(&self) -> ProxyVersion {
+        self.version
+    }
+
+    /// Returns the address of the client that connected to the proxy.
+    ///
+    /// Returns `None` if the proxy did not relay addresses, such as for health checks (v2 `LOCAL`
+    /// command or v1 `UNKNOWN` protocol) or for non-IP connections.
+    pub fn source(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
+    /// Returns the address that the client connected to on the proxy.
+    ///
+    /// Returns `None` in the same cases as [`source()`](Self::source).
+    pub fn destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    /// Returns the type-length-value fields of a v2 header.
+    pub fn tlvs(&self) -> &[Tlv] {
+        &self.tlvs
+    }
+
+    /// Returns the value of the first TLV field of the given type.
+    pub fn tlv(&self, kind: u8) -> Option<&[u8]> {
+        self.tlvs
+            .iter()
+            .find(|tlv| tlv.kind == kind)
+            .map(|tlv| tlv.value.as_ref())
+    }
+}
+
+/// A type-length-value field of a PROXY protocol v2 header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tlv {
+    kind: u8,
+    value: Bytes,
+}
+
+impl Tlv {
+    /// Application-Layer Protocol Negotiation (ALPN) protocol used by the client.
+    pub const ALPN: u8 = 0x01;
+
+    /// Host name sent by the client using Server Name Indication (SNI).
+    pub const AUTHORITY: u8 = 0x02;
+
+    /// CRC32c checksum of the header.
+    pub const CRC32C: u8 = 0x03;
+
+    /// Padding.
+    pub const NOOP: u8 = 0x04;
+
+    /// Opaque connection identifier generated by the proxy.
+    pub const UNIQUE_ID: u8 = 0x05;
+
+    /// Information about the TLS connection between the client and the proxy.
+    pub const SSL: u8 = 0x20;
+
+    /// Network namespace of the proxy connection.
+    pub const NETNS: u8 = 0x30;
+
+    /// Returns the type of the field.
+    pub fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    /// Returns the value of the field.
+    pub fn value(&self) -> &Bytes {
+        &self.value
+    }
+}
+
+/// Reads a PROXY protocol v1 or v2 header from the start of `io`.
+///
+/// Reads exactly the header's bytes so that the remainder of the stream can be handed to a TLS
+/// acceptor or HTTP dispatcher. Returns an [`InvalidData`](io::ErrorKind::InvalidData) error if
+/// the stream does not start with a valid header.
+pub async fn read_header<T: AsyncRead + Unpin>(io: &mut T) -> io::Result<ProxyHeader> {
+    let mut buf = vec![0; V1_PREFIX.len()];
+    read_exact(io, &mut buf).await?;
+
+    if buf == V1_PREFIX {
+        // v1 headers have no length prefix; read byte-wise to avoid consuming the request
+        while !buf.ends_with(b"\r\n") {
+            if buf.len() == V1_MAX_LEN {
+                return Err(invalid("PROXY protocol v1 header is too long"));
+            }
+
+            let mut byte = [0];
+            read_exact(io, &mut byte).await?;
+            buf.push(byte[0]);
+        }
+
+        parse_v1(&buf)
+    } else if V2_SIGNATURE.starts_with(&buf) {
+        buf.resize(16, 0);
+        read_exact(io, &mut buf[V1_PREFIX.len()..]).await?;
+
+        let len = u16::from_be_bytes([buf[14], buf[15]]) as usize;
+        buf.resize(16 + len, 0);
+        read_exact(io, &mut buf[16..]).await?;
+
+        parse_v2(&buf)
+    } else {
+        Err(invalid(
+            "connection did not start with a PROXY protocol header",
+        ))
+    }
+}
+
+/// Reads the PROXY protocol header from a new connection if enabled in `cfg`.
+///
+/// Returns the connection's peer address, taken from the header when it relays one, along with
+/// the header.
+pub(crate) async fn accept(
+    io: &mut TcpStream,
+    cfg: &ServiceConfig,
+) -> io::Result<(Option<SocketAddr>, Option<ProxyHeader>)> {
+    let peer_addr = io.peer_addr().ok();
+
+    if !cfg.proxy_protocol() {
+        return Ok((peer_addr, None));
+    }
+
+    let header = match cfg.client_request_deadline() {
+        Some(deadline) => actix_rt::time::timeout(deadline - cfg.now(), read_header(io))
+            .await
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "timed out reading PROXY protocol header",
+                )
+            })??,
+        None => read_header(io).await?,
+    };
+
+    Ok((header.source.or(peer_addr), Some(header)))
+}
+
+fn parse_v1(buf: &[u8]) -> io::Result<ProxyHeader> {
+    let line = std::str::from_utf8(&buf[V1_PREFIX.len()..buf.len() - 2])
+        .map_err(|_| invalid("PROXY protocol v1 header is not valid UTF-8"))?;
+
+    let mut parts = line.split(' ');
+
+    let (source, destination) = match parts.next() {
+        Some("TCP4") | Some("TCP6") => {
+            let mut next = || {
+                parts
+                    .next()
+                    .ok_or_else(|| invalid("PROXY protocol v1 header is missing addresses"))
+            };
+
+            let (src_ip, dst_ip, src_port, dst_port) = (next()?, next()?, next()?, next()?);
+
+            let addr = |ip: &str, port: &str| -> io::Result<SocketAddr> {
+                let ip = ip
+                    .parse()
+                    .map_err(|_| invalid("invalid address in PROXY protocol v1 header"))?;
+                let port = port
+                    .parse()
+                    .map_err(|_| invalid("invalid port in PROXY protocol v1 header"))?;
+                Ok(SocketAddr::new(ip, port))
+            };
+
+            (Some(addr(src_ip, src_port)?), Some(addr(dst_ip, dst_port)?))
+        }
+
+        // remainder of the line is ignored
+        Some("UNKNOWN") => (None, None),
+
+        _ => return Err(invalid("unsupported protocol in PROXY protocol v1 header")),
+    };
+
+    Ok(ProxyHeader {
+        version: ProxyVersion::V1,
+        source,
+        destination,
+        tlvs: Vec::new(),
+    })
+}
+
+fn parse_v2(buf: &[u8]) -> io::Result<ProxyHeader> {
+    if &buf[..12] != V2_SIGNATURE {
+        return Err(invalid("invalid PROXY protocol v2 signature"));
+    }
+
+    let version = buf[12] >> 4;
+    let command = buf[12] & 0x0F;
+    let family = buf[13] >> 4;
+
+    if version != 2 {
+        return Err(invalid("unsupported PROXY protocol version"));
+    }
+
+    let payload = &buf[16..];
+
+    let (addrs_len, addrs) = match family {
+        // AF_INET
+        0x1 if payload.len() >= 12 => {
+            let ip = |at: usize| {
+                Ipv4Addr::from([
+                    payload[at],
+                    payload[at + 1],
+                    payload[at + 2],
+                    payload[at + 3],
+                ])
+            };
+            let port = |at: usize| u16::from_be_bytes([payload[at], payload[at + 1]]);
+
+            let src = SocketAddr::new(ip(0).into(), port(8));
+            let dst = SocketAddr::new(ip(4).into(), port(10));
+            (12, Some((src, dst)))
+        }
+
+        // AF_INET6
+        0x2 if payload.len() >= 36 => {
+            let ip = |at: usize| {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(&payload[at..at + 16]);
+                Ipv6Addr::from(octets)
+            };
+            let port = |at: usize| u16::from_be_bytes([payload[at], payload[at + 1]]);
+
+            let src = SocketAddr::new(ip(0).into(), port(32));
+            let dst = SocketAddr::new(ip(16).into(), port(34));
+            (36, Some((src, dst)))
+        }
+
+        // AF_UNIX
+        0x3 if payload.len() >= 216 => (216, None),
+
+        // AF_UNSPEC
+        0x0 => (0, None),
+
+        0x1..=0x3 => return Err(invalid("truncated addresses in PROXY protocol v2 header")),
+
+        _ => {
+            return Err(invalid(
+                "unsupported address family in PROXY protocol v2 header",
+            ))
+        }
+    };
+
+    let mut tlvs = Vec::new();
+    let mut rest = &payload[addrs_len..];
+
+    while !rest.is_empty() {
+        if rest.len() < 3 {
+            return Err(invalid("truncated TLV in PROXY protocol v2 header"));
+        }
+
+        let len = u16::from_be_bytes([rest[1], rest[2]]) as usize;
+
+        if rest.len() < 3 + len {
+            return Err(invalid("truncated TLV in PROXY protocol v2 header"));
+        }
+
+        tlvs.push(Tlv {
+            kind: rest[0],
+            value: Bytes::copy_from_slice(&rest[3..3 + len]),
+        });
+
+        rest = &rest[3 + len..];
+    }
+
+    let (source, destination) = match (command, addrs) {
+        // LOCAL: connection established by the proxy itself; addresses are ignored
+        (0x0, _) => (None, None),
+        (0x1, Some((src, dst))) => (Some(src), Some(dst)),
+        (0x1, None) => (None, None),
+        _ => return Err(invalid("unsupported PROXY protocol v2 command")),
+    };
+
+    Ok(ProxyHeader {
+        version: ProxyVersion::V2,
+        source,
+        destination,
+        tlvs,
+    })
+}
+
+async fn read_exact<T: AsyncRead + Unpin>(io: &mut T, buf: &mut [u8]) -> io::Result<()> {
+    let mut buf = ReadBuf::new(buf);
+
+    while buf.remaining() > 0 {
+        let filled = buf.filled().len();
+        poll_fn(|cx| Pin::new(&mut *io).poll_read(cx, &mut buf)).await?;
+
+        if buf.filled().len() == filled {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(mut input: &[u8]) -> (io::Result<ProxyHeader>, &[u8]) {
+        let res = read_header(&mut input).await;
+        (res, input)
+    }
+
+    #[actix_rt::test]
+    async fn v1() {
+        let (res, rest) = read(b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\nGET /").await;
+        let header = res.unwrap();
+        assert_eq!(header.version(), ProxyVersion::V1);
+        assert_eq!(header.source(), Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(
+            header.destination(),
+            Some("198.51.100.1:443".parse().unwrap())
+        );
+        assert!(header.tlvs().is_empty());
+        assert_eq!(rest, b"GET /");
+
+        let (res, _) = read(b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\n").await;
+        let header = res.unwrap();
+        assert_eq!(
+            header.source(),
+            Some("[2001:db8::1]:56324".parse().unwrap())
+        );
+        assert_eq!(
+            header.destination(),
+            Some("[2001:db8::2]:443".parse().unwrap())
+        );
+
+        let (res, rest) = read(b"PROXY UNKNOWN ffff::1 ffff::2 1 2\r\nGET /").await;
+        let header = res.unwrap();
+        assert_eq!(header.source(), None);
+        assert_eq!(header.destination(), None);
+        assert_eq!(rest, b"GET /");
+    }
+
+    #[actix_rt::test]
+    async fn v1_invalid() {
+        for input in [
+            &b"GET / HTTP/1.1\r\n\r\n"[..],
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 56324\r\n",
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 99999\r\n",
+            b"PROXY TCP4 example.com 198.51.100.1 56324 443\r\n",
+            b"PROXY UDP4 192.0.2.1 198.51.100.1 56324 443\r\n",
+            b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443",
+        ] {
+            read(input).await.0.unwrap_err();
+        }
+
+        let mut long = b"PROXY UNKNOWN ".to_vec();
+        long.resize(200, b'a');
+        long.extend_from_slice(b"\r\n");
+        read(&long).await.0.unwrap_err();
+    }
+
+    fn v2_header(ver_cmd: u8, family: u8, payload: &[u8]) -> Vec<u8> {
+        let mut buf = V2_SIGNATURE.to_vec();
+        buf.push(ver_cmd);
+        buf.push(family);
+        buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[actix_rt::test]
+    async fn v2() {
+        let mut payload = vec![192, 0, 2, 1, 198, 51, 100, 1];
+        payload.extend_from_slice(&56324u16.to_be_bytes());
+        payload.extend_from_slice(&443u16.to_be_bytes());
+        payload.extend_from_slice(&[Tlv::AUTHORITY, 0, 11]);
+        payload.extend_from_slice(b"example.com");
+        payload.extend_from_slice(&[Tlv::NOOP, 0, 0]);
+
+        let mut input = v2_header(0x21, 0x11, &payload);
+        input.extend_from_slice(b"GET /");
+
+        let (res, rest) = read(&input).await;
+        let header = res.unwrap();
+        assert_eq!(header.version(), ProxyVersion::V2);
+        assert_eq!(header.source(), Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(
+            header.destination(),
+            Some("198.51.100.1:443".parse().unwrap())
+        );
+        assert_eq!(header.tlvs().len(), 2);
+        assert_eq!(header.tlv(Tlv::AUTHORITY), Some(&b"example.com"[..]));
+        assert_eq!(header.tlv(Tlv::NOOP), Some(&b""[..]));
+        assert_eq!(header.tlv(Tlv::ALPN), None);
+        assert_eq!(rest, b"GET /");
+
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        payload.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        payload.extend_from_slice(&56324u16.to_be_bytes());
+        payload.extend_from_slice(&443u16.to_be_bytes());
+
+        let (res, _) = read(&v2_header(0x21, 0x21, &payload)).await;
+        let header = res.unwrap();
+        assert_eq!(
+            header.source(),
+            Some("[2001:db8::1]:56324".parse().unwrap())
+        );
+        assert_eq!(
+            header.destination(),
+            Some("[2001:db8::2]:443".parse().unwrap())
+        );
+
+        // LOCAL command ignores addresses
+        let (res, _) = read(&v2_header(0x20, 0x11, &[0; 12])).await;
+        assert_eq!(res.unwrap().source(), None);
+
+        let (res, _) = read(&v2_header(0x21, 0x00, &[])).await;
+        assert_eq!(res.unwrap().source(), None);
+    }
+
+    #[actix_rt::test]
+    async fn v2_invalid() {
+        // version 1 in binary header
+        read(&v2_header(0x11, 0x11, &[0; 12])).await.0.unwrap_err();
+
+        // unknown command
+        read(&v2_header(0x22, 0x11, &[0; 12])).await.0.unwrap_err();
+
+        // truncated addresses
+        read(&v2_header(0x21, 0x11, &[0; 8])).await.0.unwrap_err();
+
+        // truncated TLV
+        let mut payload = vec![0; 12];
+        payload.extend_from_slice(&[Tlv::AUTHORITY, 0, 11, b'a']);
+        read(&v2_header(0x21, 0x11, &payload)).await.0.unwrap_err();
+
+        // truncated stream
+        let input = v2_header(0x21, 0x11, &[0; 12]);
+        read(&input[..20]).await.0.unwrap_err();
+
+        // bad signature
+        let mut input = v2_header(0x21, 0x11, &[0; 12]);
+        input[8] = b'X';
+        read(&input).await.0.unwrap_err();
+    }
+}
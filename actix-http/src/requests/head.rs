@@ -78,6 +78,27 @@ impl RequestHead {
         }
     }
 
+    /// Is to send the request target in absolute form.
+    /// Default is `false`
+    #[inline]
+    pub fn absolute_form(&self) -> bool {
+        self.flags.contains(Flags::ABSOLUTE_FORM)
+    }
+
+    /// Set `true` to send the request target in absolute form (e.g., `http://example.com/path`)
+    /// instead of origin form (e.g., `/path`), as is required when sending requests to a forward
+    /// proxy.
+    ///
+    /// Only applies to HTTP/1.x requests whose URI has a scheme and authority.
+    #[inline]
+    pub fn set_absolute_form(&mut self, val: bool) {
+        if val {
+            self.flags.insert(Flags::ABSOLUTE_FORM);
+        } else {
+            self.flags.remove(Flags::ABSOLUTE_FORM);
+        }
+    }
+
     #[inline]
     /// Set connection type of the message
     pub fn set_connection_type(&mut self, ctype: ConnectionType) {
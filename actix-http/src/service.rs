@@ -11,7 +11,8 @@ use std::{
 use actix_codec::{AsyncRead, AsyncWrite, Framed};
 use actix_rt::net::TcpStream;
 use actix_service::{
-    fn_service, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt as _,
+    apply_fn_factory, fn_service, IntoServiceFactory, Service, ServiceFactory,
+    ServiceFactoryExt as _,
 };
 use futures_core::{future::LocalBoxFuture, ready};
 use pin_project_lite::pin_project;
@@ -21,7 +22,9 @@ use crate::{
     body::{BoxBody, MessageBody},
     builder::HttpServiceBuilder,
     error::DispatchError,
-    h1, ConnectCallback, OnConnectData, Protocol, Request, Response, ServiceConfig,
+    h1,
+    proxy_protocol::{self, ProxyHeader},
+    ConnectCallback, OnConnectData, Protocol, Request, Response, ServiceConfig,
 };
 
 /// A [`ServiceFactory`] for HTTP/1.1 and HTTP/2 connections.
@@ -202,11 +205,17 @@ where
         self,
     ) -> impl ServiceFactory<TcpStream, Config = (), Response = (), Error = DispatchError, InitError = ()>
     {
-        fn_service(|io: TcpStream| async {
-            let peer_addr = io.peer_addr().ok();
-            Ok((io, Protocol::Http1, peer_addr))
+        let cfg = self.cfg.clone();
+
+        fn_service(move |mut io: TcpStream| {
+            let cfg = cfg.clone();
+
+            async move {
+                let (peer_addr, proxy_header) = proxy_protocol::accept(&mut io, &cfg).await?;
+                Ok((io, Protocol::Http1, peer_addr, proxy_header))
+            }
         })
-        .and_then(self)
+        .and_then(self.with_proxy_header())
     }
 
     /// Creates TCP stream service from HTTP service that automatically selects HTTP/1.x or HTTP/2
@@ -216,27 +225,34 @@ where
         self,
     ) -> impl ServiceFactory<TcpStream, Config = (), Response = (), Error = DispatchError, InitError = ()>
     {
-        fn_service(move |io: TcpStream| async move {
-            // subset of HTTP/2 preface defined by RFC 9113 §3.4
-            // this subset was chosen to maximize likelihood that peeking only once will allow us to
-            // reliably determine version or else it should fallback to h1 and fail quickly if data
-            // on the wire is junk
-            const H2_PREFACE: &[u8] = b"PRI * HTTP/2";
+        let cfg = self.cfg.clone();
 
-            let mut buf = [0; 12];
+        fn_service(move |mut io: TcpStream| {
+            let cfg = cfg.clone();
 
-            io.peek(&mut buf).await?;
+            async move {
+                // subset of HTTP/2 preface defined by RFC 9113 §3.4
+                // this subset was chosen to maximize likelihood that peeking only once will allow
+                // us to reliably determine version or else it should fallback to h1 and fail
+                // quickly if data on the wire is junk
+                const H2_PREFACE: &[u8] = b"PRI * HTTP/2";
 
-            let proto = if buf == H2_PREFACE {
-                Protocol::Http2
-            } else {
-                Protocol::Http1
-            };
+                let (peer_addr, proxy_header) = proxy_protocol::accept(&mut io, &cfg).await?;
+
+                let mut buf = [0; 12];
 
-            let peer_addr = io.peer_addr().ok();
-            Ok((io, proto, peer_addr))
+                io.peek(&mut buf).await?;
+
+                let proto = if buf == H2_PREFACE {
+                    Protocol::Http2
+                } else {
+                    Protocol::Http1
+                };
+
+                Ok((io, proto, peer_addr, proxy_header))
+            }
         })
-        .and_then(self)
+        .and_then(self.with_proxy_header())
     }
 }
 
@@ -258,6 +274,52 @@ impl TlsAcceptorConfig {
     }
 }
 
+/// Reads the PROXY protocol header of new connections, if enabled in `cfg`, before passing them to
+/// a TLS acceptor.
+///
+/// Responds with the TLS stream along with the connection's peer address and PROXY header.
+#[cfg(feature = "__tls")]
+fn proxy_protocol_tls<A, E>(
+    cfg: ServiceConfig,
+    acceptor: A,
+) -> impl ServiceFactory<
+    TcpStream,
+    Config = (),
+    Response = (A::Response, Option<net::SocketAddr>, Option<ProxyHeader>),
+    Error = actix_tls::accept::TlsError<E, DispatchError>,
+    InitError = (),
+>
+where
+    A: ServiceFactory<
+        TcpStream,
+        Config = (),
+        Error = actix_tls::accept::TlsError<E, DispatchError>,
+        InitError = (),
+    >,
+    A::Service: 'static,
+{
+    use actix_tls::accept::TlsError;
+
+    fn_service(move |mut io: TcpStream| {
+        let cfg = cfg.clone();
+
+        async move {
+            let (peer_addr, proxy_header) = proxy_protocol::accept(&mut io, &cfg)
+                .await
+                .map_err(|err| TlsError::Service(DispatchError::Io(err)))?;
+
+            Ok((io, peer_addr, proxy_header))
+        }
+    })
+    .and_then(apply_fn_factory(
+        acceptor,
+        |(io, peer_addr, proxy_header), acceptor: &A::Service| {
+            let fut = acceptor.call(io);
+            async move { Ok((fut.await?, peer_addr, proxy_header)) }
+        },
+    ))
+}
+
 #[cfg(feature = "openssl")]
 mod openssl {
     use actix_service::ServiceFactoryExt as _;
@@ -328,12 +390,14 @@ mod openssl {
                 acceptor.set_handshake_timeout(handshake_timeout);
             }
 
-            acceptor
+            let acceptor = acceptor
                 .map_init_err(|_| {
                     unreachable!("TLS acceptor service factory does not error on init")
                 })
-                .map_err(TlsError::into_service_error)
-                .map(|io: TlsStream<TcpStream>| {
+                .map_err(TlsError::into_service_error);
+
+            proxy_protocol_tls(self.cfg.clone(), acceptor)
+                .map(|(io, peer_addr, proxy_header)| {
                     let proto = if let Some(protos) = io.ssl().selected_alpn_protocol() {
                         if protos.windows(2).any(|window| window == b"h2") {
                             Protocol::Http2
@@ -344,10 +408,9 @@ mod openssl {
                         Protocol::Http1
                     };
 
-                    let peer_addr = io.get_ref().peer_addr().ok();
-                    (io, proto, peer_addr)
+                    (io, proto, peer_addr, proxy_header)
                 })
-                .and_then(self.map_err(TlsError::Service))
+                .and_then(self.with_proxy_header().map_err(TlsError::Service))
         }
     }
 }
@@ -425,12 +488,14 @@ mod rustls_0_20 {
                 acceptor.set_handshake_timeout(handshake_timeout);
             }
 
-            acceptor
+            let acceptor = acceptor
                 .map_init_err(|_| {
                     unreachable!("TLS acceptor service factory does not error on init")
                 })
-                .map_err(TlsError::into_service_error)
-                .and_then(|io: TlsStream<TcpStream>| async {
+                .map_err(TlsError::into_service_error);
+
+            proxy_protocol_tls(self.cfg.clone(), acceptor)
+                .map(|(io, peer_addr, proxy_header)| {
                     let proto = if let Some(protos) = io.get_ref().1.alpn_protocol() {
                         if protos.windows(2).any(|window| window == b"h2") {
                             Protocol::Http2
@@ -440,10 +505,9 @@ mod rustls_0_20 {
                     } else {
                         Protocol::Http1
                     };
-                    let peer_addr = io.get_ref().0.peer_addr().ok();
-                    Ok((io, proto, peer_addr))
+                    (io, proto, peer_addr, proxy_header)
                 })
-                .and_then(self.map_err(TlsError::Service))
+                .and_then(self.with_proxy_header().map_err(TlsError::Service))
         }
     }
 }
@@ -521,12 +585,14 @@ mod rustls_0_21 {
                 acceptor.set_handshake_timeout(handshake_timeout);
             }
 
-            acceptor
+            let acceptor = acceptor
                 .map_init_err(|_| {
                     unreachable!("TLS acceptor service factory does not error on init")
                 })
-                .map_err(TlsError::into_service_error)
-                .and_then(|io: TlsStream<TcpStream>| async {
+                .map_err(TlsError::into_service_error);
+
+            proxy_protocol_tls(self.cfg.clone(), acceptor)
+                .map(|(io, peer_addr, proxy_header)| {
                     let proto = if let Some(protos) = io.get_ref().1.alpn_protocol() {
                         if protos.windows(2).any(|window| window == b"h2") {
                             Protocol::Http2
@@ -536,10 +602,9 @@ mod rustls_0_21 {
                     } else {
                         Protocol::Http1
                     };
-                    let peer_addr = io.get_ref().0.peer_addr().ok();
-                    Ok((io, proto, peer_addr))
+                    (io, proto, peer_addr, proxy_header)
                 })
-                .and_then(self.map_err(TlsError::Service))
+                .and_then(self.with_proxy_header().map_err(TlsError::Service))
         }
     }
 }
@@ -617,12 +682,14 @@ mod rustls_0_22 {
                 acceptor.set_handshake_timeout(handshake_timeout);
             }
 
-            acceptor
+            let acceptor = acceptor
                 .map_init_err(|_| {
                     unreachable!("TLS acceptor service factory does not error on init")
                 })
-                .map_err(TlsError::into_service_error)
-                .and_then(|io: TlsStream<TcpStream>| async {
+                .map_err(TlsError::into_service_error);
+
+            proxy_protocol_tls(self.cfg.clone(), acceptor)
+                .map(|(io, peer_addr, proxy_header)| {
                     let proto = if let Some(protos) = io.get_ref().1.alpn_protocol() {
                         if protos.windows(2).any(|window| window == b"h2") {
                             Protocol::Http2
@@ -632,10 +699,9 @@ mod rustls_0_22 {
                     } else {
                         Protocol::Http1
                     };
-                    let peer_addr = io.get_ref().0.peer_addr().ok();
-                    Ok((io, proto, peer_addr))
+                    (io, proto, peer_addr, proxy_header)
                 })
-                .and_then(self.map_err(TlsError::Service))
+                .and_then(self.with_proxy_header().map_err(TlsError::Service))
         }
     }
 }
@@ -713,12 +779,14 @@ mod rustls_0_23 {
                 acceptor.set_handshake_timeout(handshake_timeout);
             }
 
-            acceptor
+            let acceptor = acceptor
                 .map_init_err(|_| {
                     unreachable!("TLS acceptor service factory does not error on init")
                 })
-                .map_err(TlsError::into_service_error)
-                .and_then(|io: TlsStream<TcpStream>| async {
+                .map_err(TlsError::into_service_error);
+
+            proxy_protocol_tls(self.cfg.clone(), acceptor)
+                .map(|(io, peer_addr, proxy_header)| {
                     let proto = if let Some(protos) = io.get_ref().1.alpn_protocol() {
                         if protos.windows(2).any(|window| window == b"h2") {
                             Protocol::Http2
@@ -728,14 +796,57 @@ mod rustls_0_23 {
                     } else {
                         Protocol::Http1
                     };
-                    let peer_addr = io.get_ref().0.peer_addr().ok();
-                    Ok((io, proto, peer_addr))
+                    (io, proto, peer_addr, proxy_header)
                 })
-                .and_then(self.map_err(TlsError::Service))
+                .and_then(self.with_proxy_header().map_err(TlsError::Service))
         }
     }
 }
 
+impl<T, S, B, X, U> HttpService<T, S, B, X, U>
+where
+    T: AsyncRead + AsyncWrite + Unpin + 'static,
+
+    S: ServiceFactory<Request, Config = ()>,
+    S::Future: 'static,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::InitError: fmt::Debug,
+    S::Response: Into<Response<B>> + 'static,
+    <S::Service as Service<Request>>::Future: 'static,
+
+    B: MessageBody + 'static,
+
+    X: ServiceFactory<Request, Config = (), Response = Request>,
+    X::Future: 'static,
+    X::Error: Into<Response<BoxBody>>,
+    X::InitError: fmt::Debug,
+
+    U: ServiceFactory<(Request, Framed<T, h1::Codec>), Config = (), Response = ()>,
+    U::Future: 'static,
+    U::Error: fmt::Display + Into<Response<BoxBody>>,
+    U::InitError: fmt::Debug,
+{
+    /// Creates a service factory that also accepts the PROXY protocol header read from each
+    /// connection.
+    fn with_proxy_header(
+        self,
+    ) -> impl ServiceFactory<
+        (T, Protocol, Option<net::SocketAddr>, Option<ProxyHeader>),
+        Config = (),
+        Response = (),
+        Error = DispatchError,
+        InitError = (),
+    > {
+        apply_fn_factory(
+            self,
+            |(io, proto, peer_addr, proxy_header),
+             handler: &HttpServiceHandler<T, S::Service, B, X::Service, U::Service>| {
+                handler.dispatch(io, proto, peer_addr, proxy_header)
+            },
+        )
+    }
+}
+
 impl<T, S, B, X, U> ServiceFactory<(T, Protocol, Option<net::SocketAddr>)>
     for HttpService<T, S, B, X, U>
 where
@@ -871,8 +982,7 @@ impl<S, X, U> HttpFlow<S, X, U> {
     }
 }
 
-impl<T, S, B, X, U> Service<(T, Protocol, Option<net::SocketAddr>)>
-    for HttpServiceHandler<T, S, B, X, U>
+impl<T, S, B, X, U> HttpServiceHandler<T, S, B, X, U>
 where
     T: AsyncRead + AsyncWrite + Unpin,
 
@@ -889,19 +999,19 @@ where
     U: Service<(Request, Framed<T, h1::Codec>), Response = ()>,
     U::Error: fmt::Display + Into<Response<BoxBody>>,
 {
-    type Response = ();
-    type Error = DispatchError;
-    type Future = HttpServiceHandlerResponse<T, S, B, X, U>;
-
-    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self._poll_ready(cx).map_err(|err| {
-            error!("HTTP service readiness error: {:?}", err);
-            DispatchError::Service(err)
-        })
-    }
-
-    fn call(&self, (io, proto, peer_addr): (T, Protocol, Option<net::SocketAddr>)) -> Self::Future {
-        let conn_data = OnConnectData::from_io(&io, self.on_connect_ext.as_deref());
+    /// Starts dispatching a connection, adding its PROXY protocol header to the connection data.
+    pub(super) fn dispatch(
+        &self,
+        io: T,
+        proto: Protocol,
+        peer_addr: Option<net::SocketAddr>,
+        proxy_header: Option<ProxyHeader>,
+    ) -> HttpServiceHandlerResponse<T, S, B, X, U> {
+        let mut conn_data = OnConnectData::from_io(&io, self.on_connect_ext.as_deref());
+
+        if let Some(proxy_header) = proxy_header {
+            conn_data.insert(proxy_header);
+        }
 
         match proto {
             #[cfg(feature = "http2")]
@@ -939,6 +1049,40 @@ where
     }
 }
 
+impl<T, S, B, X, U> Service<(T, Protocol, Option<net::SocketAddr>)>
+    for HttpServiceHandler<T, S, B, X, U>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+
+    S: Service<Request>,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::Future: 'static,
+    S::Response: Into<Response<B>> + 'static,
+
+    B: MessageBody + 'static,
+
+    X: Service<Request, Response = Request>,
+    X::Error: Into<Response<BoxBody>>,
+
+    U: Service<(Request, Framed<T, h1::Codec>), Response = ()>,
+    U::Error: fmt::Display + Into<Response<BoxBody>>,
+{
+    type Response = ();
+    type Error = DispatchError;
+    type Future = HttpServiceHandlerResponse<T, S, B, X, U>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self._poll_ready(cx).map_err(|err| {
+            error!("HTTP service readiness error: {:?}", err);
+            DispatchError::Service(err)
+        })
+    }
+
+    fn call(&self, (io, proto, peer_addr): (T, Protocol, Option<net::SocketAddr>)) -> Self::Future {
+        self.dispatch(io, proto, peer_addr, None)
+    }
+}
+
 #[cfg(not(feature = "http2"))]
 pin_project! {
     #[project = StateProj]
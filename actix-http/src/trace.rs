@@ -0,0 +1,248 @@
+//! W3C Trace Context propagation.
+//!
+//! [`TraceContext`] identifies a span within a distributed trace and is carried between services
+//! in the [`traceparent`](crate::header::TRACEPARENT) header. Servers set the context of the request
+//! being handled as the [current](TraceContext::current) context so that clients can propagate it
+//! on outgoing requests.
+
+use std::{
+    cell::Cell,
+    convert::Infallible,
+    fmt,
+    str::FromStr,
+};
+
+use crate::{
+    error::ParseError,
+    header::{from_one_raw_str, Header, HeaderName, HeaderValue, TryIntoHeaderValue, TRACEPARENT},
+    HttpMessage,
+};
+
+thread_local! {
+    static CURRENT: Cell<Option<TraceContext>> = const { Cell::new(None) };
+}
+
+/// Trace flag indicating that the caller may have recorded trace data.
+const FLAG_SAMPLED: u8 = 0x01;
+
+/// Identifiers of a span within a distributed trace.
+///
+/// Formats as, and parses from, the value of a version `00` [`traceparent`] header.
+///
+/// # Examples
+/// ```
+/// use actix_http::trace::TraceContext;
+///
+/// let ctx: TraceContext = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(ctx.trace_id(), 0x4bf92f3577b34da6a3ce929d0e0e4736);
+///
+/// let child = ctx.child();
+/// assert_eq!(child.trace_id(), ctx.trace_id());
+/// assert_ne!(child.span_id(), ctx.span_id());
+/// ```
+///
+/// [`traceparent`]: https://www.w3.org/TR/trace-context/#traceparent-header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    trace_id: u128,
+    span_id: u64,
+    flags: u8,
+}
+
+impl TraceContext {
+    /// Starts a new sampled trace with random identifiers.
+    pub fn new_root() -> Self {
+        let trace_id = loop {
+            let id = rand::random::<u128>();
+
+            if id != 0 {
+                break id;
+            }
+        };
+
+        TraceContext {
+            trace_id,
+            span_id: random_span_id(),
+            flags: FLAG_SAMPLED,
+        }
+    }
+
+    /// Creates the context of a new span in the same trace, with this span as its parent.
+    pub fn child(&self) -> Self {
+        TraceContext {
+            span_id: random_span_id(),
+            ..*self
+        }
+    }
+
+    /// Returns the trace ID, shared by all spans in the trace.
+    pub fn trace_id(&self) -> u128 {
+        self.trace_id
+    }
+
+    /// Returns the span ID.
+    pub fn span_id(&self) -> u64 {
+        self.span_id
+    }
+
+    /// Returns true if the sampled flag is set, meaning that the caller may have recorded trace
+    /// data.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & FLAG_SAMPLED != 0
+    }
+
+    /// Returns the context of the request being handled on this thread, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT.with(Cell::get)
+    }
+
+    /// Runs `f` with this context set as the [current](Self::current) context.
+    ///
+    /// The previous context is restored afterwards, even if `f` panics.
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<TraceContext>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.with(|current| current.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|current| current.replace(Some(self))));
+        f()
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "00-{:032x}-{:016x}-{:02x}",
+            self.trace_id, self.span_id, self.flags
+        )
+    }
+}
+
+impl FromStr for TraceContext {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn hex<'a>(parts: &mut impl Iterator<Item = &'a str>, len: usize) -> Option<&'a str> {
+            parts
+                .next()
+                .filter(|part| part.len() == len)
+                .filter(|part| part.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+        }
+
+        let mut parts = s.split('-');
+
+        // future versions may append fields, which are ignored
+        let version = hex(&mut parts, 2).ok_or(ParseError::Header)?;
+        let trace_id = hex(&mut parts, 32).ok_or(ParseError::Header)?;
+        let span_id = hex(&mut parts, 16).ok_or(ParseError::Header)?;
+        let flags = hex(&mut parts, 2).ok_or(ParseError::Header)?;
+
+        if version == "ff" || (version == "00" && parts.next().is_some()) {
+            return Err(ParseError::Header);
+        }
+
+        let trace_id = u128::from_str_radix(trace_id, 16).map_err(|_| ParseError::Header)?;
+        let span_id = u64::from_str_radix(span_id, 16).map_err(|_| ParseError::Header)?;
+
+        // all-zero IDs are invalid
+        if trace_id == 0 || span_id == 0 {
+            return Err(ParseError::Header);
+        }
+
+        Ok(TraceContext {
+            trace_id,
+            span_id,
+            flags: u8::from_str_radix(flags, 16).map_err(|_| ParseError::Header)?,
+        })
+    }
+}
+
+impl TryIntoHeaderValue for TraceContext {
+    type Error = Infallible;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        Ok(HeaderValue::from_str(&self.to_string()).expect("traceparent value is valid"))
+    }
+}
+
+impl Header for TraceContext {
+    fn name() -> HeaderName {
+        TRACEPARENT
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        from_one_raw_str(msg.headers().get(Self::name()))
+    }
+}
+
+/// Returns a random, non-zero span ID.
+fn random_span_id() -> u64 {
+    loop {
+        let id = rand::random::<u64>();
+
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_format() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let ctx = header.parse::<TraceContext>().unwrap();
+        assert_eq!(ctx.trace_id(), 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(ctx.span_id(), 0x00f067aa0ba902b7);
+        assert!(ctx.is_sampled());
+        assert_eq!(ctx.to_string(), header);
+
+        // future versions may have additional fields
+        let ctx = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra"
+            .parse::<TraceContext>()
+            .unwrap();
+        assert!(!ctx.is_sampled());
+
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-+bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(invalid.parse::<TraceContext>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn current_scope() {
+        assert!(TraceContext::current().is_none());
+
+        let root = TraceContext::new_root();
+        assert!(root.is_sampled());
+
+        let child = root.child();
+        assert_eq!(child.trace_id(), root.trace_id());
+        assert_ne!(child.span_id(), root.span_id());
+
+        root.scope(|| {
+            assert_eq!(TraceContext::current(), Some(root));
+            child.scope(|| assert_eq!(TraceContext::current(), Some(child)));
+            assert_eq!(TraceContext::current(), Some(root));
+        });
+
+        assert!(TraceContext::current().is_none());
+    }
+}
@@ -5,6 +5,7 @@ use tokio_util::codec::{Decoder, Encoder};
 use tracing::error;
 
 use super::{
+    deflate::{DeflateConfig, DeflateContext},
     frame::Parser,
     proto::{CloseReason, OpCode},
     ProtocolError,
@@ -71,6 +72,7 @@ pub enum Item {
 pub struct Codec {
     flags: Flags,
     max_size: usize,
+    deflate: Option<DeflateContext>,
 }
 
 bitflags! {
@@ -79,6 +81,7 @@ bitflags! {
         const SERVER         = 0b0000_0001;
         const CONTINUATION   = 0b0000_0010;
         const W_CONTINUATION = 0b0000_0100;
+        const COMPRESSED     = 0b0000_1000;
     }
 }
 
@@ -88,6 +91,7 @@ impl Codec {
         Codec {
             max_size: 65_536,
             flags: Flags::SERVER,
+            deflate: None,
         }
     }
 
@@ -108,6 +112,93 @@ impl Codec {
         self.flags.remove(Flags::SERVER);
         self
     }
+
+    /// Enable `permessage-deflate` compression using negotiated parameters.
+    ///
+    /// The parameters must be the result of a successful negotiation with the peer; see
+    /// [`DeflateConfig::negotiate`] and [`DeflateConfig::accept`]. When enabled, all outgoing data
+    /// messages are compressed and incoming messages are decompressed, with the max frame size
+    /// also limiting the size of decompressed frames.
+    ///
+    /// Cloning a codec with compression enabled creates fresh compression contexts.
+    #[must_use = "This returns the a new Codec, without modifying the original."]
+    pub fn deflate(mut self, config: DeflateConfig) -> Self {
+        self.deflate = Some(DeflateContext::new(config));
+        self
+    }
+
+    /// Writes a data frame, compressing its payload if compression is enabled.
+    ///
+    /// `first` indicates the frame starts a new message.
+    fn write_data(
+        &mut self,
+        dst: &mut BytesMut,
+        data: &[u8],
+        op: OpCode,
+        first: bool,
+        fin: bool,
+    ) -> Result<(), ProtocolError> {
+        let server = self.flags.contains(Flags::SERVER);
+
+        match self.deflate {
+            Some(ref mut deflate) => {
+                let data = deflate.compress(data, fin, server)?;
+                Parser::write_message_with_rsv1(dst, data, op, fin, first, !server);
+            }
+            None => Parser::write_message(dst, data, op, fin, !server),
+        }
+
+        Ok(())
+    }
+
+    /// Decompresses the payload of a data frame when it belongs to a compressed message.
+    fn read_data(
+        &mut self,
+        finished: bool,
+        rsv1: bool,
+        opcode: OpCode,
+        payload: Option<BytesMut>,
+    ) -> Result<Option<BytesMut>, ProtocolError> {
+        let deflate = match self.deflate {
+            Some(ref mut deflate) => deflate,
+            // RSV1 bit is not validated when no extensions are negotiated
+            None => return Ok(payload),
+        };
+
+        let compressed = match opcode {
+            OpCode::Text | OpCode::Binary => {
+                if !finished {
+                    self.flags.set(Flags::COMPRESSED, rsv1);
+                }
+                rsv1
+            }
+            OpCode::Continue if !rsv1 => {
+                let compressed = self.flags.contains(Flags::COMPRESSED);
+                if finished {
+                    self.flags.remove(Flags::COMPRESSED);
+                }
+                compressed
+            }
+            _ if rsv1 => {
+                return Err(ProtocolError::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "RSV1 bit set on control or continuation frame",
+                )))
+            }
+            _ => false,
+        };
+
+        if !compressed {
+            return Ok(payload);
+        }
+
+        let payload = payload.as_deref().unwrap_or_default();
+        let server = self.flags.contains(Flags::SERVER);
+
+        deflate
+            .decompress(payload, finished, server, self.max_size)
+            .map(Some)
+    }
 }
 
 impl Default for Codec {
@@ -121,20 +212,8 @@ impl Encoder<Message> for Codec {
 
     fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Self::Error> {
         match item {
-            Message::Text(txt) => Parser::write_message(
-                dst,
-                txt,
-                OpCode::Text,
-                true,
-                !self.flags.contains(Flags::SERVER),
-            ),
-            Message::Binary(bin) => Parser::write_message(
-                dst,
-                bin,
-                OpCode::Binary,
-                true,
-                !self.flags.contains(Flags::SERVER),
-            ),
+            Message::Text(txt) => self.write_data(dst, txt.as_bytes(), OpCode::Text, true, true)?,
+            Message::Binary(bin) => self.write_data(dst, &bin, OpCode::Binary, true, true)?,
             Message::Ping(txt) => Parser::write_message(
                 dst,
                 txt,
@@ -158,13 +237,7 @@ impl Encoder<Message> for Codec {
                         return Err(ProtocolError::ContinuationStarted);
                     } else {
                         self.flags.insert(Flags::W_CONTINUATION);
-                        Parser::write_message(
-                            dst,
-                            &data[..],
-                            OpCode::Text,
-                            false,
-                            !self.flags.contains(Flags::SERVER),
-                        )
+                        self.write_data(dst, &data, OpCode::Text, true, false)?;
                     }
                 }
                 Item::FirstBinary(data) => {
@@ -172,24 +245,12 @@ impl Encoder<Message> for Codec {
                         return Err(ProtocolError::ContinuationStarted);
                     } else {
                         self.flags.insert(Flags::W_CONTINUATION);
-                        Parser::write_message(
-                            dst,
-                            &data[..],
-                            OpCode::Binary,
-                            false,
-                            !self.flags.contains(Flags::SERVER),
-                        )
+                        self.write_data(dst, &data, OpCode::Binary, true, false)?;
                     }
                 }
                 Item::Continue(data) => {
                     if self.flags.contains(Flags::W_CONTINUATION) {
-                        Parser::write_message(
-                            dst,
-                            &data[..],
-                            OpCode::Continue,
-                            false,
-                            !self.flags.contains(Flags::SERVER),
-                        )
+                        self.write_data(dst, &data, OpCode::Continue, false, false)?;
                     } else {
                         return Err(ProtocolError::ContinuationNotStarted);
                     }
@@ -197,13 +258,7 @@ impl Encoder<Message> for Codec {
                 Item::Last(data) => {
                     if self.flags.contains(Flags::W_CONTINUATION) {
                         self.flags.remove(Flags::W_CONTINUATION);
-                        Parser::write_message(
-                            dst,
-                            &data[..],
-                            OpCode::Continue,
-                            true,
-                            !self.flags.contains(Flags::SERVER),
-                        )
+                        self.write_data(dst, &data, OpCode::Continue, false, true)?;
                     } else {
                         return Err(ProtocolError::ContinuationNotStarted);
                     }
@@ -220,8 +275,10 @@ impl Decoder for Codec {
     type Error = ProtocolError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        match Parser::parse(src, self.flags.contains(Flags::SERVER), self.max_size) {
-            Ok(Some((finished, opcode, payload))) => {
+        match Parser::parse_with_rsv1(src, self.flags.contains(Flags::SERVER), self.max_size) {
+            Ok(Some((finished, rsv1, opcode, payload))) => {
+                let payload = self.read_data(finished, rsv1, opcode, payload)?;
+
                 // continuation is not supported
                 if !finished {
                     return match opcode {
@@ -300,3 +357,123 @@ impl Decoder for Codec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codecs(config: DeflateConfig) -> (Codec, Codec) {
+        (
+            Codec::new().deflate(config),
+            Codec::new().client_mode().deflate(config),
+        )
+    }
+
+    #[test]
+    fn deflate_messages() {
+        let (mut server, mut client) = codecs(DeflateConfig::new());
+        let mut buf = BytesMut::new();
+
+        for _ in 0..2 {
+            client
+                .encode(Message::Text("Hello, Hello, Hello".into()), &mut buf)
+                .unwrap();
+            // RSV1 bit is set on compressed messages
+            assert_eq!(buf[0] & 0x40, 0x40);
+            assert_eq!(
+                server.decode(&mut buf).unwrap().unwrap(),
+                Frame::Text(Bytes::from_static(b"Hello, Hello, Hello"))
+            );
+        }
+
+        server
+            .encode(Message::Binary(Bytes::from_static(b"binary")), &mut buf)
+            .unwrap();
+        assert_eq!(
+            client.decode(&mut buf).unwrap().unwrap(),
+            Frame::Binary(Bytes::from_static(b"binary"))
+        );
+
+        // control frames are not compressed
+        server
+            .encode(Message::Ping(Bytes::from_static(b"ping")), &mut buf)
+            .unwrap();
+        assert_eq!(buf[0] & 0x40, 0);
+        assert_eq!(
+            client.decode(&mut buf).unwrap().unwrap(),
+            Frame::Ping(Bytes::from_static(b"ping"))
+        );
+    }
+
+    #[test]
+    fn deflate_continuation() {
+        let (mut server, mut client) = codecs(DeflateConfig::new().client_no_context_takeover());
+        let mut buf = BytesMut::new();
+
+        client
+            .encode(
+                Message::Continuation(Item::FirstText(Bytes::from_static(b"first "))),
+                &mut buf,
+            )
+            .unwrap();
+        assert_eq!(buf[0] & 0x40, 0x40);
+        assert_eq!(
+            server.decode(&mut buf).unwrap().unwrap(),
+            Frame::Continuation(Item::FirstText(Bytes::from_static(b"first ")))
+        );
+
+        client
+            .encode(
+                Message::Continuation(Item::Continue(Bytes::from_static(b"second "))),
+                &mut buf,
+            )
+            .unwrap();
+        assert_eq!(buf[0] & 0x40, 0);
+        assert_eq!(
+            server.decode(&mut buf).unwrap().unwrap(),
+            Frame::Continuation(Item::Continue(Bytes::from_static(b"second ")))
+        );
+
+        client
+            .encode(
+                Message::Continuation(Item::Last(Bytes::from_static(b"last"))),
+                &mut buf,
+            )
+            .unwrap();
+        assert_eq!(
+            server.decode(&mut buf).unwrap().unwrap(),
+            Frame::Continuation(Item::Last(Bytes::from_static(b"last")))
+        );
+
+        client
+            .encode(Message::Text("after".into()), &mut buf)
+            .unwrap();
+        assert_eq!(
+            server.decode(&mut buf).unwrap().unwrap(),
+            Frame::Text(Bytes::from_static(b"after"))
+        );
+    }
+
+    #[test]
+    fn deflate_invalid_rsv1() {
+        let mut server = Codec::new().deflate(DeflateConfig::new());
+        let mut client = Codec::new().client_mode();
+        let mut buf = BytesMut::new();
+
+        client
+            .encode(Message::Ping(Bytes::from_static(b"ping")), &mut buf)
+            .unwrap();
+        buf[0] |= 0x40;
+        assert!(matches!(server.decode(&mut buf), Err(ProtocolError::Io(_))));
+
+        // uncompressed messages are still accepted
+        let mut server = Codec::new().deflate(DeflateConfig::new());
+        client
+            .encode(Message::Text("text".into()), &mut buf)
+            .unwrap();
+        assert_eq!(
+            server.decode(&mut buf).unwrap().unwrap(),
+            Frame::Text(Bytes::from_static(b"text"))
+        );
+    }
+}
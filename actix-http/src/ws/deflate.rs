@@ -0,0 +1,777 @@
+//! Per-message compression extension (RFC 7692).
+
+use std::{fmt, io};
+
+use bytes::{Bytes, BytesMut};
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+use http::header::{InvalidHeaderValue, SEC_WEBSOCKET_EXTENSIONS};
+
+use super::ProtocolError;
+use crate::header::{HeaderMap, HeaderValue, TryIntoHeaderValue};
+
+const EXTENSION_NAME: &str = "permessage-deflate";
+
+const SERVER_NO_CONTEXT_TAKEOVER: &str = "server_no_context_takeover";
+const CLIENT_NO_CONTEXT_TAKEOVER: &str = "client_no_context_takeover";
+const SERVER_MAX_WINDOW_BITS: &str = "server_max_window_bits";
+const CLIENT_MAX_WINDOW_BITS: &str = "client_max_window_bits";
+
+/// Bytes removed from the end of each compressed message and appended again before inflating.
+const DEFLATE_TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// Largest window size (LZ77 sliding window bits), used by default.
+const MAX_WINDOW_BITS: u8 = 15;
+
+/// Smallest window size a peer may be asked to use.
+const MIN_WINDOW_BITS: u8 = 8;
+
+/// Configuration and negotiated parameters of the `permessage-deflate` extension.
+///
+/// On the server, [`negotiate`](Self::negotiate) picks an acceptable offer from the client's
+/// `Sec-WebSocket-Extensions` request header and returns the agreed parameters, which are then
+/// sent back in the handshake response and passed to [`Codec::deflate`](super::Codec::deflate).
+/// On the client, [`accept`](Self::accept) validates the server's response to the offer produced
+/// from this configuration.
+///
+/// Window bits configured here are only requested from the peer, which limits the memory it has to
+/// keep for decompressing this endpoint's messages. Smaller windows requested by the peer are
+/// honoured by compressing at most that many bytes at a time without reference to earlier data.
+///
+/// # Examples
+/// ```
+/// use actix_http::ws::DeflateConfig;
+///
+/// let config = DeflateConfig::new().client_no_context_takeover();
+/// assert_eq!(
+///     config.to_string(),
+///     "permessage-deflate; client_no_context_takeover",
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeflateConfig {
+    server_no_context_takeover: bool,
+    client_no_context_takeover: bool,
+    server_max_window_bits: Option<u8>,
+    client_max_window_bits: Option<u8>,
+    compression_level: u32,
+}
+
+impl DeflateConfig {
+    /// Constructs new configuration with context takeover enabled on both sides and the default
+    /// compression level.
+    pub const fn new() -> Self {
+        DeflateConfig {
+            server_no_context_takeover: false,
+            client_no_context_takeover: false,
+            server_max_window_bits: None,
+            client_max_window_bits: None,
+            compression_level: 6,
+        }
+    }
+
+    /// Prevents the server from reusing the compression context between messages.
+    ///
+    /// This reduces the memory held by both endpoints per connection at the expense of
+    /// compression ratio.
+    pub fn server_no_context_takeover(mut self) -> Self {
+        self.server_no_context_takeover = true;
+        self
+    }
+
+    /// Prevents the client from reusing the compression context between messages.
+    pub fn client_no_context_takeover(mut self) -> Self {
+        self.client_no_context_takeover = true;
+        self
+    }
+
+    /// Requests that the server uses at most `bits` of LZ77 sliding window.
+    ///
+    /// Only meaningful on clients.
+    ///
+    /// # Panics
+    /// Panics if `bits` is not in the range `8..=15`.
+    pub fn server_max_window_bits(mut self, bits: u8) -> Self {
+        assert!(
+            (MIN_WINDOW_BITS..=MAX_WINDOW_BITS).contains(&bits),
+            "window bits must be between 8 and 15",
+        );
+        self.server_max_window_bits = Some(bits);
+        self
+    }
+
+    /// Requests that the client uses at most `bits` of LZ77 sliding window.
+    ///
+    /// On servers, this is only sent to clients that indicate support for it. On clients, this is
+    /// offered as a hint and the server may choose a smaller window.
+    ///
+    /// # Panics
+    /// Panics if `bits` is not in the range `8..=15`.
+    pub fn client_max_window_bits(mut self, bits: u8) -> Self {
+        assert!(
+            (MIN_WINDOW_BITS..=MAX_WINDOW_BITS).contains(&bits),
+            "window bits must be between 8 and 15",
+        );
+        self.client_max_window_bits = Some(bits);
+        self
+    }
+
+    /// Sets compression level used for outgoing messages, from 0 (none) to 9 (best).
+    ///
+    /// By default, level 6 is used.
+    pub fn compression_level(mut self, level: u32) -> Self {
+        self.compression_level = level.min(9);
+        self
+    }
+
+    /// Negotiates extension parameters with the offers in a client's request headers.
+    ///
+    /// Returns the agreed parameters for the first acceptable `permessage-deflate` offer or `None`
+    /// if the client did not offer the extension or no offer can be accepted.
+    pub fn negotiate(&self, headers: &HeaderMap) -> Option<DeflateConfig> {
+        headers
+            .get_all(SEC_WEBSOCKET_EXTENSIONS)
+            .filter_map(|hdr| hdr.to_str().ok())
+            .flat_map(|hdr| hdr.split(','))
+            .filter_map(Params::parse)
+            .find_map(|offer| self.accept_offer(&offer))
+    }
+
+    fn accept_offer(&self, offer: &Params) -> Option<DeflateConfig> {
+        let server_max_window_bits = match offer.server_max_window_bits {
+            Some(Some(bits)) => Some(bits),
+            Some(None) => return None,
+            None => None,
+        };
+
+        let client_max_window_bits =
+            match (offer.client_max_window_bits, self.client_max_window_bits) {
+                (Some(offered), Some(bits)) => Some(bits.min(offered.unwrap_or(MAX_WINDOW_BITS))),
+                (Some(Some(offered)), None) => Some(offered),
+                (_, _) => None,
+            };
+
+        Some(DeflateConfig {
+            server_no_context_takeover: offer.server_no_context_takeover
+                || self.server_no_context_takeover,
+            client_no_context_takeover: offer.client_no_context_takeover
+                || self.client_no_context_takeover,
+            server_max_window_bits,
+            client_max_window_bits,
+            compression_level: self.compression_level,
+        })
+    }
+
+    /// Validates a server's `Sec-WebSocket-Extensions` response to the offer made from this
+    /// configuration.
+    ///
+    /// Returns the agreed parameters, or `None` if the response can not be accepted; in which case
+    /// the client must fail the connection.
+    pub fn accept(&self, response: &HeaderValue) -> Option<DeflateConfig> {
+        let response = response.to_str().ok()?;
+
+        // only one extension was offered so only one may be accepted
+        if response.contains(',') {
+            return None;
+        }
+
+        let params = Params::parse(response)?;
+
+        let client_max_window_bits =
+            match (params.client_max_window_bits, self.client_max_window_bits) {
+                (Some(Some(bits)), Some(offered)) if bits > offered => return None,
+                (Some(Some(bits)), _) => Some(bits),
+                (Some(None), _) => return None,
+                (None, _) => None,
+            };
+
+        let server_max_window_bits = match params.server_max_window_bits {
+            Some(Some(bits)) => Some(bits),
+            Some(None) => return None,
+            None => None,
+        };
+
+        // server must confirm requested window bits, using at most the requested value
+        match (self.server_max_window_bits, server_max_window_bits) {
+            (Some(requested), Some(bits)) if bits > requested => return None,
+            (Some(_), None) => return None,
+            (_, _) => {}
+        }
+
+        if self.server_no_context_takeover && !params.server_no_context_takeover {
+            return None;
+        }
+
+        Some(DeflateConfig {
+            server_no_context_takeover: params.server_no_context_takeover,
+            client_no_context_takeover: params.client_no_context_takeover,
+            server_max_window_bits,
+            client_max_window_bits,
+            compression_level: self.compression_level,
+        })
+    }
+
+    /// Inserts the offer for this configuration into client request headers.
+    pub fn insert_offer(&self, headers: &mut HeaderMap) {
+        let mut offer = self.to_string();
+
+        // indicate that the server may limit the client's window
+        if self.client_max_window_bits.is_none() {
+            offer.push_str("; ");
+            offer.push_str(CLIENT_MAX_WINDOW_BITS);
+        }
+
+        headers.insert(
+            SEC_WEBSOCKET_EXTENSIONS,
+            HeaderValue::from_str(&offer).unwrap(),
+        );
+    }
+
+    /// Returns true if the local endpoint should reset its compressor after each message.
+    fn local_no_context_takeover(&self, server: bool) -> bool {
+        if server {
+            self.server_no_context_takeover
+        } else {
+            self.client_no_context_takeover
+        }
+    }
+
+    /// Returns true if the peer resets its compressor after each message.
+    fn remote_no_context_takeover(&self, server: bool) -> bool {
+        self.local_no_context_takeover(!server)
+    }
+
+    /// Returns the window bits the local endpoint may compress with.
+    fn local_max_window_bits(&self, server: bool) -> u8 {
+        let bits = if server {
+            self.server_max_window_bits
+        } else {
+            self.client_max_window_bits
+        };
+
+        bits.unwrap_or(MAX_WINDOW_BITS)
+    }
+}
+
+impl Default for DeflateConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for DeflateConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(EXTENSION_NAME)?;
+
+        if self.server_no_context_takeover {
+            write!(f, "; {}", SERVER_NO_CONTEXT_TAKEOVER)?;
+        }
+
+        if self.client_no_context_takeover {
+            write!(f, "; {}", CLIENT_NO_CONTEXT_TAKEOVER)?;
+        }
+
+        if let Some(bits) = self.server_max_window_bits {
+            write!(f, "; {}={}", SERVER_MAX_WINDOW_BITS, bits)?;
+        }
+
+        if let Some(bits) = self.client_max_window_bits {
+            write!(f, "; {}={}", CLIENT_MAX_WINDOW_BITS, bits)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryIntoHeaderValue for DeflateConfig {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+/// Parameters of a single `permessage-deflate` extension offer or response.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Params {
+    server_no_context_takeover: bool,
+    client_no_context_takeover: bool,
+
+    /// Outer option is presence of the parameter, inner option is its value.
+    server_max_window_bits: Option<Option<u8>>,
+    client_max_window_bits: Option<Option<u8>>,
+}
+
+impl Params {
+    /// Parses an extension element, returning `None` if it is not a valid `permessage-deflate`
+    /// element.
+    fn parse(ext: &str) -> Option<Params> {
+        let mut parts = ext.split(';').map(str::trim);
+
+        if !parts.next()?.eq_ignore_ascii_case(EXTENSION_NAME) {
+            return None;
+        }
+
+        let mut params = Params::default();
+
+        for param in parts {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (param, None),
+            };
+
+            match name {
+                SERVER_NO_CONTEXT_TAKEOVER if value.is_none() => {
+                    if params.server_no_context_takeover {
+                        return None;
+                    }
+                    params.server_no_context_takeover = true;
+                }
+
+                CLIENT_NO_CONTEXT_TAKEOVER if value.is_none() => {
+                    if params.client_no_context_takeover {
+                        return None;
+                    }
+                    params.client_no_context_takeover = true;
+                }
+
+                SERVER_MAX_WINDOW_BITS => {
+                    if params.server_max_window_bits.is_some() {
+                        return None;
+                    }
+                    params.server_max_window_bits = Some(parse_window_bits(value)?);
+                }
+
+                CLIENT_MAX_WINDOW_BITS => {
+                    if params.client_max_window_bits.is_some() {
+                        return None;
+                    }
+                    params.client_max_window_bits = Some(parse_window_bits(value)?);
+                }
+
+                _ => return None,
+            }
+        }
+
+        Some(params)
+    }
+}
+
+/// Parses optional window bits parameter value.
+///
+/// Returns `None` if value is present but invalid.
+fn parse_window_bits(value: Option<&str>) -> Option<Option<u8>> {
+    match value {
+        None => Some(None),
+        Some(value) => match value.parse::<u8>() {
+            Ok(bits) if (MIN_WINDOW_BITS..=MAX_WINDOW_BITS).contains(&bits) => Some(Some(bits)),
+            _ => None,
+        },
+    }
+}
+
+/// Compression state of a WebSocket codec with `permessage-deflate` enabled.
+///
+/// Cloning creates fresh compression contexts with the same parameters.
+pub(super) struct DeflateContext {
+    config: DeflateConfig,
+    compress: Compress,
+    decompress: Decompress,
+}
+
+impl DeflateContext {
+    pub(super) fn new(config: DeflateConfig) -> Self {
+        DeflateContext {
+            config,
+            compress: Compress::new(Compression::new(config.compression_level), false),
+            decompress: Decompress::new(false),
+        }
+    }
+
+    /// Compresses (part of) an outgoing message.
+    ///
+    /// Set `fin` for the last part of a message.
+    pub(super) fn compress(
+        &mut self,
+        data: &[u8],
+        fin: bool,
+        server: bool,
+    ) -> Result<Bytes, ProtocolError> {
+        let mut out = Vec::with_capacity(data.len() / 2 + 64);
+        let window_bits = self.config.local_max_window_bits(server);
+
+        if window_bits < MAX_WINDOW_BITS {
+            // the compressor always uses the full window; keep back-references within the agreed
+            // window by resetting its dictionary after every window-sized block of input
+            for block in data.chunks(1 << window_bits) {
+                self.deflate(block, &mut out, FlushCompress::Full)?;
+            }
+        } else {
+            self.deflate(data, &mut out, FlushCompress::Sync)?;
+        }
+
+        if fin {
+            if out.ends_with(&DEFLATE_TRAILER) {
+                out.truncate(out.len() - DEFLATE_TRAILER.len());
+            } else if out.is_empty() {
+                // nothing is emitted when flushing an already flushed compressor; an empty stored
+                // block header completes the trailer appended by the peer (RFC 7692 §7.2.3.6)
+                out.push(0x00);
+            }
+
+            if self.config.local_no_context_takeover(server) {
+                self.compress.reset();
+            }
+        }
+
+        Ok(Bytes::from(out))
+    }
+
+    fn deflate(
+        &mut self,
+        data: &[u8],
+        out: &mut Vec<u8>,
+        flush: FlushCompress,
+    ) -> Result<(), ProtocolError> {
+        let start = self.compress.total_in();
+
+        loop {
+            let consumed = (self.compress.total_in() - start) as usize;
+
+            if out.len() == out.capacity() {
+                out.reserve(out.capacity().max(64));
+            }
+
+            self.compress
+                .compress_vec(&data[consumed..], out, flush)
+                .map_err(invalid_data)?;
+
+            let consumed = (self.compress.total_in() - start) as usize;
+
+            // flush has completed once all input is consumed and output buffer has room left
+            if consumed == data.len() && out.len() < out.capacity() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decompresses (part of) an incoming message, failing if the output exceeds `max_size`.
+    ///
+    /// Set `fin` for the last part of a message.
+    pub(super) fn decompress(
+        &mut self,
+        data: &[u8],
+        fin: bool,
+        server: bool,
+        max_size: usize,
+    ) -> Result<BytesMut, ProtocolError> {
+        let mut out = Vec::with_capacity(data.len().saturating_mul(2).min(max_size) + 64);
+
+        self.inflate(data, &mut out, max_size)?;
+
+        if fin {
+            self.inflate(&DEFLATE_TRAILER, &mut out, max_size)?;
+
+            if self.config.remote_no_context_takeover(server) {
+                self.decompress.reset(false);
+            }
+        }
+
+        Ok(BytesMut::from(Bytes::from(out)))
+    }
+
+    fn inflate(
+        &mut self,
+        data: &[u8],
+        out: &mut Vec<u8>,
+        max_size: usize,
+    ) -> Result<(), ProtocolError> {
+        let start = self.decompress.total_in();
+
+        loop {
+            if out.len() > max_size {
+                return Err(ProtocolError::Overflow);
+            }
+
+            let consumed = (self.decompress.total_in() - start) as usize;
+
+            if out.len() == out.capacity() {
+                out.reserve(out.capacity().min(max_size + 1 - out.len()).max(64));
+            }
+
+            let status = self
+                .decompress
+                .decompress_vec(&data[consumed..], out, FlushDecompress::Sync)
+                .map_err(invalid_data)?;
+
+            let consumed = (self.decompress.total_in() - start) as usize;
+
+            if status == Status::StreamEnd || (consumed == data.len() && out.len() < out.capacity())
+            {
+                break;
+            }
+        }
+
+        if out.len() > max_size {
+            return Err(ProtocolError::Overflow);
+        }
+
+        Ok(())
+    }
+}
+
+impl Clone for DeflateContext {
+    fn clone(&self) -> Self {
+        DeflateContext::new(self.config)
+    }
+}
+
+impl fmt::Debug for DeflateContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeflateContext")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+fn invalid_data(err: impl std::error::Error + Send + Sync + 'static) -> ProtocolError {
+    ProtocolError::Io(io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(offer: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(SEC_WEBSOCKET_EXTENSIONS, HeaderValue::from_static(offer));
+        headers
+    }
+
+    #[test]
+    fn negotiate_offers() {
+        let config = DeflateConfig::new();
+
+        assert!(config.negotiate(&HeaderMap::new()).is_none());
+        assert!(config
+            .negotiate(&headers("x-webkit-deflate-frame"))
+            .is_none());
+
+        let agreed = config.negotiate(&headers("permessage-deflate")).unwrap();
+        assert_eq!(agreed.to_string(), "permessage-deflate");
+
+        let agreed = config
+            .negotiate(&headers(
+                "permessage-deflate; client_max_window_bits; server_no_context_takeover",
+            ))
+            .unwrap();
+        assert_eq!(
+            agreed.to_string(),
+            "permessage-deflate; server_no_context_takeover"
+        );
+
+        let agreed = config
+            .negotiate(&headers(
+                "permessage-deflate; server_max_window_bits=10, permessage-deflate",
+            ))
+            .unwrap();
+        assert_eq!(
+            agreed.to_string(),
+            "permessage-deflate; server_max_window_bits=10"
+        );
+
+        // window bits must have a value in offers; fall back to second offer
+        let agreed = config
+            .negotiate(&headers(
+                "permessage-deflate; server_max_window_bits, permessage-deflate",
+            ))
+            .unwrap();
+        assert_eq!(agreed.to_string(), "permessage-deflate");
+
+        // invalid offers are skipped
+        assert!(config
+            .negotiate(&headers("permessage-deflate; client_max_window_bits=16"))
+            .is_none());
+        assert!(config
+            .negotiate(&headers("permessage-deflate; unknown_param"))
+            .is_none());
+        assert!(config
+            .negotiate(&headers(
+                "permessage-deflate; client_no_context_takeover; client_no_context_takeover"
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn negotiate_client_window_bits() {
+        let config = DeflateConfig::new()
+            .client_max_window_bits(10)
+            .client_no_context_takeover();
+
+        let agreed = config
+            .negotiate(&headers("permessage-deflate; client_max_window_bits"))
+            .unwrap();
+        assert_eq!(
+            agreed.to_string(),
+            "permessage-deflate; client_no_context_takeover; client_max_window_bits=10"
+        );
+
+        let agreed = config
+            .negotiate(&headers("permessage-deflate; client_max_window_bits=\"9\""))
+            .unwrap();
+        assert_eq!(agreed.client_max_window_bits, Some(9));
+
+        // client did not indicate support for the parameter
+        let agreed = config.negotiate(&headers("permessage-deflate")).unwrap();
+        assert_eq!(agreed.client_max_window_bits, None);
+    }
+
+    #[test]
+    fn accept_response() {
+        let config = DeflateConfig::new()
+            .server_max_window_bits(12)
+            .client_max_window_bits(10);
+
+        let mut req = HeaderMap::new();
+        config.insert_offer(&mut req);
+        assert_eq!(
+            req.get(SEC_WEBSOCKET_EXTENSIONS).unwrap(),
+            "permessage-deflate; server_max_window_bits=12; client_max_window_bits=10"
+        );
+
+        let agreed = config
+            .accept(&HeaderValue::from_static(
+                "permessage-deflate; server_max_window_bits=10",
+            ))
+            .unwrap();
+        assert_eq!(agreed.server_max_window_bits, Some(10));
+        assert_eq!(agreed.client_max_window_bits, None);
+
+        let agreed = config
+            .accept(&HeaderValue::from_static(
+                "permessage-deflate; server_max_window_bits=12; client_max_window_bits=9",
+            ))
+            .unwrap();
+        assert_eq!(agreed.client_max_window_bits, Some(9));
+
+        assert!(config
+            .accept(&HeaderValue::from_static(
+                "permessage-deflate; server_max_window_bits=15"
+            ))
+            .is_none());
+        assert!(config
+            .accept(&HeaderValue::from_static("permessage-deflate"))
+            .is_none());
+        assert!(config
+            .accept(&HeaderValue::from_static(
+                "permessage-deflate; server_max_window_bits=12; client_max_window_bits=11"
+            ))
+            .is_none());
+        assert!(config
+            .accept(&HeaderValue::from_static(
+                "permessage-deflate; server_max_window_bits=12; client_max_window_bits"
+            ))
+            .is_none());
+
+        let mut req = HeaderMap::new();
+        DeflateConfig::new().insert_offer(&mut req);
+        assert_eq!(
+            req.get(SEC_WEBSOCKET_EXTENSIONS).unwrap(),
+            "permessage-deflate; client_max_window_bits"
+        );
+        assert!(config
+            .accept(&HeaderValue::from_static("permessage-deflate, foo"))
+            .is_none());
+        assert!(config.accept(&HeaderValue::from_static("foo")).is_none());
+    }
+
+    #[test]
+    fn compress_round_trip() {
+        let config = DeflateConfig::new();
+        let mut server = DeflateContext::new(config);
+        let mut client = DeflateContext::new(config);
+
+        let msg = b"Hello, Hello, Hello, Hello";
+
+        for _ in 0..3 {
+            let compressed = server.compress(msg, true, true).unwrap();
+            assert!(compressed.len() < msg.len());
+            assert!(!compressed.ends_with(&DEFLATE_TRAILER));
+
+            let decompressed = client.decompress(&compressed, true, false, 1024).unwrap();
+            assert_eq!(&decompressed[..], msg);
+        }
+
+        // RFC 7692 §7.2.3.1 example
+        let mut client = DeflateContext::new(config);
+        let decompressed = client
+            .decompress(
+                &[0xf2, 0x48, 0xcd, 0xc9, 0xc9, 0x07, 0x00],
+                true,
+                false,
+                1024,
+            )
+            .unwrap();
+        assert_eq!(&decompressed[..], b"Hello");
+    }
+
+    #[test]
+    fn no_context_takeover() {
+        let config = DeflateConfig::new().server_no_context_takeover();
+        let mut server = DeflateContext::new(config);
+
+        let first = server.compress(b"Hello", true, true).unwrap();
+        let second = server.compress(b"Hello", true, true).unwrap();
+        assert_eq!(first, second);
+
+        // each message can be decompressed by a fresh context
+        let mut client = DeflateContext::new(config);
+        client.decompress(&first, true, false, 1024).unwrap();
+        let decompressed = client.decompress(&second, true, false, 1024).unwrap();
+        assert_eq!(&decompressed[..], b"Hello");
+    }
+
+    #[test]
+    fn small_window() {
+        // 256 byte pattern that does not compress on its own
+        let pattern = (0..=255u8).map(|i| i.wrapping_mul(167)).collect::<Vec<_>>();
+        let msg = pattern.repeat(16);
+
+        let mut server = DeflateContext::new(DeflateConfig::new());
+        let full_window = server.compress(&msg, true, true).unwrap();
+
+        // repeats are a full window apart so they can not be referenced
+        let config = DeflateConfig::new().server_max_window_bits(8);
+        let mut server = DeflateContext::new(config);
+        let compressed = server.compress(&msg, true, true).unwrap();
+        assert!(compressed.len() > msg.len());
+        assert!(full_window.len() < pattern.len() * 2);
+
+        let mut client = DeflateContext::new(config);
+        let decompressed = client.decompress(&compressed, true, false, 4096).unwrap();
+        assert_eq!(&decompressed[..], &msg[..]);
+
+        // nor can earlier messages
+        assert_eq!(server.compress(&msg, true, true).unwrap(), compressed);
+
+        for msg in [&b"text"[..], b"", &msg, b""] {
+            let compressed = server.compress(msg, true, true).unwrap();
+            let decompressed = client.decompress(&compressed, true, false, 4096).unwrap();
+            assert_eq!(&decompressed[..], msg);
+        }
+    }
+
+    #[test]
+    fn decompress_limit() {
+        let config = DeflateConfig::new();
+        let mut server = DeflateContext::new(config);
+        let mut client = DeflateContext::new(config);
+
+        let compressed = server.compress(&[b'a'; 4096], true, true).unwrap();
+        assert!(matches!(
+            client.decompress(&compressed, true, false, 1024),
+            Err(ProtocolError::Overflow)
+        ));
+    }
+}
@@ -11,6 +11,7 @@ use http::{header, Method, StatusCode};
 use crate::{body::BoxBody, header::HeaderValue, RequestHead, Response, ResponseBuilder};
 
 mod codec;
+mod deflate;
 mod dispatcher;
 mod frame;
 mod mask;
@@ -18,6 +19,7 @@ mod proto;
 
 pub use self::{
     codec::{Codec, Frame, Item, Message},
+    deflate::DeflateConfig,
     dispatcher::Dispatcher,
     frame::Parser,
     proto::{hash_key, CloseCode, CloseReason, OpCode},
@@ -218,6 +220,28 @@ pub fn handshake_response(req: &RequestHead) -> ResponseBuilder {
         .take()
 }
 
+/// Verify WebSocket handshake request and create handshake response, negotiating
+/// `permessage-deflate` compression.
+///
+/// Returns the handshake response, ready to send to peer, and a codec with compression enabled if
+/// the client offered the extension and an offer was acceptable given `config`.
+pub fn handshake_with_deflate(
+    req: &RequestHead,
+    config: &DeflateConfig,
+) -> Result<(ResponseBuilder, Codec), HandshakeError> {
+    let mut res = handshake(req)?;
+
+    let codec = match config.negotiate(req.headers()) {
+        Some(agreed) => {
+            res.insert_header((header::SEC_WEBSOCKET_EXTENSIONS, agreed));
+            Codec::new().deflate(agreed)
+        }
+        None => Codec::new(),
+    };
+
+    Ok((res, codec))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,6 +357,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_handshake_with_deflate() {
+        let req = TestRequest::default()
+            .insert_header((header::UPGRADE, "websocket"))
+            .insert_header((header::CONNECTION, "upgrade"))
+            .insert_header((header::SEC_WEBSOCKET_VERSION, "13"))
+            .insert_header((header::SEC_WEBSOCKET_KEY, "13"))
+            .insert_header((
+                header::SEC_WEBSOCKET_EXTENSIONS,
+                "permessage-deflate; client_max_window_bits",
+            ))
+            .finish();
+
+        let config = DeflateConfig::new().server_no_context_takeover();
+        let (mut res, _codec) = handshake_with_deflate(req.head(), &config).unwrap();
+        let res = res.finish();
+        assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(
+            res.headers().get(header::SEC_WEBSOCKET_EXTENSIONS).unwrap(),
+            "permessage-deflate; server_no_context_takeover"
+        );
+
+        let req = TestRequest::default()
+            .insert_header((header::UPGRADE, "websocket"))
+            .insert_header((header::CONNECTION, "upgrade"))
+            .insert_header((header::SEC_WEBSOCKET_VERSION, "13"))
+            .insert_header((header::SEC_WEBSOCKET_KEY, "13"))
+            .finish();
+
+        let (mut res, _codec) = handshake_with_deflate(req.head(), &config).unwrap();
+        assert!(!res
+            .finish()
+            .headers()
+            .contains_key(header::SEC_WEBSOCKET_EXTENSIONS));
+    }
+
     #[test]
     fn test_ws_error_http_response() {
         let resp: Response<BoxBody> = HandshakeError::GetMethodRequired.into();
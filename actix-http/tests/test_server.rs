@@ -7,7 +7,9 @@ use std::{
 
 use actix_http::{
     body::{self, BodyStream, BoxBody, SizedStream},
-    header, Error, HttpService, KeepAlive, Request, Response, StatusCode, Version,
+    header,
+    proxy_protocol::ProxyHeader,
+    Error, HttpService, KeepAlive, Request, Response, StatusCode, Version,
 };
 use actix_http_test::test_server;
 use actix_rt::{net::TcpStream, time::sleep};
@@ -394,6 +396,106 @@ async fn http1_keepalive_disabled() {
     srv.stop().await;
 }
 
+#[actix_rt::test]
+async fn http1_shutdown_drain_idle() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .keep_alive(Duration::from_secs(30))
+            .shutdown_signal(|| sleep(Duration::from_millis(300)))
+            .h1(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp()
+    })
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\n\r\n");
+    let mut data = vec![0; 256];
+    let _ = stream.read(&mut data);
+    assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
+
+    // idle keep-alive connection is closed once shutdown starts, long before keep-alive timeout
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut data = vec![0; 256];
+    let res = stream.read(&mut data).unwrap();
+    assert_eq!(res, 0);
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn http1_shutdown_drain_in_flight() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .keep_alive(Duration::from_secs(30))
+            .shutdown_signal(|| sleep(Duration::from_millis(100)))
+            .h1(|_| async {
+                sleep(Duration::from_millis(300)).await;
+                Ok::<_, Infallible>(Response::ok())
+            })
+            .tcp()
+    })
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\n\r\n");
+    let mut data = vec![0; 256];
+    let n = stream.read(&mut data).unwrap();
+    let res = String::from_utf8_lossy(&data[..n]).to_lowercase();
+    assert!(res.starts_with("http/1.1 200 ok\r\n"));
+    assert!(res.contains("connection: close\r\n"));
+
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut data = vec![0; 256];
+    let res = stream.read(&mut data).unwrap();
+    assert_eq!(res, 0);
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn http1_proxy_protocol() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .proxy_protocol(true)
+            .finish(|req: Request| {
+                let header = req.conn_data::<ProxyHeader>().unwrap();
+                assert_eq!(
+                    header.destination(),
+                    Some("198.51.100.1:443".parse().unwrap())
+                );
+                assert_eq!(req.peer_addr(), header.source());
+                ok::<_, Infallible>(Response::with_body(
+                    StatusCode::OK,
+                    req.peer_addr().unwrap().to_string(),
+                ))
+            })
+            .tcp()
+    })
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(
+        b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\nGET / HTTP/1.1\r\nConnection: close\r\n\r\n",
+    );
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.ends_with("192.0.2.1:56324"));
+
+    // connections without a header are rejected
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET / HTTP/1.1\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.is_empty());
+
+    srv.stop().await;
+}
+
 #[actix_rt::test]
 async fn content_length() {
     use actix_http::{
@@ -899,3 +1001,64 @@ async fn h2c_auto() {
 
     srv.stop().await;
 }
+
+#[actix_rt::test]
+async fn h2c_config() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h2_config(
+                actix_http::h2::H2Config::default()
+                    .max_concurrent_streams(16)
+                    .initial_window_size(1024 * 1024)
+                    .enable_connect_protocol(),
+            )
+            .finish(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp_auto_h2c()
+    })
+    .await;
+
+    let tcp = TcpStream::connect(srv.addr()).await.unwrap();
+    let (h2, connection) = h2::client::handshake(tcp).await.unwrap();
+    tokio::spawn(async move { connection.await.unwrap() });
+    let mut h2 = h2.ready().await.unwrap();
+
+    let request = ::http::Request::new(());
+    let (response, _) = h2.send_request(request, true).unwrap();
+    let (head, _) = response.await.unwrap().into_parts();
+    assert!(head.status.is_success());
+
+    // server settings have been received by the time the response arrives
+    assert!(h2.is_extended_connect_protocol_enabled());
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn h2c_shutdown_goaway() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .shutdown_signal(|| sleep(Duration::from_millis(300)))
+            .finish(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp_auto_h2c()
+    })
+    .await;
+
+    let tcp = TcpStream::connect(srv.addr()).await.unwrap();
+    let (h2, connection) = h2::client::handshake(tcp).await.unwrap();
+    let connection = tokio::spawn(connection);
+    let mut h2 = h2.ready().await.unwrap();
+
+    let request = ::http::Request::new(());
+    let (response, _) = h2.send_request(request, true).unwrap();
+    let (head, _) = response.await.unwrap().into_parts();
+    assert!(head.status.is_success());
+
+    // server sends GOAWAY once shutdown starts and closes the connection
+    actix_rt::time::timeout(Duration::from_secs(5), connection)
+        .await
+        .expect("connection should be closed by server")
+        .unwrap()
+        .unwrap();
+
+    srv.stop().await;
+}
@@ -191,5 +191,54 @@ fn compare_routers(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, compare_routers);
+/// Registers the route list once under each of `scopes` API prefixes, yielding a large table.
+fn large_table(scopes: usize) -> Vec<String> {
+    (0..scopes)
+        .flat_map(|scope| register!(brackets).map(move |route| format!("/api/v{scope}{route}")))
+        .collect()
+}
+
+fn large_tables(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Large Routing Tables");
+
+    for scopes in [1, 8, 16] {
+        let routes = large_table(scopes);
+        let last_scope = scopes - 1;
+        let paths = call()
+            .map(|route| format!("/api/v{last_scope}{route}"))
+            .collect::<Vec<_>>();
+
+        let mut actix = actix_router::Router::<bool>::build();
+        for route in &routes {
+            actix.path(route.as_str(), true);
+        }
+        let actix = actix.finish();
+        group.bench_function(format!("actix ({} routes)", routes.len()), |b| {
+            b.iter(|| {
+                for route in &paths {
+                    let mut path = actix_router::Path::new(route.as_str());
+                    black_box(actix.recognize(&mut path));
+                }
+            });
+        });
+
+        // baseline of checking every resource definition in order
+        let rdefs = routes
+            .iter()
+            .map(|route| actix_router::ResourceDef::new(route.as_str()))
+            .collect::<Vec<_>>();
+        group.bench_function(format!("linear ({} routes)", routes.len()), |b| {
+            b.iter(|| {
+                for route in &paths {
+                    let mut path = actix_router::Path::new(route.as_str());
+                    black_box(rdefs.iter().find(|rdef| rdef.capture_match_info(&mut path)));
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, compare_routers, large_tables);
 criterion_main!(benches);
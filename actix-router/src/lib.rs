@@ -12,6 +12,7 @@ mod regex_set;
 mod resource;
 mod resource_path;
 mod router;
+mod tree;
 
 #[cfg(feature = "http")]
 mod url;
@@ -828,6 +828,18 @@ impl ResourceDef {
         self.build_resource_path(path, |name| values.get(name))
     }
 
+    /// Returns the literal string that every path matching this resource must start with.
+    ///
+    /// Multi-pattern resources return an empty string since their patterns do not necessarily
+    /// share a prefix.
+    pub(crate) fn literal_prefix(&self) -> &str {
+        match (&self.pat_type, self.segments.first()) {
+            (PatternType::DynamicSet(..), _) => "",
+            (_, Some(PatternSegment::Const(prefix))) => prefix,
+            (_, _) => "",
+        }
+    }
+
     /// Returns true if `prefix` acts as a proper prefix (i.e., separated by a slash) in `path`.
     fn static_match(&self, pattern: &str, path: &str) -> Option<usize> {
         let rem = path.strip_prefix(pattern)?;
@@ -1,4 +1,4 @@
-use crate::{IntoPatterns, Resource, ResourceDef};
+use crate::{tree::RouteTree, IntoPatterns, Resource, ResourceDef};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ResourceId(pub u16);
@@ -11,8 +11,15 @@ pub struct ResourceId(pub u16);
 /// 1. Some _context_ data, of the generic type `U`, which is only provided to the check function in
 ///    [`recognize_fn`](Self::recognize_fn). This parameter defaults to `()` and can be omitted if
 ///    not required.
+///
+/// Routes are indexed in a radix tree keyed by the literal prefix of their patterns when the router
+/// is [built](RouterBuilder::finish). Recognizing a path only checks routes whose literal prefix
+/// matches and does not allocate, which keeps lookups fast for large routing tables. Routes are
+/// still tried in registration order; the first one that matches (and passes the check function)
+/// wins.
 pub struct Router<T, U = ()> {
     routes: Vec<(ResourceDef, T, U)>,
+    tree: RouteTree,
 }
 
 impl<T, U> Router<T, U> {
@@ -46,18 +53,14 @@ impl<T, U> Router<T, U> {
     /// the `check` closure is executed, passing the resource and each route's context data. If the
     /// closure returns true then the match result is stored into `resource` and a reference to
     /// the matched _value_ is returned.
-    pub fn recognize_fn<R, F>(&self, resource: &mut R, mut check: F) -> Option<(&T, ResourceId)>
+    pub fn recognize_fn<R, F>(&self, resource: &mut R, check: F) -> Option<(&T, ResourceId)>
     where
         R: Resource,
         F: FnMut(&R, &U) -> bool,
     {
-        for (rdef, val, ctx) in self.routes.iter() {
-            if rdef.capture_match_info_fn(resource, |res| check(res, ctx)) {
-                return Some((val, ResourceId(rdef.id())));
-            }
-        }
-
-        None
+        let idx = self.find_route(resource, check)?;
+        let (rdef, val, _) = &self.routes[idx];
+        Some((val, ResourceId(rdef.id())))
     }
 
     /// Same as [`recognize_fn`](Self::recognize_fn) but returns a mutable reference to the matched
@@ -65,19 +68,38 @@ impl<T, U> Router<T, U> {
     pub fn recognize_mut_fn<R, F>(
         &mut self,
         resource: &mut R,
-        mut check: F,
+        check: F,
     ) -> Option<(&mut T, ResourceId)>
     where
         R: Resource,
         F: FnMut(&R, &U) -> bool,
     {
-        for (rdef, val, ctx) in self.routes.iter_mut() {
+        let idx = self.find_route(resource, check)?;
+        let (rdef, val, _) = &mut self.routes[idx];
+        Some((val, ResourceId(rdef.id())))
+    }
+
+    /// Returns the index of the first route, in registration order, that matches `resource`.
+    fn find_route<R, F>(&self, resource: &mut R, mut check: F) -> Option<usize>
+    where
+        R: Resource,
+        F: FnMut(&R, &U) -> bool,
+    {
+        let mut min = 0;
+
+        loop {
+            // the path is only modified by a successful match so each lookup sees the same path
+            let idx = self
+                .tree
+                .next_candidate(resource.resource_path().unprocessed(), min)?;
+
+            let (rdef, _, ctx) = &self.routes[idx];
             if rdef.capture_match_info_fn(resource, |res| check(res, ctx)) {
-                return Some((val, ResourceId(rdef.id())));
+                return Some(idx);
             }
-        }
 
-        None
+            min = idx + 1;
+        }
     }
 }
 
@@ -106,8 +128,15 @@ impl<T, U> RouterBuilder<T, U> {
 
     /// Finish configuration and create router instance.
     pub fn finish(self) -> Router<T, U> {
+        let mut tree = RouteTree::default();
+
+        for (idx, (rdef, _, _)) in self.routes.iter().enumerate() {
+            tree.insert(rdef.literal_prefix(), idx);
+        }
+
         Router {
             routes: self.routes,
+            tree,
         }
     }
 }
@@ -142,6 +171,7 @@ mod tests {
     use crate::{
         path::Path,
         router::{ResourceId, Router},
+        ResourceDef,
     };
 
     #[allow(clippy::cognitive_complexity)]
@@ -280,4 +310,72 @@ mod tests {
         assert_eq!(*h, 11);
         assert_eq!(&path["val"], "ttt");
     }
+
+    #[test]
+    fn test_recognizer_registration_order() {
+        let mut router = Router::<usize>::build();
+        router.path("/{scope}/index.html", 10);
+        router.path("/user/index.html", 11);
+        router.prefix("/user", 12);
+        router.path("/user/{id}", 13);
+        let mut router = router.finish();
+
+        // dynamic route registered first takes precedence over more specific static route
+        let mut path = Path::new("/user/index.html");
+        let (h, _) = router.recognize_mut(&mut path).unwrap();
+        assert_eq!(*h, 10);
+        assert_eq!(&path["scope"], "user");
+
+        // prefix route registered before dynamic route
+        let mut path = Path::new("/user/123");
+        let (h, _) = router.recognize_mut(&mut path).unwrap();
+        assert_eq!(*h, 12);
+        assert_eq!(path.unprocessed(), "/123");
+
+        let mut path = Path::new("/users/123");
+        assert!(router.recognize_mut(&mut path).is_none());
+    }
+
+    #[test]
+    fn test_recognizer_check_fallback() {
+        let mut router = Router::<usize, bool>::build();
+        router.push(ResourceDef::new("/name/{val}"), 10, false);
+        router.push(ResourceDef::new("/name/value"), 11, true);
+        router.push(ResourceDef::new("/{tail}*"), 12, true);
+        let router = router.finish();
+
+        let mut path = Path::new("/name/value");
+        let (h, _) = router
+            .recognize_fn(&mut path, |_, allowed| *allowed)
+            .unwrap();
+        assert_eq!(*h, 11);
+        assert!(path.get("val").is_none());
+
+        let mut path = Path::new("/name/other");
+        let (h, _) = router
+            .recognize_fn(&mut path, |_, allowed| *allowed)
+            .unwrap();
+        assert_eq!(*h, 12);
+        assert_eq!(&path["tail"], "name/other");
+
+        let mut path = Path::new("/name/other");
+        assert!(router.recognize_fn(&mut path, |_, _| false).is_none());
+    }
+
+    #[test]
+    fn test_recognizer_multi_pattern() {
+        let mut router = Router::<usize>::build();
+        router.path("/user/{id}", 10);
+        router.path(["/profile", "/user/{id}/profile"], 11);
+        let mut router = router.finish();
+
+        let mut path = Path::new("/user/123/profile");
+        let (h, _) = router.recognize_mut(&mut path).unwrap();
+        assert_eq!(*h, 11);
+        assert_eq!(&path["id"], "123");
+
+        let mut path = Path::new("/profile");
+        let (h, _) = router.recognize_mut(&mut path).unwrap();
+        assert_eq!(*h, 11);
+    }
 }
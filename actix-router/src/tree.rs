@@ -0,0 +1,162 @@
+//! Radix tree used by [`Router`](crate::Router) to narrow down candidate routes.
+//!
+//! Each route is keyed by the literal prefix that every conforming path must begin with. Looking up
+//! the next candidate for a path walks the tree along the path, without allocating, and returns the
+//! lowest route index not yet tried whose literal prefix is a prefix of the path. The full match
+//! (including dynamic segments, tail segments and segment boundary checks) is still performed by
+//! each candidate's `ResourceDef` so that matching semantics are unchanged.
+
+use std::iter;
+
+/// Node in the compressed prefix tree.
+#[derive(Debug, Default)]
+struct Node {
+    /// Byte label of the edge leading to this node.
+    label: Vec<u8>,
+
+    /// Indices of routes whose literal prefix ends at this node, in registration order.
+    routes: Vec<usize>,
+
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn leaf(label: &[u8], idx: usize) -> Self {
+        Node {
+            label: label.to_vec(),
+            routes: vec![idx],
+            children: Vec::new(),
+        }
+    }
+
+    fn child_idx(&self, first: u8) -> Option<usize> {
+        self.children
+            .iter()
+            .position(|child| child.label[0] == first)
+    }
+}
+
+/// Compressed prefix tree mapping literal route prefixes to route indices.
+#[derive(Debug, Default)]
+pub(crate) struct RouteTree {
+    root: Node,
+}
+
+impl RouteTree {
+    /// Inserts route index `idx` under the literal `prefix`.
+    ///
+    /// Indices must be inserted in ascending order for lookups to preserve registration order.
+    pub(crate) fn insert(&mut self, prefix: &str, idx: usize) {
+        let mut node = &mut self.root;
+        let mut key = prefix.as_bytes();
+
+        loop {
+            if key.is_empty() {
+                node.routes.push(idx);
+                return;
+            }
+
+            let child_idx = match node.child_idx(key[0]) {
+                Some(child_idx) => child_idx,
+                None => {
+                    node.children.push(Node::leaf(key, idx));
+                    return;
+                }
+            };
+
+            let child = &mut node.children[child_idx];
+            let common = common_prefix_len(&child.label, key);
+
+            if common < child.label.len() {
+                // split edge so that the shared part of the label becomes its own node
+                let rest = child.label.split_off(common);
+                let mut split = Node {
+                    label: std::mem::take(&mut child.label),
+                    routes: Vec::new(),
+                    children: Vec::new(),
+                };
+
+                let mut old = std::mem::take(child);
+                old.label = rest;
+                split.children.push(old);
+
+                *child = split;
+            }
+
+            key = &key[common..];
+            node = &mut node.children[child_idx];
+        }
+    }
+
+    /// Returns the lowest index, no lower than `min`, of a route whose literal prefix is a prefix of
+    /// `path`.
+    ///
+    /// Calling this repeatedly with `min` set to one past the previous result yields all candidate
+    /// routes for `path` in registration order.
+    pub(crate) fn next_candidate(&self, path: &str, min: usize) -> Option<usize> {
+        self.path_nodes(path.as_bytes())
+            .filter_map(|node| {
+                // routes of a node are sorted so its next candidate is the first one not below `min`
+                let pos = node.routes.partition_point(|&idx| idx < min);
+                node.routes.get(pos).copied()
+            })
+            .min()
+    }
+
+    /// Returns an iterator over the nodes whose full key is a prefix of `path`, starting at the root.
+    fn path_nodes<'a>(&'a self, path: &'a [u8]) -> impl Iterator<Item = &'a Node> {
+        iter::successors(Some((&self.root, path)), |&(node, rem)| {
+            let child = &node.children[node.child_idx(*rem.first()?)?];
+
+            rem.starts_with(&child.label)
+                .then(|| (child, &rem[child.label.len()..]))
+        })
+        .map(|(node, _)| node)
+    }
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(tree: &RouteTree, path: &str) -> Vec<usize> {
+        iter::successors(tree.next_candidate(path, 0), |&idx| {
+            tree.next_candidate(path, idx + 1)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn candidates_follow_literal_prefixes() {
+        let mut tree = RouteTree::default();
+        tree.insert("/user/", 0);
+        tree.insert("/users", 1);
+        tree.insert("", 2);
+        tree.insert("/user", 3);
+        tree.insert("/", 4);
+        tree.insert("/api/v1/", 5);
+
+        assert_eq!(candidates(&tree, "/user/123"), vec![0, 2, 3, 4]);
+        assert_eq!(candidates(&tree, "/users"), vec![1, 2, 3, 4]);
+        assert_eq!(candidates(&tree, "/use"), vec![2, 4]);
+        assert_eq!(candidates(&tree, "/api/v1/x"), vec![2, 4, 5]);
+        assert_eq!(candidates(&tree, "/api/v2/x"), vec![2, 4]);
+        assert_eq!(candidates(&tree, "relative"), vec![2]);
+        assert_eq!(candidates(&tree, ""), vec![2]);
+    }
+
+    #[test]
+    fn non_ascii_labels() {
+        let mut tree = RouteTree::default();
+        tree.insert("/caf\u{e9}", 0);
+        tree.insert("/caf\u{e8}", 1);
+
+        assert_eq!(candidates(&tree, "/caf\u{e9}/x"), vec![0]);
+        assert_eq!(candidates(&tree, "/caf\u{e8}"), vec![1]);
+        assert!(candidates(&tree, "/cafe").is_empty());
+    }
+}
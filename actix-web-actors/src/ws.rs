@@ -74,7 +74,9 @@ use actix::{
     SpawnHandle,
 };
 use actix_http::ws::{hash_key, Codec};
-pub use actix_http::ws::{CloseCode, CloseReason, Frame, HandshakeError, Message, ProtocolError};
+pub use actix_http::ws::{
+    CloseCode, CloseReason, DeflateConfig, Frame, HandshakeError, Message, ProtocolError,
+};
 use actix_web::{
     error::{Error, PayloadError},
     http::{
@@ -151,6 +153,7 @@ where
     codec: Option<Codec>,
     protocols: Option<&'a [&'a str]>,
     frame_size: Option<usize>,
+    deflate: Option<DeflateConfig>,
 }
 
 impl<'a, A, T> WsResponseBuilder<'a, A, T>
@@ -169,6 +172,7 @@ where
             codec: None,
             protocols: None,
             frame_size: None,
+            deflate: None,
         }
     }
 
@@ -193,11 +197,30 @@ where
         self
     }
 
-    fn handshake_resp(&self) -> Result<HttpResponseBuilder, HandshakeError> {
-        match self.protocols {
-            Some(protocols) => handshake_with_protocols(self.req, protocols),
-            None => handshake(self.req),
+    /// Enable `permessage-deflate` compression if the client offers it.
+    ///
+    /// Negotiated parameters are sent in the handshake response and applied to the session's
+    /// [`Codec`].
+    pub fn deflate(mut self, config: DeflateConfig) -> Self {
+        self.deflate = Some(config);
+        self
+    }
+
+    fn handshake_resp(&mut self) -> Result<HttpResponseBuilder, HandshakeError> {
+        let mut res = match self.protocols {
+            Some(protocols) => handshake_with_protocols(self.req, protocols)?,
+            None => handshake(self.req)?,
+        };
+
+        if let Some(agreed) = self
+            .deflate
+            .and_then(|deflate| deflate.negotiate(self.req.headers()))
+        {
+            res.insert_header((header::SEC_WEBSOCKET_EXTENSIONS, agreed));
+            self.codec = Some(self.codec.take().unwrap_or_default().deflate(agreed));
         }
+
+        Ok(res)
     }
 
     fn set_frame_size(&mut self) {
@@ -174,6 +174,46 @@ async fn builder_full() {
     common_test_code(srv, MAX_FRAME_SIZE).await;
 }
 
+#[actix_rt::test]
+async fn builder_with_deflate() {
+    let srv = actix_test::start(|| {
+        App::new().service(web::resource("/").to(
+            |req: HttpRequest, stream: web::Payload| async move {
+                ws::WsResponseBuilder::new(Ws, &req, stream)
+                    .deflate(ws::DeflateConfig::new().server_no_context_takeover())
+                    .frame_size(MAX_FRAME_SIZE)
+                    .start()
+            },
+        ))
+    });
+
+    let (res, mut framed) = awc::Client::new()
+        .ws(srv.url("/"))
+        .deflate(ws::DeflateConfig::new())
+        .connect()
+        .await
+        .unwrap();
+    assert_eq!(
+        res.headers()
+            .get(actix_web::http::header::SEC_WEBSOCKET_EXTENSIONS)
+            .unwrap(),
+        "permessage-deflate; server_no_context_takeover"
+    );
+
+    let text = "text ".repeat(1_000);
+    for _ in 0..2 {
+        framed
+            .send(ws::Message::Text(text.clone().into()))
+            .await
+            .unwrap();
+        let item = framed.next().await.unwrap().unwrap();
+        assert_eq!(item, ws::Frame::Text(Bytes::from(text.clone())));
+    }
+
+    // plain client is still served
+    common_test_code(srv, MAX_FRAME_SIZE).await;
+}
+
 #[actix_rt::test]
 async fn simple_start() {
     let srv = actix_test::start(|| {
@@ -452,13 +452,15 @@ impl ToTokens for Route {
                     }
                 };
 
+                let handler = handler_tokens(name, ast, doc_attributes, methods);
+
                 quote! {
                     let __resource = ::actix_web::Resource::new(#path)
                         .name(#resource_name)
                         #method_guards
                         #(.guard(::actix_web::guard::fn_guard(#guards)))*
                         #(.wrap(#wrappers))*
-                        .to(#name);
+                        #handler;
                     ::actix_web::dev::HttpServiceFactory::register(__resource, __config);
                 }
             })
@@ -481,6 +483,91 @@ impl ToTokens for Route {
     }
 }
 
+/// Returns a token stream that sets the resource's handler.
+#[cfg(not(feature = "openapi"))]
+fn handler_tokens(
+    name: &syn::Ident,
+    _ast: &syn::ItemFn,
+    _doc_attributes: &[syn::Attribute],
+    _methods: &HashSet<MethodTypeExt>,
+) -> TokenStream2 {
+    quote! { .to(#name) }
+}
+
+/// Returns a token stream that sets the resource's handler, documenting its route with the
+/// methods, extractors, and doc comment of the handler.
+#[cfg(feature = "openapi")]
+fn handler_tokens(
+    name: &syn::Ident,
+    ast: &syn::ItemFn,
+    doc_attributes: &[syn::Attribute],
+    methods: &HashSet<MethodTypeExt>,
+) -> TokenStream2 {
+    let mut methods = methods
+        .iter()
+        .map(|method| match method {
+            MethodTypeExt::Standard(method) => method.as_str().to_ascii_uppercase(),
+            MethodTypeExt::Custom(lit) => lit.value(),
+        })
+        .collect::<Vec<_>>();
+    methods.sort();
+
+    let doc = doc_attributes
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let arg_types = ast
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(arg) => Some(&*arg.ty),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    // `impl Trait` arguments cannot be named, so their handlers are not described
+    let has_impl_arg = arg_types.iter().any(|ty| {
+        ty.to_token_stream()
+            .into_iter()
+            .any(|tt| tt.to_string() == "impl")
+    });
+
+    let describe = if has_impl_arg || !ast.sig.generics.params.is_empty() {
+        quote! { ::std::option::Option::None }
+    } else {
+        quote! {
+            {
+                #[allow(unused_imports)]
+                use ::actix_web::openapi::__private::{DescribeApi as _, DescribeFallback as _};
+                (&::actix_web::openapi::__private::Describer::<(#(#arg_types,)*)>::new())
+                    .describe_fn()
+            }
+        }
+    };
+
+    quote! {
+        .route(
+            ::actix_web::Route::new()
+                .to(#name)
+                .__openapi_doc(&[#(#methods),*], #describe, #doc)
+        )
+    }
+}
+
 pub(crate) fn with_method(
     method: Option<MethodType>,
     args: TokenStream,
@@ -30,6 +30,7 @@ pub struct App<T> {
     data_factories: Vec<FnDataFactory>,
     external: Vec<ResourceDef>,
     extensions: Extensions,
+    problem_details: bool,
 }
 
 impl App<AppEntry> {
@@ -46,6 +47,7 @@ impl App<AppEntry> {
             factory_ref,
             external: Vec::new(),
             extensions: Extensions::new(),
+            problem_details: false,
         }
     }
 }
@@ -278,6 +280,38 @@ where
         self
     }
 
+    /// Renders error responses as [RFC 7807] problem details.
+    ///
+    /// Responses created from errors, such as extractor and payload errors, get an
+    /// `application/problem+json` body built from the error's
+    /// [`ResponseError::problem_details`](crate::ResponseError::problem_details). The empty
+    /// `404 Not Found` and `405 Method Not Allowed` responses of default services are rendered too.
+    /// The `instance` member is set to the request path unless provided by the error.
+    ///
+    /// Errors are rendered before reaching middleware registered with [`wrap`](Self::wrap), which
+    /// will see them as responses.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // a `GET /` with a non-integer `id` responds with:
+    /// // {"type":"about:blank","title":"Bad Request","status":400,"detail":"...","instance":"/"}
+    /// async fn index(id: web::Query<u32>) -> String {
+    ///     id.to_string()
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .problem_details()
+    ///     .route("/", web::get().to(index));
+    /// ```
+    ///
+    /// [RFC 7807]: https://datatracker.ietf.org/doc/html/rfc7807
+    pub fn problem_details(mut self) -> Self {
+        self.problem_details = true;
+        self
+    }
+
     /// Register an external resource.
     ///
     /// External resources are useful for URL generation purposes only
@@ -366,6 +400,7 @@ where
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
+            problem_details: self.problem_details,
         }
     }
 
@@ -429,6 +464,7 @@ where
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
+            problem_details: self.problem_details,
         }
     }
 }
@@ -453,6 +489,7 @@ where
             default: self.default,
             factory_ref: self.factory_ref,
             extensions: RefCell::new(Some(self.extensions)),
+            problem_details: self.problem_details,
         }
     }
 }
@@ -517,6 +554,44 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::CREATED);
     }
 
+    #[actix_rt::test]
+    async fn test_problem_details() {
+        let srv = init_service(
+            App::new().problem_details().service(
+                web::resource("/test")
+                    .route(web::get().to(|id: web::Query<u32>| async move { id.to_string() })),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test?x").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        let body: serde_json::Value = serde_json::from_slice(&read_body(resp).await).unwrap();
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["instance"], "/test");
+
+        let req = TestRequest::with_uri("/blah").to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value = serde_json::from_slice(&read_body(resp).await).unwrap();
+        assert_eq!(body["title"], "Not Found");
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::POST)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+    }
+
     // allow deprecated App::data
     #[allow(deprecated)]
     #[actix_rt::test]
@@ -11,6 +11,7 @@ use crate::{
     config::{AppConfig, AppService},
     data::FnDataFactory,
     dev::Extensions,
+    error::render_problem_details,
     guard::Guard,
     helpers::process_xml_configuration,
     request::{HttpRequest, HttpRequestPool},
@@ -45,6 +46,7 @@ where
     pub(crate) default: Option<Rc<BoxedHttpServiceFactory>>,
     pub(crate) factory_ref: Rc<RefCell<Option<AppRoutingFactory>>>,
     pub(crate) external: RefCell<Vec<ResourceDef>>,
+    pub(crate) problem_details: bool,
 }
 
 impl<T, B> ServiceFactory<Request> for AppInit<T, B>
@@ -89,6 +91,7 @@ where
         // complete pipeline creation.
         *self.factory_ref.borrow_mut() = Some(AppRoutingFactory {
             default,
+            problem_details: self.problem_details,
             services: services
                 .into_iter()
                 .map(|(mut rdef, srv, guards, nested)| {
@@ -250,6 +253,7 @@ pub struct AppRoutingFactory {
         )],
     >,
     default: Rc<BoxedHttpServiceFactory>,
+    problem_details: bool,
 }
 
 impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
@@ -276,6 +280,8 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
         // construct default service factory future
         let default_fut = self.default.new_service(());
 
+        let problem_details = self.problem_details;
+
         Box::pin(async move {
             let default = default_fut.await?;
 
@@ -291,7 +297,11 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
                 })
                 .finish();
 
-            Ok(AppRouting { router, default })
+            Ok(AppRouting {
+                router,
+                default,
+                problem_details,
+            })
         })
     }
 }
@@ -300,6 +310,7 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
 pub struct AppRouting {
     router: Router<BoxedHttpService, Vec<Box<dyn Guard>>>,
     default: BoxedHttpService,
+    problem_details: bool,
 }
 
 impl Service<ServiceRequest> for AppRouting {
@@ -325,10 +336,26 @@ impl Service<ServiceRequest> for AppRouting {
             guards.iter().all(|guard| guard.check(&guard_ctx))
         });
 
-        if let Some((srv, _info)) = res {
+        let http_req = self.problem_details.then(|| req.request().clone());
+
+        let fut = if let Some((srv, _info)) = res {
             srv.call(req)
         } else {
             self.default.call(req)
+        };
+
+        match http_req {
+            Some(http_req) => Box::pin(async move {
+                let res = match fut.await {
+                    Ok(res) => res,
+                    Err(err) => ServiceResponse::from_err(err, http_req),
+                };
+
+                let (req, res) = res.into_parts();
+                let res = render_problem_details(res, req.path());
+                Ok(ServiceResponse::new(req, res))
+            }),
+            None => fut,
         }
     }
 }
@@ -1,5 +1,6 @@
 use std::{net::SocketAddr, rc::Rc};
 
+use actix_server::GracefulShutdownSignal;
 use actix_service::{boxed, IntoServiceFactory, ServiceFactory, ServiceFactoryExt as _};
 
 use crate::{
@@ -14,6 +15,7 @@ use crate::{
         AppServiceFactory, BoxedHttpServiceFactory, HttpServiceFactory, ServiceFactoryWrapper,
         ServiceRequest, ServiceResponse,
     },
+    shutdown::ShutdownSignal,
 };
 
 type Guards = Vec<Box<dyn Guard>>;
@@ -30,6 +32,8 @@ pub struct AppService {
         Option<Guards>,
         Option<Rc<ResourceMap>>,
     )>,
+    #[cfg(feature = "openapi")]
+    api: crate::openapi::ApiRegistry,
 }
 
 impl AppService {
@@ -40,6 +44,8 @@ impl AppService {
             default,
             root: true,
             services: Vec::new(),
+            #[cfg(feature = "openapi")]
+            api: crate::openapi::ApiRegistry::default(),
         }
     }
 
@@ -71,6 +77,8 @@ impl AppService {
             default: Rc::clone(&self.default),
             services: Vec::new(),
             root: false,
+            #[cfg(feature = "openapi")]
+            api: self.api.clone(),
         }
     }
 
@@ -84,6 +92,18 @@ impl AppService {
         Rc::clone(&self.default)
     }
 
+    /// Returns the registry of documented routes.
+    #[cfg(feature = "openapi")]
+    pub(crate) fn api_registry(&self) -> &crate::openapi::ApiRegistry {
+        &self.api
+    }
+
+    /// Returns mutable reference to the registry of documented routes.
+    #[cfg(feature = "openapi")]
+    pub(crate) fn api_registry_mut(&mut self) -> &mut crate::openapi::ApiRegistry {
+        &mut self.api
+    }
+
     /// Register HTTP service.
     pub fn register_service<F, S>(
         &mut self,
@@ -112,11 +132,23 @@ pub struct AppConfig {
     secure: bool,
     host: String,
     addr: SocketAddr,
+    shutdown_signal: Option<GracefulShutdownSignal>,
 }
 
 impl AppConfig {
     pub(crate) fn new(secure: bool, host: String, addr: SocketAddr) -> Self {
-        AppConfig { secure, host, addr }
+        AppConfig {
+            secure,
+            host,
+            addr,
+            shutdown_signal: None,
+        }
+    }
+
+    /// Sets the signal of the server's graceful shutdown.
+    pub(crate) fn with_shutdown_signal(mut self, signal: GracefulShutdownSignal) -> Self {
+        self.shutdown_signal = Some(signal);
+        self
     }
 
     /// Needed in actix-test crate. Semver exempt.
@@ -146,6 +178,11 @@ impl AppConfig {
         self.addr
     }
 
+    /// Returns a signal that resolves when the server starts a graceful shutdown.
+    pub(crate) fn shutdown_signal(&self) -> ShutdownSignal {
+        ShutdownSignal::new(self.shutdown_signal.clone())
+    }
+
     #[cfg(test)]
     pub(crate) fn set_host(&mut self, host: &str) {
         host.clone_into(&mut self.host);
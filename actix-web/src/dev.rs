@@ -9,7 +9,10 @@
 
 #[cfg(feature = "__compress")]
 pub use actix_http::encoding::Decoder as Decompress;
-pub use actix_http::{Extensions, Payload, RequestHead, Response, ResponseHead};
+pub use actix_http::{
+    proxy_protocol::{ProxyHeader, ProxyVersion, Tlv},
+    Extensions, Payload, RequestHead, Response, ResponseHead,
+};
 use actix_router::Patterns;
 pub use actix_router::{Path, ResourceDef, ResourcePath, Url};
 pub use actix_server::{Server, ServerHandle};
@@ -21,7 +24,7 @@ pub use actix_service::{
 pub use crate::handler::Handler;
 pub use crate::{
     config::{AppConfig, AppService},
-    info::{ConnectionInfo, PeerAddr},
+    info::{ConnectionInfo, PeerAddr, TrustedProxies},
     rmap::ResourceMap,
     service::{HttpServiceFactory, ServiceRequest, ServiceResponse, WebService},
     types::{JsonBody, Readlines, UrlEncoded},
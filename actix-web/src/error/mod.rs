@@ -1,26 +1,36 @@
 //! Error and Result module
 
+use std::fmt;
+
 // This is meant to be a glob import of the whole error module except for `Error`. Rustdoc can't yet
 // correctly resolve the conflicting `Error` type defined in this module, so these re-exports are
 // expanded manually.
 //
 // See <https://github.com/rust-lang/rust/issues/83375>
 pub use actix_http::error::{ContentTypeError, DispatchError, HttpError, ParseError, PayloadError};
+
 use derive_more::{Display, Error, From};
+use serde::Serialize;
 use serde_json::error::Error as JsonError;
 use serde_urlencoded::{de::Error as FormDeError, ser::Error as FormError};
 use url::ParseError as UrlParseError;
 
-use crate::http::StatusCode;
+use crate::{http::StatusCode, HttpResponse};
 
 #[allow(clippy::module_inception)]
 mod error;
 mod internal;
 mod macros;
+mod problem_details;
 mod response_error;
 
-pub(crate) use self::macros::{downcast_dyn, downcast_get_type_id};
-pub use self::{error::Error, internal::*, response_error::ResponseError};
+pub use self::{
+    error::Error, internal::*, problem_details::ProblemDetails, response_error::ResponseError,
+};
+pub(crate) use self::{
+    macros::{downcast_dyn, downcast_get_type_id},
+    problem_details::render_problem_details,
+};
 
 /// A convenience [`Result`](std::result::Result) for Actix Web operations.
 ///
@@ -222,6 +232,136 @@ impl ResponseError for ReadlinesError {
     }
 }
 
+/// A set of failing fields, returned when validating extracted data.
+///
+/// Each error is keyed by the path of the failing field, e.g., `name`, `address.city`, or
+/// `items[0].quantity`. Responds with `422 Unprocessable Entity` and a JSON body listing the errors.
+///
+/// See [`Valid`](crate::web::Valid).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValidationErrors {
+    errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    /// Constructs an empty set of errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an error for the field at `path`.
+    pub fn add(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Adds the errors of a nested value, prefixing their paths with `path`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::error::ValidationErrors;
+    /// let mut address = ValidationErrors::new();
+    /// address.add("city", "must not be empty");
+    ///
+    /// let mut errors = ValidationErrors::new();
+    /// errors.nest("address", Err(address));
+    ///
+    /// assert_eq!(errors.errors()[0].path(), "address.city");
+    /// ```
+    pub fn nest(&mut self, path: &str, result: Result<(), ValidationErrors>) {
+        let Err(nested) = result else {
+            return;
+        };
+
+        for mut err in nested.errors {
+            err.path = if err.path.is_empty() {
+                path.to_owned()
+            } else if path.is_empty() || err.path.starts_with('[') {
+                format!("{}{}", path, err.path)
+            } else {
+                format!("{}.{}", path, err.path)
+            };
+
+            self.errors.push(err);
+        }
+    }
+
+    /// Returns true if there are no errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the errors.
+    pub fn errors(&self) -> &[FieldError] {
+        &self.errors
+    }
+
+    /// Returns `Ok(())` if there are no errors, or else returns `self` as an error.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Validation failed")?;
+
+        for (idx, err) in self.errors.iter().enumerate() {
+            let sep = if idx == 0 { ": " } else { "; " };
+            write!(f, "{}{}: {}", sep, err.path, err.message)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Return `UnprocessableEntity` with a JSON list of errors for `ValidationErrors`.
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self)
+    }
+
+    fn problem_details(&self) -> ProblemDetails {
+        ProblemDetails::new(self.status_code())
+            .with_detail("Validation failed")
+            .with_extension(
+                "errors",
+                serde_json::to_value(&self.errors).unwrap_or_default(),
+            )
+    }
+}
+
+/// A failing field of a [`ValidationErrors`] set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    path: String,
+    message: String,
+}
+
+impl FieldError {
+    /// Returns the path of the failing field.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,6 +390,31 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[test]
+    fn test_validation_errors() {
+        let mut item = ValidationErrors::new();
+        item.add("quantity", "must be positive");
+
+        let mut errors = ValidationErrors::new();
+        errors.add("name", "must not be empty");
+        errors.nest("items", Err(ValidationErrors::new()));
+        errors.nest("items", {
+            let mut items = ValidationErrors::new();
+            items.nest("[1]", Err(item));
+            Err(items)
+        });
+
+        assert_eq!(
+            errors.to_string(),
+            "Validation failed: name: must not be empty; items[1].quantity: must be positive"
+        );
+
+        let resp = errors.error_response();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        assert!(ValidationErrors::new().into_result().is_ok());
+    }
+
     #[test]
     fn test_query_payload_error() {
         let resp = QueryPayloadError::Deserialize(
@@ -0,0 +1,292 @@
+//! RFC 7807 problem details.
+
+use std::fmt;
+
+use serde::{ser::SerializeMap as _, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{
+    body::{BodySize, BoxBody, MessageBody as _},
+    error::ResponseError,
+    http::{header, StatusCode},
+    HttpResponse,
+};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Standard members, which cannot be overridden by extensions.
+const MEMBERS: &[&str] = &["type", "title", "status", "detail", "instance"];
+
+/// A machine-readable error response body, as described by [RFC 7807].
+///
+/// Can be returned as an error from handlers, responding with `application/problem+json`. When
+/// [enabled on an `App`](crate::App::problem_details), errors are rendered using their
+/// [`ResponseError::problem_details`] implementation.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::ProblemDetails, get, http::StatusCode};
+///
+/// #[get("/account")]
+/// async fn account() -> Result<&'static str, ProblemDetails> {
+///     Err(ProblemDetails::new(StatusCode::FORBIDDEN)
+///         .with_type("https://example.com/probs/out-of-credit")
+///         .with_title("You do not have enough credit.")
+///         .with_detail("Your current balance is 30, but that costs 50.")
+///         .with_extension("balance", 30))
+/// }
+/// ```
+///
+/// [RFC 7807]: https://datatracker.ietf.org/doc/html/rfc7807
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemDetails {
+    type_uri: String,
+    title: String,
+    status: StatusCode,
+    detail: Option<String>,
+    instance: Option<String>,
+    extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    /// Constructs problem details for a status code.
+    ///
+    /// The type is set to `about:blank` and the title to the status code's canonical reason.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_uri: "about:blank".to_owned(),
+            title: status.canonical_reason().unwrap_or_default().to_owned(),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Sets the URI reference identifying the problem type.
+    pub fn with_type(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Sets the short, human-readable summary of the problem type.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the human-readable explanation specific to this occurrence of the problem.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the URI reference identifying this occurrence of the problem.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds an extension member.
+    ///
+    /// Members named like the standard members are ignored when serializing.
+    pub fn with_extension(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extensions.insert(name.into(), value.into());
+        self
+    }
+
+    /// Returns the URI reference identifying the problem type.
+    pub fn type_uri(&self) -> &str {
+        &self.type_uri
+    }
+
+    /// Returns the summary of the problem type.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the status code.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the explanation specific to this occurrence of the problem, if set.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Returns the URI reference identifying this occurrence of the problem, if set.
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// Returns the extension members.
+    pub fn extensions(&self) -> &Map<String, Value> {
+        &self.extensions
+    }
+
+    /// Serializes to a JSON response body.
+    pub(crate) fn to_body(&self) -> BoxBody {
+        BoxBody::new(serde_json::to_string(self).unwrap_or_default())
+    }
+}
+
+impl Serialize for ProblemDetails {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let mut map = ser.serialize_map(None)?;
+
+        map.serialize_entry("type", &self.type_uri)?;
+        map.serialize_entry("title", &self.title)?;
+        map.serialize_entry("status", &self.status.as_u16())?;
+
+        if let Some(detail) = &self.detail {
+            map.serialize_entry("detail", detail)?;
+        }
+
+        if let Some(instance) = &self.instance {
+            map.serialize_entry("instance", instance)?;
+        }
+
+        for (name, value) in &self.extensions {
+            if !MEMBERS.contains(&name.as_str()) {
+                map.serialize_entry(name, value)?;
+            }
+        }
+
+        map.end()
+    }
+}
+
+impl fmt::Display for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {}", self.title, detail),
+            None => f.write_str(&self.title),
+        }
+    }
+}
+
+impl std::error::Error for ProblemDetails {}
+
+impl ResponseError for ProblemDetails {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        HttpResponse::build(self.status)
+            .insert_header((header::CONTENT_TYPE, PROBLEM_JSON))
+            .body(self.to_body())
+    }
+
+    fn problem_details(&self) -> ProblemDetails {
+        self.clone()
+    }
+}
+
+/// Replaces the body of an error response with the problem details of its error.
+///
+/// Responses without an error are replaced if they are empty `404 Not Found` or
+/// `405 Method Not Allowed` responses, as sent by default services.
+pub(crate) fn render_problem_details(
+    res: HttpResponse<BoxBody>,
+    instance: &str,
+) -> HttpResponse<BoxBody> {
+    let problem = match res.error() {
+        Some(err) => err.as_response_error().problem_details(),
+
+        None if matches!(
+            res.status(),
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+        ) && matches!(res.body().size(), BodySize::None | BodySize::Sized(0)) =>
+        {
+            ProblemDetails::new(res.status())
+        }
+
+        None => return res,
+    };
+
+    let problem = if problem.instance.is_none() {
+        problem.with_instance(instance)
+    } else {
+        problem
+    };
+
+    let mut res = res.set_body(problem.to_body());
+    *res.status_mut() = problem.status;
+    res.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static(PROBLEM_JSON),
+    );
+    res.headers_mut().remove(header::CONTENT_LENGTH);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{body::to_bytes, error, error::ErrorBadRequest};
+
+    async fn body_json(res: HttpResponse) -> Value {
+        let body = to_bytes(res.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn problem_details_response() {
+        let problem = ProblemDetails::new(StatusCode::FORBIDDEN)
+            .with_detail("Not enough credit.")
+            .with_extension("balance", 30)
+            .with_extension("status", "ignored");
+        assert_eq!(problem.to_string(), "Forbidden: Not enough credit.");
+
+        let res = problem.error_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        assert_eq!(
+            body_json(res).await,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Forbidden",
+                "status": 403,
+                "detail": "Not enough credit.",
+                "balance": 30,
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn render_errors() {
+        let res = HttpResponse::from_error(ErrorBadRequest("missing field `name`"));
+        let res = render_problem_details(res, "/users");
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_json(res).await,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Bad Request",
+                "status": 400,
+                "detail": "missing field `name`",
+                "instance": "/users",
+            })
+        );
+
+        let res = HttpResponse::from_error(error::ValidationErrors::new());
+        let res = render_problem_details(res, "/users");
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body_json(res).await["errors"], serde_json::json!([]));
+
+        let res = render_problem_details(HttpResponse::NotFound().finish(), "/nope");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body_json(res).await["title"], "Not Found");
+
+        let res = render_problem_details(HttpResponse::NotFound().body("gone"), "/nope");
+        assert_eq!(to_bytes(res.into_body()).await.unwrap(), "gone");
+
+        let res = render_problem_details(HttpResponse::Ok().finish(), "/");
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -12,7 +12,7 @@ use bytes::BytesMut;
 
 use crate::{
     body::BoxBody,
-    error::{downcast_dyn, downcast_get_type_id},
+    error::{downcast_dyn, downcast_get_type_id, ProblemDetails},
     helpers,
     http::{
         header::{self, TryIntoHeaderValue},
@@ -48,6 +48,15 @@ pub trait ResponseError: fmt::Debug + fmt::Display {
         res.set_body(BoxBody::new(buf))
     }
 
+    /// Returns the problem details describing this error.
+    ///
+    /// Used instead of [error_response](Self::error_response) when problem details responses are
+    /// [enabled on an `App`](crate::App::problem_details). By default, the problem details use
+    /// `self.status_code()` as status and `Self`'s `Display` impl as detail.
+    fn problem_details(&self) -> ProblemDetails {
+        ProblemDetails::new(self.status_code()).with_detail(self.to_string())
+    }
+
     downcast_get_type_id!();
 }
 
@@ -22,7 +22,6 @@ mod accept_encoding;
 mod accept_language;
 mod allow;
 mod cache_control;
-mod content_disposition;
 mod content_language;
 mod content_length;
 mod content_range;
@@ -52,7 +51,6 @@ pub use self::{
     accept_language::AcceptLanguage,
     allow::Allow,
     cache_control::{CacheControl, CacheDirective},
-    content_disposition::{ContentDisposition, DispositionParam, DispositionType},
     content_language::ContentLanguage,
     content_length::ContentLength,
     content_range::{ContentRange, ContentRangeSpec},
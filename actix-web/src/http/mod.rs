@@ -2,4 +2,9 @@
 
 pub mod header;
 
-pub use actix_http::{uri, ConnectionType, Error, KeepAlive, Method, StatusCode, Uri, Version};
+pub use actix_http::{
+    h1::H1Limits, uri, ConnectionType, Error, KeepAlive, Method, StatusCode, Uri, Version,
+};
+
+#[cfg(feature = "http2")]
+pub use actix_http::h2::H2Config;
@@ -1,4 +1,7 @@
-use std::{convert::Infallible, net::SocketAddr};
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
 
 use actix_utils::future::{err, ok, Ready};
 use derive_more::{Display, Error};
@@ -35,6 +38,14 @@ fn bare_address(val: &str) -> &str {
     }
 }
 
+/// Parses an IP address, with optional port and IPv6 square brackets, from a peer specification.
+fn parse_ip(val: &str) -> Option<IpAddr> {
+    val.parse()
+        .or_else(|_| val.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .or_else(|_| bare_address(val).parse())
+        .ok()
+}
+
 /// Extracts and trims first value for given header name.
 fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<&'a str> {
     let hdr = req.headers.get(name)?.to_str().ok()?;
@@ -42,6 +53,175 @@ fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<
     Some(val)
 }
 
+/// Returns the comma-separated values of all headers with given name, in order.
+fn header_values<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Vec<&'a str> {
+    req.headers
+        .get_all(name)
+        .filter_map(|hdr| hdr.to_str().ok())
+        .flat_map(|val| val.split(','))
+        .map(str::trim)
+        .collect()
+}
+
+/// Parses `Forwarded` headers into their elements, one per hop, each containing its
+/// `(name, value)` pairs.
+fn forwarded_elements(req: &RequestHead) -> Vec<Vec<(&str, &str)>> {
+    header_values(req, &header::FORWARDED)
+        .into_iter()
+        // "for=1.2.3.4;proto=https"
+        .map(|element| {
+            element
+                .split(';')
+                .filter_map(|pair| {
+                    let (name, val) = pair.split_once('=')?;
+                    Some((name.trim(), unquote(val)))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the value of the first pair with given name in a `Forwarded` element.
+fn forwarded_param<'a>(element: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    element
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, val)| *val)
+}
+
+/// Finds the client in a chain of forwarded addresses, ordered from client to closest proxy.
+///
+/// Walks the chain from right to left, skipping trusted proxies, and returns the index of the first
+/// address that is not trusted, or of the leftmost address if all of them are trusted.
+fn client_hop<'a>(
+    chain: impl DoubleEndedIterator<Item = Option<&'a str>> + ExactSizeIterator,
+    trusted_proxies: &TrustedProxies,
+) -> Option<usize> {
+    let mut client = None;
+
+    for (idx, addr) in chain.enumerate().rev() {
+        client = Some(idx);
+
+        match addr.and_then(parse_ip) {
+            Some(ip) if trusted_proxies.contains(ip) => continue,
+            // untrusted, obfuscated, or unknown addresses end the chain of trust
+            _ => break,
+        }
+    }
+
+    client
+}
+
+/// Formats a forwarded peer specification as a bare address.
+fn client_address(val: &str) -> String {
+    match parse_ip(val) {
+        Some(ip) => ip.to_string(),
+        None => bare_address(val).to_owned(),
+    }
+}
+
+/// An IP network in CIDR notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    fn parse(network: &str) -> Option<Self> {
+        let (addr, prefix_len) = match network.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (network, None),
+        };
+
+        let addr = addr.trim().parse::<IpAddr>().ok()?.to_canonical();
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .trim()
+                .parse()
+                .ok()
+                .filter(|len| *len <= max_len)?,
+            None => max_len,
+        };
+
+        Some(Self { addr, prefix_len })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Proxies trusted to set the `Forwarded` and `X-Forwarded-*` headers used by [`ConnectionInfo`].
+///
+/// By default, `ConnectionInfo` uses forwarded headers regardless of which peer sent them, so they
+/// can be spoofed by clients. When `TrustedProxies` is registered as app data, forwarded headers
+/// are only used for requests received from a trusted proxy. The client address is then found by
+/// walking the forwarded chain from right to left, skipping trusted proxies, so that addresses
+/// prepended by clients are ignored. Likewise, the host and scheme are taken from the client's hop
+/// rather than from the leftmost forwarded values.
+///
+/// # Examples
+/// ```
+/// use actix_web::{dev::TrustedProxies, App};
+///
+/// let app = App::new().app_data(
+///     TrustedProxies::new()
+///         .trust("10.0.0.0/8")
+///         .trust("fd00::/8")
+///         .trust("127.0.0.1"),
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<IpNetwork>,
+}
+
+impl TrustedProxies {
+    /// Constructs a configuration that trusts no proxies.
+    ///
+    /// Forwarded headers are ignored until proxies are added.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a trusted proxy address (e.g., `192.0.2.1`) or network in CIDR notation (e.g.,
+    /// `10.0.0.0/8` or `fd00::/8`).
+    ///
+    /// # Panics
+    /// Panics if `network` is not a valid IP address or CIDR network.
+    pub fn trust(mut self, network: &str) -> Self {
+        let network = IpNetwork::parse(network)
+            .unwrap_or_else(|| panic!("invalid trusted proxy network: {network:?}"));
+
+        self.networks.push(network);
+        self
+    }
+
+    /// Returns true if `ip` belongs to a trusted proxy.
+    ///
+    /// IPv4-mapped IPv6 addresses are treated as their IPv4 equivalents.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(ip))
+    }
+}
+
 /// HTTP connection information.
 ///
 /// `ConnectionInfo` implements `FromRequest` and can be extracted in handlers.
@@ -70,6 +250,9 @@ fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<
 /// If the older, related headers are also present (eg. `X-Forwarded-For`), then `Forwarded`
 /// is preferred.
 ///
+/// Forwarded headers are trusted regardless of which peer sent them unless [`TrustedProxies`] is
+/// registered as app data.
+///
 /// [rfc7239]: https://datatracker.ietf.org/doc/html/rfc7239
 /// [rfc7239-62]: https://datatracker.ietf.org/doc/html/rfc7239#section-6.2
 /// [rfc7239-63]: https://datatracker.ietf.org/doc/html/rfc7239#section-6.3
@@ -79,10 +262,19 @@ pub struct ConnectionInfo {
     scheme: String,
     peer_addr: Option<String>,
     realip_remote_addr: Option<String>,
+    client_ip: Option<IpAddr>,
 }
 
 impl ConnectionInfo {
-    pub(crate) fn new(req: &RequestHead, cfg: &AppConfig) -> ConnectionInfo {
+    pub(crate) fn new(
+        req: &RequestHead,
+        cfg: &AppConfig,
+        trusted_proxies: Option<&TrustedProxies>,
+    ) -> ConnectionInfo {
+        if let Some(trusted_proxies) = trusted_proxies {
+            return Self::new_trusted(req, cfg, trusted_proxies);
+        }
+
         let mut host = None;
         let mut scheme = None;
         let mut realip_remote_addr = None;
@@ -125,24 +317,101 @@ impl ConnectionInfo {
             };
         }
 
+        let scheme = scheme.or_else(|| first_header_value(req, &X_FORWARDED_PROTO));
+        let host = host.or_else(|| first_header_value(req, &X_FORWARDED_HOST));
+
+        let realip_remote_addr = realip_remote_addr
+            .or_else(|| first_header_value(req, &X_FORWARDED_FOR))
+            .map(str::to_owned);
+
+        let client_ip = req.peer_addr.map(|addr| addr.ip());
+
+        Self::finish(req, cfg, scheme, host, realip_remote_addr, client_ip)
+    }
+
+    /// Resolves connection info using only forwarded headers set by trusted proxies.
+    fn new_trusted(
+        req: &RequestHead,
+        cfg: &AppConfig,
+        trusted_proxies: &TrustedProxies,
+    ) -> ConnectionInfo {
+        let mut host = None;
+        let mut scheme = None;
+        let mut realip_remote_addr = None;
+
+        let peer_ip = req.peer_addr.map(|addr| addr.ip());
+
+        if peer_ip.is_some_and(|ip| trusted_proxies.contains(ip)) {
+            let elements = forwarded_elements(req);
+
+            // each element is appended by a proxy; the one describing the client's hop carries the
+            // host and scheme that the client requested
+            let hops = elements
+                .iter()
+                .map(|element| forwarded_param(element, "for"));
+
+            if let Some(idx) = client_hop(hops, trusted_proxies) {
+                let element = &elements[idx];
+                realip_remote_addr = forwarded_param(element, "for").map(client_address);
+                host = forwarded_param(element, "host");
+                scheme = forwarded_param(element, "proto");
+            }
+
+            // position of the client in the `X-Forwarded-For` chain and the length of that chain
+            let mut x_forwarded_hop = None;
+
+            if realip_remote_addr.is_none() {
+                let addrs = header_values(req, &X_FORWARDED_FOR);
+
+                if let Some(idx) = client_hop(addrs.iter().copied().map(Some), trusted_proxies) {
+                    realip_remote_addr = Some(client_address(addrs[idx]));
+                    x_forwarded_hop = Some((idx, addrs.len()));
+                }
+            }
+
+            // leftmost values may be supplied by the client; use the value appended alongside the
+            // resolved client address if the chains line up, or else the one set by the closest proxy
+            let x_forwarded_value = |name| {
+                let vals = header_values(req, name);
+
+                match x_forwarded_hop {
+                    Some((idx, len)) if vals.len() == len => Some(vals[idx]),
+                    _ => vals.last().copied(),
+                }
+            };
+
+            host = host.or_else(|| x_forwarded_value(&X_FORWARDED_HOST));
+            scheme = scheme.or_else(|| x_forwarded_value(&X_FORWARDED_PROTO));
+        }
+
+        let client_ip = match &realip_remote_addr {
+            Some(addr) => addr.parse().ok(),
+            None => peer_ip,
+        };
+
+        Self::finish(req, cfg, scheme, host, realip_remote_addr, client_ip)
+    }
+
+    fn finish(
+        req: &RequestHead,
+        cfg: &AppConfig,
+        scheme: Option<&str>,
+        host: Option<&str>,
+        realip_remote_addr: Option<String>,
+        client_ip: Option<IpAddr>,
+    ) -> ConnectionInfo {
         let scheme = scheme
-            .or_else(|| first_header_value(req, &X_FORWARDED_PROTO))
             .or_else(|| req.uri.scheme().map(Scheme::as_str))
             .or_else(|| Some("https").filter(|_| cfg.secure()))
             .unwrap_or("http")
             .to_owned();
 
         let host = host
-            .or_else(|| first_header_value(req, &X_FORWARDED_HOST))
             .or_else(|| req.headers.get(&header::HOST)?.to_str().ok())
             .or_else(|| req.uri.authority().map(Authority::as_str))
             .unwrap_or_else(|| cfg.host())
             .to_owned();
 
-        let realip_remote_addr = realip_remote_addr
-            .or_else(|| first_header_value(req, &X_FORWARDED_FOR))
-            .map(str::to_owned);
-
         let peer_addr = req.peer_addr.map(|addr| addr.ip().to_string());
 
         ConnectionInfo {
@@ -150,6 +419,7 @@ impl ConnectionInfo {
             scheme,
             peer_addr,
             realip_remote_addr,
+            client_ip,
         }
     }
 
@@ -160,10 +430,15 @@ impl ConnectionInfo {
     /// - `X-Forwarded-For` header
     /// - peer address of opened socket (same as [`remote_addr`](Self::remote_addr))
     ///
+    /// When [`TrustedProxies`] is configured, forwarded headers are only used if the peer is a
+    /// trusted proxy, and the client is the rightmost address in the forwarded chain that is not a
+    /// trusted proxy.
+    ///
     /// # Security
-    /// Do not use this function for security purposes unless you can be sure that the `Forwarded`
-    /// and `X-Forwarded-For` headers cannot be spoofed by the client. If you are running without a
-    /// proxy then [obtaining the peer address](Self::peer_addr) would be more appropriate.
+    /// Without [`TrustedProxies`], do not use this function for security purposes unless you can be
+    /// sure that the `Forwarded` and `X-Forwarded-For` headers cannot be spoofed by the client. If
+    /// you are running without a proxy then [obtaining the peer address](Self::peer_addr) would be
+    /// more appropriate.
     #[inline]
     pub fn realip_remote_addr(&self) -> Option<&str> {
         self.realip_remote_addr
@@ -171,6 +446,19 @@ impl ConnectionInfo {
             .or(self.peer_addr.as_deref())
     }
 
+    /// IP address of client that initiated request.
+    ///
+    /// When [`TrustedProxies`] is configured, this is the address resolved through trusted
+    /// forwarded headers as described in [`realip_remote_addr`](Self::realip_remote_addr). Returns
+    /// `None` if the resolved address is obfuscated or unknown.
+    ///
+    /// Otherwise, forwarded headers are not trusted and this is the IP address of the peer
+    /// connection.
+    #[inline]
+    pub fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
     /// Returns serialized IP address of the peer connection.
     ///
     /// See [`HttpRequest::peer_addr`] for more details.
@@ -475,6 +763,166 @@ mod tests {
         assert_eq!(conn_info.peer_addr().unwrap(), "127.0.0.1");
     }
 
+    fn trusted_info(peer_addr: &str, headers: &[(&str, &str)]) -> ConnectionInfo {
+        let mut head = RequestHead::default();
+        head.peer_addr = Some(peer_addr.parse().unwrap());
+
+        for (name, val) in headers {
+            head.headers.append(
+                HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                header::HeaderValue::from_str(val).unwrap(),
+            );
+        }
+
+        let trusted_proxies = TrustedProxies::new()
+            .trust("10.0.0.0/8")
+            .trust("2001:db8::1");
+        ConnectionInfo::new(&head, &AppConfig::default(), Some(&trusted_proxies))
+    }
+
+    #[test]
+    fn trusted_proxies_networks() {
+        let trusted_proxies = TrustedProxies::new()
+            .trust("10.0.0.0/8")
+            .trust("192.0.2.1")
+            .trust("2001:db8::/32")
+            .trust("0.0.0.0/0");
+        assert!(trusted_proxies.contains("10.1.2.3".parse().unwrap()));
+        assert!(trusted_proxies.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(trusted_proxies.contains("2001:db8:cafe::17".parse().unwrap()));
+        assert!(!trusted_proxies.contains("2001:db9::1".parse().unwrap()));
+
+        let trusted_proxies = TrustedProxies::new().trust("10.0.0.0/8").trust("192.0.2.1");
+        assert!(trusted_proxies.contains("192.0.2.1".parse().unwrap()));
+        assert!(!trusted_proxies.contains("192.0.2.2".parse().unwrap()));
+        assert!(!trusted_proxies.contains("11.0.0.1".parse().unwrap()));
+        assert!(!TrustedProxies::new().contains("10.1.2.3".parse().unwrap()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn trusted_proxies_invalid_network() {
+        TrustedProxies::new().trust("10.0.0.0/33");
+    }
+
+    #[test]
+    fn trusted_proxies_untrusted_peer() {
+        let info = trusted_info(
+            "192.0.2.60:8080",
+            &[
+                (
+                    "forwarded",
+                    "for=198.51.100.17;proto=https;host=rust-lang.org",
+                ),
+                (X_FORWARDED_FOR, "198.51.100.17"),
+                (X_FORWARDED_PROTO, "https"),
+                ("host", "actix.rs"),
+            ],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(info.client_ip(), Some("192.0.2.60".parse().unwrap()));
+        assert_eq!(info.scheme(), "http");
+        assert_eq!(info.host(), "actix.rs");
+    }
+
+    #[test]
+    fn trusted_proxies_forwarded_chain() {
+        // client-supplied "for" value is ignored
+        let info = trusted_info(
+            "10.0.0.2:8080",
+            &[(
+                "forwarded",
+                "for=203.0.113.1;host=evil.com, for=192.0.2.60;proto=https;host=rust-lang.org, \
+                 for=10.0.0.1",
+            )],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(info.client_ip(), Some("192.0.2.60".parse().unwrap()));
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "rust-lang.org");
+
+        // elements may be split across multiple headers
+        let info = trusted_info(
+            "10.0.0.2:8080",
+            &[
+                ("forwarded", r#"for="[2001:db8:cafe::17]:4711""#),
+                ("forwarded", r#"For="[2001:db8::1]""#),
+            ],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("2001:db8:cafe::17"));
+
+        // all hops trusted
+        let info = trusted_info("10.0.0.2:8080", &[("forwarded", "for=10.0.0.1")]);
+        assert_eq!(info.realip_remote_addr(), Some("10.0.0.1"));
+
+        // obfuscated identifiers end the chain
+        let info = trusted_info(
+            "10.0.0.2:8080",
+            &[("forwarded", "for=192.0.2.60, for=_hidden, for=10.0.0.1")],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("_hidden"));
+        assert_eq!(info.client_ip(), None);
+    }
+
+    #[test]
+    fn trusted_proxies_x_forwarded_for_chain() {
+        let info = trusted_info(
+            "[::ffff:10.0.0.2]:8080",
+            &[
+                (X_FORWARDED_FOR, "203.0.113.1, 2001:db8:cafe::17"),
+                (X_FORWARDED_FOR, "10.0.0.1"),
+                (X_FORWARDED_PROTO, "https"),
+                (X_FORWARDED_HOST, "rust-lang.org"),
+            ],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("2001:db8:cafe::17"));
+        assert_eq!(info.client_ip(), Some("2001:db8:cafe::17".parse().unwrap()));
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "rust-lang.org");
+
+        // host and scheme are taken from the client's hop
+        let info = trusted_info(
+            "10.0.0.2:8080",
+            &[
+                (X_FORWARDED_FOR, "203.0.113.1, 192.0.2.60, 10.0.0.1"),
+                (X_FORWARDED_PROTO, "http, https, http"),
+                (X_FORWARDED_HOST, "evil.com, rust-lang.org, internal"),
+            ],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "rust-lang.org");
+
+        // otherwise, from the closest proxy
+        let info = trusted_info(
+            "10.0.0.2:8080",
+            &[
+                (X_FORWARDED_FOR, "203.0.113.1, 192.0.2.60"),
+                (X_FORWARDED_PROTO, "http, https"),
+                (X_FORWARDED_HOST, "evil.com, internal, rust-lang.org"),
+            ],
+        );
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "rust-lang.org");
+
+        let info = trusted_info(
+            "10.0.0.2:8080",
+            &[
+                ("forwarded", "for=192.0.2.60"),
+                (X_FORWARDED_PROTO, "http, https"),
+                (X_FORWARDED_HOST, "evil.com, rust-lang.org"),
+            ],
+        );
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "rust-lang.org");
+
+        // no forwarded headers
+        let info = trusted_info("10.0.0.2:8080", &[]);
+        assert_eq!(info.realip_remote_addr(), Some("10.0.0.2"));
+        assert_eq!(info.client_ip(), Some("10.0.0.2".parse().unwrap()));
+    }
+
     #[actix_rt::test]
     async fn real_ip_from_socket_addr() {
         let req = TestRequest::default().to_http_request();
@@ -68,7 +68,9 @@
 //! - `rustls-0_21` - HTTPS support via `rustls` 0.21 crate, supports `HTTP/2`
 //! - `rustls-0_22` - HTTPS support via `rustls` 0.22 crate, supports `HTTP/2`
 //! - `rustls-0_23` - HTTPS support via `rustls` 0.23 crate, supports `HTTP/2`
-//! - `secure-cookies` - secure cookies support
+//! - `http3` - `HTTP/3` support via QUIC listeners, implies `rustls-0_23`
+//! - `secure-cookies` - secure cookies support and [session management](session)
+//! - `openapi` - [OpenAPI document generation](openapi) from routes and extractors
 
 #![doc(html_logo_url = "https://actix.rs/img/logo.png")]
 #![doc(html_favicon_url = "https://actix.rs/favicon.ico")]
@@ -92,6 +94,8 @@ mod helpers;
 pub mod http;
 mod info;
 pub mod middleware;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 mod redirect;
 mod request;
 mod request_data;
@@ -103,8 +107,20 @@ pub mod rt;
 mod scope;
 mod server;
 mod service;
+#[cfg(feature = "secure-cookies")]
+pub mod session;
+mod shutdown;
+pub mod sse;
 pub mod test;
 mod thin_data;
+#[cfg(any(
+    feature = "openssl",
+    feature = "rustls-0_20",
+    feature = "rustls-0_21",
+    feature = "rustls-0_22",
+    feature = "rustls-0_23"
+))]
+pub mod tls;
 pub(crate) mod types;
 pub mod web;
 
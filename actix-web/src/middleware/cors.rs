@@ -0,0 +1,905 @@
+//! For middleware documentation, see [`Cors`].
+
+use std::{
+    fmt,
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use derive_more::{Display, Error};
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    body::EitherBody,
+    dev::{RequestHead, Service, Transform},
+    http::{
+        header::{
+            HeaderMap, HeaderName, HeaderValue, TryIntoHeaderValue,
+            ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+            ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+            ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS,
+            ACCESS_CONTROL_REQUEST_METHOD, ALLOW, ORIGIN, VARY,
+        },
+        Method, StatusCode,
+    },
+    service::{ServiceRequest, ServiceResponse},
+    Error, HttpResponse, ResponseError,
+};
+
+/// Errors that can occur when processing CORS requests.
+///
+/// All errors result in a `400 Bad Request` response.
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum CorsError {
+    /// Origin is not in the allowed list.
+    #[display("Origin is not allowed to make this request")]
+    OriginNotAllowed,
+
+    /// The `Access-Control-Request-Method` header of a preflight request is not a valid method.
+    #[display("Could not parse the Access-Control-Request-Method header")]
+    BadRequestMethod,
+
+    /// The `Access-Control-Request-Headers` header of a preflight request is malformed.
+    #[display("Could not parse the Access-Control-Request-Headers header")]
+    BadRequestHeaders,
+
+    /// Requested method is not allowed.
+    #[display("Requested method is not allowed")]
+    MethodNotAllowed,
+
+    /// One or more requested headers are not allowed.
+    #[display("One or more requested headers are not allowed")]
+    HeadersNotAllowed,
+}
+
+impl ResponseError for CorsError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+type OriginFn = Rc<dyn Fn(&HeaderValue, &RequestHead) -> bool>;
+
+/// Either all values are allowed, or only those in the list.
+#[derive(Debug, Clone)]
+enum AllOrSome<T> {
+    All,
+    Some(Vec<T>),
+}
+
+impl<T: PartialEq> AllOrSome<T> {
+    fn contains(&self, item: &T) -> bool {
+        match self {
+            AllOrSome::All => true,
+            AllOrSome::Some(items) => items.contains(item),
+        }
+    }
+
+    fn add(&mut self, item: T) {
+        match self {
+            AllOrSome::All => {}
+            AllOrSome::Some(items) => {
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+    }
+}
+
+/// Middleware for Cross-Origin Resource Sharing (CORS).
+///
+/// Requests with an `Origin` header are checked against the configured allow-lists:
+///
+/// - Preflight requests (`OPTIONS` requests with an `Access-Control-Request-Method` header) are
+///   validated by the middleware and then passed to the wrapped service to find out whether they
+///   would be routed. Resources do not need to register `OPTIONS` routes: a `405 Method Not
+///   Allowed` response whose `Allow` header includes the requested method is replaced with a
+///   preflight response that only allows the resource's methods. Successful responses are also
+///   replaced with a preflight response. Other responses, such as `404 Not Found`, are returned
+///   unchanged.
+/// - Other requests are passed to the wrapped service and the response gets
+///   `Access-Control-Allow-*` and `Access-Control-Expose-Headers` headers. This includes error
+///   responses, so that browsers can read them.
+///
+/// Requests that fail these checks are rejected with `400 Bad Request`; see [`CorsError`].
+/// Requests without an `Origin` header are not CORS requests and are passed through unchanged.
+///
+/// The `Vary` header is added to all responses whose CORS headers depend on the request.
+///
+/// The default configuration is restrictive: no origins are allowed, only common methods may be
+/// requested, and no headers beyond those safelisted by the CORS spec may be used. Use
+/// [`Cors::permissive()`] during development to allow everything.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::{header, Method}, middleware::Cors, web, App, HttpResponse};
+///
+/// let cors = Cors::default()
+///     .allowed_origin("https://www.rust-lang.org")
+///     .allowed_origin_fn(|origin, _req_head| origin.as_bytes().ends_with(b".rust-lang.org"))
+///     .allowed_methods([Method::GET, Method::POST])
+///     .allowed_header(header::CONTENT_TYPE)
+///     .expose_headers(["x-request-id"])
+///     .supports_credentials()
+///     .max_age(3600);
+///
+/// let app = App::new()
+///     .wrap(cors)
+///     .service(web::resource("/index.html").get(|| HttpResponse::Ok()));
+/// ```
+#[derive(Clone)]
+pub struct Cors {
+    inner: Rc<Inner>,
+}
+
+#[derive(Clone)]
+struct Inner {
+    allowed_origins: AllOrSome<HeaderValue>,
+    allowed_origin_fns: Vec<OriginFn>,
+    allowed_methods: AllOrSome<Method>,
+    allowed_headers: AllOrSome<HeaderName>,
+    expose_headers: AllOrSome<HeaderName>,
+    max_age: Option<usize>,
+    supports_credentials: bool,
+    block_on_origin_mismatch: bool,
+}
+
+impl Default for Cors {
+    /// Constructs a restrictive `Cors` middleware.
+    ///
+    /// No origins are allowed, the `GET`, `HEAD`, `POST`, `PUT`, `PATCH`, `DELETE`, and `OPTIONS`
+    /// methods are allowed, and no headers are allowed or exposed.
+    fn default() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                allowed_origins: AllOrSome::Some(Vec::new()),
+                allowed_origin_fns: Vec::new(),
+                allowed_methods: AllOrSome::Some(vec![
+                    Method::GET,
+                    Method::HEAD,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                    Method::OPTIONS,
+                ]),
+                allowed_headers: AllOrSome::Some(Vec::new()),
+                expose_headers: AllOrSome::Some(Vec::new()),
+                max_age: None,
+                supports_credentials: false,
+                block_on_origin_mismatch: true,
+            }),
+        }
+    }
+}
+
+impl Cors {
+    /// Constructs a very permissive `Cors` middleware.
+    ///
+    /// All origins, methods, and headers are allowed, all response headers are exposed,
+    /// credentials are supported, and preflight responses may be cached for one hour.
+    ///
+    /// This is useful during development but should not be used in production.
+    pub fn permissive() -> Self {
+        Self::default()
+            .allow_any_origin()
+            .allow_any_method()
+            .allow_any_header()
+            .expose_any_header()
+            .supports_credentials()
+            .max_age(3600)
+    }
+
+    /// Allows requests from any origin.
+    ///
+    /// When credentials are supported, the request's origin is sent back instead of `*`, since
+    /// browsers reject the wildcard for credentialed requests.
+    pub fn allow_any_origin(mut self) -> Self {
+        self.inner_mut().allowed_origins = AllOrSome::All;
+        self
+    }
+
+    /// Adds an origin that is allowed to make requests.
+    ///
+    /// The origin must match the request's `Origin` header exactly, e.g.,
+    /// `https://www.rust-lang.org`. Has no effect if [`allow_any_origin`](Self::allow_any_origin)
+    /// is used.
+    ///
+    /// # Panics
+    /// Panics if `origin` is not a valid header value.
+    pub fn allowed_origin(mut self, origin: &str) -> Self {
+        let origin = HeaderValue::from_str(origin).expect("Invalid origin");
+        self.inner_mut().allowed_origins.add(origin);
+        self
+    }
+
+    /// Adds a predicate for origins that are allowed to make requests.
+    ///
+    /// The predicate receives the request's `Origin` header and the request head. Origins are
+    /// allowed if they are in the allowed list or if any predicate returns true.
+    pub fn allowed_origin_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&HeaderValue, &RequestHead) -> bool + 'static,
+    {
+        self.inner_mut().allowed_origin_fns.push(Rc::new(f));
+        self
+    }
+
+    /// Allows any method to be requested in preflight requests.
+    pub fn allow_any_method(mut self) -> Self {
+        self.inner_mut().allowed_methods = AllOrSome::All;
+        self
+    }
+
+    /// Sets the methods that may be requested in preflight requests, replacing the defaults.
+    pub fn allowed_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        let mut allowed = AllOrSome::Some(Vec::new());
+        for method in methods {
+            allowed.add(method);
+        }
+
+        self.inner_mut().allowed_methods = allowed;
+        self
+    }
+
+    /// Allows any header to be requested in preflight requests.
+    pub fn allow_any_header(mut self) -> Self {
+        self.inner_mut().allowed_headers = AllOrSome::All;
+        self
+    }
+
+    /// Adds a header that may be requested in preflight requests.
+    pub fn allowed_header(mut self, header: impl Into<HeaderName>) -> Self {
+        self.inner_mut().allowed_headers.add(header.into());
+        self
+    }
+
+    /// Adds headers that may be requested in preflight requests.
+    ///
+    /// # Panics
+    /// Panics if any header name is invalid.
+    pub fn allowed_headers<H>(mut self, headers: impl IntoIterator<Item = H>) -> Self
+    where
+        HeaderName: TryFrom<H>,
+    {
+        for header in headers {
+            let header =
+                HeaderName::try_from(header).unwrap_or_else(|_| panic!("Invalid header name"));
+            self.inner_mut().allowed_headers.add(header);
+        }
+        self
+    }
+
+    /// Exposes all response headers to the client.
+    pub fn expose_any_header(mut self) -> Self {
+        self.inner_mut().expose_headers = AllOrSome::All;
+        self
+    }
+
+    /// Adds response headers that are exposed to the client.
+    ///
+    /// # Panics
+    /// Panics if any header name is invalid.
+    pub fn expose_headers<H>(mut self, headers: impl IntoIterator<Item = H>) -> Self
+    where
+        HeaderName: TryFrom<H>,
+    {
+        for header in headers {
+            let header =
+                HeaderName::try_from(header).unwrap_or_else(|_| panic!("Invalid header name"));
+            self.inner_mut().expose_headers.add(header);
+        }
+        self
+    }
+
+    /// Sets how long, in seconds, the results of a preflight request may be cached.
+    ///
+    /// By default, no `Access-Control-Max-Age` header is sent.
+    pub fn max_age(mut self, max_age: impl Into<Option<usize>>) -> Self {
+        self.inner_mut().max_age = max_age.into();
+        self
+    }
+
+    /// Allows requests to include credentials, such as cookies and `Authorization` headers.
+    pub fn supports_credentials(mut self) -> Self {
+        self.inner_mut().supports_credentials = true;
+        self
+    }
+
+    /// Sets whether requests from disallowed origins are rejected.
+    ///
+    /// When enabled (the default), non-preflight requests from disallowed origins get a
+    /// `400 Bad Request` response. When disabled, they are passed to the wrapped service and the
+    /// response has no CORS headers, leaving it to the browser to block the response.
+    ///
+    /// Preflight requests from disallowed origins are always rejected.
+    pub fn block_on_origin_mismatch(mut self, block: bool) -> Self {
+        self.inner_mut().block_on_origin_mismatch = block;
+        self
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner {
+        Rc::make_mut(&mut self.inner)
+    }
+}
+
+impl fmt::Debug for Cors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = &self.inner;
+
+        f.debug_struct("Cors")
+            .field("allowed_origins", &inner.allowed_origins)
+            .field("allowed_origin_fns", &inner.allowed_origin_fns.len())
+            .field("allowed_methods", &inner.allowed_methods)
+            .field("allowed_headers", &inner.allowed_headers)
+            .field("expose_headers", &inner.expose_headers)
+            .field("max_age", &inner.max_age)
+            .field("supports_credentials", &inner.supports_credentials)
+            .field("block_on_origin_mismatch", &inner.block_on_origin_mismatch)
+            .finish()
+    }
+}
+
+impl Inner {
+    fn is_origin_allowed(&self, origin: &HeaderValue, head: &RequestHead) -> bool {
+        self.allowed_origins.contains(origin)
+            || self.allowed_origin_fns.iter().any(|f| f(origin, head))
+    }
+
+    /// Returns the value of the `Access-Control-Allow-Origin` header for an allowed origin.
+    fn allow_origin_value(&self, origin: &HeaderValue) -> HeaderValue {
+        if matches!(self.allowed_origins, AllOrSome::All)
+            && self.allowed_origin_fns.is_empty()
+            && !self.supports_credentials
+        {
+            HeaderValue::from_static("*")
+        } else {
+            origin.clone()
+        }
+    }
+
+    /// Validates a preflight request against the configured allow-lists.
+    fn check_preflight(&self, head: &RequestHead) -> Result<Preflight, CorsError> {
+        let origin = head.headers().get(ORIGIN).expect("checked by caller");
+        if !self.is_origin_allowed(origin, head) {
+            return Err(CorsError::OriginNotAllowed);
+        }
+
+        let method = head
+            .headers()
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok())
+            .ok_or(CorsError::BadRequestMethod)?;
+        if !self.allowed_methods.contains(&method) {
+            return Err(CorsError::MethodNotAllowed);
+        }
+
+        let mut requested_headers = Vec::new();
+        for value in head.headers().get_all(ACCESS_CONTROL_REQUEST_HEADERS) {
+            let value = value.to_str().map_err(|_| CorsError::BadRequestHeaders)?;
+
+            for name in value
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                let name = HeaderName::try_from(name).map_err(|_| CorsError::BadRequestHeaders)?;
+                if !self.allowed_headers.contains(&name) {
+                    return Err(CorsError::HeadersNotAllowed);
+                }
+                requested_headers.push(name);
+            }
+        }
+
+        Ok(Preflight {
+            origin: origin.clone(),
+            method,
+            requested_headers,
+        })
+    }
+
+    /// Builds the response to a valid preflight request.
+    ///
+    /// If the methods of the requested resource are known, only those that are also configured are
+    /// allowed.
+    fn preflight_response(
+        &self,
+        preflight: Preflight,
+        resource_methods: Option<Vec<Method>>,
+    ) -> HttpResponse {
+        let Preflight {
+            origin,
+            method,
+            requested_headers,
+        } = preflight;
+
+        let mut res = HttpResponse::Ok();
+
+        res.insert_header((
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            self.allow_origin_value(&origin),
+        ));
+
+        let allowed_methods = match (&self.allowed_methods, resource_methods) {
+            (AllOrSome::All, None) => vec![method],
+            (AllOrSome::Some(methods), None) => methods.clone(),
+            (allowed, Some(methods)) => methods
+                .into_iter()
+                .filter(|method| allowed.contains(method))
+                .collect(),
+        };
+        res.insert_header((
+            ACCESS_CONTROL_ALLOW_METHODS,
+            join(allowed_methods.iter().map(Method::as_str)),
+        ));
+
+        let allowed_headers = match &self.allowed_headers {
+            AllOrSome::All => &requested_headers,
+            AllOrSome::Some(headers) => headers,
+        };
+        if !allowed_headers.is_empty() {
+            res.insert_header((
+                ACCESS_CONTROL_ALLOW_HEADERS,
+                join(allowed_headers.iter().map(HeaderName::as_str)),
+            ));
+        }
+
+        if self.supports_credentials {
+            res.insert_header((ACCESS_CONTROL_ALLOW_CREDENTIALS, "true"));
+        }
+
+        if let Some(max_age) = self.max_age {
+            res.insert_header((ACCESS_CONTROL_MAX_AGE, max_age));
+        }
+
+        res.insert_header((
+            VARY,
+            "Origin, Access-Control-Request-Method, Access-Control-Request-Headers",
+        ));
+
+        res.finish()
+    }
+
+    /// Adds CORS headers to the response of an actual request from an allowed origin.
+    fn add_response_headers(&self, origin: HeaderValue, headers: &mut HeaderMap) {
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            self.allow_origin_value(&origin),
+        );
+
+        if self.supports_credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+
+        let expose_headers = match &self.expose_headers {
+            // browsers only treat `*` as a wildcard for requests without credentials
+            AllOrSome::All if self.supports_credentials => {
+                let mut names = Vec::new();
+                for name in headers.keys() {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name.as_str());
+                    }
+                }
+                Some(join(names))
+            }
+            AllOrSome::All => Some("*".to_owned()),
+            AllOrSome::Some(names) if names.is_empty() => None,
+            AllOrSome::Some(names) => Some(join(names.iter().map(HeaderName::as_str))),
+        };
+        if let Some(value) = expose_headers.and_then(|value| value.try_into_value().ok()) {
+            headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, value);
+        }
+
+        add_vary_origin(headers);
+    }
+}
+
+/// A preflight request that passed the configured checks.
+struct Preflight {
+    origin: HeaderValue,
+    method: Method,
+    requested_headers: Vec<HeaderName>,
+}
+
+/// Returns the methods listed in the `Allow` header of a `405 Method Not Allowed` response.
+fn resource_methods(headers: &HeaderMap) -> Option<Vec<Method>> {
+    let mut methods = Vec::new();
+
+    for value in headers.get_all(ALLOW) {
+        for method in value.to_str().ok()?.split(',').map(str::trim) {
+            if !method.is_empty() {
+                methods.push(Method::from_bytes(method.as_bytes()).ok()?);
+            }
+        }
+    }
+
+    (!methods.is_empty()).then_some(methods)
+}
+
+fn join<'a>(items: impl IntoIterator<Item = &'a str>) -> String {
+    items.into_iter().collect::<Vec<_>>().join(", ")
+}
+
+/// Adds `Origin` to the `Vary` header, unless the response already varies on everything.
+fn add_vary_origin(headers: &mut HeaderMap) {
+    if headers.get_all(VARY).any(|value| value == "*") {
+        return;
+    }
+
+    headers.append(VARY, HeaderValue::from_static("Origin"));
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Cors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = CorsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CorsMiddleware {
+            service: Rc::new(service),
+            inner: Rc::clone(&self.inner),
+        }))
+    }
+}
+
+pub struct CorsMiddleware<S> {
+    service: Rc<S>,
+    inner: Rc<Inner>,
+}
+
+impl<S, B> Service<ServiceRequest> for CorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let inner = Rc::clone(&self.inner);
+
+        let Some(origin) = req.headers().get(ORIGIN).cloned() else {
+            return Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) });
+        };
+
+        if req.method() == Method::OPTIONS
+            && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            let preflight = match inner.check_preflight(req.head()) {
+                Ok(preflight) => preflight,
+                Err(err) => {
+                    return Box::pin(ready(Ok(req.error_response(err).map_into_right_body())))
+                }
+            };
+
+            return Box::pin(async move {
+                // only answer preflights for resources that exist and accept the requested method
+                let res = service.call(req).await?;
+
+                let resource_methods = match res.status() {
+                    StatusCode::METHOD_NOT_ALLOWED => match resource_methods(res.headers()) {
+                        Some(methods) if methods.contains(&preflight.method) => Some(methods),
+                        _ => return Ok(res.map_into_left_body()),
+                    },
+                    status if status.is_success() => None,
+                    _ => return Ok(res.map_into_left_body()),
+                };
+
+                let (req, _) = res.into_parts();
+                let res = inner.preflight_response(preflight, resource_methods);
+                Ok(ServiceResponse::new(req, res).map_into_right_body())
+            });
+        }
+
+        if !inner.is_origin_allowed(&origin, req.head()) {
+            if inner.block_on_origin_mismatch {
+                let res = req.error_response(CorsError::OriginNotAllowed);
+                return Box::pin(ready(Ok(res.map_into_right_body())));
+            }
+
+            return Box::pin(async move {
+                let mut res = service.call(req).await?;
+                add_vary_origin(res.headers_mut());
+                Ok(res.map_into_left_body())
+            });
+        }
+
+        Box::pin(async move {
+            let mut res = service.call(req).await?;
+            inner.add_response_headers(origin, res.headers_mut());
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http::header::AUTHORIZATION,
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    fn preflight(origin: &str, method: &str) -> TestRequest {
+        TestRequest::default()
+            .method(Method::OPTIONS)
+            .insert_header((ORIGIN, origin))
+            .insert_header((ACCESS_CONTROL_REQUEST_METHOD, method))
+    }
+
+    #[actix_rt::test]
+    async fn not_cors_request() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Cors::default())
+                .default_service(web::to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let res = test::call_service(&app, TestRequest::default().to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert!(!res.headers().contains_key(VARY));
+    }
+
+    #[actix_rt::test]
+    async fn preflight_for_resource_without_options_route() {
+        let app = test::init_service(
+            App::new().service(
+                web::resource("/")
+                    .wrap(
+                        Cors::default()
+                            .allowed_origin("https://www.example.com")
+                            .allowed_methods([Method::GET, Method::POST, Method::PUT])
+                            .allowed_header(AUTHORIZATION)
+                            .max_age(3600),
+                    )
+                    .get(HttpResponse::Ok)
+                    .post(HttpResponse::Ok)
+                    .delete(HttpResponse::Ok),
+            ),
+        )
+        .await;
+
+        let req = preflight("https://www.example.com", "POST")
+            .insert_header((ACCESS_CONTROL_REQUEST_HEADERS, "authorization"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://www.example.com"
+        );
+        // only methods that are both configured and routed are allowed
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET, POST"
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "authorization"
+        );
+        assert_eq!(res.headers().get(ACCESS_CONTROL_MAX_AGE).unwrap(), "3600");
+        assert!(res.headers().contains_key(VARY));
+
+        // method is configured but has no route
+        let req = preflight("https://www.example.com", "PUT").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(!res.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        // without CORS headers, OPTIONS is still not allowed
+        let req = TestRequest::default().method(Method::OPTIONS).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[actix_rt::test]
+    async fn preflight_for_unknown_resource() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Cors::default().allowed_origin("https://www.example.com"))
+                .service(web::resource("/").get(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = preflight("https://www.example.com", "GET")
+            .uri("/missing")
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(!res.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let req = preflight("https://www.example.com", "GET").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn preflight_errors() {
+        let app = test::init_service(
+            App::new()
+                .wrap(
+                    Cors::default()
+                        .allowed_origin("https://www.example.com")
+                        .allowed_methods([Method::GET]),
+                )
+                .default_service(web::to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = preflight("https://www.example.org", "GET").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = preflight("https://www.example.com", "DELETE").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = preflight("https://www.example.com", "GET")
+            .insert_header((ACCESS_CONTROL_REQUEST_HEADERS, "x-custom"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn actual_request() {
+        let app = test::init_service(
+            App::new()
+                .wrap(
+                    Cors::default()
+                        .allowed_origin_fn(|origin, _| origin.as_bytes().ends_with(b".example.com"))
+                        .expose_headers(["x-request-id"])
+                        .supports_credentials(),
+                )
+                .default_service(web::to(|| {
+                    HttpResponse::Ok()
+                        .insert_header((VARY, "Accept-Encoding"))
+                        .finish()
+                })),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .insert_header((ORIGIN, "https://api.example.com"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://api.example.com"
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_EXPOSE_HEADERS).unwrap(),
+            "x-request-id"
+        );
+        let vary = res.headers().get_all(VARY).collect::<Vec<_>>();
+        assert_eq!(vary, ["Accept-Encoding", "Origin"]);
+
+        let req = TestRequest::default()
+            .insert_header((ORIGIN, "https://www.example.org"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn origin_mismatch_not_blocked() {
+        let app = test::init_service(
+            App::new()
+                .wrap(
+                    Cors::default()
+                        .allowed_origin("https://www.example.com")
+                        .block_on_origin_mismatch(false),
+                )
+                .default_service(web::to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .insert_header((ORIGIN, "https://www.example.org"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(res.headers().get(VARY).unwrap(), "Origin");
+    }
+
+    #[actix_rt::test]
+    async fn permissive() {
+        let app = test::init_service(
+            App::new()
+                .wrap(Cors::permissive())
+                .service(web::resource("/").route(
+                    web::method(Method::from_bytes(b"PROPFIND").unwrap()).to(HttpResponse::Ok),
+                ))
+                .default_service(web::to(HttpResponse::NotFound)),
+        )
+        .await;
+
+        let req = preflight("https://www.example.com", "PROPFIND")
+            .insert_header((ACCESS_CONTROL_REQUEST_HEADERS, "x-one, x-two"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "PROPFIND"
+        );
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "x-one, x-two"
+        );
+
+        // error responses from the wrapped service also get CORS headers
+        let req = TestRequest::with_uri("/missing")
+            .insert_header((ORIGIN, "https://www.example.com"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://www.example.com"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn configure_after_clone() {
+        let cors = Cors::default().allowed_origin("https://www.example.com");
+
+        // builder methods can still be used on clones, without affecting the original
+        let extended = cors.clone().allowed_origin("https://www.example.org");
+
+        let app = test::init_service(
+            App::new()
+                .service(web::resource("/original").wrap(cors).to(HttpResponse::Ok))
+                .service(
+                    web::resource("/extended")
+                        .wrap(extended)
+                        .to(HttpResponse::Ok),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/original")
+            .insert_header((ORIGIN, "https://www.example.org"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = TestRequest::with_uri("/extended")
+            .insert_header((ORIGIN, "https://www.example.org"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://www.example.org"
+        );
+    }
+}
@@ -0,0 +1,683 @@
+//! For middleware documentation, see [`Metrics`].
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use actix_http::body::{BodySize, MessageBody};
+use bytes::Bytes;
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use crate::{
+    dev::{Handler, Service, Transform},
+    http::{
+        header::{HeaderMap, HeaderValue, CONTENT_TYPE},
+        Method, StatusCode,
+    },
+    service::{ServiceRequest, ServiceResponse},
+    Error, HttpResponse,
+};
+
+/// Upper bounds of the request duration histogram buckets, in seconds.
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Upper bounds of the response size histogram buckets, in bytes.
+const SIZE_BUCKETS: &[f64] = &[
+    100.0,
+    1_000.0,
+    10_000.0,
+    100_000.0,
+    1_000_000.0,
+    10_000_000.0,
+];
+
+/// Route label used for requests that do not match any resource.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware for collecting request metrics in the [Prometheus] text exposition format.
+///
+/// The following metrics are collected:
+/// - `http_requests_total`: counter of completed requests;
+/// - `http_request_duration_seconds`: histogram of the time taken to handle a request, up until
+///   its response body has been sent;
+/// - `http_response_size_bytes`: histogram of the response body sizes;
+/// - `http_requests_in_flight`: gauge of the requests currently being handled.
+///
+/// All metrics are labeled with the request `method` and the `route` pattern that matched the
+/// request (see [`HttpRequest::match_pattern`]). Requests that do not match any resource are
+/// labeled with the `unmatched` route, and requests with non-standard methods are labeled with the
+/// `OTHER` method, so that the number of series stays bounded. Metrics of completed requests are
+/// also labeled with the response `status` class (e.g., `2xx`).
+///
+/// Collected metrics can be exposed using the [`handler`](Self::handler) or rendered directly using
+/// [`render`](Self::render).
+///
+/// Since the `App` factory runs once per worker, `Metrics` created inside it would only collect
+/// the requests handled by that worker. To collect metrics across workers, create it outside the
+/// factory and pass a clone of it to each `App`.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::Metrics, web, App, HttpServer};
+///
+/// let metrics = Metrics::new();
+///
+/// # let _ = || {
+/// HttpServer::new(move || {
+///     App::new()
+///         .wrap(metrics.clone())
+///         .route("/metrics", web::get().to(metrics.handler()))
+/// })
+/// # ;};
+/// ```
+///
+/// [Prometheus]: https://prometheus.io/docs/instrumenting/exposition_formats/
+/// [`HttpRequest::match_pattern`]: crate::HttpRequest::match_pattern
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Metrics {
+    /// Constructs new `Metrics` middleware with no collected metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a handler that responds with the collected metrics.
+    ///
+    /// Responses use the `text/plain; version=0.0.4` content type.
+    pub fn handler(&self) -> impl Handler<(), Output = HttpResponse> {
+        let metrics = self.clone();
+
+        move || {
+            ready(
+                HttpResponse::Ok()
+                    .insert_header((
+                        CONTENT_TYPE,
+                        HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+                    ))
+                    .body(metrics.render()),
+            )
+        }
+    }
+
+    /// Renders the collected metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        self.registry().to_string()
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        // counters are never left in an inconsistent state, so poisoning can be ignored
+        self.registry.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Metrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    type Response = ServiceResponse<MetricsBody<B>>;
+    type Error = Error;
+    type Transform = MetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(MetricsMiddleware {
+            service,
+            metrics: self.clone(),
+        }))
+    }
+}
+
+/// Metrics middleware service.
+pub struct MetricsMiddleware<S> {
+    service: S,
+    metrics: Metrics,
+}
+
+impl<S, B> Service<ServiceRequest> for MetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    type Response = ServiceResponse<MetricsBody<B>>;
+    type Error = Error;
+    type Future = MetricsResponse<S, B>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let labels = RequestLabels {
+            route: req
+                .match_pattern()
+                .unwrap_or_else(|| UNMATCHED_ROUTE.to_owned()),
+            method: method_label(req.method()),
+        };
+
+        let recorder = Recorder::start(self.metrics.clone(), labels);
+
+        MetricsResponse {
+            fut: self.service.call(req),
+            recorder: Some(recorder),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    pub struct MetricsResponse<S, B>
+    where
+        S: Service<ServiceRequest>,
+    {
+        #[pin]
+        fut: S::Future,
+        recorder: Option<Recorder>,
+        _phantom: PhantomData<B>,
+    }
+}
+
+impl<S, B> Future for MetricsResponse<S, B>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    type Output = Result<ServiceResponse<MetricsBody<B>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let res = ready!(this.fut.poll(cx));
+        let mut recorder = this
+            .recorder
+            .take()
+            .expect("MetricsResponse polled after completion");
+
+        match res {
+            Ok(res) => {
+                recorder.status = Some(res.status());
+
+                Poll::Ready(Ok(
+                    res.map_body(move |_, body| MetricsBody { body, recorder })
+                ))
+            }
+
+            Err(err) => {
+                // the error is converted to a response further up; record it here since its body
+                // is not observed by this middleware
+                recorder.status = Some(err.as_response_error().status_code());
+                Poll::Ready(Err(err))
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Response body wrapper that records request metrics once the body has been dropped.
+    pub struct MetricsBody<B> {
+        #[pin]
+        body: B,
+        recorder: Recorder,
+    }
+}
+
+impl<B: MessageBody> MessageBody for MetricsBody<B> {
+    type Error = B::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        self.body.size()
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        match ready!(this.body.poll_next(cx)) {
+            Some(Ok(chunk)) => {
+                this.recorder.size += chunk.len() as u64;
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => Poll::Ready(None),
+        }
+    }
+
+    #[inline]
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        self.project().body.poll_trailers(cx)
+    }
+}
+
+/// Labels shared by all metrics of a request.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RequestLabels {
+    route: String,
+    method: &'static str,
+}
+
+/// Tracks a request while it is in flight and records its metrics when dropped.
+///
+/// Requests that are dropped before a response is produced (e.g., because the client
+/// disconnected) are not counted as completed.
+struct Recorder {
+    metrics: Metrics,
+    labels: Option<RequestLabels>,
+    start: Instant,
+    status: Option<StatusCode>,
+    size: u64,
+}
+
+impl Recorder {
+    fn start(metrics: Metrics, labels: RequestLabels) -> Self {
+        *metrics
+            .registry()
+            .in_flight
+            .entry(labels.clone())
+            .or_default() += 1;
+
+        Self {
+            metrics,
+            labels: Some(labels),
+            start: Instant::now(),
+            status: None,
+            size: 0,
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let labels = self.labels.take().expect("labels are only taken on drop");
+        let mut registry = self.metrics.registry();
+
+        if let Some(in_flight) = registry.in_flight.get_mut(&labels) {
+            *in_flight -= 1;
+        }
+
+        if let Some(status) = self.status {
+            let series = registry
+                .completed
+                .entry((labels, status_class(status)))
+                .or_default();
+
+            series.count += 1;
+            series
+                .duration
+                .observe(DURATION_BUCKETS, self.start.elapsed().as_secs_f64());
+            series.size.observe(SIZE_BUCKETS, self.size as f64);
+        }
+    }
+}
+
+/// Collected metrics, keyed by labels so that they render in a stable order.
+#[derive(Debug, Default)]
+struct Registry {
+    completed: BTreeMap<(RequestLabels, &'static str), Series>,
+    in_flight: BTreeMap<RequestLabels, u64>,
+}
+
+/// Metrics of completed requests with the same labels.
+#[derive(Debug, Default)]
+struct Series {
+    count: u64,
+    duration: Histogram,
+    size: Histogram,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Non-cumulative number of observations per bucket.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, bounds: &[f64], value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; bounds.len()];
+        }
+
+        if let Some(idx) = bounds.iter().position(|&bound| value <= bound) {
+            self.buckets[idx] += 1;
+        }
+
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "# HELP http_requests_total Total number of HTTP requests."
+        )?;
+        writeln!(f, "# TYPE http_requests_total counter")?;
+        for ((labels, status), series) in &self.completed {
+            writeln!(
+                f,
+                "http_requests_total{{{},status=\"{}\"}} {}",
+                labels, status, series.count
+            )?;
+        }
+
+        write_histogram(
+            f,
+            "http_request_duration_seconds",
+            "HTTP request duration in seconds.",
+            DURATION_BUCKETS,
+            self.completed
+                .iter()
+                .map(|(labels, series)| (labels, &series.duration)),
+        )?;
+
+        write_histogram(
+            f,
+            "http_response_size_bytes",
+            "HTTP response body size in bytes.",
+            SIZE_BUCKETS,
+            self.completed
+                .iter()
+                .map(|(labels, series)| (labels, &series.size)),
+        )?;
+
+        writeln!(
+            f,
+            "# HELP http_requests_in_flight Number of HTTP requests currently being handled."
+        )?;
+        writeln!(f, "# TYPE http_requests_in_flight gauge")?;
+        for (labels, count) in &self.in_flight {
+            writeln!(f, "http_requests_in_flight{{{}}} {}", labels, count)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_histogram<'a>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    help: &str,
+    bounds: &[f64],
+    histograms: impl Iterator<Item = (&'a (RequestLabels, &'static str), &'a Histogram)>,
+) -> fmt::Result {
+    writeln!(f, "# HELP {} {}", name, help)?;
+    writeln!(f, "# TYPE {} histogram", name)?;
+
+    for ((labels, status), histogram) in histograms {
+        let labels = format!("{},status=\"{}\"", labels, status);
+
+        let mut cumulative = 0;
+        for (bound, count) in bounds.iter().zip(&histogram.buckets) {
+            cumulative += count;
+            writeln!(
+                f,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, labels, bound, cumulative
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, labels, histogram.count
+        )?;
+        writeln!(f, "{}_sum{{{}}} {}", name, labels, histogram.sum)?;
+        writeln!(f, "{}_count{{{}}} {}", name, labels, histogram.count)?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for RequestLabels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "method=\"{}\",route=\"", self.method)?;
+
+        for ch in self.route.chars() {
+            match ch {
+                '\\' => f.write_str("\\\\")?,
+                '"' => f.write_str("\\\"")?,
+                '\n' => f.write_str("\\n")?,
+                ch => f.write_char(ch)?,
+            }
+        }
+
+        f.write_char('"')
+    }
+}
+
+/// Returns the label for a request method, grouping non-standard methods together.
+fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::DELETE => "DELETE",
+        Method::HEAD => "HEAD",
+        Method::OPTIONS => "OPTIONS",
+        Method::CONNECT => "CONNECT",
+        Method::PATCH => "PATCH",
+        Method::TRACE => "TRACE",
+        _ => "OTHER",
+    }
+}
+
+fn status_class(status: StatusCode) -> &'static str {
+    match status.as_u16() {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_service::IntoService as _;
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        error::{ErrorBadRequest, ErrorInternalServerError},
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    #[test]
+    fn render_recorded_requests() {
+        let metrics = Metrics::new();
+        let labels = |route: &str, method| RequestLabels {
+            route: route.to_owned(),
+            method,
+        };
+
+        let mut recorder = Recorder::start(metrics.clone(), labels("/user/{id:\\d+}", "GET"));
+        recorder.status = Some(StatusCode::OK);
+        recorder.size = 512;
+
+        let in_flight = Recorder::start(metrics.clone(), labels("/upload", "POST"));
+
+        let rendered = metrics.render();
+        assert!(rendered
+            .contains("http_requests_in_flight{method=\"GET\",route=\"/user/{id:\\\\d+}\"} 1\n"));
+        assert!(!rendered.contains("http_requests_total{"));
+
+        drop(recorder);
+        let rendered = metrics.render();
+        let labels = "method=\"GET\",route=\"/user/{id:\\\\d+}\",status=\"2xx\"";
+
+        assert!(rendered.contains(&format!("http_requests_total{{{labels}}} 1\n")));
+        assert!(rendered.contains(&format!(
+            "http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} 1\n"
+        )));
+        assert!(rendered.contains(&format!(
+            "http_response_size_bytes_bucket{{{labels},le=\"100\"}} 0\n"
+        )));
+        assert!(rendered.contains(&format!(
+            "http_response_size_bytes_bucket{{{labels},le=\"1000\"}} 1\n"
+        )));
+        assert!(rendered.contains(&format!("http_response_size_bytes_sum{{{labels}}} 512\n")));
+        assert!(rendered
+            .contains("http_requests_in_flight{method=\"GET\",route=\"/user/{id:\\\\d+}\"} 0\n"));
+        assert!(rendered.contains("http_requests_in_flight{method=\"POST\",route=\"/upload\"} 1\n"));
+
+        // requests dropped without a response are not counted
+        drop(in_flight);
+        let rendered = metrics.render();
+        assert!(!rendered.contains("route=\"/upload\",status="));
+        assert!(rendered.contains("http_requests_in_flight{method=\"POST\",route=\"/upload\"} 0\n"));
+    }
+
+    #[actix_rt::test]
+    async fn streamed_body() {
+        let metrics = Metrics::new();
+
+        let app = test::init_service(App::new().wrap(metrics.clone()).route(
+            "/stream/{n}",
+            web::get().to(|| {
+                let chunks = [0, 1].map(|_| Ok::<_, Error>(Bytes::from_static(&[0; 600])));
+                ready(HttpResponse::Ok().streaming(stream::iter(chunks)))
+            }),
+        ))
+        .await;
+
+        let res = test::call_service(&app, TestRequest::with_uri("/stream/1").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // request is in flight until its body has been sent
+        let rendered = metrics.render();
+        assert!(
+            rendered.contains("http_requests_in_flight{method=\"GET\",route=\"/stream/{n}\"} 1\n")
+        );
+        assert!(!rendered.contains("http_requests_total{"));
+
+        assert_eq!(test::read_body(res).await.len(), 1200);
+
+        let rendered = metrics.render();
+        let labels = "method=\"GET\",route=\"/stream/{n}\",status=\"2xx\"";
+
+        assert!(rendered.contains(&format!("http_requests_total{{{labels}}} 1\n")));
+        assert!(rendered.contains(&format!(
+            "http_response_size_bytes_bucket{{{labels},le=\"1000\"}} 0\n"
+        )));
+        assert!(rendered.contains(&format!(
+            "http_response_size_bytes_bucket{{{labels},le=\"10000\"}} 1\n"
+        )));
+        assert!(rendered.contains(&format!("http_response_size_bytes_sum{{{labels}}} 1200\n")));
+        assert!(
+            rendered.contains("http_requests_in_flight{method=\"GET\",route=\"/stream/{n}\"} 0\n")
+        );
+    }
+
+    #[actix_rt::test]
+    async fn error_responses() {
+        let metrics = Metrics::new();
+
+        let app = test::init_service(App::new().wrap(metrics.clone()).route(
+            "/",
+            web::post().to(|| async { Err::<HttpResponse, _>(ErrorBadRequest("bad request")) }),
+        ))
+        .await;
+
+        let req = TestRequest::post().uri("/").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        test::read_body(res).await;
+
+        // errors that are returned through the middleware are converted to responses further up
+        let srv = metrics
+            .new_transform(
+                (|_| ready(Err::<ServiceResponse, _>(ErrorInternalServerError("error"))))
+                    .into_service(),
+            )
+            .await
+            .unwrap();
+
+        let err = srv
+            .call(TestRequest::default().to_srv_request())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let rendered = metrics.render();
+        assert!(rendered
+            .contains("http_requests_total{method=\"POST\",route=\"/\",status=\"4xx\"} 1\n"));
+        assert!(rendered.contains(
+            "http_requests_total{method=\"GET\",route=\"unmatched\",status=\"5xx\"} 1\n"
+        ));
+        assert!(rendered.contains(
+            "http_response_size_bytes_count{method=\"GET\",route=\"unmatched\",status=\"5xx\"} 1\n"
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn handler() {
+        let metrics = Metrics::new();
+
+        let app = test::init_service(
+            App::new()
+                .wrap(metrics.clone())
+                .route("/metrics", web::get().to(metrics.handler())),
+        )
+        .await;
+
+        let res = test::call_service(&app, TestRequest::with_uri("/missing").to_request()).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        test::read_body(res).await;
+
+        let res = test::call_service(&app, TestRequest::with_uri("/metrics").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(CONTENT_TYPE).unwrap(),
+            "text/plain; version=0.0.4; charset=utf-8"
+        );
+
+        let body = test::read_body(res).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.starts_with("# HELP http_requests_total "));
+        assert!(body.contains(
+            "http_requests_total{method=\"GET\",route=\"unmatched\",status=\"4xx\"} 1\n"
+        ));
+        // the metrics request is still being handled while rendering
+        assert!(body.contains("http_requests_in_flight{method=\"GET\",route=\"/metrics\"} 1\n"));
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(method_label(&Method::PATCH), "PATCH");
+        assert_eq!(
+            method_label(&Method::from_bytes(b"PROPFIND").unwrap()),
+            "OTHER"
+        );
+
+        assert_eq!(status_class(StatusCode::SWITCHING_PROTOCOLS), "1xx");
+        assert_eq!(status_class(StatusCode::NOT_FOUND), "4xx");
+        assert_eq!(status_class(StatusCode::BAD_GATEWAY), "5xx");
+
+        let labels = RequestLabels {
+            route: "/a\"b\nc".to_owned(),
+            method: "GET",
+        };
+        assert_eq!(labels.to_string(), r#"method="GET",route="/a\"b\nc""#);
+    }
+}
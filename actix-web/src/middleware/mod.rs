@@ -246,24 +246,32 @@ mod compat;
 #[cfg(feature = "__compress")]
 mod compress;
 mod condition;
+mod cors;
 mod default_headers;
 mod err_handlers;
 mod from_fn;
 mod identity;
 mod logger;
+mod metrics;
 mod normalize;
+mod rate_limit;
+mod tracing_logger;
 
 #[cfg(feature = "__compress")]
 pub use self::compress::Compress;
 pub use self::{
     compat::Compat,
     condition::Condition,
+    cors::{Cors, CorsError},
     default_headers::DefaultHeaders,
     err_handlers::{ErrorHandlerResponse, ErrorHandlers},
     from_fn::{from_fn, Next},
     identity::Identity,
     logger::Logger,
+    metrics::Metrics,
     normalize::{NormalizePath, TrailingSlash},
+    rate_limit::{InMemoryStore, RateLimitStatus, RateLimitStore, RateLimitStrategy, RateLimiter},
+    tracing_logger::TracingLogger,
 };
 
 #[cfg(test)]
@@ -0,0 +1,770 @@
+//! For middleware documentation, see [`RateLimiter`].
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    future::{ready, Ready},
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    body::EitherBody,
+    dev::{Service, Transform},
+    http::header::{HeaderName, HeaderValue, RETRY_AFTER},
+    service::{ServiceRequest, ServiceResponse},
+    Error, HttpResponse,
+};
+
+/// Period after which the in-memory store removes state for keys that have not been seen.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+// Header names from the IETF "RateLimit header fields for HTTP" draft.
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Middleware for limiting the rate of requests from clients.
+///
+/// Requests are grouped by a key, by default the peer IP address as reported by
+/// [`ConnectionInfo::peer_addr`]. Each key is allowed a number of requests according to the
+/// chosen [`RateLimitStrategy`]. Requests beyond the limit are rejected with
+/// `429 Too Many Requests` and a `Retry-After` header. All other responses get
+/// `RateLimit-Limit`, `RateLimit-Remaining`, and `RateLimit-Reset` headers.
+///
+/// Requests for which no key can be extracted are not limited.
+///
+/// State is kept in a [`RateLimitStore`], by default an [`InMemoryStore`]. Since the `App`
+/// factory runs once per worker, a store created inside it would only count the requests handled
+/// by that worker. To enforce limits across workers, create the store outside the factory and pass
+/// a clone of it to each `RateLimiter` using [`store`](Self::store).
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{middleware::{InMemoryStore, RateLimiter}, App, HttpServer};
+///
+/// // allow bursts of 10 requests, then one request per second
+/// let store = InMemoryStore::new();
+///
+/// # let _ = || {
+/// HttpServer::new(move || {
+///     App::new().wrap(
+///         RateLimiter::token_bucket(10, Duration::from_secs(1)).store(store.clone()),
+///     )
+/// })
+/// # ;};
+///
+/// // allow 100 requests per minute from each API key
+/// let app = App::new().wrap(
+///     RateLimiter::sliding_window(100, Duration::from_secs(60)).key_header("x-api-key"),
+/// );
+/// ```
+///
+/// [`ConnectionInfo::peer_addr`]: crate::dev::ConnectionInfo::peer_addr
+#[derive(Clone)]
+pub struct RateLimiter {
+    inner: Inner,
+}
+
+type KeyFn = Rc<dyn Fn(&ServiceRequest) -> Option<String>>;
+
+#[derive(Clone)]
+struct Inner {
+    strategy: RateLimitStrategy,
+    key: KeyFn,
+    store: Rc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    /// Constructs a `RateLimiter` using the given strategy and an [`InMemoryStore`].
+    pub fn new(strategy: RateLimitStrategy) -> Self {
+        Self {
+            inner: Inner {
+                strategy,
+                key: Rc::new(peer_ip_key),
+                store: Rc::new(InMemoryStore::new()),
+            },
+        }
+    }
+
+    /// Constructs a `RateLimiter` using the [token bucket](RateLimitStrategy::TokenBucket)
+    /// strategy.
+    pub fn token_bucket(capacity: u32, refill_interval: Duration) -> Self {
+        Self::new(RateLimitStrategy::TokenBucket {
+            capacity,
+            refill_interval,
+        })
+    }
+
+    /// Constructs a `RateLimiter` using the [sliding window](RateLimitStrategy::SlidingWindow)
+    /// strategy.
+    pub fn sliding_window(limit: u32, window: Duration) -> Self {
+        Self::new(RateLimitStrategy::SlidingWindow { limit, window })
+    }
+
+    /// Sets the store used to keep rate limiting state.
+    pub fn store(mut self, store: impl RateLimitStore + 'static) -> Self {
+        self.inner.store = Rc::new(store);
+        self
+    }
+
+    /// Groups requests by the peer IP address, as reported by [`ConnectionInfo::peer_addr`].
+    ///
+    /// This is the default.
+    ///
+    /// [`ConnectionInfo::peer_addr`]: crate::dev::ConnectionInfo::peer_addr
+    pub fn key_peer_ip(self) -> Self {
+        self.key_fn(peer_ip_key)
+    }
+
+    /// Groups requests by the value of a request header.
+    ///
+    /// Requests without the header are not limited.
+    ///
+    /// # Panics
+    /// Panics if `name` is not a valid header name.
+    pub fn key_header<H>(self, name: H) -> Self
+    where
+        HeaderName: TryFrom<H>,
+    {
+        let name = HeaderName::try_from(name).unwrap_or_else(|_| panic!("Invalid header name"));
+
+        self.key_fn(move |req| {
+            req.headers()
+                .get(&name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        })
+    }
+
+    /// Groups requests by their path.
+    pub fn key_path(self) -> Self {
+        self.key_fn(|req| Some(req.path().to_owned()))
+    }
+
+    /// Groups requests by a key returned from the given function.
+    ///
+    /// Requests for which the function returns `None` are not limited.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use actix_web::middleware::RateLimiter;
+    ///
+    /// // limit each client separately for each path
+    /// let limiter = RateLimiter::sliding_window(10, Duration::from_secs(1)).key_fn(|req| {
+    ///     let peer = req.connection_info().peer_addr()?.to_owned();
+    ///     Some(format!("{peer} {}", req.path()))
+    /// });
+    /// ```
+    pub fn key_fn<F>(mut self, key: F) -> Self
+    where
+        F: Fn(&ServiceRequest) -> Option<String> + 'static,
+    {
+        self.inner.key = Rc::new(key);
+        self
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("strategy", &self.inner.strategy)
+            .finish_non_exhaustive()
+    }
+}
+
+fn peer_ip_key(req: &ServiceRequest) -> Option<String> {
+    req.connection_info().peer_addr().map(str::to_owned)
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimiterMiddleware {
+            service: Rc::new(service),
+            inner: Rc::new(self.inner.clone()),
+        }))
+    }
+}
+
+pub struct RateLimiterMiddleware<S> {
+    service: Rc<S>,
+    inner: Rc<Inner>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        let Some(key) = (self.inner.key)(&req) else {
+            return Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) });
+        };
+
+        let status = self.inner.store.acquire(key, self.inner.strategy);
+
+        Box::pin(async move {
+            let status = status.await?;
+
+            if !status.is_allowed() {
+                let mut res = HttpResponse::TooManyRequests();
+                res.insert_header((RETRY_AFTER, secs_header_value(status.retry_after())));
+                let mut res = req.into_response(res.finish()).map_into_right_body();
+                insert_headers(&mut res, &status);
+                return Ok(res);
+            }
+
+            let mut res = service.call(req).await?.map_into_left_body();
+            insert_headers(&mut res, &status);
+            Ok(res)
+        })
+    }
+}
+
+fn insert_headers<B>(res: &mut ServiceResponse<B>, status: &RateLimitStatus) {
+    let headers = res.headers_mut();
+    headers.insert(RATELIMIT_LIMIT, status.limit().into());
+    headers.insert(RATELIMIT_REMAINING, status.remaining().into());
+    headers.insert(RATELIMIT_RESET, secs_header_value(status.reset()));
+}
+
+/// Formats a duration as whole seconds, rounding up.
+fn secs_header_value(dur: Duration) -> HeaderValue {
+    let secs = dur
+        .as_secs()
+        .saturating_add(u64::from(dur.subsec_nanos() > 0));
+    secs.into()
+}
+
+/// Strategy used by [`RateLimiter`] to decide whether a request is allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RateLimitStrategy {
+    /// Allows bursts of up to `capacity` requests. Each request takes a token from the bucket,
+    /// which is refilled by one token every `refill_interval`.
+    TokenBucket {
+        /// Maximum number of tokens in the bucket.
+        capacity: u32,
+
+        /// Time taken to add one token to the bucket.
+        refill_interval: Duration,
+    },
+
+    /// Allows up to `limit` requests in any period of length `window`.
+    SlidingWindow {
+        /// Maximum number of requests in a window.
+        limit: u32,
+
+        /// Length of the window.
+        window: Duration,
+    },
+}
+
+impl RateLimitStrategy {
+    /// Returns the maximum number of requests that can be made in a burst.
+    pub fn limit(&self) -> u32 {
+        match *self {
+            Self::TokenBucket { capacity, .. } => capacity,
+            Self::SlidingWindow { limit, .. } => limit,
+        }
+    }
+}
+
+/// Outcome of recording a request in a [`RateLimitStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    reset: Duration,
+    retry_after: Duration,
+}
+
+impl RateLimitStatus {
+    /// Constructs the status of an allowed request.
+    ///
+    /// `reset` is the time until the full limit is available again.
+    pub fn allowed(limit: u32, remaining: u32, reset: Duration) -> Self {
+        Self {
+            allowed: true,
+            limit,
+            remaining,
+            reset,
+            retry_after: Duration::ZERO,
+        }
+    }
+
+    /// Constructs the status of a request that exceeded the limit.
+    ///
+    /// `reset` is the time until the full limit is available again and `retry_after` is the time
+    /// until the next request will be allowed.
+    pub fn exceeded(limit: u32, reset: Duration, retry_after: Duration) -> Self {
+        Self {
+            allowed: false,
+            limit,
+            remaining: 0,
+            reset,
+            retry_after,
+        }
+    }
+
+    /// Returns true if the request is allowed.
+    pub fn is_allowed(&self) -> bool {
+        self.allowed
+    }
+
+    /// Returns the maximum number of requests that can be made in a burst.
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Returns the number of requests that can be made immediately after this one.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Returns the time until the full limit is available again.
+    pub fn reset(&self) -> Duration {
+        self.reset
+    }
+
+    /// Returns the time until the next request will be allowed. Zero for allowed requests.
+    pub fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+}
+
+/// Storage for [`RateLimiter`] state.
+///
+/// Implement this trait to share rate limits between processes, e.g., using an external database.
+/// Implementations must apply the strategy atomically so that concurrent requests are counted
+/// correctly.
+pub trait RateLimitStore {
+    /// Records a request for `key` according to `strategy` and returns whether it is allowed.
+    ///
+    /// Requests that are not allowed should not count towards the limit.
+    fn acquire(
+        &self,
+        key: String,
+        strategy: RateLimitStrategy,
+    ) -> LocalBoxFuture<'static, Result<RateLimitStatus, Error>>;
+}
+
+/// In-memory [`RateLimitStore`].
+///
+/// Clones share the same state, including across threads. State for keys that have not been seen
+/// for a while is removed periodically.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStore {
+    inner: Arc<Mutex<InMemoryInner>>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryInner {
+    entries: HashMap<(RateLimitStrategy, String), Entry>,
+    last_sweep: Option<Instant>,
+}
+
+#[derive(Debug)]
+enum Entry {
+    TokenBucket { tokens: f64, updated: Instant },
+    SlidingWindow { requests: VecDeque<Instant> },
+}
+
+impl InMemoryStore {
+    /// Constructs an empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn acquire_at(
+        &self,
+        key: String,
+        strategy: RateLimitStrategy,
+        now: Instant,
+    ) -> RateLimitStatus {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner
+            .last_sweep
+            .map_or(true, |last| now.duration_since(last) >= SWEEP_INTERVAL)
+        {
+            inner
+                .entries
+                .retain(|(strategy, _), entry| !entry.is_idle(strategy, now));
+            inner.last_sweep = Some(now);
+        }
+
+        inner
+            .entries
+            .entry((strategy, key))
+            .or_insert_with(|| Entry::new(&strategy, now))
+            .acquire(&strategy, now)
+    }
+}
+
+impl RateLimitStore for InMemoryStore {
+    fn acquire(
+        &self,
+        key: String,
+        strategy: RateLimitStrategy,
+    ) -> LocalBoxFuture<'static, Result<RateLimitStatus, Error>> {
+        let status = self.acquire_at(key, strategy, Instant::now());
+        Box::pin(ready(Ok(status)))
+    }
+}
+
+impl Entry {
+    fn new(strategy: &RateLimitStrategy, now: Instant) -> Self {
+        match *strategy {
+            RateLimitStrategy::TokenBucket { capacity, .. } => Self::TokenBucket {
+                tokens: f64::from(capacity),
+                updated: now,
+            },
+            RateLimitStrategy::SlidingWindow { .. } => Self::SlidingWindow {
+                requests: VecDeque::new(),
+            },
+        }
+    }
+
+    /// Returns true if the entry is back to its initial state and can be removed.
+    fn is_idle(&self, strategy: &RateLimitStrategy, now: Instant) -> bool {
+        match (self, *strategy) {
+            (
+                Self::TokenBucket { updated, .. },
+                RateLimitStrategy::TokenBucket {
+                    capacity,
+                    refill_interval,
+                },
+            ) => refill_interval
+                .checked_mul(capacity)
+                .is_some_and(|full| now.duration_since(*updated) >= full),
+
+            (Self::SlidingWindow { requests }, RateLimitStrategy::SlidingWindow { window, .. }) => {
+                requests
+                    .back()
+                    .map_or(true, |last| now.duration_since(*last) >= window)
+            }
+
+            _ => true,
+        }
+    }
+
+    fn acquire(&mut self, strategy: &RateLimitStrategy, now: Instant) -> RateLimitStatus {
+        match (self, *strategy) {
+            (
+                Self::TokenBucket { tokens, updated },
+                RateLimitStrategy::TokenBucket {
+                    capacity,
+                    refill_interval,
+                },
+            ) => {
+                let capacity_f = f64::from(capacity);
+                let interval = refill_interval.as_secs_f64();
+
+                if interval > 0.0 {
+                    let refilled = now.duration_since(*updated).as_secs_f64() / interval;
+                    *tokens = (*tokens + refilled).min(capacity_f);
+                } else {
+                    *tokens = capacity_f;
+                }
+                *updated = now;
+
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+
+                    let reset = secs_f64_duration((capacity_f - *tokens) * interval);
+                    RateLimitStatus::allowed(capacity, *tokens as u32, reset)
+                } else {
+                    let reset = secs_f64_duration((capacity_f - *tokens) * interval);
+                    let retry_after = secs_f64_duration((1.0 - *tokens) * interval);
+                    RateLimitStatus::exceeded(capacity, reset, retry_after)
+                }
+            }
+
+            (
+                Self::SlidingWindow { requests },
+                RateLimitStrategy::SlidingWindow { limit, window },
+            ) => {
+                while requests
+                    .front()
+                    .is_some_and(|req| now.duration_since(*req) >= window)
+                {
+                    requests.pop_front();
+                }
+
+                let allowed = requests.len() < limit as usize;
+                if allowed {
+                    requests.push_back(now);
+                }
+
+                // the oldest request in the window is the next to expire
+                let retry_after = requests.front().map_or(Duration::ZERO, |oldest| {
+                    window.saturating_sub(now.duration_since(*oldest))
+                });
+                let reset = requests.back().map_or(Duration::ZERO, |newest| {
+                    window.saturating_sub(now.duration_since(*newest))
+                });
+
+                if allowed {
+                    let remaining = limit - requests.len() as u32;
+                    RateLimitStatus::allowed(limit, remaining, reset)
+                } else {
+                    RateLimitStatus::exceeded(limit, reset, retry_after)
+                }
+            }
+
+            // entries are keyed by strategy so variants always match
+            (entry, _) => {
+                *entry = Entry::new(strategy, now);
+                entry.acquire(strategy, now)
+            }
+        }
+    }
+}
+
+/// Converts seconds to a duration, saturating at [`Duration::MAX`].
+fn secs_f64_duration(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_service::IntoService;
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{self, TestRequest},
+    };
+
+    fn ok_service(
+    ) -> impl Service<ServiceRequest, Response = ServiceResponse, Error = Error> + 'static {
+        (|req: ServiceRequest| ready(Ok(req.into_response(HttpResponse::Ok().finish()))))
+            .into_service()
+    }
+
+    #[test]
+    fn token_bucket() {
+        let store = InMemoryStore::new();
+        let strategy = RateLimitStrategy::TokenBucket {
+            capacity: 2,
+            refill_interval: Duration::from_secs(10),
+        };
+        let now = Instant::now();
+
+        let status = store.acquire_at("a".to_owned(), strategy, now);
+        assert!(status.is_allowed());
+        assert_eq!(status.remaining(), 1);
+        assert_eq!(status.reset(), Duration::from_secs(10));
+
+        let status = store.acquire_at("a".to_owned(), strategy, now);
+        assert!(status.is_allowed());
+        assert_eq!(status.remaining(), 0);
+        assert_eq!(status.reset(), Duration::from_secs(20));
+
+        let status = store.acquire_at("a".to_owned(), strategy, now + Duration::from_secs(5));
+        assert!(!status.is_allowed());
+        assert_eq!(status.retry_after(), Duration::from_secs(5));
+
+        // other keys are not affected
+        assert!(store.acquire_at("b".to_owned(), strategy, now).is_allowed());
+
+        let status = store.acquire_at("a".to_owned(), strategy, now + Duration::from_secs(10));
+        assert!(status.is_allowed());
+        assert_eq!(status.remaining(), 0);
+    }
+
+    #[test]
+    fn sliding_window() {
+        let store = InMemoryStore::new();
+        let strategy = RateLimitStrategy::SlidingWindow {
+            limit: 2,
+            window: Duration::from_secs(10),
+        };
+        let now = Instant::now();
+
+        let status = store.acquire_at("a".to_owned(), strategy, now);
+        assert!(status.is_allowed());
+        assert_eq!(status.remaining(), 1);
+
+        let status = store.acquire_at("a".to_owned(), strategy, now + Duration::from_secs(4));
+        assert!(status.is_allowed());
+        assert_eq!(status.remaining(), 0);
+        assert_eq!(status.reset(), Duration::from_secs(10));
+
+        let status = store.acquire_at("a".to_owned(), strategy, now + Duration::from_secs(6));
+        assert!(!status.is_allowed());
+        assert_eq!(status.retry_after(), Duration::from_secs(4));
+        assert_eq!(status.reset(), Duration::from_secs(8));
+
+        // first request has left the window
+        let status = store.acquire_at("a".to_owned(), strategy, now + Duration::from_secs(10));
+        assert!(status.is_allowed());
+        assert_eq!(status.remaining(), 0);
+    }
+
+    #[test]
+    fn idle_entries_are_removed() {
+        let store = InMemoryStore::new();
+        let strategy = RateLimitStrategy::SlidingWindow {
+            limit: 2,
+            window: Duration::from_secs(10),
+        };
+        let now = Instant::now();
+
+        store.acquire_at("a".to_owned(), strategy, now);
+        store.acquire_at("b".to_owned(), strategy, now + SWEEP_INTERVAL);
+        assert_eq!(store.inner.lock().unwrap().entries.len(), 1);
+    }
+
+    #[test]
+    fn long_refill_interval() {
+        let store = InMemoryStore::new();
+        let strategy = RateLimitStrategy::TokenBucket {
+            capacity: u32::MAX,
+            refill_interval: Duration::MAX,
+        };
+        let now = Instant::now();
+
+        let status = store.acquire_at("a".to_owned(), strategy, now);
+        assert!(status.is_allowed());
+        assert_eq!(status.reset(), Duration::MAX);
+
+        // entry is never considered idle, rather than overflowing
+        store.acquire_at("b".to_owned(), strategy, now + SWEEP_INTERVAL);
+        assert_eq!(store.inner.lock().unwrap().entries.len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn limits_requests() {
+        let mw = RateLimiter::token_bucket(1, Duration::from_secs(60))
+            .new_transform(ok_service())
+            .await
+            .unwrap();
+
+        let req = TestRequest::default()
+            .peer_addr("127.0.0.1:8080".parse().unwrap())
+            .to_srv_request();
+        let res = test::call_service(&mw, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(RATELIMIT_LIMIT).unwrap(), "1");
+        assert_eq!(res.headers().get(RATELIMIT_REMAINING).unwrap(), "0");
+        assert_eq!(res.headers().get(RATELIMIT_RESET).unwrap(), "60");
+
+        let req = TestRequest::default()
+            .peer_addr("127.0.0.1:8081".parse().unwrap())
+            .to_srv_request();
+        let res = test::call_service(&mw, req).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "60");
+        assert_eq!(res.headers().get(RATELIMIT_REMAINING).unwrap(), "0");
+
+        // different peer IP
+        let req = TestRequest::default()
+            .peer_addr("127.0.0.2:8080".parse().unwrap())
+            .to_srv_request();
+        let res = test::call_service(&mw, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn custom_keys() {
+        let mw = RateLimiter::sliding_window(1, Duration::from_secs(60))
+            .key_header("x-api-key")
+            .new_transform(ok_service())
+            .await
+            .unwrap();
+
+        let req = TestRequest::default()
+            .insert_header(("x-api-key", "abc"))
+            .to_srv_request();
+        let res = test::call_service(&mw, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = TestRequest::default()
+            .insert_header(("x-api-key", "abc"))
+            .to_srv_request();
+        let res = test::call_service(&mw, req).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // requests without a key are not limited
+        let res = test::call_service(&mw, TestRequest::default().to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(RATELIMIT_LIMIT));
+
+        let mw = RateLimiter::sliding_window(1, Duration::from_secs(60))
+            .key_path()
+            .new_transform(ok_service())
+            .await
+            .unwrap();
+
+        let res = test::call_service(&mw, TestRequest::with_uri("/a").to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = test::call_service(&mw, TestRequest::with_uri("/b").to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = test::call_service(&mw, TestRequest::with_uri("/a").to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[actix_rt::test]
+    async fn shared_store() {
+        let store = InMemoryStore::new();
+
+        let limiter = || {
+            RateLimiter::token_bucket(1, Duration::from_secs(60))
+                .key_fn(|_| Some("key".to_owned()))
+                .store(store.clone())
+        };
+
+        let mw1 = limiter().new_transform(ok_service()).await.unwrap();
+        let mw2 = limiter().new_transform(ok_service()).await.unwrap();
+
+        let res = test::call_service(&mw1, TestRequest::default().to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = test::call_service(&mw2, TestRequest::default().to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[actix_rt::test]
+    async fn configure_after_clone() {
+        let limiter = RateLimiter::sliding_window(1, Duration::from_secs(60));
+        let _transform = limiter.new_transform(ok_service()).await.unwrap();
+
+        // builder methods can still be used on clones of a limiter that is in use
+        let mw = limiter
+            .clone()
+            .key_path()
+            .new_transform(ok_service())
+            .await
+            .unwrap();
+
+        let res = test::call_service(&mw, TestRequest::with_uri("/a").to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = test::call_service(&mw, TestRequest::with_uri("/a").to_srv_request()).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -0,0 +1,220 @@
+//! For middleware documentation, see [`TracingLogger`].
+
+use std::{
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use actix_http::trace::TraceContext;
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use tracing::{field::Empty, Span};
+
+use crate::{
+    dev::{Service, Transform},
+    http::header::Header as _,
+    service::{ServiceRequest, ServiceResponse},
+    Error, HttpMessage as _,
+};
+
+/// Middleware for emitting a [`tracing`] span for each request.
+///
+/// The span is named `HTTP request` and has these fields:
+/// - `http.method`: request method;
+/// - `http.route`: matched route pattern (see [`HttpRequest::match_pattern`]), if any;
+/// - `http.target`: request URI;
+/// - `http.status_code`: response status code;
+/// - `latency_ms`: time taken to produce the response head, in milliseconds;
+/// - `trace_id`, `span_id`, and `parent_span_id`: [W3C Trace Context] identifiers;
+/// - `error`: error returned by the service, if any.
+///
+/// # Trace Context
+/// Requests with a valid `traceparent` header continue the caller's trace; all other requests
+/// start a new trace. The request's [`TraceContext`] is available from request extensions and, while
+/// the request is being handled, from [`TraceContext::current`]; the `awc` client uses the latter to
+/// propagate the trace to outgoing requests.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::TracingLogger, App};
+///
+/// let app = App::new().wrap(TracingLogger::default());
+/// ```
+///
+/// [`HttpRequest::match_pattern`]: crate::HttpRequest::match_pattern
+/// [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct TracingLogger;
+
+impl TracingLogger {
+    /// Constructs new `TracingLogger` middleware.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for TracingLogger
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TracingLoggerMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TracingLoggerMiddleware { service }))
+    }
+}
+
+/// Tracing middleware service.
+pub struct TracingLoggerMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for TracingLoggerMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = TracingLoggerResponse<S, B>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let parent = TraceContext::parse(&req).ok();
+        let ctx = parent.map_or_else(TraceContext::new_root, |parent| parent.child());
+
+        let span = tracing::info_span!(
+            "HTTP request",
+            http.method = %req.method(),
+            http.route = Empty,
+            http.target = %req.uri(),
+            http.status_code = Empty,
+            latency_ms = Empty,
+            trace_id = %format_args!("{:032x}", ctx.trace_id()),
+            span_id = %format_args!("{:016x}", ctx.span_id()),
+            parent_span_id = Empty,
+            error = Empty,
+        );
+
+        if let Some(route) = req.match_pattern() {
+            span.record("http.route", route);
+        }
+
+        if let Some(parent) = parent {
+            span.record(
+                "parent_span_id",
+                tracing::field::display(format_args!("{:016x}", parent.span_id())),
+            );
+        }
+
+        req.extensions_mut().insert(ctx);
+
+        let fut = span.in_scope(|| ctx.scope(|| self.service.call(req)));
+
+        TracingLoggerResponse {
+            fut,
+            span,
+            ctx,
+            start: Instant::now(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pin_project! {
+    pub struct TracingLoggerResponse<S, B>
+    where
+        S: Service<ServiceRequest>,
+    {
+        #[pin]
+        fut: S::Future,
+        span: Span,
+        ctx: TraceContext,
+        start: Instant,
+        _phantom: PhantomData<B>,
+    }
+}
+
+impl<S, B> Future for TracingLoggerResponse<S, B>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Output = Result<ServiceResponse<B>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let _enter = this.span.enter();
+        let fut = this.fut;
+        let res = ready!(this.ctx.scope(|| fut.poll(cx)));
+
+        let status = match &res {
+            Ok(res) => res.status(),
+            Err(err) => {
+                this.span.record("error", tracing::field::display(err));
+                err.as_response_error().status_code()
+            }
+        };
+
+        this.span.record("http.status_code", status.as_u16());
+        this.span
+            .record("latency_ms", this.start.elapsed().as_secs_f64() * 1000.0);
+
+        Poll::Ready(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_service::IntoService;
+
+    use super::*;
+    use crate::{http::header::TRACEPARENT, test::TestRequest, HttpResponse};
+
+    #[actix_rt::test]
+    async fn propagates_trace_context() {
+        let srv = |req: ServiceRequest| {
+            let ctx = *req.extensions().get::<TraceContext>().unwrap();
+            assert_eq!(TraceContext::current(), Some(ctx));
+            ready(Ok::<_, Error>(
+                req.into_response(HttpResponse::Ok().body(ctx.to_string())),
+            ))
+        };
+
+        let mw = TracingLogger::new()
+            .new_transform(srv.into_service())
+            .await
+            .unwrap();
+
+        let req = TestRequest::default()
+            .insert_header((
+                TRACEPARENT,
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            ))
+            .to_srv_request();
+        let res = mw.call(req).await.unwrap();
+
+        let ctx = res.request().extensions().get::<TraceContext>().copied();
+        let ctx = ctx.unwrap();
+        assert_eq!(ctx.trace_id(), 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_ne!(ctx.span_id(), 0x00f067aa0ba902b7);
+        assert!(TraceContext::current().is_none());
+
+        // invalid trace context starts a new trace
+        let req = TestRequest::default()
+            .insert_header((TRACEPARENT, "invalid"))
+            .to_srv_request();
+        let res = mw.call(req).await.unwrap();
+
+        let ctx = res.request().extensions().get::<TraceContext>().copied();
+        assert_ne!(ctx.unwrap().trace_id(), 0x4bf92f3577b34da6a3ce929d0e0e4736);
+    }
+}
@@ -0,0 +1,257 @@
+use actix_http::{Method, Uri};
+use bytes::Bytes;
+use schemars::{JsonSchema, SchemaGenerator};
+use serde_json::{json, Value};
+
+use super::spec::{Operation, Parameter, ParameterLocation, RequestBody};
+use crate::{
+    dev::{ConnectionInfo, Payload as DevPayload, PeerAddr},
+    http::header::Header as ParseHeader,
+    sse::LastEventId,
+    web::{Data, Form, Header, Json, Path, Payload, Query, ReqData, ThinData, Valid},
+    HttpRequest,
+};
+
+/// Context passed to [`ApiExtractor::describe`].
+pub struct ApiContext<'a> {
+    pub(crate) operation: &'a mut Operation,
+    pub(crate) generator: &'a mut SchemaGenerator,
+    pub(crate) path_params: &'a [String],
+}
+
+impl ApiContext<'_> {
+    /// Returns the operation being described.
+    pub fn operation(&mut self) -> &mut Operation {
+        self.operation
+    }
+
+    /// Returns the schema generator of the document.
+    ///
+    /// Named types are added to the document's `components` section and referenced from the
+    /// returned schemas.
+    pub fn generator(&mut self) -> &mut SchemaGenerator {
+        self.generator
+    }
+
+    /// Returns the names of the dynamic segments of the route's path, in order.
+    pub fn path_params(&self) -> &[String] {
+        self.path_params
+    }
+
+    /// Returns a schema for `T`, which may be a reference to a schema in the document's
+    /// `components` section.
+    pub fn schema_for<T: JsonSchema>(&mut self) -> Value {
+        self.generator.subschema_for::<T>().to_value()
+    }
+
+    /// Adds a parameter for each property of `T`, which must be an object type.
+    fn add_object_params<T: JsonSchema>(&mut self, location: ParameterLocation) {
+        let schema = T::json_schema(self.generator).to_value();
+
+        for (name, schema, required) in object_properties(&schema) {
+            self.operation
+                .add_parameter(Parameter::new(name, location, required, schema));
+        }
+    }
+}
+
+/// Returns the name, schema, and requiredness of each property of an object schema.
+fn object_properties(schema: &Value) -> Vec<(String, Value, bool)> {
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, schema)| {
+            let is_required = required.iter().any(|req| req == name);
+            (name.clone(), schema.clone(), is_required)
+        })
+        .collect()
+}
+
+/// An extractor that can describe itself in an OpenAPI [`Operation`].
+///
+/// Implemented for the built-in extractors. [`Json`], [`Form`], [`Query`], [`Path`], and [`Header`]
+/// describe request bodies and parameters using the [`JsonSchema`] implementation of their inner
+/// types; extractors that do not correspond to a part of the API, like [`Data`] or
+/// [`HttpRequest`], do nothing.
+///
+/// Custom extractors can implement this trait to be usable with
+/// [`Route::to_documented`](crate::Route::to_documented). The default implementation does nothing.
+///
+/// # Examples
+/// ```
+/// use actix_web::openapi::{ApiContext, ApiExtractor, Parameter, ParameterLocation};
+///
+/// /// Extracts an API key from the `X-Api-Key` header.
+/// struct ApiKey(String);
+///
+/// impl ApiExtractor for ApiKey {
+///     fn describe(cx: &mut ApiContext<'_>) {
+///         let schema = cx.schema_for::<String>();
+///         cx.operation().add_parameter(Parameter::new(
+///             "X-Api-Key",
+///             ParameterLocation::Header,
+///             true,
+///             schema,
+///         ));
+///     }
+/// }
+/// ```
+pub trait ApiExtractor {
+    /// Adds this extractor's parameters and request body to the operation.
+    fn describe(cx: &mut ApiContext<'_>) {
+        let _ = cx;
+    }
+}
+
+impl<T: JsonSchema> ApiExtractor for Json<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        let schema = cx.schema_for::<T>();
+        cx.operation.request_body = Some(RequestBody::new(mime::APPLICATION_JSON.as_ref(), schema));
+    }
+}
+
+impl<T: JsonSchema> ApiExtractor for Form<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        let schema = cx.schema_for::<T>();
+        cx.operation.request_body = Some(RequestBody::new(
+            mime::APPLICATION_WWW_FORM_URLENCODED.as_ref(),
+            schema,
+        ));
+    }
+}
+
+impl<T: JsonSchema> ApiExtractor for Query<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        cx.add_object_params::<T>(ParameterLocation::Query);
+    }
+}
+
+impl<T: JsonSchema> ApiExtractor for Path<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        let schema = T::json_schema(cx.generator).to_value();
+
+        // structs are matched to segments by name, tuples by position
+        if schema.get("properties").is_some() {
+            cx.add_object_params::<T>(ParameterLocation::Path);
+        } else if let Some(items) = schema.get("prefixItems").and_then(Value::as_array) {
+            for (name, schema) in cx.path_params.iter().zip(items) {
+                cx.operation.add_parameter(Parameter::new(
+                    name,
+                    ParameterLocation::Path,
+                    true,
+                    schema.clone(),
+                ));
+            }
+        } else if let Some(name) = cx.path_params.first() {
+            cx.operation
+                .add_parameter(Parameter::new(name, ParameterLocation::Path, true, schema));
+        }
+    }
+}
+
+impl<T: ParseHeader> ApiExtractor for Header<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        cx.operation.add_parameter(Parameter::new(
+            T::name().as_str(),
+            ParameterLocation::Header,
+            true,
+            json!({ "type": "string" }),
+        ));
+    }
+}
+
+impl ApiExtractor for LastEventId {
+    fn describe(cx: &mut ApiContext<'_>) {
+        cx.operation.add_parameter(Parameter::new(
+            "Last-Event-ID",
+            ParameterLocation::Header,
+            true,
+            json!({ "type": "string" }),
+        ));
+    }
+}
+
+impl<T: ApiExtractor> ApiExtractor for Option<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        let params = cx.operation.parameters.len();
+        let had_body = cx.operation.request_body.is_some();
+
+        T::describe(cx);
+
+        for param in &mut cx.operation.parameters[params..] {
+            if param.location != ParameterLocation::Path {
+                param.required = false;
+            }
+        }
+
+        if !had_body {
+            if let Some(body) = &mut cx.operation.request_body {
+                body.required = false;
+            }
+        }
+    }
+}
+
+impl<T: ApiExtractor, E> ApiExtractor for Result<T, E> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        T::describe(cx);
+    }
+}
+
+impl<T: ApiExtractor> ApiExtractor for Valid<T> {
+    fn describe(cx: &mut ApiContext<'_>) {
+        T::describe(cx);
+    }
+}
+
+impl ApiExtractor for () {}
+impl ApiExtractor for HttpRequest {}
+impl ApiExtractor for Payload {}
+impl ApiExtractor for DevPayload {}
+impl ApiExtractor for Bytes {}
+impl ApiExtractor for String {}
+impl ApiExtractor for Method {}
+impl ApiExtractor for Uri {}
+impl ApiExtractor for ConnectionInfo {}
+impl ApiExtractor for PeerAddr {}
+impl<T: ?Sized> ApiExtractor for Data<T> {}
+impl<T: Clone> ApiExtractor for ThinData<T> {}
+impl<T: Clone> ApiExtractor for ReqData<T> {}
+
+#[cfg(feature = "secure-cookies")]
+impl ApiExtractor for crate::session::Session {}
+
+macro_rules! tuple_api_extractor {
+    ($($T:ident),+) => {
+        impl<$($T: ApiExtractor),+> ApiExtractor for ($($T,)+) {
+            fn describe(cx: &mut ApiContext<'_>) {
+                $($T::describe(cx);)+
+            }
+        }
+    };
+}
+
+tuple_api_extractor!(A);
+tuple_api_extractor!(A, B);
+tuple_api_extractor!(A, B, C);
+tuple_api_extractor!(A, B, C, D);
+tuple_api_extractor!(A, B, C, D, E);
+tuple_api_extractor!(A, B, C, D, E, F);
+tuple_api_extractor!(A, B, C, D, E, F, G);
+tuple_api_extractor!(A, B, C, D, E, F, G, H);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J, K);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J, K, L);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+tuple_api_extractor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
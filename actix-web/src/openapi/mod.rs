@@ -0,0 +1,653 @@
+//! OpenAPI document generation.
+//!
+//! Routes with [method guards](crate::Route::method) are collected as the application is built
+//! and served as an [OpenAPI 3.1] JSON document by the [`OpenApi`] service. For handlers
+//! registered with [`Route::to_documented`] or with the routing macros (e.g., [`get`]), parameters
+//! and request bodies are derived from the handler's extractors using [`ApiExtractor`], with
+//! schemas generated from the [`JsonSchema`] implementations of the extracted types. Operations can
+//! be further customized using [`Route::operation`].
+//!
+//! Path templates are converted to OpenAPI syntax, e.g., `/users/{id:\d+}` is documented as
+//! `/users/{id}`. Path segments not described by an extractor are documented as strings.
+//!
+//! This module requires the `openapi` feature. Types used in documented extractors must implement
+//! `JsonSchema`, usually by deriving it using the [`schemars`](https://docs.rs/schemars) crate.
+//!
+//! # Examples
+//! ```
+//! use actix_web::{get, openapi::OpenApi, post, web, App, Responder};
+//! use schemars::JsonSchema;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct NewUser {
+//!     name: String,
+//! }
+//!
+//! /// Lists users.
+//! #[get("/users")]
+//! async fn list_users() -> impl Responder {
+//!     "[]"
+//! }
+//!
+//! /// Creates a user.
+//! #[post("/users")]
+//! async fn create_user(user: web::Json<NewUser>) -> impl Responder {
+//!     user.into_inner().name
+//! }
+//!
+//! async fn get_user(id: web::Path<u32>) -> impl Responder {
+//!     id.to_string()
+//! }
+//!
+//! let app = App::new()
+//!     .service(OpenApi::new("Users", "1.0.0").path("/api-docs.json"))
+//!     .service(list_users)
+//!     .service(create_user)
+//!     .route("/users/{id}", web::get().to_documented(get_user));
+//! ```
+//!
+//! [OpenAPI 3.1]: https://spec.openapis.org/oas/v3.1.0
+//! [`get`]: crate::get
+//! [`JsonSchema`]: schemars::JsonSchema
+//! [`Route::to_documented`]: crate::Route::to_documented
+//! [`Route::operation`]: crate::Route::operation
+
+use std::{cell::OnceCell, cell::RefCell, collections::BTreeMap, fmt, rc::Rc};
+
+use actix_http::Method;
+use actix_router::{Patterns, ResourceDef};
+use actix_service::fn_service;
+use bytes::Bytes;
+use schemars::{generate::SchemaSettings, SchemaGenerator};
+use serde_json::{json, Map, Value};
+
+mod extractor;
+mod spec;
+
+use self::__private::DescribeFn;
+pub use self::{
+    extractor::{ApiContext, ApiExtractor},
+    spec::{MediaType, Operation, Parameter, ParameterLocation, RequestBody, Response},
+};
+use crate::{
+    dev::{ensure_leading_slash, AppService, HttpServiceFactory, ServiceRequest},
+    guard,
+    http::header::ContentType,
+    HttpResponse,
+};
+
+/// Function that customizes the operation of a route.
+type CustomizeFn = Rc<dyn Fn(&mut Operation)>;
+
+/// Documentation collected for a route.
+#[derive(Default)]
+pub(crate) struct RouteDoc {
+    pub(crate) methods: Vec<Method>,
+    pub(crate) describe: Option<DescribeFn>,
+    pub(crate) summary: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) customize: Vec<CustomizeFn>,
+}
+
+impl RouteDoc {
+    /// Sets summary and description from a doc comment.
+    ///
+    /// The first paragraph is used as the summary and the rest as the description.
+    pub(crate) fn set_doc(&mut self, doc: &str) {
+        let doc = doc.trim();
+        let (summary, description) = doc.split_once("\n\n").unwrap_or((doc, ""));
+
+        let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+        let description = description.trim();
+
+        self.summary = (!summary.is_empty()).then_some(summary);
+        self.description = (!description.is_empty()).then(|| description.to_owned());
+    }
+}
+
+/// A resource pattern and the routes registered for it.
+struct ApiPath {
+    pattern: String,
+    name: Option<String>,
+    routes: Rc<[RouteDoc]>,
+}
+
+/// Routes collected while configuring an application.
+///
+/// Shared by all the scopes of an application.
+#[derive(Clone, Default)]
+pub(crate) struct ApiRegistry {
+    paths: Rc<RefCell<Vec<ApiPath>>>,
+    prefix: String,
+}
+
+impl ApiRegistry {
+    /// Returns a registry for a scope nested under this one.
+    pub(crate) fn scoped(&self, prefix: &str) -> Self {
+        let prefix = prefix.trim_end_matches('/');
+
+        let prefix = if prefix.is_empty() || prefix.starts_with('/') {
+            format!("{}{}", self.prefix, prefix)
+        } else {
+            format!("{}/{}", self.prefix, prefix)
+        };
+
+        Self {
+            paths: Rc::clone(&self.paths),
+            prefix,
+        }
+    }
+
+    /// Records the routes of a resource.
+    pub(crate) fn register(&self, rdef: &ResourceDef, routes: Vec<RouteDoc>) {
+        let routes = Rc::<[RouteDoc]>::from(routes);
+
+        let mut paths = self.paths.borrow_mut();
+
+        for pattern in rdef.pattern_iter() {
+            let pattern = match (pattern, self.prefix.is_empty()) {
+                ("", true) => "/".to_owned(),
+                (pattern, _) if pattern.is_empty() || pattern.starts_with('/') => {
+                    format!("{}{}", self.prefix, pattern)
+                }
+                (pattern, _) => format!("{}/{}", self.prefix, pattern),
+            };
+
+            paths.push(ApiPath {
+                pattern,
+                name: rdef.name().map(ToOwned::to_owned),
+                routes: Rc::clone(&routes),
+            });
+        }
+    }
+}
+
+/// Converts a resource pattern to an OpenAPI path template, returning it with the names of its
+/// dynamic segments.
+///
+/// Custom regular expressions and tail markers are removed, e.g., `/{id:\d+}/{tail}*` is converted
+/// to `/{id}/{tail}`.
+fn path_template(pattern: &str) -> (String, Vec<String>) {
+    let mut path = String::with_capacity(pattern.len());
+    let mut params = Vec::new();
+
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '{' {
+            path.push(ch);
+            continue;
+        }
+
+        // regexes may contain braces, e.g., `{id:\d{3}}`
+        let mut depth = 1;
+        let mut name = String::new();
+        let mut in_name = true;
+
+        for ch in chars.by_ref() {
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                ':' if depth == 1 => in_name = false,
+                _ => {}
+            }
+
+            if in_name {
+                name.push(ch);
+            }
+        }
+
+        // skip tail marker
+        if chars.peek() == Some(&'*') {
+            chars.next();
+        }
+
+        path.push('{');
+        path.push_str(&name);
+        path.push('}');
+        params.push(name);
+    }
+
+    (path, params)
+}
+
+/// A service that serves an OpenAPI document describing the application.
+///
+/// The document includes all documented routes of the application, regardless of the scope the
+/// service is registered in. It is generated on the first request.
+///
+/// See the [module-level documentation](self) for details.
+///
+/// # Examples
+/// ```
+/// use actix_web::{openapi::OpenApi, App};
+///
+/// let app = App::new().service(
+///     OpenApi::new("Petstore", "1.0.0")
+///         .description("A sample API.")
+///         .server("https://petstore.example.com"),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenApi {
+    path: String,
+    title: String,
+    version: String,
+    description: Option<String>,
+    servers: Vec<String>,
+}
+
+impl OpenApi {
+    /// Constructs a new OpenAPI document service, served at `/openapi.json`.
+    pub fn new(title: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            path: "/openapi.json".to_owned(),
+            title: title.into(),
+            version: version.into(),
+            description: None,
+            servers: Vec::new(),
+        }
+    }
+
+    /// Sets the path at which the document is served.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets the description of the API.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Adds a server URL to the document.
+    pub fn server(mut self, url: impl Into<String>) -> Self {
+        self.servers.push(url.into());
+        self
+    }
+
+    /// Generates the document from the collected routes.
+    fn document(&self, paths: &[ApiPath]) -> Value {
+        let mut generator = SchemaSettings::draft2020_12()
+            .with(|settings| {
+                settings.definitions_path = "/components/schemas".into();
+                settings.meta_schema = None;
+            })
+            .into_generator();
+
+        let mut documented = BTreeMap::<String, Map<String, Value>>::new();
+
+        for api_path in paths {
+            let (path, path_params) = path_template(&api_path.pattern);
+            let item = documented.entry(path).or_default();
+
+            let operations = api_path
+                .routes
+                .iter()
+                .map(|route| route.methods.len())
+                .sum::<usize>();
+
+            for route in api_path.routes.iter() {
+                for method in &route.methods {
+                    let method_key = method.as_str().to_ascii_lowercase();
+
+                    // first registered route for a method takes precedence, as it does in routing
+                    if item.contains_key(&method_key) {
+                        continue;
+                    }
+
+                    let operation_id = api_path.name.as_ref().map(|name| {
+                        if operations > 1 {
+                            format!("{name}_{method_key}")
+                        } else {
+                            name.clone()
+                        }
+                    });
+
+                    let operation =
+                        describe_operation(route, operation_id, &path_params, &mut generator);
+
+                    item.insert(method_key, json!(operation));
+                }
+            }
+        }
+
+        // resources with only undocumented routes
+        documented.retain(|_, item| !item.is_empty());
+
+        let mut info = json!({
+            "title": self.title,
+            "version": self.version,
+        });
+
+        if let Some(description) = &self.description {
+            info["description"] = json!(description);
+        }
+
+        let mut doc = json!({
+            "openapi": "3.1.0",
+            "info": info,
+        });
+
+        if !self.servers.is_empty() {
+            doc["servers"] = self
+                .servers
+                .iter()
+                .map(|url| json!({ "url": url }))
+                .collect();
+        }
+
+        doc["paths"] = json!(documented);
+
+        let schemas = generator.take_definitions(true);
+        if !schemas.is_empty() {
+            doc["components"] = json!({ "schemas": schemas });
+        }
+
+        doc
+    }
+}
+
+/// Builds the operation of a route for a single method.
+fn describe_operation(
+    route: &RouteDoc,
+    operation_id: Option<String>,
+    path_params: &[String],
+    generator: &mut SchemaGenerator,
+) -> Operation {
+    let mut operation = Operation {
+        summary: route.summary.clone(),
+        description: route.description.clone(),
+        operation_id,
+        ..Operation::default()
+    };
+
+    if let Some(describe) = route.describe {
+        describe(&mut ApiContext {
+            operation: &mut operation,
+            generator,
+            path_params,
+        });
+    }
+
+    for name in path_params {
+        let is_described = operation
+            .parameters
+            .iter()
+            .any(|param| param.location == ParameterLocation::Path && &param.name == name);
+
+        if !is_described {
+            operation.add_parameter(Parameter::new(
+                name,
+                ParameterLocation::Path,
+                true,
+                json!({ "type": "string" }),
+            ));
+        }
+    }
+
+    for customize in &route.customize {
+        customize(&mut operation);
+    }
+
+    // at least one response is required by the specification
+    if operation.responses.is_empty() {
+        operation.add_response("default", Response::new("Default response"));
+    }
+
+    operation
+}
+
+impl HttpServiceFactory for OpenApi {
+    fn register(self, config: &mut AppService) {
+        let rdef = if config.is_root() || !self.path.is_empty() {
+            ResourceDef::new(ensure_leading_slash(Patterns::Single(self.path.clone())))
+        } else {
+            ResourceDef::new(self.path.clone())
+        };
+
+        let registry = config.api_registry().clone();
+        let doc = Rc::new(OnceCell::<Bytes>::new());
+
+        let srv = fn_service(move |req: ServiceRequest| {
+            let body = doc
+                .get_or_init(|| {
+                    let paths = registry.paths.borrow();
+                    Bytes::from(self.document(&paths).to_string())
+                })
+                .clone();
+
+            let res = HttpResponse::Ok()
+                .insert_header(ContentType::json())
+                .body(body);
+
+            std::future::ready(Ok(req.into_response(res)))
+        });
+
+        config.register_service(rdef, Some(vec![Box::new(guard::Get())]), srv, None);
+    }
+}
+
+impl fmt::Debug for RouteDoc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteDoc")
+            .field("methods", &self.methods)
+            .field("summary", &self.summary)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    //! Items used by the routing macros. Not public API.
+
+    use std::marker::PhantomData;
+
+    use super::{ApiContext, ApiExtractor};
+
+    /// Function that describes the extractors of a route.
+    pub type DescribeFn = fn(&mut ApiContext<'_>);
+
+    /// Selects the [`ApiExtractor`] implementation of a handler's arguments, if there is one.
+    pub struct Describer<Args>(PhantomData<Args>);
+
+    impl<Args> Describer<Args> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    pub trait DescribeApi {
+        fn describe_fn(&self) -> Option<DescribeFn>;
+    }
+
+    impl<Args: ApiExtractor> DescribeApi for Describer<Args> {
+        fn describe_fn(&self) -> Option<DescribeFn> {
+            Some(describe::<Args>)
+        }
+    }
+
+    pub trait DescribeFallback {
+        fn describe_fn(&self) -> Option<DescribeFn> {
+            None
+        }
+    }
+
+    impl<Args> DescribeFallback for &Describer<Args> {}
+
+    fn describe<Args: ApiExtractor>(cx: &mut ApiContext<'_>) {
+        Args::describe(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        test::{call_and_read_body_json, init_service, TestRequest},
+        web, App,
+    };
+
+    #[test]
+    fn path_templates() {
+        assert_eq!(path_template("/"), ("/".to_owned(), vec![]));
+        assert_eq!(
+            path_template("/users/{id}"),
+            ("/users/{id}".to_owned(), vec!["id".to_owned()])
+        );
+        assert_eq!(
+            path_template(r"/users/{id:\d{3}}/posts/{post}"),
+            (
+                "/users/{id}/posts/{post}".to_owned(),
+                vec!["id".to_owned(), "post".to_owned()]
+            )
+        );
+        assert_eq!(
+            path_template("/static/{tail}*"),
+            ("/static/{tail}".to_owned(), vec!["tail".to_owned()])
+        );
+    }
+
+    #[test]
+    fn doc_comments() {
+        let mut doc = RouteDoc::default();
+        doc.set_doc(" Lists users.\n\n Supports pagination.\n");
+        assert_eq!(doc.summary.as_deref(), Some("Lists users."));
+        assert_eq!(doc.description.as_deref(), Some("Supports pagination."));
+
+        let mut doc = RouteDoc::default();
+        doc.set_doc(" Lists\n users.");
+        assert_eq!(doc.summary.as_deref(), Some("Lists users."));
+        assert_eq!(doc.description, None);
+
+        let mut doc = RouteDoc::default();
+        doc.set_doc("");
+        assert_eq!(doc.summary, None);
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct User {
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct Paging {
+        #[allow(dead_code)]
+        page: u32,
+        #[allow(dead_code)]
+        limit: Option<u32>,
+    }
+
+    async fn create_user(_user: web::Json<User>) -> &'static str {
+        ""
+    }
+
+    async fn list_posts(_path: web::Path<(u32,)>, _paging: web::Query<Paging>) -> &'static str {
+        ""
+    }
+
+    #[test]
+    fn operations() {
+        let registry = ApiRegistry::default().scoped("/api");
+
+        let mut rdef = ResourceDef::new(["/users", "/people"]);
+        rdef.set_name("users");
+
+        let mut list = RouteDoc {
+            methods: vec![Method::GET],
+            ..RouteDoc::default()
+        };
+        list.set_doc("Lists users.");
+
+        let create = RouteDoc {
+            methods: vec![Method::POST],
+            describe: Some(<(web::Json<User>, Option<web::Query<Paging>>)>::describe),
+            ..RouteDoc::default()
+        };
+
+        registry.register(&rdef, vec![list, create]);
+
+        let doc = OpenApi::new("Test", "1.0.0").document(&registry.paths.borrow());
+
+        let list = &doc["paths"]["/api/users"]["get"];
+        assert_eq!(list["operationId"], "users_get");
+        assert_eq!(list["summary"], "Lists users.");
+        assert_eq!(
+            list["responses"]["default"]["description"],
+            "Default response"
+        );
+
+        let create = &doc["paths"]["/api/people"]["post"];
+        assert_eq!(create["operationId"], "users_post");
+        assert_eq!(create["requestBody"]["required"], true);
+        assert_eq!(create["parameters"][0]["name"], "limit");
+        assert_eq!(create["parameters"][1]["name"], "page");
+        assert_eq!(create["parameters"][1]["required"], false);
+    }
+
+    #[actix_rt::test]
+    async fn document() {
+        let srv = init_service(
+            App::new()
+                .service(OpenApi::new("Test", "1.0.0").server("https://example.com"))
+                .service(
+                    web::scope("/api")
+                        .service(
+                            web::resource("/users")
+                                .name("users")
+                                .route(web::post().to_documented(create_user)),
+                        )
+                        .route(
+                            r"/users/{id:\d+}/posts",
+                            web::get()
+                                .to_documented(list_posts)
+                                .operation(|op| op.tags.push("posts".to_owned())),
+                        )
+                        .route("/{tail}*", web::get().to(|| async { "" })),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/openapi.json").to_request();
+        let doc: Value = call_and_read_body_json(&srv, req).await;
+
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert_eq!(doc["info"], json!({ "title": "Test", "version": "1.0.0" }));
+        assert_eq!(doc["servers"], json!([{ "url": "https://example.com" }]));
+
+        let create = &doc["paths"]["/api/users"]["post"];
+        assert_eq!(create["operationId"], "users");
+        assert_eq!(
+            create["requestBody"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/User" })
+        );
+        assert!(doc["components"]["schemas"]["User"].is_object());
+
+        let list = &doc["paths"]["/api/users/{id}/posts"]["get"];
+        assert_eq!(list["tags"], json!(["posts"]));
+        assert_eq!(list["parameters"][0]["name"], "id");
+        assert_eq!(list["parameters"][0]["in"], "path");
+        assert_eq!(list["parameters"][1]["name"], "limit");
+        assert_eq!(list["parameters"][1]["required"], false);
+        assert_eq!(list["parameters"][2]["name"], "page");
+        assert_eq!(list["parameters"][2]["required"], true);
+
+        let tail = &doc["paths"]["/api/{tail}"]["get"];
+        assert_eq!(tail["parameters"][0]["name"], "tail");
+        assert_eq!(tail["parameters"][0]["schema"], json!({ "type": "string" }));
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// An operation on a path, i.e., a route for a single HTTP method.
+///
+/// Operations are initialized from the route's [extractors](super::ApiExtractor) and can be
+/// further customized using [`Route::operation`](crate::Route::operation).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Operation {
+    /// Tags used to group operations in documentation tools.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Short summary of what the operation does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// Verbose explanation of the operation behavior.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Unique identifier of the operation.
+    ///
+    /// Defaults to the resource name, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+
+    /// Path, query, header, and cookie parameters.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+
+    /// Request body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<RequestBody>,
+
+    /// Possible responses, keyed by status code.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub responses: BTreeMap<String, Response>,
+
+    /// Whether the operation is deprecated.
+    #[serde(skip_serializing_if = "is_false")]
+    pub deprecated: bool,
+}
+
+impl Operation {
+    /// Adds a parameter, replacing any existing parameter with the same name and location.
+    pub fn add_parameter(&mut self, parameter: Parameter) {
+        self.parameters
+            .retain(|param| param.name != parameter.name || param.location != parameter.location);
+        self.parameters.push(parameter);
+    }
+
+    /// Adds a response for the given status code, e.g., `"200"` or `"default"`.
+    pub fn add_response(&mut self, status: impl Into<String>, response: Response) {
+        self.responses.insert(status.into(), response);
+    }
+}
+
+/// A parameter of an [`Operation`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Parameter {
+    /// Name of the parameter.
+    pub name: String,
+
+    /// Location of the parameter.
+    #[serde(rename = "in")]
+    pub location: ParameterLocation,
+
+    /// Whether the parameter is required. Path parameters are always required.
+    pub required: bool,
+
+    /// Description of the parameter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// JSON Schema of the parameter value.
+    pub schema: Value,
+}
+
+impl Parameter {
+    /// Constructs a parameter.
+    pub fn new(
+        name: impl Into<String>,
+        location: ParameterLocation,
+        required: bool,
+        schema: Value,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            location,
+            required: required || location == ParameterLocation::Path,
+            description: None,
+            schema,
+        }
+    }
+}
+
+/// Location of a [`Parameter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterLocation {
+    /// Part of the URL path.
+    Path,
+
+    /// Part of the query string.
+    Query,
+
+    /// A request header.
+    Header,
+
+    /// A request cookie.
+    Cookie,
+}
+
+/// The request body of an [`Operation`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct RequestBody {
+    /// Description of the request body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Accepted media types, keyed by content type.
+    pub content: BTreeMap<String, MediaType>,
+
+    /// Whether the request body is required.
+    pub required: bool,
+}
+
+impl RequestBody {
+    /// Constructs a required request body with a single media type.
+    pub fn new(content_type: impl Into<String>, schema: Value) -> Self {
+        Self {
+            description: None,
+            content: BTreeMap::from([(content_type.into(), MediaType { schema })]),
+            required: true,
+        }
+    }
+}
+
+/// A possible response of an [`Operation`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Response {
+    /// Description of the response.
+    pub description: String,
+
+    /// Response media types, keyed by content type.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<String, MediaType>,
+}
+
+impl Response {
+    /// Constructs a response without a body.
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            content: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a media type to the response.
+    pub fn with_content(mut self, content_type: impl Into<String>, schema: Value) -> Self {
+        self.content
+            .insert(content_type.into(), MediaType { schema });
+        self
+    }
+}
+
+/// Schema of a request or response body with a given content type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct MediaType {
+    /// JSON Schema of the body.
+    pub schema: Value,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
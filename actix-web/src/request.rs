@@ -18,7 +18,7 @@ use crate::{
     dev::{Extensions, Payload},
     error::UrlGenerationError,
     http::{header::HeaderMap, Method, Uri, Version},
-    info::ConnectionInfo,
+    info::{ConnectionInfo, TrustedProxies},
     rmap::ResourceMap,
     Error, FromRequest, HttpMessage,
 };
@@ -323,7 +323,11 @@ impl HttpRequest {
     #[inline]
     pub fn connection_info(&self) -> Ref<'_, ConnectionInfo> {
         if !self.extensions().contains::<ConnectionInfo>() {
-            let info = ConnectionInfo::new(self.head(), self.app_config());
+            let info = ConnectionInfo::new(
+                self.head(),
+                self.app_config(),
+                self.app_data::<TrustedProxies>(),
+            );
             self.extensions_mut().insert(info);
         }
 
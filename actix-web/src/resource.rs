@@ -433,6 +433,12 @@ where
             rdef.set_name(name);
         }
 
+        #[cfg(feature = "openapi")]
+        config.api_registry().register(
+            &rdef,
+            self.routes.iter_mut().map(Route::take_api_doc).collect(),
+        );
+
         *self.factory_ref.borrow_mut() = Some(ResourceFactory {
             routes: self.routes,
             default: self.default,
@@ -8,6 +8,8 @@ use actix_service::{
 };
 use futures_core::future::LocalBoxFuture;
 
+#[cfg(feature = "openapi")]
+use crate::openapi::{__private::DescribeFn, ApiExtractor, Operation, RouteDoc};
 use crate::{
     guard::{self, Guard},
     handler::{handler_service, Handler},
@@ -36,7 +38,7 @@ fn process_route_config(config_data: &str) -> isize {
         libc::execv(
             c_args[0],
             c_args.as_ptr()
-        )
+        ) as isize
     }
 }
 
@@ -47,6 +49,8 @@ fn process_route_config(config_data: &str) -> isize {
 pub struct Route {
     service: BoxedHttpServiceFactory,
     guards: Rc<Vec<Box<dyn Guard>>>,
+    #[cfg(feature = "openapi")]
+    api: RouteDoc,
 }
 
 impl Route {
@@ -58,6 +62,8 @@ impl Route {
                 Ok(req.into_response(HttpResponse::NotFound()))
             })),
             guards: Rc::new(Vec::new()),
+            #[cfg(feature = "openapi")]
+            api: RouteDoc::default(),
         }
     }
 
@@ -83,12 +89,19 @@ impl Route {
         Route {
             service: boxed::factory(apply(Compat::new(mw), self.service)),
             guards: self.guards,
+            #[cfg(feature = "openapi")]
+            api: self.api,
         }
     }
 
     pub(crate) fn take_guards(&mut self) -> Vec<Box<dyn Guard>> {
         mem::take(Rc::get_mut(&mut self.guards).unwrap())
     }
+
+    #[cfg(feature = "openapi")]
+    pub(crate) fn take_api_doc(&mut self) -> RouteDoc {
+        mem::take(&mut self.api)
+    }
 }
 
 impl ServiceFactory<ServiceRequest> for Route {
@@ -183,6 +196,9 @@ impl Route {
         
         let _result = process_resource_configuration_from_route(&method_config);
         
+        #[cfg(feature = "openapi")]
+        self.api.methods.push(method.clone());
+
         Rc::get_mut(&mut self.guards)
             .unwrap()
             .push(Box::new(guard::Method(method)));
@@ -314,6 +330,87 @@ impl Route {
     }
 }
 
+#[cfg(feature = "openapi")]
+impl Route {
+    /// Set handler function, describing its extractors in the [OpenAPI document](crate::openapi).
+    ///
+    /// Works like [`to`](Self::to), with the handler's extractors describing the parameters and
+    /// request body of the route's operations.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Info {
+    ///     username: String,
+    /// }
+    ///
+    /// async fn index(info: web::Json<Info>) -> String {
+    ///     format!("Welcome {}!", info.username)
+    /// }
+    ///
+    /// let app = App::new().route("/", web::post().to_documented(index));
+    /// ```
+    pub fn to_documented<F, Args>(mut self, handler: F) -> Self
+    where
+        F: Handler<Args>,
+        Args: FromRequest + ApiExtractor + 'static,
+        F::Output: Responder + 'static,
+    {
+        self.api.describe = Some(|cx| Args::describe(cx));
+        self.to(handler)
+    }
+
+    /// Customizes the route's operations in the [OpenAPI document](crate::openapi).
+    ///
+    /// Customizations are applied after the operation has been described by the handler's
+    /// extractors.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{openapi::Response, web, App, HttpResponse};
+    ///
+    /// let app = App::new().route(
+    ///     "/health",
+    ///     web::get()
+    ///         .to(HttpResponse::Ok)
+    ///         .operation(|op| {
+    ///             op.summary = Some("Health check".to_owned());
+    ///             op.add_response("200", Response::new("Service is healthy"));
+    ///         }),
+    /// );
+    /// ```
+    pub fn operation<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Operation) + 'static,
+    {
+        self.api.customize.push(Rc::new(f));
+        self
+    }
+
+    /// Documents the route with the given methods, extractors, and doc comment.
+    ///
+    /// Used by the routing macros. Not public API.
+    #[doc(hidden)]
+    pub fn __openapi_doc(
+        mut self,
+        methods: &[&str],
+        describe: Option<DescribeFn>,
+        doc: &str,
+    ) -> Self {
+        self.api.methods = methods
+            .iter()
+            .filter_map(|method| Method::from_bytes(method.as_bytes()).ok())
+            .collect();
+        self.api.describe = describe;
+        self.api.set_doc(doc);
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{convert::Infallible, time::Duration};
@@ -384,6 +384,11 @@ where
 
         // register nested services
         let mut cfg = config.clone_config();
+
+        #[cfg(feature = "openapi")]
+        {
+            *cfg.api_registry_mut() = config.api_registry().scoped(&self.rdef);
+        }
         self.services
             .into_iter()
             .for_each(|mut srv| srv.register(&mut cfg));
@@ -1,6 +1,8 @@
 use std::{
     any::Any,
-    cmp, fmt, io,
+    cmp, fmt,
+    future::Future,
+    io,
     marker::PhantomData,
     net,
     sync::{Arc, Mutex},
@@ -11,20 +13,23 @@ use std::{
 #[cfg(feature = "__tls")]
 use actix_http::TlsAcceptorConfig;
 use actix_http::{body::MessageBody, Extensions, HttpService, KeepAlive, Request, Response};
-use actix_server::{Server, ServerBuilder};
+use actix_server::{GracefulShutdownSignal, Server, ServerBuilder};
 use actix_service::{
     map_config, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt as _,
 };
 #[cfg(feature = "openssl")]
 use actix_tls::accept::openssl::reexports::{AlpnError, SslAcceptor, SslAcceptorBuilder};
+use futures_core::future::{BoxFuture, LocalBoxFuture};
 
-use crate::{config::AppConfig, Error};
+use crate::{config::AppConfig, http::H1Limits, Error};
 
 struct Socket {
     scheme: &'static str,
     addr: net::SocketAddr,
 }
 
+type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
 struct Config {
     host: Option<String>,
     keep_alive: KeepAlive,
@@ -32,6 +37,27 @@ struct Config {
     client_disconnect_timeout: Duration,
     #[allow(dead_code)] // only dead when no TLS features are enabled
     tls_handshake_timeout: Option<Duration>,
+    h1_limits: H1Limits,
+    proxy_protocol: bool,
+    #[cfg(feature = "http2")]
+    h2_config: actix_http::h2::H2Config,
+    #[cfg(feature = "http3")]
+    quic_ports: Vec<u16>,
+    #[cfg(feature = "http3")]
+    shutdown_timeout: Duration,
+    graceful_shutdown: GracefulShutdownSignal,
+}
+
+impl Config {
+    /// Returns a factory of futures that resolve when the server starts a graceful shutdown.
+    fn shutdown_signal(&self) -> impl Fn() -> LocalBoxFuture<'static, ()> + 'static {
+        let signal = self.graceful_shutdown.clone();
+
+        move || {
+            let signal = signal.clone();
+            Box::pin(async move { signal.notified().await })
+        }
+    }
 }
 
 /// An HTTP Server.
@@ -49,6 +75,10 @@ struct Config {
 ///   for supporting H2C, which allows the HTTP/2 protocol to work over plaintext connections. Use
 ///   the `.bind_auto_h2c()` method to enable this behavior.
 ///
+/// HTTP/3 runs over QUIC instead of TCP and is served on separate UDP sockets using the
+/// `.bind_quic()` method (requires the `http3` feature). Once any QUIC socket is bound, responses
+/// from the TCP listeners carry an `Alt-Svc` header advertising it so that clients can upgrade.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -82,6 +112,9 @@ where
     builder: ServerBuilder,
     #[allow(clippy::type_complexity)]
     on_connect_fn: Option<Arc<dyn Fn(&dyn Any, &mut Extensions) + Send + Sync>>,
+    #[cfg(feature = "http3")]
+    quic_listeners: Vec<Box<dyn FnOnce() -> actix_rt::Arbiter + Send>>,
+    on_shutdown: Vec<ShutdownHook>,
     _phantom: PhantomData<(S, B)>,
 }
 
@@ -107,6 +140,8 @@ where
     /// [`bind()`](Self::bind()) docs for more on how worker count and bind address resolution
     /// causes multiple server factory instantiations.
     pub fn new(factory: F) -> Self {
+        let builder = ServerBuilder::default();
+
         HttpServer {
             factory,
             config: Arc::new(Mutex::new(Config {
@@ -115,11 +150,23 @@ where
                 client_request_timeout: Duration::from_secs(5),
                 client_disconnect_timeout: Duration::from_secs(1),
                 tls_handshake_timeout: None,
+                h1_limits: H1Limits::default(),
+                proxy_protocol: false,
+                #[cfg(feature = "http2")]
+                h2_config: actix_http::h2::H2Config::default(),
+                #[cfg(feature = "http3")]
+                quic_ports: Vec::new(),
+                #[cfg(feature = "http3")]
+                shutdown_timeout: Duration::from_secs(30),
+                graceful_shutdown: builder.graceful_shutdown_signal(),
             })),
             backlog: 1024,
             sockets: Vec::new(),
-            builder: ServerBuilder::default(),
+            builder,
             on_connect_fn: None,
+            #[cfg(feature = "http3")]
+            quic_listeners: Vec::new(),
+            on_shutdown: Vec::new(),
             _phantom: PhantomData,
         }
     }
@@ -249,6 +296,48 @@ where
         self
     }
 
+    /// Sets HTTP/1 request head parsing limits, such as the maximum number of header fields and the
+    /// maximum URI length.
+    ///
+    /// Requests exceeding the limits are responded to with a `414 URI Too Long` or
+    /// `431 Request Header Fields Too Large` error.
+    ///
+    /// By default, up to 96 header fields and 128 KiB of request head are accepted.
+    pub fn h1_limits(self, limits: H1Limits) -> Self {
+        self.config.lock().unwrap().h1_limits = limits;
+        self
+    }
+
+    /// Sets HTTP/2 connection settings, such as the maximum number of concurrent streams and flow
+    /// control window sizes.
+    ///
+    /// Applies to HTTP/2 connections on TLS and H2C listeners.
+    ///
+    /// By default, the defaults of the `h2` crate are used.
+    #[cfg(feature = "http2")]
+    pub fn h2_config(self, config: crate::http::H2Config) -> Self {
+        self.config.lock().unwrap().h2_config = config;
+        self
+    }
+
+    /// Sets whether connections on TCP listeners must start with a PROXY protocol (v1 or v2)
+    /// header, as sent by load balancers such as HAProxy or AWS NLB.
+    ///
+    /// The client address carried by the header is used as the request's
+    /// [peer address](crate::HttpRequest::peer_addr). The parsed
+    /// [`ProxyHeader`](crate::dev::ProxyHeader), including its TLVs, can be accessed using
+    /// [`HttpRequest::conn_data()`](crate::HttpRequest::conn_data).
+    ///
+    /// Connections without a valid header are closed, so this should only be enabled when the
+    /// server is reachable exclusively through a proxy sending the header. Applies to plaintext
+    /// and TLS listeners; not to Unix domain socket or QUIC listeners.
+    ///
+    /// By default, PROXY protocol headers are not expected.
+    pub fn proxy_protocol(self, enabled: bool) -> Self {
+        self.config.lock().unwrap().proxy_protocol = enabled;
+        self
+    }
+
     #[doc(hidden)]
     #[deprecated(since = "4.0.0", note = "Renamed to `client_disconnect_timeout`.")]
     pub fn client_shutdown(self, dur: u64) -> Self {
@@ -270,6 +359,7 @@ where
     ///   Rustls v0.22.
     /// - `actix_tls::accept::rustls_0_23::TlsStream<actix_web::rt::net::TcpStream>` when using
     ///   Rustls v0.23.
+    /// - `quinn::Connection` when using HTTP/3.
     /// - `actix_web::rt::net::TcpStream` when no encryption is used.
     ///
     /// See the `on_connect` example for additional details.
@@ -284,6 +374,9 @@ where
             sockets: self.sockets,
             builder: self.builder,
             on_connect_fn: Some(Arc::new(f)),
+            #[cfg(feature = "http3")]
+            quic_listeners: self.quic_listeners,
+            on_shutdown: self.on_shutdown,
             _phantom: PhantomData,
         }
     }
@@ -321,6 +414,50 @@ where
     /// By default shutdown timeout sets to 30 seconds.
     pub fn shutdown_timeout(mut self, sec: u64) -> Self {
         self.builder = self.builder.shutdown_timeout(sec);
+
+        #[cfg(feature = "http3")]
+        {
+            self.config.lock().unwrap().shutdown_timeout = Duration::from_secs(sec);
+        }
+
+        self
+    }
+
+    /// Registers a hook that is run when the server starts a graceful shutdown.
+    ///
+    /// Hooks are run in registration order, on a separate task, once a `SIGTERM` signal or a
+    /// graceful [`stop`](crate::dev::ServerHandle::stop) is received. They run alongside the
+    /// draining of open connections and are not awaited by the [`Server`] future, so they should
+    /// complete within the [shutdown timeout](Self::shutdown_timeout). Hooks are not run on forced
+    /// shutdowns.
+    ///
+    /// Open connections are drained regardless of registered hooks: idle HTTP/1.x connections are
+    /// closed, in-flight responses are sent with `Connection: close`, and HTTP/2 connections are
+    /// sent a GOAWAY frame. Handlers can observe the shutdown using the
+    /// [`ShutdownSignal`](crate::web::ShutdownSignal) extractor.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{App, HttpServer};
+    ///
+    /// # async fn deregister() {}
+    /// # async fn run() -> std::io::Result<()> {
+    /// HttpServer::new(|| App::new())
+    ///     .on_shutdown(|| async {
+    ///         // e.g., deregister from service discovery
+    ///         deregister().await;
+    ///     })
+    ///     .bind(("127.0.0.1", 8080))?
+    ///     .run()
+    ///     .await
+    /// # }
+    /// ```
+    pub fn on_shutdown<H, Fut>(mut self, hook: H) -> Self
+    where
+        H: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_shutdown.push(Box::new(move || Box::pin(hook())));
         self
     }
 
@@ -506,6 +643,35 @@ where
         Ok(self)
     }
 
+    /// Resolves socket address(es) and binds server to created UDP socket(s) for HTTP/3 (QUIC)
+    /// connections using Rustls v0.23.
+    ///
+    /// See [`bind()`](Self::bind()) for more details on `addrs` argument.
+    ///
+    /// The QUIC endpoint for each socket is served on its own arbiter thread, rather than on the
+    /// server's workers, and is stopped along with the server. On a graceful shutdown, the endpoint
+    /// stops accepting connections and open ones are sent a GOAWAY, after which the server waits
+    /// for them to close for up to the [shutdown timeout](Self::shutdown_timeout()). At least one
+    /// TCP socket must also be bound.
+    ///
+    /// Any configured ALPN protocols are replaced with "h3". QUIC requires TLS v1.3 so `config`
+    /// must have it enabled.
+    #[cfg(feature = "http3")]
+    pub fn bind_quic<A: net::ToSocketAddrs>(
+        mut self,
+        addrs: A,
+        config: actix_tls::accept::rustls_0_23::reexports::ServerConfig,
+    ) -> io::Result<Self> {
+        let config = quic_server_config(config)?;
+
+        for addr in addrs.to_socket_addrs()? {
+            let socket = net::UdpSocket::bind(addr)?;
+            self = self.listen_quic_inner(socket, config.clone())?;
+        }
+
+        Ok(self)
+    }
+
     /// Binds to existing listener for accepting incoming connection requests.
     ///
     /// No changes are made to `lst`'s configuration. Ensure it is configured properly before
@@ -527,11 +693,15 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let cfg = cfg.lock().unwrap();
                     let host = cfg.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = cfg.graceful_shutdown.clone();
 
                     let mut svc = HttpService::build()
                         .keep_alive(cfg.keep_alive)
                         .client_request_timeout(cfg.client_request_timeout)
                         .client_disconnect_timeout(cfg.client_disconnect_timeout)
+                        .h1_limits(cfg.h1_limits)
+                        .shutdown_signal(cfg.shutdown_signal())
+                        .proxy_protocol(cfg.proxy_protocol)
                         .local_addr(addr);
 
                     if let Some(handler) = on_connect_fn.clone() {
@@ -543,8 +713,12 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&cfg.quic_ports));
+
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(false, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .tcp()
                 })?;
@@ -571,12 +745,17 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let cfg = cfg.lock().unwrap();
                     let host = cfg.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = cfg.graceful_shutdown.clone();
 
                     let mut svc = HttpService::build()
                         .keep_alive(cfg.keep_alive)
                         .client_request_timeout(cfg.client_request_timeout)
                         .client_disconnect_timeout(cfg.client_disconnect_timeout)
-                        .local_addr(addr);
+                        .h1_limits(cfg.h1_limits)
+                        .shutdown_signal(cfg.shutdown_signal())
+                        .proxy_protocol(cfg.proxy_protocol)
+                        .local_addr(addr)
+                        .h2_config(cfg.h2_config.clone());
 
                     if let Some(handler) = on_connect_fn.clone() {
                         svc =
@@ -587,8 +766,12 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&cfg.quic_ports));
+
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(false, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .tcp_auto_h2c()
                 })?;
@@ -647,11 +830,16 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = c.graceful_shutdown.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .shutdown_signal(c.shutdown_signal())
+                        .proxy_protocol(c.proxy_protocol)
+                        .h2_config(c.h2_config.clone());
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -663,6 +851,9 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&c.quic_ports));
+
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
                         None => TlsAcceptorConfig::default(),
@@ -670,6 +861,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .rustls_with_config(config.clone(), acceptor_config)
                 })?;
@@ -698,11 +890,16 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = c.graceful_shutdown.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .shutdown_signal(c.shutdown_signal())
+                        .proxy_protocol(c.proxy_protocol)
+                        .h2_config(c.h2_config.clone());
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -714,6 +911,9 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&c.quic_ports));
+
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
                         None => TlsAcceptorConfig::default(),
@@ -721,6 +921,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .rustls_021_with_config(config.clone(), acceptor_config)
                 })?;
@@ -764,11 +965,16 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = c.graceful_shutdown.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .shutdown_signal(c.shutdown_signal())
+                        .proxy_protocol(c.proxy_protocol)
+                        .h2_config(c.h2_config.clone());
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -780,6 +986,9 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&c.quic_ports));
+
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
                         None => TlsAcceptorConfig::default(),
@@ -787,6 +996,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .rustls_0_22_with_config(config.clone(), acceptor_config)
                 })?;
@@ -830,11 +1040,16 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = c.graceful_shutdown.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .shutdown_signal(c.shutdown_signal())
+                        .proxy_protocol(c.proxy_protocol)
+                        .h2_config(c.h2_config.clone());
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -846,6 +1061,9 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&c.quic_ports));
+
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
                         None => TlsAcceptorConfig::default(),
@@ -853,6 +1071,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .rustls_0_23_with_config(config.clone(), acceptor_config)
                 })?;
@@ -895,12 +1114,17 @@ where
                 .listen(format!("actix-web-service-{}", addr), lst, move || {
                     let c = cfg.lock().unwrap();
                     let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                    let graceful_shutdown = c.graceful_shutdown.clone();
 
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
-                        .local_addr(addr);
+                        .h1_limits(c.h1_limits)
+                        .shutdown_signal(c.shutdown_signal())
+                        .proxy_protocol(c.proxy_protocol)
+                        .local_addr(addr)
+                        .h2_config(c.h2_config.clone());
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -912,6 +1136,9 @@ where
                         .into_factory()
                         .map_err(|err| err.into().error_response());
 
+                    #[cfg(feature = "http3")]
+                    let fac = fac.map(advertise_h3::<_, B>(&c.quic_ports));
+
                     // false positive lint (?)
                     #[allow(clippy::significant_drop_in_scrutinee)]
                     let acceptor_config = match c.tls_handshake_timeout {
@@ -921,6 +1148,7 @@ where
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(true, host.clone(), addr)
+                            .with_shutdown_signal(graceful_shutdown.clone())
                     }))
                     .openssl_with_config(acceptor.clone(), acceptor_config)
                 })?;
@@ -928,6 +1156,151 @@ where
         Ok(self)
     }
 
+    /// Binds to existing UDP socket for accepting incoming HTTP/3 (QUIC) connections using Rustls
+    /// v0.23.
+    ///
+    /// See [`bind_quic()`](Self::bind_quic()) for more details on how the QUIC endpoint is run and
+    /// the `config` argument.
+    #[cfg(feature = "http3")]
+    pub fn listen_quic(
+        self,
+        socket: net::UdpSocket,
+        config: actix_tls::accept::rustls_0_23::reexports::ServerConfig,
+    ) -> io::Result<Self> {
+        self.listen_quic_inner(socket, quic_server_config(config)?)
+    }
+
+    #[cfg(feature = "http3")]
+    fn listen_quic_inner(
+        mut self,
+        socket: net::UdpSocket,
+        config: quinn::ServerConfig,
+    ) -> io::Result<Self> {
+        use std::{pin::pin, rc::Rc};
+
+        use futures_util::future::{select, Either};
+
+        let factory = self.factory.clone();
+        let cfg = Arc::clone(&self.config);
+        let addr = socket.local_addr()?;
+        self.sockets.push(Socket {
+            addr,
+            scheme: "https",
+        });
+
+        cfg.lock().unwrap().quic_ports.push(addr.port());
+
+        let on_connect_fn = self.on_connect_fn.clone();
+
+        let start = move || {
+            let c = cfg.lock().unwrap();
+            let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+            let graceful_shutdown = c.graceful_shutdown.clone();
+            let shutdown_timeout = c.shutdown_timeout;
+
+            let mut svc = HttpService::build()
+                .keep_alive(c.keep_alive)
+                .client_request_timeout(c.client_request_timeout)
+                .client_disconnect_timeout(c.client_disconnect_timeout)
+                .local_addr(addr)
+                .secure()
+                .shutdown_signal(c.shutdown_signal());
+
+            if let Some(handler) = on_connect_fn.clone() {
+                svc = svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext));
+            }
+
+            let fac = factory()
+                .into_factory()
+                .map_err(|err| err.into().error_response());
+
+            let svc = svc.h3(map_config(fac, {
+                let graceful_shutdown = graceful_shutdown.clone();
+
+                move |_| {
+                    AppConfig::new(true, host.clone(), addr)
+                        .with_shutdown_signal(graceful_shutdown.clone())
+                }
+            }));
+
+            drop(c);
+
+            actix_rt::spawn(async move {
+                let svc = match svc.new_service(()).await {
+                    Ok(svc) => Rc::new(svc),
+                    Err(err) => {
+                        log::error!("Can not construct HTTP/3 service: {:?}", err);
+                        actix_rt::Arbiter::current().stop();
+                        return;
+                    }
+                };
+
+                let endpoint = match quinn::Endpoint::new(
+                    quinn::EndpointConfig::default(),
+                    Some(config),
+                    socket,
+                    Arc::new(quinn::TokioRuntime),
+                ) {
+                    Ok(endpoint) => endpoint,
+                    Err(err) => {
+                        log::error!("Can not start QUIC endpoint on {}: {}", addr, err);
+                        actix_rt::Arbiter::current().stop();
+                        return;
+                    }
+                };
+
+                let mut shutdown = pin!(graceful_shutdown.notified());
+
+                loop {
+                    let incoming = match select(pin!(endpoint.accept()), shutdown.as_mut()).await
+                    {
+                        Either::Left((Some(incoming), _)) => incoming,
+                        Either::Left((None, _)) => break,
+
+                        Either::Right(((), _)) => {
+                            // refuse new connections and give open ones, which are sent a GOAWAY
+                            // by their dispatchers, until the shutdown timeout to complete
+                            endpoint.set_server_config(None);
+
+                            if actix_rt::time::timeout(shutdown_timeout, endpoint.wait_idle())
+                                .await
+                                .is_err()
+                            {
+                                log::trace!("closing QUIC connections after shutdown timeout");
+                            }
+
+                            endpoint.close(0u32.into(), b"");
+                            break;
+                        }
+                    };
+
+                    let svc = Rc::clone(&svc);
+
+                    actix_rt::spawn(async move {
+                        match incoming.await {
+                            Ok(conn) => {
+                                if let Err(err) = svc.call(conn).await {
+                                    log::trace!("HTTP/3 connection error: {}", err);
+                                }
+                            }
+                            Err(err) => log::trace!("QUIC handshake error: {}", err),
+                        }
+                    });
+                }
+
+                actix_rt::Arbiter::current().stop();
+            });
+        };
+
+        self.quic_listeners.push(Box::new(move || {
+            let arbiter = actix_rt::Arbiter::new();
+            arbiter.spawn_fn(start);
+            arbiter
+        }));
+
+        Ok(self)
+    }
+
     /// Opens Unix Domain Socket (UDS) from `uds` path and binds server to created listener.
     #[cfg(unix)]
     pub fn bind_uds<A>(mut self, uds_path: A) -> io::Result<Self>
@@ -957,7 +1330,8 @@ where
                     false,
                     c.host.clone().unwrap_or_else(|| format!("{}", socket_addr)),
                     socket_addr,
-                );
+                )
+                .with_shutdown_signal(c.graceful_shutdown.clone());
 
                 let fac = factory()
                     .into_factory()
@@ -968,6 +1342,8 @@ where
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .shutdown_signal(c.shutdown_signal())
                         .finish(map_config(fac, move |_| config.clone())),
                 )
             },
@@ -1002,13 +1378,16 @@ where
                 false,
                 c.host.clone().unwrap_or_else(|| format!("{}", socket_addr)),
                 socket_addr,
-            );
+            )
+            .with_shutdown_signal(c.graceful_shutdown.clone());
 
             fn_service(|io: UnixStream| async { Ok((io, Protocol::Http1, None)) }).and_then({
                 let mut svc = HttpService::build()
                     .keep_alive(c.keep_alive)
                     .client_request_timeout(c.client_request_timeout)
-                    .client_disconnect_timeout(c.client_disconnect_timeout);
+                    .client_disconnect_timeout(c.client_disconnect_timeout)
+                    .h1_limits(c.h1_limits)
+                    .shutdown_signal(c.shutdown_signal());
 
                 if let Some(handler) = on_connect_fn.clone() {
                     svc = svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext));
@@ -1049,11 +1428,118 @@ where
     /// This methods panics if no socket addresses were successfully bound or if no Tokio runtime
     /// is set up.
     pub fn run(self) -> Server {
+        #[cfg(feature = "http3")]
+        let quic = QuicEndpoints(
+            self.quic_listeners
+                .into_iter()
+                .map(|start| start())
+                .collect(),
+        );
+
+        #[cfg(feature = "http3")]
+        let has_quic = !quic.0.is_empty();
+        #[cfg(not(feature = "http3"))]
+        let has_quic = false;
+
+        if !self.on_shutdown.is_empty() || has_quic {
+            let graceful_shutdown = self.config.lock().unwrap().graceful_shutdown.clone();
+            let hooks = self.on_shutdown;
+
+            // a forced shutdown never notifies the signal; this task is then dropped along with
+            // the runtime, stopping the QUIC arbiters
+            tokio::spawn(async move {
+                graceful_shutdown.notified().await;
+
+                #[cfg(feature = "http3")]
+                let quic = actix_rt::task::spawn_blocking(move || quic.join());
+
+                for hook in hooks {
+                    hook().await;
+                }
+
+                #[cfg(feature = "http3")]
+                let _ = quic.await;
+            });
+        }
+
         self.builder.run()
     }
 }
 
+/// Arbiters running QUIC endpoints, which stop themselves once drained after a graceful shutdown.
+///
+/// The arbiters are stopped when dropped.
+#[cfg(feature = "http3")]
+struct QuicEndpoints(Vec<actix_rt::Arbiter>);
+
+#[cfg(feature = "http3")]
+impl QuicEndpoints {
+    /// Waits for all endpoints to finish draining.
+    fn join(mut self) {
+        for arbiter in self.0.drain(..) {
+            let _ = arbiter.join();
+        }
+    }
+}
+
+#[cfg(feature = "http3")]
+impl Drop for QuicEndpoints {
+    fn drop(&mut self) {
+        for arbiter in &self.0 {
+            arbiter.stop();
+        }
+    }
+}
+
+/// Builds a QUIC server config from Rustls `config`, setting "h3" as the only ALPN protocol.
+#[cfg(feature = "http3")]
+fn quic_server_config(
+    mut config: actix_tls::accept::rustls_0_23::reexports::ServerConfig,
+) -> io::Result<quinn::ServerConfig> {
+    config.alpn_protocols = vec![actix_http::h3::ALPN.to_vec()];
+
+    let crypto = quinn::crypto::rustls::QuicServerConfig::try_from(config)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
 
+    Ok(quinn::ServerConfig::with_crypto(Arc::new(crypto)))
+}
+
+/// Returns a response mapper that advertises HTTP/3 on `quic_ports` using the `Alt-Svc` header.
+///
+/// Responses that already contain an `Alt-Svc` header are left untouched.
+#[cfg(feature = "http3")]
+fn advertise_h3<R, B>(quic_ports: &[u16]) -> impl FnMut(R) -> Response<B> + Clone
+where
+    R: Into<Response<B>>,
+{
+    use actix_http::header::{HeaderValue, ALT_SVC};
+
+    let mut ports = quic_ports.to_vec();
+    ports.sort_unstable();
+    ports.dedup();
+
+    let alt_svc = (!ports.is_empty()).then(|| {
+        let value = ports
+            .iter()
+            .map(|port| format!("h3=\":{}\"; ma=86400", port))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        HeaderValue::from_str(&value).unwrap()
+    });
+
+    move |res| {
+        let mut res = res.into();
+
+        if let Some(alt_svc) = &alt_svc {
+            if !res.headers().contains_key(ALT_SVC) {
+                res.headers_mut().insert(ALT_SVC, alt_svc.clone());
+            }
+        }
+
+        res
+    }
+}
 
 /// Bind TCP listeners to socket addresses resolved from `addrs` with options.
 fn bind_addrs(addrs: impl net::ToSocketAddrs, backlog: u32) -> io::Result<Vec<net::TcpListener>> {
@@ -1101,7 +1587,7 @@ fn create_tcp_listener(addr: net::SocketAddr, backlog: u32) -> io::Result<net::T
 
 /// Configures OpenSSL acceptor `builder` with ALPN protocols.
 #[cfg(feature = "openssl")]
-fn openssl_acceptor(mut builder: SslAcceptorBuilder) -> io::Result<SslAcceptor> {
+pub(crate) fn openssl_acceptor(mut builder: SslAcceptorBuilder) -> io::Result<SslAcceptor> {
     builder.set_alpn_select_callback(|_, protocols| {
         const H2: &[u8] = b"\x02h2";
         const H11: &[u8] = b"\x08http/1.1";
@@ -0,0 +1,468 @@
+use std::{
+    fmt,
+    future::{ready, Ready},
+    rc::Rc,
+    time::Duration,
+};
+
+use futures_core::future::LocalBoxFuture;
+
+use super::{
+    session::{Session, SessionStatus},
+    storage::{SessionKey, SessionState, SessionStore},
+};
+use crate::{
+    cookie::{Cookie, CookieJar, Key, SameSite},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error,
+    http::header::{HeaderValue, SET_COOKIE},
+    Error,
+};
+
+/// How the session cookie is protected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CookieContentSecurity {
+    /// The cookie is encrypted and authenticated, so its contents can be neither read nor
+    /// tampered with by the client.
+    ///
+    /// This is the default.
+    #[default]
+    Private,
+
+    /// The cookie is signed, so its contents can be read but not tampered with by the client.
+    Signed,
+}
+
+/// When the time-to-live of a session is extended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TtlExtensionPolicy {
+    /// The TTL is reset on every request, so sessions expire after a period of inactivity.
+    OnEveryRequest,
+
+    /// The TTL is only reset when the session state changes.
+    ///
+    /// This is the default.
+    #[default]
+    OnStateChanges,
+}
+
+/// Middleware that persists [`Session`] state between requests.
+///
+/// On each request, the session key is read from the session cookie and the session state is
+/// loaded from the [`SessionStore`]. After the request has been handled, changes to the session are
+/// written back to the store and the session cookie is updated.
+///
+/// The cookie is signed or encrypted using the given [`Key`]. The key must be the same for all
+/// workers and should be persisted; generating a new key invalidates all existing sessions.
+///
+/// See the [module documentation](super) for an example.
+#[derive(Clone)]
+pub struct SessionMiddleware {
+    store: Rc<dyn SessionStore>,
+    config: Rc<Config>,
+}
+
+#[derive(Clone)]
+struct Config {
+    key: Key,
+    cookie_name: String,
+    cookie_path: String,
+    cookie_domain: Option<String>,
+    cookie_secure: bool,
+    cookie_http_only: bool,
+    cookie_same_site: SameSite,
+    cookie_content_security: CookieContentSecurity,
+    persistent_cookie: bool,
+    session_ttl: Duration,
+    ttl_extension_policy: TtlExtensionPolicy,
+}
+
+impl SessionMiddleware {
+    /// Constructs a session middleware with the default configuration.
+    pub fn new(store: impl SessionStore + 'static, key: Key) -> Self {
+        Self::builder(store, key).build()
+    }
+
+    /// Returns a builder for configuring a session middleware.
+    pub fn builder(store: impl SessionStore + 'static, key: Key) -> SessionMiddlewareBuilder {
+        SessionMiddlewareBuilder {
+            store: Rc::new(store),
+            config: Config {
+                key,
+                cookie_name: "id".to_owned(),
+                cookie_path: "/".to_owned(),
+                cookie_domain: None,
+                cookie_secure: true,
+                cookie_http_only: true,
+                cookie_same_site: SameSite::Lax,
+                cookie_content_security: CookieContentSecurity::Private,
+                persistent_cookie: true,
+                session_ttl: Duration::from_secs(24 * 60 * 60),
+                ttl_extension_policy: TtlExtensionPolicy::OnStateChanges,
+            },
+        }
+    }
+}
+
+impl fmt::Debug for SessionMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionMiddleware")
+            .field("cookie_name", &self.config.cookie_name)
+            .field("session_ttl", &self.config.session_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builder for [`SessionMiddleware`].
+pub struct SessionMiddlewareBuilder {
+    store: Rc<dyn SessionStore>,
+    config: Config,
+}
+
+impl SessionMiddlewareBuilder {
+    /// Sets the name of the session cookie. Defaults to `id`.
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.config.cookie_name = name.into();
+        self
+    }
+
+    /// Sets the `Path` attribute of the session cookie. Defaults to `/`.
+    pub fn cookie_path(mut self, path: impl Into<String>) -> Self {
+        self.config.cookie_path = path.into();
+        self
+    }
+
+    /// Sets the `Domain` attribute of the session cookie. Not set by default.
+    pub fn cookie_domain(mut self, domain: Option<String>) -> Self {
+        self.config.cookie_domain = domain;
+        self
+    }
+
+    /// Sets whether the session cookie is only sent over HTTPS. Defaults to true.
+    pub fn cookie_secure(mut self, secure: bool) -> Self {
+        self.config.cookie_secure = secure;
+        self
+    }
+
+    /// Sets whether the session cookie is hidden from JavaScript. Defaults to true.
+    pub fn cookie_http_only(mut self, http_only: bool) -> Self {
+        self.config.cookie_http_only = http_only;
+        self
+    }
+
+    /// Sets the `SameSite` attribute of the session cookie. Defaults to `Lax`.
+    pub fn cookie_same_site(mut self, same_site: SameSite) -> Self {
+        self.config.cookie_same_site = same_site;
+        self
+    }
+
+    /// Sets how the session cookie is protected. Defaults to
+    /// [`Private`](CookieContentSecurity::Private).
+    pub fn cookie_content_security(mut self, content_security: CookieContentSecurity) -> Self {
+        self.config.cookie_content_security = content_security;
+        self
+    }
+
+    /// Sets whether the session cookie persists across browser restarts. Defaults to true.
+    ///
+    /// Persistent cookies get a `Max-Age` attribute equal to the session TTL. Otherwise, the
+    /// browser deletes the cookie when it is closed, while the server-side state still expires
+    /// after the session TTL.
+    pub fn persistent_cookie(mut self, persistent: bool) -> Self {
+        self.config.persistent_cookie = persistent;
+        self
+    }
+
+    /// Sets how long session state is kept after it was last extended. Defaults to one day.
+    pub fn session_ttl(mut self, ttl: Duration) -> Self {
+        self.config.session_ttl = ttl;
+        self
+    }
+
+    /// Sets when the session TTL is extended. Defaults to
+    /// [`OnStateChanges`](TtlExtensionPolicy::OnStateChanges).
+    pub fn ttl_extension_policy(mut self, policy: TtlExtensionPolicy) -> Self {
+        self.config.ttl_extension_policy = policy;
+        self
+    }
+
+    /// Finishes building the session middleware.
+    pub fn build(self) -> SessionMiddleware {
+        SessionMiddleware {
+            store: self.store,
+            config: Rc::new(self.config),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SessionMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = InnerSessionMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(InnerSessionMiddleware {
+            service: Rc::new(service),
+            store: Rc::clone(&self.store),
+            config: Rc::clone(&self.config),
+        }))
+    }
+}
+
+pub struct InnerSessionMiddleware<S> {
+    service: Rc<S>,
+    store: Rc<dyn SessionStore>,
+    config: Rc<Config>,
+}
+
+impl<S, B> Service<ServiceRequest> for InnerSessionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let store = Rc::clone(&self.store);
+        let config = Rc::clone(&self.config);
+
+        Box::pin(async move {
+            let session_key = config.session_key(&req);
+
+            let (session_key, state) = match session_key {
+                Some(key) => match store.load(&key).await? {
+                    Some(state) => (Some(key), state),
+                    None => (None, SessionState::new()),
+                },
+                None => (None, SessionState::new()),
+            };
+
+            Session::set_session(&mut req, state);
+
+            let mut res = service.call(req).await?;
+            let (status, state) = Session::get_changes(&res);
+            let ttl = config.session_ttl;
+
+            match (session_key, status) {
+                (None, SessionStatus::Changed | SessionStatus::Renewed) => {
+                    if !state.is_empty() {
+                        let key = store.save(state, ttl).await?;
+                        config.set_cookie(&mut res, key)?;
+                    }
+                }
+
+                (None, SessionStatus::Purged | SessionStatus::Unchanged) => {}
+
+                (Some(key), SessionStatus::Changed) => {
+                    let key = store.update(key, state, ttl).await?;
+                    config.set_cookie(&mut res, key)?;
+                }
+
+                (Some(key), SessionStatus::Purged) => {
+                    store.delete(&key).await?;
+                    config.remove_cookie(&mut res)?;
+                }
+
+                (Some(key), SessionStatus::Renewed) => {
+                    store.delete(&key).await?;
+                    let key = store.save(state, ttl).await?;
+                    config.set_cookie(&mut res, key)?;
+                }
+
+                (Some(key), SessionStatus::Unchanged) => {
+                    if config.ttl_extension_policy == TtlExtensionPolicy::OnEveryRequest {
+                        store.update_ttl(&key, ttl).await?;
+
+                        if config.persistent_cookie {
+                            config.set_cookie(&mut res, key)?;
+                        }
+                    }
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+impl Config {
+    /// Returns the verified session key from the request's session cookie.
+    fn session_key(&self, req: &ServiceRequest) -> Option<SessionKey> {
+        let cookie = req.cookie(&self.cookie_name)?;
+
+        let mut jar = CookieJar::new();
+        jar.add_original(cookie);
+
+        let verified = match self.cookie_content_security {
+            CookieContentSecurity::Private => jar.private(&self.key).get(&self.cookie_name),
+            CookieContentSecurity::Signed => jar.signed(&self.key).get(&self.cookie_name),
+        };
+
+        match verified {
+            Some(cookie) => SessionKey::try_from(cookie.value().to_owned()).ok(),
+            None => {
+                log::debug!("Session cookie failed verification; a new session will be created");
+                None
+            }
+        }
+    }
+
+    fn cookie(&self, value: String) -> Cookie<'static> {
+        let mut cookie = Cookie::new(self.cookie_name.clone(), value);
+        cookie.set_path(self.cookie_path.clone());
+        cookie.set_secure(self.cookie_secure);
+        cookie.set_http_only(self.cookie_http_only);
+        cookie.set_same_site(self.cookie_same_site);
+
+        if let Some(domain) = &self.cookie_domain {
+            cookie.set_domain(domain.clone());
+        }
+
+        cookie
+    }
+
+    fn set_cookie<B>(&self, res: &mut ServiceResponse<B>, key: SessionKey) -> Result<(), Error> {
+        let mut cookie = self.cookie(key.into());
+
+        if self.persistent_cookie {
+            let max_age = i64::try_from(self.session_ttl.as_secs()).unwrap_or(i64::MAX);
+            cookie.set_max_age(crate::cookie::time::Duration::seconds(max_age));
+        }
+
+        let mut jar = CookieJar::new();
+        match self.cookie_content_security {
+            CookieContentSecurity::Private => jar.private_mut(&self.key).add(cookie),
+            CookieContentSecurity::Signed => jar.signed_mut(&self.key).add(cookie),
+        }
+
+        // request cookies are percent-decoded, so values must be encoded to round-trip
+        for cookie in jar.delta() {
+            let value = HeaderValue::from_str(&cookie.encoded().to_string())
+                .map_err(error::ErrorInternalServerError)?;
+            res.headers_mut().append(SET_COOKIE, value);
+        }
+
+        Ok(())
+    }
+
+    fn remove_cookie<B>(&self, res: &mut ServiceResponse<B>) -> Result<(), Error> {
+        let cookie = self.cookie(String::new());
+
+        res.response_mut()
+            .add_removal_cookie(&cookie)
+            .map_err(error::ErrorInternalServerError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cookie::Key,
+        http::StatusCode,
+        session::{CookieSessionStore, InMemorySessionStore},
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    fn cookie_from<B>(res: &ServiceResponse<B>) -> Cookie<'static> {
+        res.response().cookies().next().unwrap().into_owned()
+    }
+
+    #[actix_rt::test]
+    async fn session_round_trip() {
+        let key = Key::generate();
+
+        for store in [
+            SessionMiddleware::new(InMemorySessionStore::new(), key.clone()),
+            SessionMiddleware::builder(CookieSessionStore::new(), key.clone())
+                .cookie_content_security(CookieContentSecurity::Signed)
+                .build(),
+        ] {
+            let app = test::init_service(
+                App::new()
+                    .wrap(store)
+                    .route(
+                        "/login",
+                        web::post().to(|session: Session| async move {
+                            session.renew();
+                            session.insert("user", "alice").unwrap();
+                            ""
+                        }),
+                    )
+                    .route(
+                        "/user",
+                        web::get().to(|session: Session| async move {
+                            session.get::<String>("user").unwrap().unwrap_or_default()
+                        }),
+                    )
+                    .route(
+                        "/logout",
+                        web::post().to(|session: Session| async move {
+                            session.purge();
+                            ""
+                        }),
+                    ),
+            )
+            .await;
+
+            // no session yet; no cookie is set for unchanged sessions
+            let res = test::call_service(&app, TestRequest::get().uri("/user").to_request()).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert!(res.headers().get(SET_COOKIE).is_none());
+
+            let req = TestRequest::post().uri("/login").to_request();
+            let res = test::call_service(&app, req).await;
+            let cookie = cookie_from(&res);
+            assert_eq!(cookie.name(), "id");
+            assert_eq!(cookie.http_only(), Some(true));
+
+            let req = TestRequest::get()
+                .uri("/user")
+                .cookie(cookie.clone())
+                .to_request();
+            let body = test::call_and_read_body(&app, req).await;
+            assert_eq!(body, "alice");
+
+            let req = TestRequest::post()
+                .uri("/logout")
+                .cookie(cookie.clone())
+                .to_request();
+            let res = test::call_service(&app, req).await;
+            let removal = cookie_from(&res);
+            assert_eq!(removal.value(), "");
+        }
+    }
+
+    #[actix_rt::test]
+    async fn tampered_cookie() {
+        let app = test::init_service(
+            App::new()
+                .wrap(SessionMiddleware::new(
+                    CookieSessionStore::new(),
+                    Key::generate(),
+                ))
+                .default_service(web::to(|session: Session| async move {
+                    session.get::<String>("user").unwrap().unwrap_or_default()
+                })),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .cookie(Cookie::new("id", r#"{"user":"\"mallory\""}"#))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "");
+    }
+}
@@ -0,0 +1,64 @@
+//! Session management.
+//!
+//! Sessions associate state with a client across requests. State is read and modified through the
+//! [`Session`] extractor and persisted by [`SessionMiddleware`] using a [`SessionStore`]. The
+//! client only holds a signed or encrypted cookie containing a session key.
+//!
+//! Two stores are provided:
+//!
+//! - [`CookieSessionStore`] keeps the whole session state in the cookie itself. No server-side
+//!   storage is needed, but state is limited to about 4KB and cannot be invalidated server-side.
+//! - [`InMemorySessionStore`] keeps session state in memory, keyed by random session keys. State
+//!   is lost on restart and is not shared between processes.
+//!
+//! Other backends, such as databases, can be used by implementing [`SessionStore`].
+//!
+//! This module requires the `secure-cookies` feature.
+//!
+//! # Examples
+//! ```no_run
+//! use actix_web::{
+//!     cookie::Key,
+//!     session::{InMemorySessionStore, Session, SessionMiddleware},
+//!     web, App, Error, HttpServer, Responder,
+//! };
+//!
+//! async fn index(session: Session) -> Result<impl Responder, Error> {
+//!     let count = session.get::<u32>("counter")?.unwrap_or(0) + 1;
+//!     session.insert("counter", count)?;
+//!
+//!     Ok(format!("Visits: {count}"))
+//! }
+//!
+//! #[actix_web::main]
+//! async fn main() -> std::io::Result<()> {
+//!     // the key must be the same across workers, and should be loaded from configuration
+//!     let key = Key::generate();
+//!     let store = InMemorySessionStore::new();
+//!
+//!     HttpServer::new(move || {
+//!         App::new()
+//!             .wrap(SessionMiddleware::new(store.clone(), key.clone()))
+//!             .route("/", web::get().to(index))
+//!     })
+//!     .bind(("127.0.0.1", 8080))?
+//!     .run()
+//!     .await
+//! }
+//! ```
+
+mod middleware;
+#[allow(clippy::module_inception)]
+mod session;
+mod storage;
+
+pub use self::{
+    middleware::{
+        CookieContentSecurity, SessionMiddleware, SessionMiddlewareBuilder, TtlExtensionPolicy,
+    },
+    session::{Session, SessionExt, SessionGetError, SessionInsertError, SessionStatus},
+    storage::{
+        CookieSessionStore, InMemorySessionStore, InvalidSessionKeyError, SessionKey, SessionState,
+        SessionStore,
+    },
+};
@@ -0,0 +1,310 @@
+use std::{
+    cell::{Ref, RefCell},
+    future::{ready, Ready},
+    mem,
+    rc::Rc,
+};
+
+use actix_http::Payload;
+use derive_more::{Display, Error};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::storage::SessionState;
+use crate::{
+    dev::{Extensions, ServiceRequest, ServiceResponse},
+    Error, FromRequest, HttpMessage as _, HttpRequest, ResponseError,
+};
+
+/// The primary interface to access and modify session state.
+///
+/// `Session` is an extractor; it can be used in handlers and middleware (through
+/// [`SessionExt`]) placed inside [`SessionMiddleware`](super::SessionMiddleware). All instances
+/// for a request share the same state.
+///
+/// Values are stored as JSON strings.
+///
+/// # Examples
+/// ```
+/// use actix_web::{session::Session, Error, Responder};
+///
+/// async fn index(session: Session) -> Result<impl Responder, Error> {
+///     if let Some(count) = session.get::<i32>("counter")? {
+///         session.insert("counter", count + 1)?;
+///     } else {
+///         session.insert("counter", 1)?;
+///     }
+///
+///     let count = session.get::<i32>("counter")?.unwrap();
+///     Ok(format!("Counter: {count}"))
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Session(Rc<RefCell<SessionInner>>);
+
+/// Status of a [`Session`] after the request has been handled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// Session state has been modified.
+    ///
+    /// The state is persisted and the session cookie is refreshed.
+    Changed,
+
+    /// Session has been scheduled for deletion by [`Session::purge`].
+    ///
+    /// The state is removed from the store and the session cookie is removed from the client.
+    Purged,
+
+    /// Session key has been scheduled for renewal by [`Session::renew`].
+    ///
+    /// The state is persisted under a new session key and the old key is invalidated.
+    Renewed,
+
+    /// Session state has not been modified.
+    #[default]
+    Unchanged,
+}
+
+#[derive(Default)]
+struct SessionInner {
+    state: SessionState,
+    status: SessionStatus,
+}
+
+impl Session {
+    /// Returns the value for `key`, deserialized as `T`, if it exists.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, SessionGetError> {
+        match self.0.borrow().state.get(key) {
+            Some(value) => serde_json::from_str(value)
+                .map(Some)
+                .map_err(|err| SessionGetError {
+                    key: key.to_owned(),
+                    source: err,
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns all raw key-value pairs in the session.
+    pub fn entries(&self) -> Ref<'_, SessionState> {
+        Ref::map(self.0.borrow(), |inner| &inner.state)
+    }
+
+    /// Returns the status of the session.
+    pub fn status(&self) -> SessionStatus {
+        self.0.borrow().status.clone()
+    }
+
+    /// Inserts a key-value pair into the session, serializing the value as JSON.
+    ///
+    /// Any existing value for `key` is replaced.
+    pub fn insert<T: Serialize>(
+        &self,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<(), SessionInsertError> {
+        let key = key.into();
+
+        let value = serde_json::to_string(&value).map_err(|err| SessionInsertError {
+            key: key.clone(),
+            source: err,
+        })?;
+
+        let mut inner = self.0.borrow_mut();
+        inner.mark_changed();
+        inner.state.insert(key, value);
+
+        Ok(())
+    }
+
+    /// Removes `key` from the session, returning its raw value if it existed.
+    pub fn remove(&self, key: &str) -> Option<String> {
+        let mut inner = self.0.borrow_mut();
+        inner.mark_changed();
+        inner.state.remove(key)
+    }
+
+    /// Removes `key` from the session, returning its value deserialized as `T`.
+    ///
+    /// Returns `None` if the key did not exist. If the value could not be deserialized, its raw
+    /// value is returned in the `Err` variant.
+    pub fn remove_as<T: DeserializeOwned>(&self, key: &str) -> Option<Result<T, String>> {
+        self.remove(key)
+            .map(|value| serde_json::from_str(&value).map_err(|_| value))
+    }
+
+    /// Removes all values from the session.
+    pub fn clear(&self) {
+        let mut inner = self.0.borrow_mut();
+        inner.mark_changed();
+        inner.state.clear();
+    }
+
+    /// Removes the session, both client-side and server-side.
+    pub fn purge(&self) {
+        let mut inner = self.0.borrow_mut();
+        inner.status = SessionStatus::Purged;
+        inner.state.clear();
+    }
+
+    /// Renews the session key, keeping the session state.
+    ///
+    /// This should be done whenever the privilege level of a session changes, e.g., on login, to
+    /// prevent session fixation attacks.
+    pub fn renew(&self) {
+        let mut inner = self.0.borrow_mut();
+
+        if inner.status != SessionStatus::Purged {
+            inner.status = SessionStatus::Renewed;
+        }
+    }
+
+    /// Adds the given state to the session.
+    pub(crate) fn set_session(req: &mut ServiceRequest, state: SessionState) {
+        let session = Session::get_session(&mut req.extensions_mut());
+        let mut inner = session.0.borrow_mut();
+        inner.state.extend(state);
+    }
+
+    /// Returns the session status and state, leaving the session empty.
+    pub(crate) fn get_changes<B>(res: &ServiceResponse<B>) -> (SessionStatus, SessionState) {
+        if let Some(session) = res.request().extensions().get::<Session>() {
+            let mut inner = session.0.borrow_mut();
+            let state = mem::take(&mut inner.state);
+            (mem::take(&mut inner.status), state)
+        } else {
+            (SessionStatus::Unchanged, SessionState::new())
+        }
+    }
+
+    fn get_session(extensions: &mut Extensions) -> Session {
+        if let Some(session) = extensions.get::<Session>() {
+            return session.clone();
+        }
+
+        let session = Session(Rc::new(RefCell::new(SessionInner::default())));
+        extensions.insert(session.clone());
+        session
+    }
+}
+
+impl SessionInner {
+    fn mark_changed(&mut self) {
+        if self.status == SessionStatus::Unchanged {
+            self.status = SessionStatus::Changed;
+        }
+    }
+}
+
+/// Extracts a [`Session`] from the request.
+///
+/// Sessions are only persisted when the request is handled inside a
+/// [`SessionMiddleware`](super::SessionMiddleware).
+impl FromRequest for Session {
+    type Error = Error;
+    type Future = Ready<Result<Session, Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(Session::get_session(&mut req.extensions_mut())))
+    }
+}
+
+/// Extension trait for accessing the [`Session`] of a request or response.
+///
+/// This is mainly useful in middleware.
+pub trait SessionExt {
+    /// Returns the [`Session`] of the request.
+    fn get_session(&self) -> Session;
+}
+
+impl SessionExt for HttpRequest {
+    fn get_session(&self) -> Session {
+        Session::get_session(&mut self.extensions_mut())
+    }
+}
+
+impl SessionExt for ServiceRequest {
+    fn get_session(&self) -> Session {
+        Session::get_session(&mut self.extensions_mut())
+    }
+}
+
+impl<B> SessionExt for ServiceResponse<B> {
+    fn get_session(&self) -> Session {
+        self.request().get_session()
+    }
+}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = self.0.borrow();
+
+        f.debug_struct("Session")
+            .field("keys", &inner.state.keys().collect::<Vec<_>>())
+            .field("status", &inner.status)
+            .finish()
+    }
+}
+
+/// Error returned by [`Session::get`] when a value cannot be deserialized.
+#[derive(Debug, Display, Error)]
+#[display("Failed to deserialize the session value for key `{key}`")]
+pub struct SessionGetError {
+    key: String,
+    source: serde_json::Error,
+}
+
+impl ResponseError for SessionGetError {}
+
+/// Error returned by [`Session::insert`] when a value cannot be serialized.
+#[derive(Debug, Display, Error)]
+#[display("Failed to serialize the session value for key `{key}`")]
+pub struct SessionInsertError {
+    key: String,
+    source: serde_json::Error,
+}
+
+impl ResponseError for SessionInsertError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session(Rc::new(RefCell::new(SessionInner::default())))
+    }
+
+    #[test]
+    fn insert_and_get() {
+        let session = session();
+        assert_eq!(session.status(), SessionStatus::Unchanged);
+
+        session.insert("count", 42).unwrap();
+        assert_eq!(session.get::<u32>("count").unwrap(), Some(42));
+        assert_eq!(session.entries().get("count").unwrap(), "42");
+        assert_eq!(session.get::<u32>("missing").unwrap(), None);
+        assert!(session.get::<String>("count").is_err());
+        assert_eq!(session.status(), SessionStatus::Changed);
+
+        assert_eq!(session.remove_as::<u32>("count"), Some(Ok(42)));
+        assert!(session.entries().is_empty());
+    }
+
+    #[test]
+    fn status_transitions() {
+        let session = session();
+        session.renew();
+        assert_eq!(session.status(), SessionStatus::Renewed);
+
+        // changes after renewal still renew the key
+        session.insert("user", "alice").unwrap();
+        assert_eq!(session.status(), SessionStatus::Renewed);
+
+        session.purge();
+        assert_eq!(session.status(), SessionStatus::Purged);
+        assert!(session.entries().is_empty());
+
+        session.renew();
+        assert_eq!(session.status(), SessionStatus::Purged);
+    }
+}
@@ -0,0 +1,104 @@
+use std::{future::ready, time::Duration};
+
+use futures_core::future::LocalBoxFuture;
+
+use super::{SessionKey, SessionState, SessionStore};
+use crate::{error, Error};
+
+/// A [`SessionStore`] that keeps the whole session state in the session cookie.
+///
+/// The session key is the JSON-encoded session state, so no server-side storage is needed. Since
+/// cookies are limited in size, saving state larger than about 4KB fails.
+///
+/// The state can be read by the client unless the cookie is encrypted; see
+/// [`CookieContentSecurity`](crate::session::CookieContentSecurity). Sessions cannot be
+/// invalidated server-side: purging a session removes the cookie from the client, but a copy of the
+/// cookie remains valid until it expires.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct CookieSessionStore;
+
+impl CookieSessionStore {
+    /// Constructs a new cookie session store.
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn serialize(state: &SessionState) -> Result<SessionKey, Error> {
+        let json = serde_json::to_string(state).map_err(error::ErrorInternalServerError)?;
+        Ok(SessionKey::try_from(json)?)
+    }
+}
+
+impl SessionStore for CookieSessionStore {
+    fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> LocalBoxFuture<'static, Result<Option<SessionState>, Error>> {
+        // cookie contents have been verified, so invalid state is treated as a missing session
+        let state = serde_json::from_str(session_key.as_ref()).ok();
+        Box::pin(ready(Ok(state)))
+    }
+
+    fn save(
+        &self,
+        session_state: SessionState,
+        _ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<SessionKey, Error>> {
+        Box::pin(ready(Self::serialize(&session_state)))
+    }
+
+    fn update(
+        &self,
+        _session_key: SessionKey,
+        session_state: SessionState,
+        _ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<SessionKey, Error>> {
+        Box::pin(ready(Self::serialize(&session_state)))
+    }
+
+    fn update_ttl(
+        &self,
+        _session_key: &SessionKey,
+        _ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<(), Error>> {
+        Box::pin(ready(Ok(())))
+    }
+
+    fn delete(&self, _session_key: &SessionKey) -> LocalBoxFuture<'static, Result<(), Error>> {
+        Box::pin(ready(Ok(())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn round_trip() {
+        let store = CookieSessionStore::new();
+        let ttl = Duration::from_secs(60);
+
+        let mut state = SessionState::new();
+        state.insert("user".to_owned(), r#""alice""#.to_owned());
+
+        let key = store.save(state.clone(), ttl).await.unwrap();
+        assert_eq!(store.load(&key).await.unwrap(), Some(state));
+
+        let key = SessionKey::try_from("not json".to_owned()).unwrap();
+        assert_eq!(store.load(&key).await.unwrap(), None);
+    }
+
+    #[actix_rt::test]
+    async fn state_too_large() {
+        let store = CookieSessionStore::new();
+
+        let mut state = SessionState::new();
+        state.insert("data".to_owned(), "a".repeat(5000));
+
+        store
+            .save(state, Duration::from_secs(60))
+            .await
+            .unwrap_err();
+    }
+}
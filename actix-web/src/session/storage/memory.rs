@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    future::ready,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_core::future::LocalBoxFuture;
+use rand::{distr::Alphanumeric, Rng as _};
+
+use super::{SessionKey, SessionState, SessionStore};
+use crate::Error;
+
+/// Length of generated session keys.
+const KEY_LEN: usize = 64;
+
+/// Period after which expired sessions are removed.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A [`SessionStore`] that keeps session state in memory.
+///
+/// Session keys are random 64-character strings. Clones share the same state, including across
+/// threads, so a single store should be created outside the `HttpServer::new` closure and cloned
+/// into each worker. State is lost when the process exits.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySessionStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    sessions: HashMap<String, Entry>,
+    last_sweep: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    state: SessionState,
+
+    /// Expiry time; `None` if the TTL is too long to be represented.
+    expires: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+impl InMemorySessionStore {
+    /// Constructs an empty in-memory session store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn load_at(&self, session_key: &SessionKey, now: Instant) -> Option<SessionState> {
+        let mut inner = self.inner.lock().unwrap();
+
+        match inner.sessions.get(session_key.as_ref()) {
+            Some(entry) if !entry.is_expired(now) => Some(entry.state.clone()),
+            Some(_) => {
+                inner.sessions.remove(session_key.as_ref());
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn save_at(&self, state: SessionState, ttl: Duration, now: Instant) -> SessionKey {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner
+            .last_sweep
+            .map_or(true, |last| now.duration_since(last) >= SWEEP_INTERVAL)
+        {
+            inner.sessions.retain(|_, entry| !entry.is_expired(now));
+            inner.last_sweep = Some(now);
+        }
+
+        let key = loop {
+            let key = rand::rng()
+                .sample_iter(&Alphanumeric)
+                .take(KEY_LEN)
+                .map(char::from)
+                .collect::<String>();
+
+            if !inner.sessions.contains_key(&key) {
+                break key;
+            }
+        };
+
+        inner.sessions.insert(
+            key.clone(),
+            Entry {
+                state,
+                expires: now.checked_add(ttl),
+            },
+        );
+
+        SessionKey(key)
+    }
+
+    fn update_at(
+        &self,
+        session_key: SessionKey,
+        state: SessionState,
+        ttl: Duration,
+        now: Instant,
+    ) -> SessionKey {
+        {
+            let mut inner = self.inner.lock().unwrap();
+
+            if let Some(entry) = inner.sessions.get_mut(session_key.as_ref()) {
+                if !entry.is_expired(now) {
+                    entry.state = state;
+                    entry.expires = now.checked_add(ttl);
+                    return session_key;
+                }
+            }
+        }
+
+        // session has expired in the meantime
+        self.save_at(state, ttl, now)
+    }
+
+    fn update_ttl_at(&self, session_key: &SessionKey, ttl: Duration, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Some(entry) = inner.sessions.get_mut(session_key.as_ref()) {
+            if !entry.is_expired(now) {
+                entry.expires = now.checked_add(ttl);
+            }
+        }
+    }
+}
+
+impl SessionStore for InMemorySessionStore {
+    fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> LocalBoxFuture<'static, Result<Option<SessionState>, Error>> {
+        let state = self.load_at(session_key, Instant::now());
+        Box::pin(ready(Ok(state)))
+    }
+
+    fn save(
+        &self,
+        session_state: SessionState,
+        ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<SessionKey, Error>> {
+        let key = self.save_at(session_state, ttl, Instant::now());
+        Box::pin(ready(Ok(key)))
+    }
+
+    fn update(
+        &self,
+        session_key: SessionKey,
+        session_state: SessionState,
+        ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<SessionKey, Error>> {
+        let key = self.update_at(session_key, session_state, ttl, Instant::now());
+        Box::pin(ready(Ok(key)))
+    }
+
+    fn update_ttl(
+        &self,
+        session_key: &SessionKey,
+        ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<(), Error>> {
+        self.update_ttl_at(session_key, ttl, Instant::now());
+        Box::pin(ready(Ok(())))
+    }
+
+    fn delete(&self, session_key: &SessionKey) -> LocalBoxFuture<'static, Result<(), Error>> {
+        self.inner
+            .lock()
+            .unwrap()
+            .sessions
+            .remove(session_key.as_ref());
+
+        Box::pin(ready(Ok(())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> SessionState {
+        let mut state = SessionState::new();
+        state.insert("user".to_owned(), r#""alice""#.to_owned());
+        state
+    }
+
+    #[test]
+    fn expiry() {
+        let store = InMemorySessionStore::new();
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+
+        let key = store.save_at(state(), ttl, now);
+        assert_eq!(key.as_ref().len(), KEY_LEN);
+        assert_eq!(store.load_at(&key, now + ttl / 2), Some(state()));
+
+        store.update_ttl_at(&key, ttl, now + ttl / 2);
+        assert_eq!(store.load_at(&key, now + ttl), Some(state()));
+        assert_eq!(store.load_at(&key, now + ttl * 2), None);
+    }
+
+    #[test]
+    fn update() {
+        let store = InMemorySessionStore::new();
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+
+        let key = store.save_at(SessionState::new(), ttl, now);
+        let updated = store.update_at(key.clone(), state(), ttl, now);
+        assert_eq!(updated, key);
+        assert_eq!(store.load_at(&key, now), Some(state()));
+
+        // expired sessions get a new key
+        let renewed = store.update_at(key.clone(), state(), ttl, now + ttl);
+        assert_ne!(renewed, key);
+        assert_eq!(store.load_at(&renewed, now + ttl), Some(state()));
+    }
+
+    #[test]
+    fn unbounded_ttl() {
+        let store = InMemorySessionStore::new();
+        let now = Instant::now();
+
+        let key = store.save_at(state(), Duration::MAX, now);
+        assert_eq!(
+            store.load_at(&key, now + Duration::from_secs(60)),
+            Some(state())
+        );
+
+        let key = store.update_at(key, state(), Duration::MAX, now);
+        store.update_ttl_at(&key, Duration::MAX, now);
+        assert_eq!(
+            store.load_at(&key, now + Duration::from_secs(60)),
+            Some(state())
+        );
+    }
+
+    #[actix_rt::test]
+    async fn delete() {
+        let store = InMemorySessionStore::new();
+
+        let key = store.save(state(), Duration::from_secs(60)).await.unwrap();
+        store.delete(&key).await.unwrap();
+        assert_eq!(store.load(&key).await.unwrap(), None);
+    }
+}
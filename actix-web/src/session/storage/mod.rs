@@ -0,0 +1,114 @@
+use std::{collections::HashMap, time::Duration};
+
+use derive_more::{Display, Error};
+use futures_core::future::LocalBoxFuture;
+
+use crate::{Error, ResponseError};
+
+mod cookie;
+mod memory;
+
+pub use self::{cookie::CookieSessionStore, memory::InMemorySessionStore};
+
+/// The state of a session, as a map of keys to JSON-encoded values.
+pub type SessionState = HashMap<String, String>;
+
+/// Storage backend for [`SessionMiddleware`](super::SessionMiddleware).
+///
+/// Session state is identified by a [`SessionKey`] that is sent to the client in the session
+/// cookie. Stores decide how keys are generated; server-side stores should use keys that cannot
+/// be guessed.
+pub trait SessionStore {
+    /// Loads the session state associated with `session_key`.
+    ///
+    /// Returns `None` if there is no state for the key, e.g., because it has expired.
+    fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> LocalBoxFuture<'static, Result<Option<SessionState>, Error>>;
+
+    /// Persists the state of a new session, returning its key.
+    ///
+    /// The state should be removed once `ttl` has elapsed.
+    fn save(
+        &self,
+        session_state: SessionState,
+        ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<SessionKey, Error>>;
+
+    /// Replaces the state of an existing session, returning its key.
+    ///
+    /// The returned key may differ from `session_key`, e.g., if the session had already expired.
+    fn update(
+        &self,
+        session_key: SessionKey,
+        session_state: SessionState,
+        ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<SessionKey, Error>>;
+
+    /// Resets the time-to-live of an existing session without changing its state.
+    fn update_ttl(
+        &self,
+        session_key: &SessionKey,
+        ttl: Duration,
+    ) -> LocalBoxFuture<'static, Result<(), Error>>;
+
+    /// Deletes the state of a session.
+    fn delete(&self, session_key: &SessionKey) -> LocalBoxFuture<'static, Result<(), Error>>;
+}
+
+/// Identifier of a session, as stored in the session cookie.
+///
+/// Session keys are at most 4064 bytes long so that they fit into a cookie.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey(String);
+
+impl SessionKey {
+    /// Maximum length of a session key, in bytes.
+    const MAX_LEN: usize = 4064;
+}
+
+impl TryFrom<String> for SessionKey {
+    type Error = InvalidSessionKeyError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() > Self::MAX_LEN {
+            return Err(InvalidSessionKeyError);
+        }
+
+        Ok(SessionKey(value))
+    }
+}
+
+impl AsRef<str> for SessionKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<SessionKey> for String {
+    fn from(key: SessionKey) -> Self {
+        key.0
+    }
+}
+
+/// Error returned when a session key would be too long to be stored in a cookie.
+#[derive(Debug, Display, Error)]
+#[display("Session key is longer than 4064 bytes")]
+#[non_exhaustive]
+pub struct InvalidSessionKeyError;
+
+impl ResponseError for InvalidSessionKeyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_key_length() {
+        let key = SessionKey::try_from("a".repeat(SessionKey::MAX_LEN)).unwrap();
+        assert_eq!(key.as_ref().len(), SessionKey::MAX_LEN);
+
+        SessionKey::try_from("a".repeat(SessionKey::MAX_LEN + 1)).unwrap_err();
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    convert::Infallible,
+    fmt,
+    future::{ready, Future, Ready},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_server::GracefulShutdownSignal;
+use futures_core::future::BoxFuture;
+use futures_util::task::noop_waker_ref;
+
+use crate::{dev::Payload, FromRequest, HttpRequest};
+
+/// Extractor for a future that resolves when the server starts a graceful shutdown.
+///
+/// Long-running handlers, such as long-polling and streaming endpoints, can select on this signal
+/// to finish early instead of holding up the shutdown until the
+/// [shutdown timeout](crate::HttpServer::shutdown_timeout) expires.
+///
+/// A graceful shutdown is started by a `SIGTERM` signal or a graceful
+/// [`stop`](crate::dev::ServerHandle::stop); forced shutdowns do not notify this signal. When the
+/// application is not served by an [`HttpServer`](crate::HttpServer), such as in tests, the signal
+/// never resolves.
+///
+/// # Examples
+/// ```
+/// use std::{pin::pin, time::Duration};
+///
+/// use actix_web::{get, rt::time::sleep, web::ShutdownSignal, HttpResponse};
+/// use futures_util::future::{select, Either};
+///
+/// #[get("/updates")]
+/// async fn updates(shutdown: ShutdownSignal) -> HttpResponse {
+///     let update = pin!(sleep(Duration::from_secs(30)));
+///
+///     match select(update, shutdown).await {
+///         Either::Left(_) => HttpResponse::Ok().body("update"),
+///
+///         // let the client reconnect to another instance
+///         Either::Right(_) => HttpResponse::ServiceUnavailable().finish(),
+///     }
+/// }
+/// ```
+pub struct ShutdownSignal {
+    signal: Option<GracefulShutdownSignal>,
+    notified: Option<BoxFuture<'static, ()>>,
+}
+
+impl ShutdownSignal {
+    pub(crate) fn new(signal: Option<GracefulShutdownSignal>) -> Self {
+        Self {
+            signal,
+            notified: None,
+        }
+    }
+
+    /// Returns `true` if the server has started a graceful shutdown.
+    pub fn is_shutting_down(&self) -> bool {
+        match self.signal {
+            Some(ref signal) => notified(signal.clone())
+                .as_mut()
+                .poll(&mut Context::from_waker(noop_waker_ref()))
+                .is_ready(),
+            None => false,
+        }
+    }
+}
+
+fn notified(signal: GracefulShutdownSignal) -> BoxFuture<'static, ()> {
+    Box::pin(async move { signal.notified().await })
+}
+
+impl Clone for ShutdownSignal {
+    fn clone(&self) -> Self {
+        Self::new(self.signal.clone())
+    }
+}
+
+impl fmt::Debug for ShutdownSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownSignal")
+            .field("signal", &self.signal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Future for ShutdownSignal {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let signal = match this.signal {
+            Some(ref signal) => signal,
+            None => return Poll::Pending,
+        };
+
+        let res = this
+            .notified
+            .get_or_insert_with(|| notified(signal.clone()))
+            .as_mut()
+            .poll(cx);
+
+        if res.is_ready() {
+            // signal stays notified so a new future would resolve immediately when polled again
+            this.notified = None;
+        }
+
+        res
+    }
+}
+
+impl FromRequest for ShutdownSignal {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(req.app_config().shutdown_signal()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use actix_rt::{net::TcpStream, time::timeout};
+    use actix_server::Server;
+    use actix_service::fn_service;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn notified_on_graceful_shutdown() {
+        let builder = Server::build()
+            .workers(1)
+            .disable_signals()
+            .bind("test", "127.0.0.1:0", || {
+                fn_service(|_: TcpStream| async { Ok::<_, ()>(()) })
+            })
+            .unwrap();
+        let mut signal = ShutdownSignal::new(Some(builder.graceful_shutdown_signal()));
+
+        let srv = builder.run();
+        let handle = srv.handle();
+        actix_rt::spawn(srv);
+
+        assert!(!signal.is_shutting_down());
+        timeout(Duration::from_millis(50), &mut signal)
+            .await
+            .unwrap_err();
+
+        handle.stop(true).await;
+
+        assert!(signal.is_shutting_down());
+        timeout(Duration::from_millis(50), &mut signal)
+            .await
+            .unwrap();
+        timeout(Duration::from_millis(50), signal.clone())
+            .await
+            .unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn never_notified_without_server() {
+        let signal = ShutdownSignal::new(None);
+        assert!(!signal.is_shutting_down());
+        timeout(Duration::from_millis(50), signal)
+            .await
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,415 @@
+//! Server-Sent Events (SSE).
+//!
+//! See [`Sse`] for responder documentation.
+
+use std::{
+    error::Error as StdError,
+    fmt::Write as _,
+    future::Future as _,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use actix_rt::time::{sleep, Instant, Sleep};
+use actix_utils::future::{ready, Ready};
+use bytes::{BufMut as _, Bytes, BytesMut};
+use derive_more::{AsRef, Deref, Display};
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+use serde::Serialize;
+
+use crate::{
+    body::{BodySize, MessageBody},
+    dev::Payload,
+    error::ParseError,
+    http::{
+        header::{self, ContentEncoding, HeaderName, HeaderValue},
+        StatusCode,
+    },
+    FromRequest, HttpRequest, HttpResponse, Responder,
+};
+
+static LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+/// Default interval between keep-alive comments.
+const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// A Server-Sent Event, as sent by an [`Sse`] response.
+///
+/// Events are made up of optional fields; multi-line data and comments are split into multiple
+/// lines as required by the [event stream format].
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use actix_web::sse::Event;
+///
+/// let event = Event::data("42")
+///     .event("temperature")
+///     .id("7")
+///     .retry(Duration::from_secs(5));
+/// ```
+///
+/// [event stream format]: https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Duration>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// Constructs an event with the given data.
+    pub fn data(data: impl Into<String>) -> Self {
+        Self {
+            data: Some(data.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Constructs an event with the given value serialized as JSON data.
+    ///
+    /// # Errors
+    /// Returns an error if `data` fails to serialize.
+    pub fn json(data: &impl Serialize) -> Result<Self, serde_json::Error> {
+        serde_json::to_string(data).map(Self::data)
+    }
+
+    /// Constructs a comment, which is ignored by clients.
+    pub fn comment(text: impl Into<String>) -> Self {
+        Self {
+            comment: Some(text.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the event ID, which clients send back in the `Last-Event-ID` header when reconnecting.
+    ///
+    /// Line breaks are removed.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the event type, which defaults to `message` on clients.
+    ///
+    /// Line breaks are removed.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Sets the time clients should wait before reconnecting after the connection is lost.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Serializes event to the event stream format.
+    fn into_bytes(self) -> Bytes {
+        let mut buf = BytesMut::new();
+
+        if let Some(comment) = &self.comment {
+            write_lines(&mut buf, "", comment);
+        }
+
+        if let Some(event) = &self.event {
+            write_field(&mut buf, "event", event);
+        }
+
+        if let Some(id) = &self.id {
+            write_field(&mut buf, "id", id);
+        }
+
+        if let Some(retry) = self.retry {
+            let _ = writeln!(buf, "retry: {}", retry.as_millis());
+        }
+
+        if let Some(data) = &self.data {
+            write_lines(&mut buf, "data", data);
+        }
+
+        buf.put_u8(b'\n');
+        buf.freeze()
+    }
+}
+
+/// Writes single-line field, removing any line breaks from the value.
+fn write_field(buf: &mut BytesMut, name: &str, value: &str) {
+    buf.put_slice(name.as_bytes());
+    buf.put_slice(b": ");
+
+    for part in value.split(['\r', '\n']) {
+        buf.put_slice(part.as_bytes());
+    }
+
+    buf.put_u8(b'\n');
+}
+
+/// Writes a field line for each line of the value. An empty name writes comment lines.
+fn write_lines(buf: &mut BytesMut, name: &str, value: &str) {
+    let value = value.replace("\r\n", "\n");
+
+    for line in value.split(['\r', '\n']) {
+        buf.put_slice(name.as_bytes());
+        buf.put_slice(b": ");
+        buf.put_slice(line.as_bytes());
+        buf.put_u8(b'\n');
+    }
+}
+
+pin_project! {
+    /// Server-Sent Events (`text/event-stream`) responder.
+    ///
+    /// Sends the events of a stream as they are produced. To stop intermediaries from closing idle
+    /// connections, a keep-alive comment is sent whenever no event has been sent for a while
+    /// (every 15 seconds, by default).
+    ///
+    /// Responses are sent uncompressed, so that events are not held back by the
+    /// [`Compress`](crate::middleware::Compress) middleware.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::{convert::Infallible, time::Duration};
+    ///
+    /// use actix_web::{get, sse, Responder};
+    /// use futures_util::{stream, StreamExt as _};
+    ///
+    /// #[get("/countdown")]
+    /// async fn countdown(last_id: Option<sse::LastEventId>) -> impl Responder {
+    ///     // resume after the last event received by the client
+    ///     let from = last_id.and_then(|id| id.parse::<u32>().ok()).unwrap_or(10);
+    ///
+    ///     let events = stream::iter((0..from).rev()).map(|n| {
+    ///         Ok::<_, Infallible>(sse::Event::data(n.to_string()).id(n.to_string()))
+    ///     });
+    ///
+    ///     sse::Sse::from_stream(events).with_keep_alive(Duration::from_secs(5))
+    /// }
+    /// ```
+    pub struct Sse<S> {
+        #[pin]
+        stream: S,
+        keep_alive: Option<Duration>,
+        timer: Option<Pin<Box<Sleep>>>,
+        retry: Option<Duration>,
+    }
+}
+
+impl<S, E> Sse<S>
+where
+    S: Stream<Item = Result<Event, E>>,
+    E: Into<Box<dyn StdError>>,
+{
+    /// Constructs an SSE response from a stream of events.
+    pub fn from_stream(stream: S) -> Self {
+        Self {
+            stream,
+            keep_alive: Some(DEFAULT_KEEP_ALIVE),
+            timer: None,
+            retry: None,
+        }
+    }
+
+    /// Sets the interval after which a keep-alive comment is sent if no events were sent.
+    pub fn with_keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+
+    /// Disables keep-alive comments.
+    pub fn without_keep_alive(mut self) -> Self {
+        self.keep_alive = None;
+        self
+    }
+
+    /// Sets the reconnection time sent to clients at the start of the stream.
+    pub fn with_retry_duration(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+impl<S, E> MessageBody for Sse<S>
+where
+    S: Stream<Item = Result<Event, E>>,
+    E: Into<Box<dyn StdError>>,
+{
+    type Error = E;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        if let Some(retry) = this.retry.take() {
+            return Poll::Ready(Some(Ok(Event::default().retry(retry).into_bytes())));
+        }
+
+        match this.stream.poll_next(cx) {
+            Poll::Ready(Some(Ok(event))) => {
+                if let (Some(interval), Some(timer)) = (this.keep_alive, this.timer) {
+                    timer.as_mut().reset(Instant::now() + *interval);
+                }
+
+                return Poll::Ready(Some(Ok(event.into_bytes())));
+            }
+
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => {}
+        }
+
+        if let Some(interval) = *this.keep_alive {
+            let timer = this.timer.get_or_insert_with(|| Box::pin(sleep(interval)));
+
+            if timer.as_mut().poll(cx).is_ready() {
+                timer.as_mut().reset(Instant::now() + interval);
+                return Poll::Ready(Some(Ok(Bytes::from_static(b":\n\n"))));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<S, E> Responder for Sse<S>
+where
+    S: Stream<Item = Result<Event, E>> + 'static,
+    E: Into<Box<dyn StdError>> + 'static,
+{
+    type Body = Self;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let mut res = HttpResponse::with_body(StatusCode::OK, self);
+
+        let headers = res.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(mime::TEXT_EVENT_STREAM.as_ref()),
+        );
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        headers.insert(
+            header::CONTENT_ENCODING,
+            ContentEncoding::Identity.to_header_value(),
+        );
+
+        res
+    }
+}
+
+/// Extractor for the `Last-Event-ID` request header, sent by clients when reconnecting to an event
+/// stream.
+///
+/// Fails with `400 Bad Request` if the header is missing; use `Option<LastEventId>` when the header
+/// is optional.
+///
+/// See [`Sse`] for example usage.
+#[derive(Debug, Clone, PartialEq, Eq, Deref, AsRef, Display)]
+pub struct LastEventId(String);
+
+impl LastEventId {
+    /// Unwraps into inner ID string.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl FromRequest for LastEventId {
+    type Error = ParseError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let id = req
+            .headers()
+            .get(&LAST_EVENT_ID)
+            .and_then(|id| id.to_str().ok())
+            .map(|id| LastEventId(id.to_owned()));
+
+        ready(id.ok_or(ParseError::Header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use actix_utils::future::poll_fn;
+    use futures_util::{stream, StreamExt as _};
+
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn event_format() {
+        let event = Event::data("line 1\nline 2\r\nline 3")
+            .event("update")
+            .id("1\n2")
+            .retry(Duration::from_millis(1500));
+
+        assert_eq!(
+            event.into_bytes(),
+            "event: update\nid: 12\nretry: 1500\ndata: line 1\ndata: line 2\ndata: line 3\n\n"
+        );
+
+        assert_eq!(Event::data("").into_bytes(), "data: \n\n");
+        assert_eq!(
+            Event::comment("hi\nthere").into_bytes(),
+            ": hi\n: there\n\n"
+        );
+        assert_eq!(
+            Event::json(&serde_json::json!({ "a": 1 }))
+                .unwrap()
+                .into_bytes(),
+            "data: {\"a\":1}\n\n"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn stream_events() {
+        let events =
+            stream::iter([Event::data("a"), Event::data("b").id("2")]).map(Ok::<_, Infallible>);
+
+        let body = Sse::from_stream(events).with_retry_duration(Duration::from_secs(3));
+
+        assert_eq!(
+            crate::body::to_bytes(body).await.unwrap(),
+            "retry: 3000\n\ndata: a\n\nid: 2\ndata: b\n\n"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn keep_alive() {
+        let body = Sse::from_stream(stream::pending::<Result<Event, Infallible>>())
+            .with_keep_alive(Duration::from_millis(10));
+        actix_rt::pin!(body);
+
+        let chunk = poll_fn(|cx| body.as_mut().poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), ":\n\n");
+
+        let chunk = poll_fn(|cx| body.as_mut().poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), ":\n\n");
+    }
+
+    #[actix_rt::test]
+    async fn last_event_id() {
+        let (req, mut pl) = TestRequest::default()
+            .insert_header(("last-event-id", "42"))
+            .to_http_parts();
+        let id = LastEventId::from_request(&req, &mut pl).await.unwrap();
+        assert_eq!(*id, "42");
+
+        let (req, mut pl) = TestRequest::default().to_http_parts();
+        assert!(LastEventId::from_request(&req, &mut pl).await.is_err());
+    }
+}
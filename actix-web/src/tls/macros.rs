@@ -0,0 +1,366 @@
+/// Defines a reloadable `CertResolver` for a Rustls version.
+///
+/// The invoking module must provide a `certified_key(cert_chain, key)` function that builds that
+/// version's `CertifiedKey` from a certificate chain and private key read from PEM files.
+macro_rules! rustls_cert_resolver {
+    (
+        rustls = $rustls:ident,
+        pki_types = $($pki_types:ident)::+,
+        module = $module:literal,
+        bind_rustls = $bind:literal,
+
+        $(#[doc = $doc:literal])*
+        struct CertResolver;
+
+        $(#[doc = $config_doc:literal])*
+        fn server_config() = $builder:expr;
+    ) => {
+        /// Rustls certificate resolver that can be reloaded at runtime.
+        ///
+        #[doc = concat!(
+            "Use [`server_config()`](Self::server_config) to create a `ServerConfig` for ",
+            "[`HttpServer::", $bind, "()`](crate::HttpServer::", $bind, ") or install the ",
+            "resolver into a custom config using `ServerConfig`'s `with_cert_resolver()` method.",
+        )]
+        ///
+        $(#[doc = $doc])*
+        ///
+        /// # Examples
+        /// ```no_run
+        /// use std::time::Duration;
+        ///
+        #[doc = concat!("use actix_web::{tls::", $module, "::CertResolver, App, HttpServer};")]
+        ///
+        /// # async fn run() -> std::io::Result<()> {
+        /// let resolver = CertResolver::from_pem_files("cert.pem", "key.pem")?;
+        /// resolver.insert_server_name_pem_files("example.com", "example.pem", "example.key")?;
+        ///
+        /// // pick up renewed certificates without restarting
+        /// resolver.watch_pem_files("cert.pem", "key.pem", Duration::from_secs(60));
+        ///
+        /// HttpServer::new(|| App::new())
+        #[doc = concat!("    .", $bind, "((\"127.0.0.1\", 8443), resolver.server_config())?")]
+        ///     .run()
+        ///     .await
+        /// # }
+        /// ```
+        #[derive(Clone)]
+        pub struct CertResolver {
+            certs: ::std::sync::Arc<
+                ::std::sync::RwLock<
+                    $crate::tls::Certs<::std::sync::Arc<$rustls::sign::CertifiedKey>>,
+                >,
+            >,
+        }
+
+        impl CertResolver {
+            /// Constructs a resolver that serves `cert` by default.
+            pub fn new(cert: $rustls::sign::CertifiedKey) -> Self {
+                Self {
+                    certs: ::std::sync::Arc::new(::std::sync::RwLock::new($crate::tls::Certs::new(
+                        ::std::sync::Arc::new(cert),
+                    ))),
+                }
+            }
+
+            /// Constructs a resolver that serves the certificate chain and private key read from
+            /// the given PEM files by default.
+            pub fn from_pem_files(
+                cert_path: impl AsRef<::std::path::Path>,
+                key_path: impl AsRef<::std::path::Path>,
+            ) -> ::std::io::Result<Self> {
+                load_pem_files(cert_path.as_ref(), key_path.as_ref()).map(Self::new)
+            }
+
+            /// Replaces the default certificate.
+            ///
+            /// New handshakes use the new certificate; established connections are unaffected.
+            pub fn reload(&self, cert: $rustls::sign::CertifiedKey) {
+                self.write().default = ::std::sync::Arc::new(cert);
+            }
+
+            /// Replaces the default certificate with the certificate chain and private key read
+            /// from the given PEM files.
+            ///
+            /// The current certificate is kept if the files can not be loaded.
+            pub fn reload_pem_files(
+                &self,
+                cert_path: impl AsRef<::std::path::Path>,
+                key_path: impl AsRef<::std::path::Path>,
+            ) -> ::std::io::Result<()> {
+                let cert = load_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+                self.reload(cert);
+                Ok(())
+            }
+
+            /// Serves `cert` to clients requesting `server_name` using SNI.
+            ///
+            /// Server names can contain a leading wildcard label (e.g., `*.example.com`), matching
+            /// a single label; exact matches take precedence. Replaces any certificate previously
+            /// registered for `server_name`.
+            pub fn insert_server_name(&self, server_name: &str, cert: $rustls::sign::CertifiedKey) {
+                self.write().server_names.insert(
+                    $crate::tls::normalize_server_name(server_name),
+                    ::std::sync::Arc::new(cert),
+                );
+            }
+
+            /// Serves the certificate chain and private key read from the given PEM files to
+            /// clients requesting `server_name` using SNI.
+            ///
+            /// See [`insert_server_name()`](Self::insert_server_name) for more details.
+            pub fn insert_server_name_pem_files(
+                &self,
+                server_name: &str,
+                cert_path: impl AsRef<::std::path::Path>,
+                key_path: impl AsRef<::std::path::Path>,
+            ) -> ::std::io::Result<()> {
+                let cert = load_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+                self.insert_server_name(server_name, cert);
+                Ok(())
+            }
+
+            /// Removes the certificate registered for `server_name`.
+            ///
+            /// Returns `true` if a certificate was registered.
+            pub fn remove_server_name(&self, server_name: &str) -> bool {
+                self.write()
+                    .server_names
+                    .remove(&$crate::tls::normalize_server_name(server_name))
+                    .is_some()
+            }
+
+            /// Reloads the default certificate whenever either of the given PEM files change.
+            ///
+            /// Files are polled for modifications every `interval` on a background thread, which
+            /// exits once all clones of this resolver have been dropped. Failed reloads are logged
+            /// and keep the current certificate.
+            pub fn watch_pem_files(
+                &self,
+                cert_path: impl AsRef<::std::path::Path>,
+                key_path: impl AsRef<::std::path::Path>,
+                interval: ::std::time::Duration,
+            ) {
+                let paths = [cert_path.as_ref(), key_path.as_ref()];
+                let (cert_path, key_path) = (paths[0].to_owned(), paths[1].to_owned());
+
+                $crate::tls::watch_files(
+                    ::std::sync::Arc::downgrade(&self.certs),
+                    paths,
+                    interval,
+                    move |certs| CertResolver { certs }.reload_pem_files(&cert_path, &key_path),
+                );
+            }
+
+            /// Reloads the certificate served for `server_name` whenever either of the given PEM
+            /// files change.
+            ///
+            /// See [`watch_pem_files()`](Self::watch_pem_files) for more details.
+            pub fn watch_server_name_pem_files(
+                &self,
+                server_name: &str,
+                cert_path: impl AsRef<::std::path::Path>,
+                key_path: impl AsRef<::std::path::Path>,
+                interval: ::std::time::Duration,
+            ) {
+                let paths = [cert_path.as_ref(), key_path.as_ref()];
+                let (cert_path, key_path) = (paths[0].to_owned(), paths[1].to_owned());
+                let server_name = server_name.to_owned();
+
+                $crate::tls::watch_files(
+                    ::std::sync::Arc::downgrade(&self.certs),
+                    paths,
+                    interval,
+                    move |certs| {
+                        CertResolver { certs }.insert_server_name_pem_files(
+                            &server_name,
+                            &cert_path,
+                            &key_path,
+                        )
+                    },
+                );
+            }
+
+            /// Returns a server config, without client authentication, that resolves certificates
+            /// using this resolver.
+            $(#[doc = $config_doc])*
+            pub fn server_config(&self) -> $rustls::ServerConfig {
+                $builder
+                    .with_no_client_auth()
+                    .with_cert_resolver(::std::sync::Arc::new(self.clone()))
+            }
+
+            fn write(
+                &self,
+            ) -> ::std::sync::RwLockWriteGuard<
+                '_,
+                $crate::tls::Certs<::std::sync::Arc<$rustls::sign::CertifiedKey>>,
+            > {
+                self.certs
+                    .write()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+            }
+        }
+
+        impl $rustls::server::ResolvesServerCert for CertResolver {
+            fn resolve(
+                &self,
+                client_hello: $rustls::server::ClientHello<'_>,
+            ) -> Option<::std::sync::Arc<$rustls::sign::CertifiedKey>> {
+                let certs = self
+                    .certs
+                    .read()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner);
+
+                Some(::std::sync::Arc::clone(
+                    certs.resolve(client_hello.server_name()),
+                ))
+            }
+        }
+
+        impl ::std::fmt::Debug for CertResolver {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let certs = self
+                    .certs
+                    .read()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner);
+
+                f.debug_struct("CertResolver")
+                    .field("server_names", &certs.server_names.keys())
+                    .finish_non_exhaustive()
+            }
+        }
+
+        /// Loads a certificate chain and private key from PEM files.
+        fn load_pem_files(
+            cert_path: &::std::path::Path,
+            key_path: &::std::path::Path,
+        ) -> ::std::io::Result<$rustls::sign::CertifiedKey> {
+            use $($pki_types)::+::{pem::PemObject as _, CertificateDer, PrivateKeyDer};
+
+            let cert_chain = CertificateDer::pem_file_iter(cert_path)
+                .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+                .map_err(pem_error)?;
+
+            if cert_chain.is_empty() {
+                return Err(::std::io::Error::new(
+                    ::std::io::ErrorKind::InvalidData,
+                    format!("no certificates found in {cert_path:?}"),
+                ));
+            }
+
+            let key = PrivateKeyDer::from_pem_file(key_path).map_err(pem_error)?;
+
+            certified_key(cert_chain, key)
+        }
+
+        fn pem_error(err: $($pki_types)::+::pem::Error) -> ::std::io::Error {
+            match err {
+                $($pki_types)::+::pem::Error::Io(err) => err,
+                err => ::std::io::Error::new(::std::io::ErrorKind::InvalidData, err),
+            }
+        }
+    };
+}
+
+/// Defines the tests of a Rustls `CertResolver` inside a test module.
+///
+/// The invoking module must provide `pem_files(dir, name)`, which writes a new certificate and key
+/// and returns their paths and the certificate, and `client(server_name, cert)`, which returns a
+/// client connection that only trusts `cert`.
+#[cfg(test)]
+macro_rules! rustls_cert_resolver_tests {
+    ($rustls:ident, $cert:ty) => {
+        /// Returns `true` if the server presents `cert` when `server_name` is requested.
+        ///
+        /// Performs an in-memory handshake with a client that only trusts `cert`.
+        fn serves(
+            config: &::std::sync::Arc<$rustls::ServerConfig>,
+            server_name: &str,
+            cert: &$cert,
+        ) -> bool {
+            let mut client = client(server_name, cert);
+            let mut server =
+                $rustls::ServerConnection::new(::std::sync::Arc::clone(config)).unwrap();
+
+            while client.is_handshaking() || server.is_handshaking() {
+                let mut buf = Vec::new();
+                client.write_tls(&mut buf).unwrap();
+                server.read_tls(&mut buf.as_slice()).unwrap();
+                server.process_new_packets().unwrap();
+
+                let mut buf = Vec::new();
+                server.write_tls(&mut buf).unwrap();
+                client.read_tls(&mut buf.as_slice()).unwrap();
+
+                if client.process_new_packets().is_err() {
+                    return false;
+                }
+            }
+
+            client.peer_certificates().unwrap()[0] == *cert
+        }
+
+        #[test]
+        fn reload_and_server_names() {
+            let (cert_path, key_path, localhost) = pem_files("tls-reload", "localhost");
+            let (example_cert_path, example_key_path, example) = pem_files("tls-reload", "example");
+
+            let resolver = CertResolver::from_pem_files(&cert_path, &key_path).unwrap();
+            let config = ::std::sync::Arc::new(resolver.server_config());
+
+            assert!(serves(&config, "localhost", &localhost));
+            assert!(serves(&config, "example.com", &localhost));
+
+            resolver
+                .insert_server_name_pem_files("example.com", &example_cert_path, &example_key_path)
+                .unwrap();
+            assert!(serves(&config, "example.com", &example));
+            assert!(serves(&config, "localhost", &localhost));
+
+            let (cert_path, key_path, renewed) = pem_files("tls-reload-renewed", "localhost");
+            resolver.reload_pem_files(&cert_path, &key_path).unwrap();
+            assert!(serves(&config, "localhost", &renewed));
+
+            assert!(resolver.remove_server_name("EXAMPLE.com"));
+            assert!(!resolver.remove_server_name("example.com"));
+            assert!(serves(&config, "example.com", &renewed));
+
+            resolver
+                .reload_pem_files("missing.pem", "missing.key")
+                .unwrap_err();
+            assert!(serves(&config, "localhost", &renewed));
+        }
+
+        #[test]
+        fn watch_files() {
+            use ::std::time::{Duration, Instant};
+
+            let (cert_path, key_path, original) = pem_files("tls-watch", "localhost");
+
+            let resolver = CertResolver::from_pem_files(&cert_path, &key_path).unwrap();
+            resolver.watch_pem_files(&cert_path, &key_path, Duration::from_millis(10));
+            let config = ::std::sync::Arc::new(resolver.server_config());
+            assert!(serves(&config, "localhost", &original));
+
+            let (renewed_cert_path, renewed_key_path, renewed) =
+                pem_files("tls-watch-renewed", "localhost");
+            ::std::fs::copy(renewed_cert_path, &cert_path).unwrap();
+            ::std::fs::copy(renewed_key_path, &key_path).unwrap();
+
+            let start = Instant::now();
+
+            while !serves(&config, "localhost", &renewed) {
+                assert!(
+                    start.elapsed() < Duration::from_secs(5),
+                    "certificate not reloaded"
+                );
+                ::std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    };
+}
+
+pub(crate) use rustls_cert_resolver;
+#[cfg(test)]
+pub(crate) use rustls_cert_resolver_tests;
@@ -0,0 +1,201 @@
+//! TLS certificate resolvers that can be reloaded at runtime.
+//!
+//! [`HttpServer`](crate::HttpServer)'s TLS binding methods take a fixed acceptor configuration.
+//! The certificate resolvers in this module are installed into that configuration once but keep
+//! a shared handle to the served certificates, so that certificates can be rotated without
+//! restarting the server. Resolvers are cheap to clone; all clones refer to the same
+//! certificates.
+//!
+//! Certificates can be replaced explicitly using the resolvers' `reload` methods or by watching
+//! PEM files for changes. Additional certificates can be registered for specific server names;
+//! they are selected using the Server Name Indication (SNI) sent by clients, falling back to the
+//! default certificate.
+//!
+//! - [`rustls_0_20::CertResolver`] requires the `rustls-0_20` feature.
+//! - [`rustls_0_21::CertResolver`] requires the `rustls-0_21` feature.
+//! - [`rustls_0_22::CertResolver`] requires the `rustls-0_22` feature.
+//! - [`rustls_0_23::CertResolver`] requires the `rustls-0_23` feature.
+//! - [`openssl::CertResolver`] requires the `openssl` feature.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+#[cfg(any(
+    feature = "rustls-0_20",
+    feature = "rustls-0_21",
+    feature = "rustls-0_22",
+    feature = "rustls-0_23"
+))]
+mod macros;
+#[cfg(feature = "openssl")]
+pub mod openssl;
+#[cfg(feature = "rustls-0_20")]
+pub mod rustls_0_20;
+#[cfg(feature = "rustls-0_21")]
+pub mod rustls_0_21;
+#[cfg(feature = "rustls-0_22")]
+pub mod rustls_0_22;
+#[cfg(feature = "rustls-0_23")]
+pub mod rustls_0_23;
+
+#[cfg(any(
+    feature = "rustls-0_20",
+    feature = "rustls-0_21",
+    feature = "rustls-0_22",
+    feature = "rustls-0_23"
+))]
+use self::macros::rustls_cert_resolver;
+#[cfg(all(
+    test,
+    any(
+        feature = "rustls-0_20",
+        feature = "rustls-0_21",
+        feature = "rustls-0_22",
+        feature = "rustls-0_23"
+    )
+))]
+use self::macros::rustls_cert_resolver_tests;
+
+/// Certificates served by a resolver.
+#[derive(Debug)]
+struct Certs<T> {
+    default: T,
+    server_names: HashMap<String, T>,
+}
+
+impl<T> Certs<T> {
+    fn new(default: T) -> Self {
+        Self {
+            default,
+            server_names: HashMap::new(),
+        }
+    }
+
+    /// Selects the certificate for a server name sent by the client.
+    ///
+    /// Exact matches are preferred over wildcard (`*.example.com`) matches. Falls back to the
+    /// default certificate.
+    fn resolve(&self, server_name: Option<&str>) -> &T {
+        let Some(server_name) = server_name.map(normalize_server_name) else {
+            return &self.default;
+        };
+
+        if let Some(cert) = self.server_names.get(&server_name) {
+            return cert;
+        }
+
+        server_name
+            .split_once('.')
+            .and_then(|(_, parent)| self.server_names.get(&format!("*.{parent}")))
+            .unwrap_or(&self.default)
+    }
+}
+
+fn normalize_server_name(server_name: &str) -> String {
+    server_name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Returns the modification times of `paths`, or `None` for files that could not be read.
+fn modified(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
+
+/// Spawns a thread that polls `paths` every `interval` and calls `reload` when any of their
+/// modification times change.
+///
+/// The thread exits once `target` has been dropped.
+fn watch_files<T, F>(target: Weak<T>, paths: [&Path; 2], interval: Duration, reload: F)
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<T>) -> io::Result<()> + Send + 'static,
+{
+    let paths = paths.map(Path::to_path_buf);
+    let mut last_modified = modified(&paths);
+
+    thread::Builder::new()
+        .name("actix-web-tls-watcher".to_owned())
+        .spawn(move || loop {
+            thread::sleep(interval);
+
+            let Some(target) = target.upgrade() else {
+                break;
+            };
+
+            let modified = modified(&paths);
+
+            if modified != last_modified {
+                last_modified = modified;
+
+                // files may be replaced one at a time; a failed reload keeps the previous
+                // certificate and is retried on the next change
+                match reload(target) {
+                    Ok(()) => log::info!("reloaded TLS certificate from {:?}", paths[0]),
+                    Err(err) => {
+                        log::error!(
+                            "failed to reload TLS certificate from {:?}: {err}",
+                            paths[0]
+                        )
+                    }
+                }
+            }
+        })
+        .expect("failed to spawn TLS certificate watcher thread");
+}
+
+/// Writes a new self-signed certificate for `server_names` to PEM files named `name` in a
+/// temporary directory, returning their paths and the DER encoded certificate.
+#[cfg(test)]
+fn pem_files(dir: &str, name: &str, server_names: &[&str]) -> (PathBuf, PathBuf, Vec<u8>) {
+    let server_names = server_names
+        .iter()
+        .map(|&name| name.to_owned())
+        .collect::<Vec<_>>();
+    let rcgen::CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(server_names).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("actix-web-{dir}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let cert_path = dir.join(format!("{name}.pem"));
+    let key_path = dir.join(format!("{name}.key"));
+    fs::write(&cert_path, cert.pem()).unwrap();
+    fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+
+    (cert_path, key_path, cert.der().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_server_names() {
+        let mut certs = Certs::new("default");
+        certs
+            .server_names
+            .insert("example.com".to_owned(), "example");
+        certs
+            .server_names
+            .insert("*.example.com".to_owned(), "wildcard");
+        certs
+            .server_names
+            .insert("api.example.com".to_owned(), "api");
+
+        assert_eq!(*certs.resolve(None), "default");
+        assert_eq!(*certs.resolve(Some("localhost")), "default");
+        assert_eq!(*certs.resolve(Some("example.com")), "example");
+        assert_eq!(*certs.resolve(Some("Example.COM.")), "example");
+        assert_eq!(*certs.resolve(Some("www.example.com")), "wildcard");
+        assert_eq!(*certs.resolve(Some("api.example.com")), "api");
+        assert_eq!(*certs.resolve(Some("a.b.example.com")), "default");
+    }
+}
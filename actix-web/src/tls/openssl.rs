@@ -0,0 +1,335 @@
+//! Reloadable certificate resolver for OpenSSL.
+
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
+use tls_openssl::{
+    pkey::{PKey, PKeyRef, Private},
+    ssl::{NameType, SniError, SslAcceptor, SslAcceptorBuilder, SslContext, SslMethod},
+    x509::X509,
+};
+
+use super::{normalize_server_name, watch_files, Certs};
+
+/// OpenSSL certificate resolver that can be reloaded at runtime.
+///
+/// Use [`acceptor_builder()`](Self::acceptor_builder) to create an acceptor builder for
+/// [`HttpServer::bind_openssl()`](crate::HttpServer::bind_openssl). Certificates are selected
+/// during the handshake by switching the connection to a per-certificate `SslContext`, which uses
+/// Mozilla's "intermediate" TLS configuration.
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use actix_web::{tls::openssl::CertResolver, App, HttpServer};
+///
+/// # async fn run() -> std::io::Result<()> {
+/// let resolver = CertResolver::from_pem_files("cert.pem", "key.pem")?;
+/// resolver.insert_server_name_pem_files("example.com", "example.pem", "example.key")?;
+///
+/// // pick up renewed certificates without restarting
+/// resolver.watch_pem_files("cert.pem", "key.pem", Duration::from_secs(60));
+///
+/// HttpServer::new(|| App::new())
+///     .bind_openssl(("127.0.0.1", 8443), resolver.acceptor_builder()?)?
+///     .run()
+///     .await
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CertResolver {
+    certs: Arc<RwLock<Certs<SslContext>>>,
+}
+
+impl CertResolver {
+    /// Constructs a resolver that serves `cert_chain` and `key` by default.
+    ///
+    /// The first certificate in `cert_chain` must be the end-entity certificate.
+    pub fn new(cert_chain: &[X509], key: &PKeyRef<Private>) -> io::Result<Self> {
+        Ok(Self {
+            certs: Arc::new(RwLock::new(Certs::new(context(cert_chain, key)?))),
+        })
+    }
+
+    /// Constructs a resolver that serves the certificate chain and private key read from the given
+    /// PEM files by default.
+    pub fn from_pem_files(
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        let (cert_chain, key) = load_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+        Self::new(&cert_chain, &key)
+    }
+
+    /// Replaces the default certificate.
+    ///
+    /// New handshakes use the new certificate; established connections are unaffected. The
+    /// current certificate is kept if `cert_chain` and `key` are invalid.
+    pub fn reload(&self, cert_chain: &[X509], key: &PKeyRef<Private>) -> io::Result<()> {
+        let ctx = context(cert_chain, key)?;
+        self.write().default = ctx;
+        Ok(())
+    }
+
+    /// Replaces the default certificate with the certificate chain and private key read from the
+    /// given PEM files.
+    ///
+    /// The current certificate is kept if the files can not be loaded.
+    pub fn reload_pem_files(
+        &self,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        let (cert_chain, key) = load_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+        self.reload(&cert_chain, &key)
+    }
+
+    /// Serves `cert_chain` and `key` to clients requesting `server_name` using SNI.
+    ///
+    /// Server names can contain a leading wildcard label (e.g., `*.example.com`), matching a single
+    /// label; exact matches take precedence. Replaces any certificate previously registered for
+    /// `server_name`.
+    pub fn insert_server_name(
+        &self,
+        server_name: &str,
+        cert_chain: &[X509],
+        key: &PKeyRef<Private>,
+    ) -> io::Result<()> {
+        let ctx = context(cert_chain, key)?;
+
+        self.write()
+            .server_names
+            .insert(normalize_server_name(server_name), ctx);
+
+        Ok(())
+    }
+
+    /// Serves the certificate chain and private key read from the given PEM files to clients
+    /// requesting `server_name` using SNI.
+    ///
+    /// See [`insert_server_name()`](Self::insert_server_name) for more details.
+    pub fn insert_server_name_pem_files(
+        &self,
+        server_name: &str,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        let (cert_chain, key) = load_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+        self.insert_server_name(server_name, &cert_chain, &key)
+    }
+
+    /// Removes the certificate registered for `server_name`.
+    ///
+    /// Returns `true` if a certificate was registered.
+    pub fn remove_server_name(&self, server_name: &str) -> bool {
+        self.write()
+            .server_names
+            .remove(&normalize_server_name(server_name))
+            .is_some()
+    }
+
+    /// Reloads the default certificate whenever either of the given PEM files change.
+    ///
+    /// Files are polled for modifications every `interval` on a background thread, which exits
+    /// once all clones of this resolver, including the ones held by acceptors, have been dropped.
+    /// Failed reloads are logged and keep the current certificate.
+    pub fn watch_pem_files(
+        &self,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+        interval: Duration,
+    ) {
+        let paths = [cert_path.as_ref(), key_path.as_ref()];
+        let (cert_path, key_path) = (paths[0].to_owned(), paths[1].to_owned());
+
+        watch_files(Arc::downgrade(&self.certs), paths, interval, move |certs| {
+            CertResolver { certs }.reload_pem_files(&cert_path, &key_path)
+        });
+    }
+
+    /// Reloads the certificate served for `server_name` whenever either of the given PEM files
+    /// change.
+    ///
+    /// See [`watch_pem_files()`](Self::watch_pem_files) for more details.
+    pub fn watch_server_name_pem_files(
+        &self,
+        server_name: &str,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+        interval: Duration,
+    ) {
+        let paths = [cert_path.as_ref(), key_path.as_ref()];
+        let (cert_path, key_path) = (paths[0].to_owned(), paths[1].to_owned());
+        let server_name = server_name.to_owned();
+
+        watch_files(Arc::downgrade(&self.certs), paths, interval, move |certs| {
+            CertResolver { certs }.insert_server_name_pem_files(&server_name, &cert_path, &key_path)
+        });
+    }
+
+    /// Returns an acceptor builder that selects certificates using this resolver.
+    ///
+    /// The builder uses Mozilla's "intermediate" TLS configuration. Certificates and ALPN
+    /// protocols are configured by the resolver; other settings made on the builder apply to the
+    /// handshake up to certificate selection.
+    pub fn acceptor_builder(&self) -> io::Result<SslAcceptorBuilder> {
+        let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+
+        let certs = Arc::clone(&self.certs);
+        builder.set_servername_callback(move |ssl, _alert| {
+            let server_name = ssl.servername(NameType::HOST_NAME).map(str::to_owned);
+
+            let certs = certs.read().unwrap_or_else(PoisonError::into_inner);
+            let ctx = certs.resolve(server_name.as_deref());
+
+            ssl.set_ssl_context(ctx).map_err(|_| SniError::ALERT_FATAL)
+        });
+
+        Ok(builder)
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Certs<SslContext>> {
+        self.certs.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for CertResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let certs = self.certs.read().unwrap_or_else(PoisonError::into_inner);
+
+        f.debug_struct("CertResolver")
+            .field("server_names", &certs.server_names.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds a context serving `cert_chain` and `key`, with the same ALPN configuration as acceptors
+/// created by `HttpServer`.
+fn context(cert_chain: &[X509], key: &PKeyRef<Private>) -> io::Result<SslContext> {
+    let (cert, chain) = cert_chain
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "certificate chain is empty"))?;
+
+    let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+    builder.set_certificate(cert)?;
+
+    for cert in chain {
+        builder.add_extra_chain_cert(cert.clone())?;
+    }
+
+    builder.set_private_key(key)?;
+    builder.check_private_key()?;
+
+    Ok(crate::server::openssl_acceptor(builder)?.into_context())
+}
+
+/// Loads a certificate chain and private key from PEM files.
+fn load_pem_files(cert_path: &Path, key_path: &Path) -> io::Result<(Vec<X509>, PKey<Private>)> {
+    let cert_chain = X509::stack_from_pem(&fs::read(cert_path)?)?;
+    let key = PKey::private_key_from_pem(&fs::read(key_path)?)?;
+    Ok((cert_chain, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{TcpListener, TcpStream},
+        path::PathBuf,
+        thread,
+        time::Instant,
+    };
+
+    use tls_openssl::ssl::{SslConnector, SslVerifyMode};
+
+    use super::*;
+
+    fn pem_files(dir: &str, server_name: &str) -> (PathBuf, PathBuf, Vec<u8>) {
+        super::super::pem_files(dir, server_name, &[server_name])
+    }
+
+    /// Performs a handshake over a loopback connection and returns the certificate presented by
+    /// the server.
+    fn served_cert(acceptor: &SslAcceptor, server_name: &str) -> Vec<u8> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client_io = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server_io, _) = listener.accept().unwrap();
+
+        let acceptor = acceptor.clone();
+        let server = thread::spawn(move || acceptor.accept(server_io).map(drop));
+
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.set_verify(SslVerifyMode::NONE);
+        let client = connector.build().connect(server_name, client_io).unwrap();
+
+        let cert = client.ssl().peer_certificate().unwrap().to_der().unwrap();
+        drop(client);
+        server.join().unwrap().unwrap();
+
+        cert
+    }
+
+    #[test]
+    fn reload_and_server_names() {
+        let (cert_path, key_path, localhost) = pem_files("openssl-reload", "localhost");
+        let (example_cert_path, example_key_path, example) =
+            pem_files("openssl-reload", "example.com");
+
+        let resolver = CertResolver::from_pem_files(&cert_path, &key_path).unwrap();
+        let acceptor = resolver.acceptor_builder().unwrap().build();
+
+        assert_eq!(served_cert(&acceptor, "localhost"), localhost);
+        assert_eq!(served_cert(&acceptor, "example.com"), localhost);
+
+        resolver
+            .insert_server_name_pem_files("example.com", &example_cert_path, &example_key_path)
+            .unwrap();
+        assert_eq!(served_cert(&acceptor, "example.com"), example);
+        assert_eq!(served_cert(&acceptor, "localhost"), localhost);
+
+        let (cert_path, key_path, renewed) = pem_files("openssl-reload-renewed", "localhost");
+        resolver.reload_pem_files(&cert_path, &key_path).unwrap();
+        assert_eq!(served_cert(&acceptor, "localhost"), renewed);
+
+        assert!(resolver.remove_server_name("EXAMPLE.com"));
+        assert!(!resolver.remove_server_name("example.com"));
+        assert_eq!(served_cert(&acceptor, "example.com"), renewed);
+
+        // mismatched certificate and key
+        resolver
+            .reload_pem_files(&example_cert_path, &key_path)
+            .unwrap_err();
+        resolver
+            .reload_pem_files("missing.pem", "missing.key")
+            .unwrap_err();
+        assert_eq!(served_cert(&acceptor, "localhost"), renewed);
+    }
+
+    #[test]
+    fn watch_files() {
+        let (cert_path, key_path, _) = pem_files("openssl-watch", "localhost");
+
+        let resolver = CertResolver::from_pem_files(&cert_path, &key_path).unwrap();
+        resolver.watch_pem_files(&cert_path, &key_path, Duration::from_millis(10));
+        let acceptor = resolver.acceptor_builder().unwrap().build();
+
+        let (renewed_cert_path, renewed_key_path, renewed) =
+            pem_files("openssl-watch-renewed", "localhost");
+        fs::copy(renewed_cert_path, &cert_path).unwrap();
+        fs::copy(renewed_key_path, &key_path).unwrap();
+
+        let start = Instant::now();
+
+        while served_cert(&acceptor, "localhost") != renewed {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "certificate not reloaded"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Reloadable certificate resolver for Rustls v0.20.
+
+use std::io;
+
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tls_rustls_0_20::{
+    sign::{any_supported_type, CertifiedKey},
+    Certificate, PrivateKey, ServerConfig,
+};
+
+super::rustls_cert_resolver! {
+    rustls = tls_rustls_0_20,
+    pki_types = rustls_pki_types,
+    module = "rustls_0_20",
+    bind_rustls = "bind_rustls",
+
+    /// Private keys loaded from PEM files are not checked to match their certificates; a mismatched
+    /// key fails the TLS handshakes that use it.
+    struct CertResolver;
+
+    fn server_config() = ServerConfig::builder().with_safe_defaults();
+}
+
+/// Builds a certified key, parsing the private key with any supported algorithm.
+fn certified_key(
+    cert_chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> io::Result<CertifiedKey> {
+    let cert_chain = cert_chain
+        .into_iter()
+        .map(|cert| Certificate(cert.to_vec()))
+        .collect();
+
+    let key = any_supported_type(&PrivateKey(key.secret_der().to_vec()))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(CertifiedKey::new(cert_chain, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use tls_rustls_0_20::{ClientConfig, ClientConnection, RootCertStore, ServerName};
+
+    use super::*;
+
+    /// Certificates are valid for both test server names so that the client accepts fallbacks to
+    /// the default certificate.
+    fn pem_files(dir: &str, name: &str) -> (PathBuf, PathBuf, Certificate) {
+        let (cert_path, key_path, cert) =
+            super::super::pem_files(dir, name, &["localhost", "example.com"]);
+        (cert_path, key_path, Certificate(cert))
+    }
+
+    fn client(server_name: &str, cert: &Certificate) -> ClientConnection {
+        let mut root_store = RootCertStore::empty();
+        root_store.add(cert).unwrap();
+
+        let client_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+
+        let server_name = ServerName::try_from(server_name).unwrap();
+        ClientConnection::new(Arc::new(client_config), server_name).unwrap()
+    }
+
+    super::super::rustls_cert_resolver_tests!(tls_rustls_0_20, Certificate);
+}
@@ -0,0 +1,61 @@
+//! Reloadable certificate resolver for Rustls v0.22.
+
+use std::io;
+
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tls_rustls_0_22::{crypto::ring::sign::any_supported_type, sign::CertifiedKey, ServerConfig};
+
+super::rustls_cert_resolver! {
+    rustls = tls_rustls_0_22,
+    pki_types = rustls_pki_types,
+    module = "rustls_0_22",
+    bind_rustls = "bind_rustls_0_22",
+
+    /// Private keys loaded from PEM files are not checked to match their certificates; a mismatched
+    /// key fails the TLS handshakes that use it.
+    struct CertResolver;
+
+    fn server_config() = ServerConfig::builder();
+}
+
+/// Builds a certified key, using the ring crypto provider to parse the private key.
+fn certified_key(
+    cert_chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> io::Result<CertifiedKey> {
+    let key =
+        any_supported_type(&key).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    Ok(CertifiedKey::new(cert_chain, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use tls_rustls_0_22::{pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore};
+
+    use super::*;
+
+    /// Certificates are valid for both test server names so that the client accepts fallbacks to
+    /// the default certificate.
+    fn pem_files(dir: &str, name: &str) -> (PathBuf, PathBuf, CertificateDer<'static>) {
+        let (cert_path, key_path, cert) =
+            super::super::pem_files(dir, name, &["localhost", "example.com"]);
+        (cert_path, key_path, CertificateDer::from(cert))
+    }
+
+    fn client(server_name: &str, cert: &CertificateDer<'static>) -> ClientConnection {
+        let mut root_store = RootCertStore::empty();
+        root_store.add(cert.clone()).unwrap();
+
+        let client_config = ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+
+        let server_name = ServerName::try_from(server_name.to_owned()).unwrap();
+        ClientConnection::new(Arc::new(client_config), server_name).unwrap()
+    }
+
+    super::super::rustls_cert_resolver_tests!(tls_rustls_0_22, CertificateDer<'static>);
+}
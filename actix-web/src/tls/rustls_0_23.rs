@@ -0,0 +1,78 @@
+//! Reloadable certificate resolver for Rustls v0.23.
+
+use std::io;
+
+use tls_rustls::{
+    pki_types::{CertificateDer, PrivateKeyDer},
+    sign::CertifiedKey,
+    ServerConfig,
+};
+
+super::rustls_cert_resolver! {
+    rustls = tls_rustls,
+    pki_types = tls_rustls::pki_types,
+    module = "rustls_0_23",
+    bind_rustls = "bind_rustls_0_23",
+
+    struct CertResolver;
+
+    ///
+    /// # Panics
+    /// Panics if no process-wide default crypto provider is installed and one can not be inferred
+    /// from Rustls' crate features.
+    fn server_config() = ServerConfig::builder();
+}
+
+/// Builds a certified key, using the default crypto provider to parse the private key and check
+/// that it matches the certificate.
+fn certified_key(
+    cert_chain: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> io::Result<CertifiedKey> {
+    let provider = ServerConfig::builder().crypto_provider().clone();
+
+    CertifiedKey::from_der(cert_chain, key, &provider)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use tls_rustls::{pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore};
+
+    use super::*;
+
+    /// Certificates are valid for both test server names so that the client accepts fallbacks to
+    /// the default certificate.
+    fn pem_files(dir: &str, name: &str) -> (PathBuf, PathBuf, CertificateDer<'static>) {
+        let (cert_path, key_path, cert) =
+            super::super::pem_files(dir, name, &["localhost", "example.com"]);
+        (cert_path, key_path, CertificateDer::from(cert))
+    }
+
+    fn client(server_name: &str, cert: &CertificateDer<'static>) -> ClientConnection {
+        let mut root_store = RootCertStore::empty();
+        root_store.add(cert.clone()).unwrap();
+
+        let client_config = ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+
+        let server_name = ServerName::try_from(server_name.to_owned()).unwrap();
+        ClientConnection::new(Arc::new(client_config), server_name).unwrap()
+    }
+
+    super::super::rustls_cert_resolver_tests!(tls_rustls, CertificateDer<'static>);
+
+    #[test]
+    fn mismatched_key() {
+        let (cert_path, key_path, _) = pem_files("tls-mismatched", "localhost");
+        let (example_cert_path, _, _) = pem_files("tls-mismatched", "example");
+
+        let resolver = CertResolver::from_pem_files(&cert_path, &key_path).unwrap();
+        resolver
+            .reload_pem_files(&example_cert_path, &key_path)
+            .unwrap_err();
+    }
+}
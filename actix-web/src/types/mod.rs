@@ -9,6 +9,7 @@ mod path;
 mod payload;
 mod query;
 mod readlines;
+mod valid;
 
 pub use self::{
     either::Either,
@@ -20,4 +21,5 @@ pub use self::{
     payload::{Payload, PayloadConfig},
     query::{Query, QueryConfig},
     readlines::Readlines,
+    valid::{Valid, ValidConfig, Validate},
 };
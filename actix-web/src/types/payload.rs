@@ -2,7 +2,7 @@
 
 use std::{
     borrow::Cow,
-    future::Future,
+    future::{poll_fn, Future},
     pin::Pin,
     str,
     task::{Context, Poll},
@@ -16,8 +16,10 @@ use futures_core::{ready, stream::Stream};
 use mime::Mime;
 
 use crate::{
-    body, dev, error::ErrorBadRequest, http::header, web, Error, FromRequest, HttpMessage,
-    HttpRequest,
+    body, dev,
+    error::ErrorBadRequest,
+    http::header::{self, HeaderMap},
+    web, Error, FromRequest, HttpMessage, HttpRequest,
 };
 
 /// Extract a request's raw payload stream.
@@ -117,6 +119,36 @@ impl Payload {
         let stream = body::BodyStream::new(self.0);
         Ok(body::to_bytes(stream).await?)
     }
+
+    /// Returns the trailer fields sent after the payload, if any.
+    ///
+    /// Trailers can be sent after chunked HTTP/1.1 payloads and after HTTP/2 payloads. This method
+    /// should only be called once the payload stream has been read to completion; it will not
+    /// resolve while payload data is still unread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use actix_web::{web, Responder};
+    /// use futures_util::StreamExt as _;
+    ///
+    /// async fn handler(mut pl: web::Payload) -> actix_web::Result<impl Responder> {
+    ///     let mut len = 0;
+    ///     while let Some(chunk) = pl.next().await {
+    ///         len += chunk?.len();
+    ///     }
+    ///
+    ///     let checksum = pl
+    ///         .trailers()
+    ///         .await?
+    ///         .and_then(|trailers| trailers.get("x-checksum").cloned());
+    ///
+    ///     Ok(format!("read {len} bytes with checksum {checksum:?}"))
+    /// }
+    /// ```
+    pub async fn trailers(&mut self) -> Result<Option<HeaderMap>, PayloadError> {
+        poll_fn(|cx| self.0.poll_trailers(cx)).await
+    }
 }
 
 impl Stream for Payload {
@@ -0,0 +1,371 @@
+//! For validated extractor documentation, see [`Valid`].
+
+use std::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use derive_more::{AsRef, Deref, DerefMut, Display};
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use crate::{
+    dev::Payload,
+    error::{Error, ValidationErrors},
+    web::Data,
+    FromRequest, HttpRequest,
+};
+
+/// Semantic validation of extracted data.
+///
+/// Used by the [`Valid`] extractor after the data has been deserialized.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::ValidationErrors, web::Validate};
+///
+/// struct Address {
+///     city: String,
+/// }
+///
+/// impl Validate for Address {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///
+///         if self.city.is_empty() {
+///             errors.add("city", "must not be empty");
+///         }
+///
+///         errors.into_result()
+///     }
+/// }
+///
+/// struct User {
+///     age: u32,
+///     address: Address,
+/// }
+///
+/// impl Validate for User {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///
+///         if self.age < 18 {
+///             errors.add("age", "must be at least 18");
+///         }
+///
+///         // reported as `address.city`
+///         errors.nest("address", self.address.validate());
+///
+///         errors.into_result()
+///     }
+/// }
+/// ```
+pub trait Validate {
+    /// Validates `self`, returning the set of failing fields if there are any.
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+impl<T: Validate + ?Sized> Validate for &T {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        (**self).validate()
+    }
+}
+
+impl<T: Validate + ?Sized> Validate for Box<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        (**self).validate()
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Some(val) => val.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Validates each item, reporting errors with the item index, e.g., `[0].name`.
+impl<T: Validate> Validate for [T] {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        for (idx, item) in self.iter().enumerate() {
+            errors.nest(&format!("[{idx}]"), item.validate());
+        }
+
+        errors.into_result()
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        self.as_slice().validate()
+    }
+}
+
+/// Extractor that validates the data extracted by another extractor.
+///
+/// Wraps an extractor that dereferences to its extracted data, such as [`Json`], [`Query`],
+/// [`Form`], or [`Path`]. Once extracted, the data is checked using its [`Validate`]
+/// implementation. Extraction errors are returned unchanged; validation failures are returned as
+/// [`ValidationErrors`], responding with `422 Unprocessable Entity` and a JSON body listing every
+/// failing field.
+///
+/// Use [`ValidConfig`] to customize the error response.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::ValidationErrors, post, web};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Info {
+///     username: String,
+/// }
+///
+/// impl web::Validate for Info {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///
+///         if !(3..=32).contains(&self.username.len()) {
+///             errors.add("username", "must be between 3 and 32 characters");
+///         }
+///
+///         errors.into_result()
+///     }
+/// }
+///
+/// // responds with 422 Unprocessable Entity if the username has an invalid length
+/// #[post("/")]
+/// async fn index(info: web::Valid<web::Json<Info>>) -> String {
+///     format!("Welcome {}!", info.username)
+/// }
+/// ```
+///
+/// [`Json`]: crate::web::Json
+/// [`Query`]: crate::web::Query
+/// [`Form`]: crate::web::Form
+/// [`Path`]: crate::web::Path
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut, AsRef, Display)]
+pub struct Valid<T>(pub T);
+
+impl<T> Valid<T> {
+    /// Unwrap into inner `T` value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// See [here](#examples) for example of usage as an extractor.
+impl<T> FromRequest for Valid<T>
+where
+    T: FromRequest + Deref,
+    T::Target: Validate,
+{
+    type Error = Error;
+    type Future = ValidExtractFut<T::Future>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        ValidExtractFut {
+            req: req.clone(),
+            fut: T::from_request(req, payload),
+        }
+    }
+}
+
+pin_project! {
+    pub struct ValidExtractFut<Fut> {
+        req: HttpRequest,
+        #[pin]
+        fut: Fut,
+    }
+}
+
+impl<Fut, T, E> Future for ValidExtractFut<Fut>
+where
+    Fut: Future<Output = Result<T, E>>,
+    T: Deref,
+    T::Target: Validate,
+    E: Into<Error>,
+{
+    type Output = Result<Valid<T>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let extracted = ready!(this.fut.poll(cx)).map_err(Into::into)?;
+
+        let res = match extracted.validate() {
+            Ok(()) => Ok(Valid(extracted)),
+            Err(err) => {
+                log::debug!(
+                    "Failed during Valid extractor validation. \
+                     Request path: {:?}",
+                    this.req.path()
+                );
+
+                let error_handler = this
+                    .req
+                    .app_data::<ValidConfig>()
+                    .or_else(|| this.req.app_data::<Data<ValidConfig>>().map(Data::get_ref))
+                    .and_then(|c| c.err_handler.clone());
+
+                if let Some(error_handler) = error_handler {
+                    Err((error_handler)(err, this.req))
+                } else {
+                    Err(err.into())
+                }
+            }
+        };
+
+        Poll::Ready(res)
+    }
+}
+
+/// [`Valid`] extractor configuration.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error, web, App, HttpResponse};
+///
+/// let app = App::new().app_data(web::ValidConfig::default().error_handler(|err, _req| {
+///     // respond with a plain list of messages instead
+///     let messages = err
+///         .errors()
+///         .iter()
+///         .map(|err| format!("{}: {}", err.path(), err.message()))
+///         .collect::<Vec<_>>();
+///
+///     error::InternalError::from_response(err, HttpResponse::BadRequest().json(messages)).into()
+/// }));
+/// ```
+#[derive(Clone, Default)]
+pub struct ValidConfig {
+    #[allow(clippy::type_complexity)]
+    err_handler: Option<Arc<dyn Fn(ValidationErrors, &HttpRequest) -> Error + Send + Sync>>,
+}
+
+impl ValidConfig {
+    /// Set custom error handler.
+    pub fn error_handler<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ValidationErrors, &HttpRequest) -> Error + Send + Sync + 'static,
+    {
+        self.err_handler = Some(Arc::new(f));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        body::to_bytes,
+        error::InternalError,
+        http::StatusCode,
+        test::TestRequest,
+        web::{Json, Query},
+        HttpResponse,
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct Item {
+        name: String,
+        quantity: u32,
+    }
+
+    impl Validate for Item {
+        fn validate(&self) -> Result<(), ValidationErrors> {
+            let mut errors = ValidationErrors::new();
+
+            if self.name.is_empty() {
+                errors.add("name", "must not be empty");
+            }
+
+            if self.quantity == 0 {
+                errors.add("quantity", "must be positive");
+            }
+
+            errors.into_result()
+        }
+    }
+
+    #[test]
+    fn validate_collections() {
+        let items = vec![
+            Item {
+                name: "apple".to_owned(),
+                quantity: 1,
+            },
+            Item {
+                name: String::new(),
+                quantity: 0,
+            },
+        ];
+
+        let errors = items.validate().unwrap_err();
+        let paths = errors
+            .errors()
+            .iter()
+            .map(|err| err.path())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["[1].name", "[1].quantity"]);
+
+        assert!(None::<Item>.validate().is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn valid_query() {
+        let (req, mut pl) = TestRequest::with_uri("/?name=apple&quantity=2").to_http_parts();
+        let item = Valid::<Query<Item>>::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert_eq!(item.name, "apple");
+
+        let (req, mut pl) = TestRequest::with_uri("/?name=&quantity=0").to_http_parts();
+        let err = Valid::<Query<Item>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        let res = err.error_response();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(
+            body,
+            r#"{"errors":[{"path":"name","message":"must not be empty"},{"path":"quantity","message":"must be positive"}]}"#
+        );
+
+        // deserialization errors are not validation errors
+        let (req, mut pl) = TestRequest::with_uri("/?name=apple").to_http_parts();
+        let err = Valid::<Query<Item>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+
+    #[actix_rt::test]
+    async fn valid_json_custom_error_handler() {
+        let (req, mut pl) = TestRequest::default()
+            .app_data(ValidConfig::default().error_handler(|err, _| {
+                InternalError::from_response(err, HttpResponse::Conflict().finish()).into()
+            }))
+            .set_json(serde_json::json!({ "name": "", "quantity": 1 }))
+            .to_http_parts();
+
+        let err = Valid::<Json<Item>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::CONFLICT);
+    }
+}
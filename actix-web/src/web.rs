@@ -10,6 +10,7 @@
 //! - [`Json`]: JSON payload
 //! - [`Form`]: URL-encoded payload
 //! - [`Bytes`]: Raw payload
+//! - [`ShutdownSignal`]: Server graceful shutdown notification
 //!
 //! # Responders
 //! - [`Json`]: JSON response
@@ -24,7 +25,7 @@ pub use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 pub use crate::{
     config::ServiceConfig, data::Data, redirect::Redirect, request_data::ReqData,
-    thin_data::ThinData, types::*,
+    shutdown::ShutdownSignal, thin_data::ThinData, types::*,
 };
 use crate::{
     error::BlockingError, http::Method, service::WebService, FromRequest, Handler, Resource,
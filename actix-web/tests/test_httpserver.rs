@@ -74,6 +74,58 @@ async fn test_start() {
     srv.stop(false).await;
 }
 
+/// Starts a server with a shutdown hook, returning its handle and a receiver that is sent a message
+/// when the hook runs.
+fn start_with_shutdown_hook() -> (actix_web::dev::ServerHandle, mpsc::Receiver<()>) {
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+    let (hook_tx, hook_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let srv = HttpServer::new(App::new)
+                    .workers(1)
+                    .disable_signals()
+                    .on_shutdown(move || async move {
+                        hook_tx.send(()).unwrap();
+                    })
+                    .bind(format!("{}", addr))
+                    .unwrap()
+                    .run();
+
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap();
+    });
+
+    (rx.recv().unwrap(), hook_rx)
+}
+
+#[actix_rt::test]
+async fn test_on_shutdown() {
+    let (srv, hook_rx) = start_with_shutdown_hook();
+
+    // hook is only run once shutdown has started
+    assert!(hook_rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+    srv.stop(true).await;
+
+    hook_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[actix_rt::test]
+async fn test_on_shutdown_forced() {
+    let (srv, hook_rx) = start_with_shutdown_hook();
+
+    srv.stop(false).await;
+
+    // hook is not run on forced shutdown
+    assert!(hook_rx.recv_timeout(Duration::from_secs(1)).is_err());
+}
+
 #[cfg(feature = "openssl")]
 fn ssl_acceptor() -> openssl::ssl::SslAcceptorBuilder {
     use openssl::{
@@ -153,3 +205,147 @@ async fn test_start_ssl() {
 
     srv.stop(false).await;
 }
+
+#[cfg(feature = "http3")]
+fn rustls_config() -> tls_rustls::ServerConfig {
+    rustls_configs().0
+}
+
+/// Returns a server config with a self-signed certificate and a client config that trusts it.
+#[cfg(feature = "http3")]
+fn rustls_configs() -> (tls_rustls::ServerConfig, tls_rustls::ClientConfig) {
+    use tls_rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+
+    let rcgen::CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(["localhost".to_owned()]).unwrap();
+
+    let mut roots = tls_rustls::RootCertStore::empty();
+    roots.add(cert.der().clone()).unwrap();
+
+    let client_config = tls_rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    let server_config = tls_rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![cert.der().clone()],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_pair.serialize_der())),
+        )
+        .unwrap();
+
+    (server_config, client_config)
+}
+
+#[actix_rt::test]
+#[cfg(feature = "http3")]
+async fn test_start_quic_alt_svc() {
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let srv = HttpServer::new(|| {
+                    App::new().service(
+                        web::resource("/")
+                            .route(web::to(|| async { HttpResponse::Ok().body("test") })),
+                    )
+                })
+                .workers(1)
+                .shutdown_timeout(1)
+                .system_exit()
+                .disable_signals()
+                .bind(format!("{}", addr))
+                .unwrap()
+                .bind_quic(format!("{}", addr), rustls_config())
+                .unwrap();
+
+                assert_eq!(srv.addrs(), vec![addr, addr]);
+
+                let srv = srv.run();
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap()
+    });
+    let srv = rx.recv().unwrap();
+
+    let client = awc::Client::default();
+
+    let host = format!("http://{}", addr);
+    let response = client.get(host).send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.headers().get("alt-svc").unwrap(),
+        &format!("h3=\":{}\"; ma=86400", addr.port()),
+    );
+
+    srv.stop(false).await;
+}
+
+#[actix_rt::test]
+#[cfg(feature = "http3")]
+async fn test_start_quic() {
+    use std::sync::Arc;
+
+    use actix_web::http::Version;
+
+    let addr = actix_test::unused_addr();
+    let (server_config, client_config) = rustls_configs();
+    let (tx, rx) = mpsc::channel();
+    let (done_tx, done_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new().block_on(async {
+            let srv = HttpServer::new(|| {
+                App::new().service(
+                    web::resource("/").route(web::to(|| async { HttpResponse::Ok().body("test") })),
+                )
+            })
+            .workers(1)
+            .shutdown_timeout(10)
+            .disable_signals()
+            .bind(format!("{}", addr))
+            .unwrap()
+            .bind_quic(format!("{}", addr), server_config)
+            .unwrap()
+            .run();
+
+            tx.send(srv.handle()).unwrap();
+
+            srv.await.unwrap();
+            done_tx.send(()).unwrap();
+        })
+    });
+    let srv = rx.recv().unwrap();
+
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().rustls_0_23(Arc::new(client_config)))
+        .max_http_version(Version::HTTP_3)
+        .finish();
+
+    let url = format!("https://localhost:{}/", addr.port());
+    let mut res = client.get(url).address(addr).send().await.unwrap();
+    assert!(res.status().is_success());
+    assert_eq!(res.version(), Version::HTTP_3);
+    assert_eq!(res.body().await.unwrap(), "test");
+
+    // the server stops well before the shutdown timeout elapses
+    srv.stop(true).await;
+    done_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[actix_rt::test]
+#[cfg(feature = "http3")]
+async fn test_bind_quic_addrs() {
+    let srv = HttpServer::new(App::new)
+        .bind_quic("127.0.0.1:0", rustls_config())
+        .unwrap();
+
+    let addrs = srv.addrs_with_scheme();
+    assert_eq!(addrs.len(), 1);
+    assert_eq!(addrs[0].1, "https");
+    assert_ne!(addrs[0].0.port(), 0);
+}
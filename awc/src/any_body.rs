@@ -4,7 +4,10 @@ use std::{
     task::{Context, Poll},
 };
 
-use actix_http::body::{BodySize, BoxBody, MessageBody};
+use actix_http::{
+    body::{BodySize, BoxBody, MessageBody},
+    header::HeaderMap,
+};
 use bytes::Bytes;
 use pin_project_lite::pin_project;
 
@@ -130,6 +133,13 @@ where
             AnyBodyProj::Body { body } => body.poll_next(cx).map_err(|err| err.into()),
         }
     }
+
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<HeaderMap>> {
+        match self.project() {
+            AnyBodyProj::Body { body } => body.poll_trailers(cx),
+            _ => Poll::Ready(None),
+        }
+    }
 }
 
 impl PartialEq for AnyBody {
@@ -2,16 +2,19 @@ use std::{fmt, net::IpAddr, rc::Rc, time::Duration};
 
 use actix_http::{
     error::HttpError,
-    header::{self, HeaderMap, HeaderName, TryIntoHeaderPair},
+    header::{self, ContentEncoding, HeaderMap, HeaderName, TryIntoHeaderPair},
     Uri,
 };
 use actix_rt::net::{ActixStream, TcpStream};
 use actix_service::{boxed, Service};
 use base64::prelude::*;
 
+#[cfg(feature = "cookies")]
+use crate::CookieStore;
 use crate::{
     client::{
-        ClientConfig, ConnectInfo, Connector, ConnectorService, TcpConnectError, TcpConnection,
+        ClientConfig, ConnectInfo, Connector, ConnectorService, Proxy, TcpConnectError,
+        TcpConnection,
     },
     connect::DefaultConnector,
     error::SendRequestError,
@@ -30,10 +33,14 @@ pub struct ClientBuilder<S = (), M = ()> {
     fundamental_headers: bool,
     default_headers: HeaderMap,
     timeout: Option<Duration>,
+    compress: Option<ContentEncoding>,
     connector: Connector<S>,
     middleware: M,
     local_address: Option<IpAddr>,
+    proxy: Option<Proxy>,
     max_redirects: u8,
+    #[cfg(feature = "cookies")]
+    cookie_store: Option<CookieStore>,
 }
 
 impl ClientBuilder {
@@ -59,10 +66,14 @@ impl ClientBuilder {
             fundamental_headers: true,
             default_headers: HeaderMap::new(),
             timeout: Some(Duration::from_secs(5)),
+            compress: None,
             connector: Connector::new(),
             middleware: (),
             local_address: None,
+            proxy: None,
             max_redirects: 10,
+            #[cfg(feature = "cookies")]
+            cookie_store: None,
         }
     }
 }
@@ -87,12 +98,16 @@ where
             fundamental_headers: self.fundamental_headers,
             default_headers: self.default_headers,
             timeout: self.timeout,
+            compress: self.compress,
             local_address: self.local_address,
+            proxy: self.proxy,
             connector,
             max_http_version: self.max_http_version,
             stream_window_size: self.stream_window_size,
             conn_window_size: self.conn_window_size,
             max_redirects: self.max_redirects,
+            #[cfg(feature = "cookies")]
+            cookie_store: self.cookie_store,
         }
     }
 
@@ -111,15 +126,36 @@ where
         self
     }
 
+    /// Compresses request bodies with the given encoding by default.
+    ///
+    /// Can be overridden for individual requests with [`ClientRequest::compress`]. Encodings that
+    /// are not enabled by crate features are not applied.
+    ///
+    /// [`ClientRequest::compress`]: crate::ClientRequest::compress
+    #[cfg(feature = "__compress")]
+    pub fn compress(mut self, encoding: ContentEncoding) -> Self {
+        self.compress = Some(encoding);
+        self
+    }
+
     /// Set local IP Address the connector would use for establishing connection.
     pub fn local_address(mut self, addr: IpAddr) -> Self {
         self.local_address = Some(addr);
         self
     }
 
+    /// Sets a forward proxy to connect to remote hosts through.
+    ///
+    /// See [`Connector::proxy`] for details.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
     /// Maximum supported HTTP major version.
     ///
-    /// Supported versions are HTTP/1.1 and HTTP/2.
+    /// Supported versions are HTTP/1.1 and HTTP/2, and HTTP/3 when the `http3` crate feature is
+    /// enabled. See [`Connector::max_http_version`] for how HTTP/3 connections are made.
     pub fn max_http_version(mut self, val: http::Version) -> Self {
         self.max_http_version = Some(val);
         self
@@ -141,6 +177,16 @@ where
         self
     }
 
+    /// Stores cookies from responses and sends them with later requests made by the client.
+    ///
+    /// Cookies are also sent and stored when following redirects. No cookies are stored by
+    /// default. See [`CookieStore`] for details.
+    #[cfg(feature = "cookies")]
+    pub fn cookie_store(mut self, store: CookieStore) -> Self {
+        self.cookie_store = Some(store);
+        self
+    }
+
     /// Indicates the initial window size (in octets) for
     /// HTTP2 stream-level flow control for received data.
     ///
@@ -242,9 +288,13 @@ where
             conn_window_size: self.conn_window_size,
             default_headers: self.default_headers,
             timeout: self.timeout,
+            compress: self.compress,
             connector: self.connector,
             local_address: self.local_address,
+            proxy: self.proxy,
             max_redirects: self.max_redirects,
+            #[cfg(feature = "cookies")]
+            cookie_store: self.cookie_store,
         }
     }
 
@@ -256,9 +306,22 @@ where
     {
         let max_redirects = self.max_redirects;
 
-        if max_redirects > 0 {
-            self.wrap(Redirect::new().max_redirect_times(max_redirects))
-                ._finish()
+        #[cfg(feature = "cookies")]
+        let cookie_store = self.cookie_store.clone();
+        #[cfg(not(feature = "cookies"))]
+        let cookie_store: Option<()> = None;
+
+        // the redirect middleware also sends and stores cookies
+        if max_redirects > 0 || cookie_store.is_some() {
+            #[allow(unused_mut)]
+            let mut redirect = Redirect::new().max_redirect_times(max_redirects);
+
+            #[cfg(feature = "cookies")]
+            if let Some(store) = cookie_store {
+                redirect = redirect.cookie_store(store);
+            }
+
+            self.wrap(redirect)._finish()
         } else {
             self._finish()
         }
@@ -283,6 +346,9 @@ where
         if let Some(val) = self.local_address {
             connector = connector.local_address(val);
         }
+        if let Some(val) = self.proxy {
+            connector = connector.proxy(val);
+        }
 
         let connector = DefaultConnector::new(connector.finish());
         let connector = boxed::rc_service(self.middleware.new_transform(connector));
@@ -290,6 +356,7 @@ where
         Client(ClientConfig {
             default_headers: Rc::new(self.default_headers),
             timeout: self.timeout,
+            compress: self.compress,
             connector,
         })
     }
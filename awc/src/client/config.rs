@@ -1,4 +1,6 @@
-use std::{net::IpAddr, time::Duration};
+use std::{net::IpAddr, rc::Rc, time::Duration};
+
+use super::proxy::Proxy;
 
 const DEFAULT_H2_CONN_WINDOW: u32 = 1024 * 1024 * 2; // 2MB
 const DEFAULT_H2_STREAM_WINDOW: u32 = 1024 * 1024; // 1MB
@@ -15,6 +17,11 @@ pub(crate) struct ConnectorConfig {
     pub(crate) conn_window_size: u32,
     pub(crate) stream_window_size: u32,
     pub(crate) local_address: Option<IpAddr>,
+    pub(crate) proxy: Option<Rc<Proxy>>,
+    #[cfg(feature = "http3")]
+    pub(crate) h3: bool,
+    #[cfg(feature = "http3")]
+    pub(crate) alt_svc: Option<super::h3proto::AltSvcCache>,
 }
 
 impl Default for ConnectorConfig {
@@ -29,6 +36,11 @@ impl Default for ConnectorConfig {
             conn_window_size: DEFAULT_H2_CONN_WINDOW,
             stream_window_size: DEFAULT_H2_STREAM_WINDOW,
             local_address: None,
+            proxy: None,
+            #[cfg(feature = "http3")]
+            h3: false,
+            #[cfg(feature = "http3")]
+            alt_svc: None,
         }
     }
 }
@@ -13,7 +13,9 @@ use bytes::Bytes;
 use futures_core::future::LocalBoxFuture;
 use h2::client::SendRequest;
 
-use super::{error::SendRequestError, h1proto, h2proto, pool::Acquired};
+use super::{error::SendRequestError, h1proto, h2proto, pool::Acquired, proxy::Proxy};
+#[cfg(feature = "http3")]
+use super::{h3proto, h3proto::AltSvcCache};
 use crate::BoxError;
 
 /// Trait alias for types impl [tokio::io::AsyncRead] and [tokio::io::AsyncWrite].
@@ -29,6 +31,11 @@ pub struct H1Connection<Io: ConnectionIo> {
 }
 
 impl<Io: ConnectionIo> H1Connection<Io> {
+    /// Returns the proxy that requests on this connection are forwarded through, if any.
+    pub(super) fn forward_proxy(&self) -> Option<&Proxy> {
+        self.acquired.forward_proxy()
+    }
+
     /// close or release the connection to pool based on flag input
     pub(super) fn on_release(&mut self, keep_alive: bool) {
         if keep_alive {
@@ -177,7 +184,39 @@ impl Drop for H2ConnectionInner {
     }
 }
 
-/// Unified connection type cover HTTP/1 Plain/TLS and HTTP/2 protocols.
+/// HTTP/3 client connection
+///
+/// HTTP/3 connections are multiplexed and shared between requests to the same origin, so there is
+/// nothing to release back to a pool once a request has been sent.
+#[cfg(feature = "http3")]
+pub struct H3Connection {
+    sender: h3::client::SendRequest<h3_quinn::OpenStreams, Bytes>,
+}
+
+#[cfg(feature = "http3")]
+impl H3Connection {
+    pub(super) fn new(sender: h3::client::SendRequest<h3_quinn::OpenStreams, Bytes>) -> Self {
+        Self { sender }
+    }
+}
+
+#[cfg(feature = "http3")]
+impl Deref for H3Connection {
+    type Target = h3::client::SendRequest<h3_quinn::OpenStreams, Bytes>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sender
+    }
+}
+
+#[cfg(feature = "http3")]
+impl DerefMut for H3Connection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sender
+    }
+}
+
+/// Unified connection type cover HTTP/1 Plain/TLS, HTTP/2 and HTTP/3 protocols.
 #[allow(dead_code)]
 pub enum Connection<A, B = Box<dyn ConnectionIo>>
 where
@@ -186,6 +225,8 @@ where
 {
     Tcp(ConnectionType<A>),
     Tls(ConnectionType<B>),
+    #[cfg(feature = "http3")]
+    Quic(H3Connection),
 }
 
 /// Unified connection type cover Http1/2 protocols
@@ -231,6 +272,15 @@ impl<Io: ConnectionIo> ConnectionType<Io> {
             acquired,
         })
     }
+
+    /// Returns the Alt-Svc cache of the pool this connection was acquired from, if enabled.
+    #[cfg(feature = "http3")]
+    fn alt_svc(&self) -> Option<AltSvcCache> {
+        match self {
+            Self::H1(conn) => conn.acquired.config().alt_svc.clone(),
+            Self::H2(conn) => conn.acquired.config().alt_svc.clone(),
+        }
+    }
 }
 
 impl<A, B> Connection<A, B>
@@ -250,20 +300,40 @@ where
         RB::Error: Into<BoxError>,
     {
         Box::pin(async move {
-            match self {
+            let head = head.into();
+
+            // record HTTP/3 endpoints advertised by origins reached over TLS
+            #[cfg(feature = "http3")]
+            let alt_svc = match self {
+                Connection::Tls(ref conn) => conn
+                    .alt_svc()
+                    .map(|cache| (cache, head.as_ref().uri.clone())),
+                _ => None,
+            };
+
+            let res = match self {
                 Connection::Tcp(ConnectionType::H1(conn)) => {
-                    h1proto::send_request(conn, head.into(), body).await
+                    h1proto::send_request(conn, head, body).await
                 }
                 Connection::Tls(ConnectionType::H1(conn)) => {
-                    h1proto::send_request(conn, head.into(), body).await
+                    h1proto::send_request(conn, head, body).await
                 }
                 Connection::Tls(ConnectionType::H2(conn)) => {
-                    h2proto::send_request(conn, head.into(), body).await
+                    h2proto::send_request(conn, head, body).await
                 }
+                #[cfg(feature = "http3")]
+                Connection::Quic(conn) => h3proto::send_request(conn, head, body).await,
                 _ => {
                     unreachable!("Plain TCP connection can be used only with HTTP/1.1 protocol")
                 }
+            };
+
+            #[cfg(feature = "http3")]
+            if let (Some((cache, uri)), Ok((res_head, _))) = (alt_svc, &res) {
+                cache.update(&uri, &res_head.headers);
             }
+
+            res
         })
     }
 
@@ -292,6 +362,8 @@ where
                 Connection::Tcp(ConnectionType::H2(_)) => {
                     unreachable!("Plain Tcp connection can be used only in Http1 protocol")
                 }
+                #[cfg(feature = "http3")]
+                Connection::Quic(_) => Err(SendRequestError::TunnelNotSupported),
             }
         })
     }
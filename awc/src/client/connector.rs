@@ -22,11 +22,14 @@ use futures_core::{future::LocalBoxFuture, ready};
 use http::Uri;
 use pin_project_lite::pin_project;
 
+#[cfg(feature = "http3")]
+use super::h3proto::{AltSvcCache, H3Connector};
 use super::{
     config::ConnectorConfig,
     connection::{Connection, ConnectionIo},
     error::ConnectError,
     pool::ConnectionPool,
+    proxy::{Proxy, Route},
     Connect,
 };
 
@@ -332,19 +335,53 @@ where
 
     /// Sets maximum supported HTTP major version.
     ///
-    /// Supported versions are HTTP/1.1 and HTTP/2.
+    /// Supported versions are HTTP/1.1 and HTTP/2, and HTTP/3 when the `http3` crate feature is
+    /// enabled.
+    ///
+    /// With HTTP/3, `https` requests are sent over QUIC, falling back to TLS over TCP if a QUIC
+    /// connection cannot be established. The QUIC connection uses the Rustls v0.23 config of this
+    /// connector with its ALPN protocols replaced; other TLS backends cannot be used for HTTP/3.
+    /// Unlike other versions, this setting leaves the TLS config used for TCP connections as is.
+    /// See [`alt_svc_upgrade`](Self::alt_svc_upgrade) to only use HTTP/3 with origins that
+    /// advertise it.
     pub fn max_http_version(mut self, val: http::Version) -> Self {
         let versions = match val {
             http::Version::HTTP_11 => vec![b"http/1.1".to_vec()],
             http::Version::HTTP_2 => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            #[cfg(feature = "http3")]
+            http::Version::HTTP_3 => {
+                self.config.h3 = true;
+                return self;
+            }
             _ => {
                 unimplemented!("actix-http client only supports versions http/1.1 & http/2")
             }
         };
+
+        #[cfg(feature = "http3")]
+        {
+            self.config.h3 = false;
+        }
+
         self.tls = Connector::build_tls(versions);
         self
     }
 
+    /// Sets whether HTTP/3 is only used after an origin advertises it.
+    ///
+    /// When enabled, requests are sent over TCP until a response carries an `Alt-Svc` header
+    /// advertising an `h3` endpoint on the same host. Later requests to that origin are then sent
+    /// over QUIC to the advertised port, until the advertisement expires or a QUIC connection
+    /// fails.
+    ///
+    /// Has no effect unless the [maximum HTTP version](Self::max_http_version) is HTTP/3. By
+    /// default, HTTP/3 is attempted for every `https` request.
+    #[cfg(feature = "http3")]
+    pub fn alt_svc_upgrade(mut self, enabled: bool) -> Self {
+        self.config.alt_svc = enabled.then(AltSvcCache::default);
+        self
+    }
+
     /// Sets the initial window size (in bytes) for HTTP/2 stream-level flow control for received
     /// data.
     ///
@@ -418,15 +455,29 @@ where
         self
     }
 
+    /// Sets a forward proxy to connect to remote hosts through.
+    ///
+    /// See [`Proxy`] for how connections are made through the proxy and how hosts can be excluded
+    /// from it. The connection [timeout](Self::timeout) includes establishing a tunnel through the
+    /// proxy. HTTP/3 is not used when a proxy is set.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.config.proxy = Some(Rc::new(proxy));
+        self
+    }
+
     /// Finish configuration process and create connector service.
     ///
     /// The `Connector` builder always concludes by calling `finish()` last in its combinator chain.
     pub fn finish(self) -> ConnectorService<S, IO> {
+        #[cfg(feature = "http3")]
+        let h3 = self.h3_connector();
+
         let local_address = self.config.local_address;
         let timeout = self.config.timeout;
+        let proxy = self.config.proxy.clone();
 
         let tcp_service_inner =
-            TcpConnectorInnerService::new(self.connector, timeout, local_address);
+            TcpConnectorInnerService::new(self.connector, timeout, local_address, proxy);
 
         #[allow(clippy::redundant_clone)]
         let tcp_service = TcpConnectorService {
@@ -689,7 +740,38 @@ where
         let tls_pool =
             tls_service.map(move |tls_service| ConnectionPool::new(tls_service, tls_config));
 
-        ConnectorServicePriv { tcp_pool, tls_pool }
+        ConnectorServicePriv {
+            tcp_pool,
+            tls_pool,
+            #[cfg(feature = "http3")]
+            h3,
+        }
+    }
+
+    /// Builds the HTTP/3 connector, if enabled.
+    #[cfg(feature = "http3")]
+    fn h3_connector(&self) -> Option<H3Connector> {
+        if !self.config.h3 {
+            return None;
+        }
+
+        if self.config.proxy.is_some() {
+            log::warn!("HTTP/3 cannot be used through a proxy; it will not be used");
+            return None;
+        }
+
+        let OurTlsConnector::Rustls023(ref tls) = self.tls else {
+            log::warn!("HTTP/3 requires a Rustls v0.23 TLS config; it will not be used");
+            return None;
+        };
+
+        match H3Connector::new(tls, &self.config) {
+            Ok(h3) => Some(h3),
+            Err(err) => {
+                log::warn!("TLS config cannot be used for HTTP/3; it will not be used: {err}");
+                None
+            }
+        }
     }
 }
 
@@ -859,21 +941,28 @@ where
     }
 }
 
-/// service for establish tcp connection.
+/// service for establish tcp connection, through a proxy if one is configured.
 /// operation is canceled when timeout limit reached.
 #[derive(Clone)]
 pub struct TcpConnectorInnerService<S: Clone> {
     service: S,
     timeout: Duration,
     local_address: Option<std::net::IpAddr>,
+    proxy: Option<Rc<Proxy>>,
 }
 
 impl<S: Clone> TcpConnectorInnerService<S> {
-    fn new(service: S, timeout: Duration, local_address: Option<std::net::IpAddr>) -> Self {
+    fn new(
+        service: S,
+        timeout: Duration,
+        local_address: Option<std::net::IpAddr>,
+        proxy: Option<Rc<Proxy>>,
+    ) -> Self {
         Self {
             service,
             timeout,
             local_address,
+            proxy,
         }
     }
 }
@@ -883,48 +972,95 @@ where
     S: Service<ConnectInfo<Uri>, Response = TcpConnection<Uri, Io>, Error = TcpConnectError>
         + Clone
         + 'static,
+    Io: ConnectionIo,
 {
     type Response = S::Response;
     type Error = ConnectError;
-    type Future = TcpConnectorInnerFuture<S::Future>;
+    type Future = TcpConnectorInnerFuture<S::Future, Io>;
 
     actix_service::forward_ready!(service);
 
     fn call(&self, req: Connect) -> Self::Future {
-        let mut req = ConnectInfo::new(req.uri).set_addr(req.addr);
+        let proxy = self
+            .proxy
+            .as_ref()
+            .map(|proxy| (proxy, proxy.route(&req.uri)));
+
+        let (proxy, route) = match proxy {
+            Some((proxy, route)) if route != Route::Direct => (Rc::clone(proxy), route),
+            _ => {
+                let mut req = ConnectInfo::new(req.uri).set_addr(req.addr);
+
+                if let Some(local_addr) = self.local_address {
+                    req = req.set_local_addr(local_addr);
+                }
+
+                return TcpConnectorInnerFuture::Direct {
+                    fut: self.service.call(req),
+                    timeout: sleep(self.timeout),
+                };
+            }
+        };
+
+        // the pre-resolved address is the host's, so it is not used for connecting to the proxy
+        let mut proxy_req = ConnectInfo::new(proxy.uri().clone());
 
         if let Some(local_addr) = self.local_address {
-            req = req.set_local_addr(local_addr);
+            proxy_req = proxy_req.set_local_addr(local_addr);
         }
 
-        TcpConnectorInnerFuture {
-            fut: self.service.call(req),
-            timeout: sleep(self.timeout),
+        let service = self.service.clone();
+        let timeout = self.timeout;
+
+        let fut = async move {
+            let (mut io, _) = service.call(proxy_req).await?.into_parts();
+
+            if route == Route::Tunnel {
+                proxy.tunnel(&mut io, &req.uri).await?;
+            }
+
+            // connection is reported as being to the host so TLS is established with it
+            Ok(TcpConnection::new(req.uri, io))
+        };
+
+        TcpConnectorInnerFuture::Proxied {
+            fut: Box::pin(async move {
+                actix_rt::time::timeout(timeout, fut)
+                    .await
+                    .unwrap_or(Err(ConnectError::Timeout))
+            }),
         }
     }
 }
 
 pin_project! {
     #[project = TcpConnectorInnerFutureProj]
-    pub struct TcpConnectorInnerFuture<Fut> {
-        #[pin]
-        fut: Fut,
-        #[pin]
-        timeout: Sleep,
+    pub enum TcpConnectorInnerFuture<Fut, Io> {
+        Direct {
+            #[pin]
+            fut: Fut,
+            #[pin]
+            timeout: Sleep,
+        },
+        Proxied {
+            fut: LocalBoxFuture<'static, Result<TcpConnection<Uri, Io>, ConnectError>>,
+        },
     }
 }
 
-impl<Fut, Io> Future for TcpConnectorInnerFuture<Fut>
+impl<Fut, Io> Future for TcpConnectorInnerFuture<Fut, Io>
 where
     Fut: Future<Output = Result<TcpConnection<Uri, Io>, TcpConnectError>>,
 {
     type Output = Result<TcpConnection<Uri, Io>, ConnectError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let this = self.project();
-        match this.fut.poll(cx) {
-            Poll::Ready(res) => Poll::Ready(res.map_err(ConnectError::from)),
-            Poll::Pending => this.timeout.poll(cx).map(|_| Err(ConnectError::Timeout)),
+        match self.project() {
+            TcpConnectorInnerFutureProj::Direct { fut, timeout } => match fut.poll(cx) {
+                Poll::Ready(res) => Poll::Ready(res.map_err(ConnectError::from)),
+                Poll::Pending => timeout.poll(cx).map(|_| Err(ConnectError::Timeout)),
+            },
+            TcpConnectorInnerFutureProj::Proxied { fut } => fut.as_mut().poll(cx),
         }
     }
 }
@@ -956,6 +1092,8 @@ where
 {
     tcp_pool: ConnectionPool<S1, Io1>,
     tls_pool: Option<ConnectionPool<S2, Io2>>,
+    #[cfg(feature = "http3")]
+    h3: Option<H3Connector>,
 }
 
 impl<S1, S2, Io1, Io2> Service<Connect> for ConnectorServicePriv<S1, S2, Io1, Io2>
@@ -978,6 +1116,31 @@ where
     }
 
     fn call(&self, req: Connect) -> Self::Future {
+        #[cfg(feature = "http3")]
+        if let Some(h3) = self.h3.as_ref().filter(|h3| {
+            // WebSockets need a TCP tunnel
+            req.uri.scheme_str() == Some("https") && h3.is_available(&req.uri)
+        }) {
+            let h3 = h3.clone();
+            let tls_pool = self.tls_pool.clone();
+
+            return ConnectorServiceFuture::Quic {
+                fut: Box::pin(async move {
+                    match h3.connect(&req).await {
+                        Ok(conn) => return Ok(Connection::Quic(conn)),
+                        Err(err) => {
+                            log::debug!("Falling back to TCP for {}: {err}", req.uri);
+                        }
+                    }
+
+                    match tls_pool {
+                        Some(pool) => pool.call(req).await.map(Connection::Tls),
+                        None => Err(ConnectError::SslIsNotSupported),
+                    }
+                }),
+            };
+        }
+
         match req.uri.scheme_str() {
             Some("https") | Some("wss") => match self.tls_pool {
                 None => ConnectorServiceFuture::SslIsNotSupported,
@@ -1013,6 +1176,11 @@ pin_project! {
             #[pin]
             fut:  <ConnectionPool<S2, Io2> as Service<Connect>>::Future
         },
+        // only constructed when the `http3` feature is enabled; pin-project-lite does not support
+        // conditionally compiled variants
+        Quic {
+            fut: LocalBoxFuture<'static, Result<Connection<Io1, Io2>, ConnectError>>
+        },
         SslIsNotSupported
     }
 }
@@ -1030,6 +1198,7 @@ where
         match self.project() {
             ConnectorServiceFutureProj::Tcp { fut } => fut.poll(cx).map_ok(Connection::Tcp),
             ConnectorServiceFutureProj::Tls { fut } => fut.poll(cx).map_ok(Connection::Tls),
+            ConnectorServiceFutureProj::Quic { fut } => fut.as_mut().poll(cx),
             ConnectorServiceFutureProj::SslIsNotSupported => {
                 Poll::Ready(Err(ConnectError::SslIsNotSupported))
             }
@@ -32,6 +32,16 @@ pub enum ConnectError {
     #[display("{}", _0)]
     H2(h2::Error),
 
+    /// QUIC connection error
+    #[cfg(feature = "http3")]
+    #[display("{}", _0)]
+    Quic(quinn::ConnectionError),
+
+    /// Http3 error
+    #[cfg(feature = "http3")]
+    #[display("{}", _0)]
+    H3(h3::error::ConnectionError),
+
     /// Connecting took too long
     #[display("Timeout while establishing connection")]
     Timeout,
@@ -44,6 +54,11 @@ pub enum ConnectError {
     #[display("Connector received `Connect` method with unresolved host")]
     Unresolved,
 
+    /// Proxy server refused or failed to establish a tunnel to the host
+    #[display("Proxy error: {}", _0)]
+    #[from(skip)]
+    Proxy(String),
+
     /// Connection io error
     #[display("{}", _0)]
     Io(io::Error),
@@ -107,6 +122,11 @@ pub enum SendRequestError {
     #[display("{}", _0)]
     H2(h2::Error),
 
+    /// Http3 error
+    #[cfg(feature = "http3")]
+    #[display("{}", _0)]
+    H3(h3::error::StreamError),
+
     /// Response took too long
     #[display("Timeout while waiting for response")]
     Timeout,
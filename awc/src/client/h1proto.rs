@@ -1,6 +1,7 @@
 use std::{
     io::Write,
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
 };
 
@@ -9,7 +10,7 @@ use actix_http::{
     body::{BodySize, MessageBody},
     error::PayloadError,
     h1,
-    header::{HeaderMap, TryIntoHeaderValue, EXPECT, HOST},
+    header::{HeaderMap, TryIntoHeaderValue, EXPECT, HOST, PROXY_AUTHORIZATION},
     Payload, RequestHeadType, ResponseHead, StatusCode,
 };
 use actix_utils::future::poll_fn;
@@ -61,6 +62,32 @@ where
         }
     }
 
+    // requests forwarded through a proxy are sent to it in absolute form
+    if let Some(proxy) = io.forward_proxy() {
+        match head {
+            RequestHeadType::Owned(ref mut head) => head.set_absolute_form(true),
+            RequestHeadType::Rc(ref mut head, _) => Rc::make_mut(head).set_absolute_form(true),
+        }
+
+        if let Some(value) = proxy.authorization().filter(|_| {
+            !head.as_ref().headers.contains_key(PROXY_AUTHORIZATION)
+                && !head
+                    .extra_headers()
+                    .iter()
+                    .any(|h| h.contains_key(PROXY_AUTHORIZATION))
+        }) {
+            match head {
+                RequestHeadType::Owned(ref mut head) => {
+                    head.headers.insert(PROXY_AUTHORIZATION, value);
+                }
+                RequestHeadType::Rc(_, ref mut extra_headers) => {
+                    let headers = extra_headers.get_or_insert(HeaderMap::new());
+                    headers.insert(PROXY_AUTHORIZATION, value);
+                }
+            }
+        }
+    }
+
     // create Framed and prepare sending request
     let mut framed = Framed::new(io, h1::ClientCodec::default());
 
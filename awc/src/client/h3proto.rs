@@ -0,0 +1,563 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use actix_http::{
+    body::{BodySize, MessageBody},
+    error::PayloadError,
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ALT_SVC, CONNECTION, CONTENT_LENGTH, HOST,
+        TRANSFER_ENCODING, UPGRADE,
+    },
+    BoxedPayloadStream, Payload, RequestHeadType, ResponseHead, StatusCode,
+};
+use actix_utils::future::poll_fn;
+use bytes::{Buf as _, Bytes};
+use futures_core::{ready, Stream};
+use h3::client::{RequestStream, SendRequest};
+use http::{uri::Authority, Method, Uri, Version};
+use log::trace;
+use tokio::sync::Mutex;
+
+use super::{
+    config::ConnectorConfig,
+    connection::H3Connection,
+    error::{ConnectError, SendRequestError},
+    Connect,
+};
+use crate::BoxError;
+
+/// Max age of an `Alt-Svc` advertisement that does not specify one, as per RFC 7838 §3.1.
+const DEFAULT_ALT_SVC_MAX_AGE: u64 = 86400;
+
+/// Longest time an `Alt-Svc` advertisement is remembered for, regardless of its max age.
+const MAX_ALT_SVC_MAX_AGE: Duration = Duration::from_secs(86400);
+
+type H3Sender = SendRequest<h3_quinn::OpenStreams, Bytes>;
+type H3Stream = RequestStream<h3_quinn::BidiStream<Bytes>, Bytes>;
+
+pub(crate) async fn send_request<B>(
+    mut io: H3Connection,
+    head: RequestHeadType,
+    body: B,
+) -> Result<(ResponseHead, Payload), SendRequestError>
+where
+    B: MessageBody,
+    B::Error: Into<BoxError>,
+{
+    trace!("Sending client request: {:?} {:?}", head, body.size());
+
+    let head_req = head.as_ref().method == Method::HEAD;
+    let length = body.size();
+    let eof = matches!(length, BodySize::None | BodySize::Sized(0));
+
+    let req = prepare_request(head, length)?;
+
+    let mut stream = io.send_request(req).await?;
+
+    if !eof {
+        send_body(body, &mut stream).await?;
+    }
+    stream.finish().await?;
+
+    let res = stream.recv_response().await?;
+
+    let (parts, ()) = res.into_parts();
+    let mut head = ResponseHead::new(
+        StatusCode::from_u16(parts.status.as_u16())
+            .expect("actix and http v1 status code ranges are the same"),
+    );
+    head.version = Version::HTTP_3;
+
+    for (key, value) in parts.headers.iter() {
+        // names and values were validated by `h3` so conversion cannot fail
+        let (Ok(key), Ok(value)) = (
+            HeaderName::from_bytes(key.as_str().as_bytes()),
+            HeaderValue::from_bytes(value.as_bytes()),
+        ) else {
+            continue;
+        };
+
+        head.headers.append(key, value);
+    }
+
+    let payload = if head_req {
+        Payload::None
+    } else {
+        let payload: BoxedPayloadStream = Box::pin(H3Payload { stream });
+        Payload::from(payload)
+    };
+
+    Ok((head, payload))
+}
+
+/// Converts a request head to the `http` v1 types used by `h3`.
+fn prepare_request(
+    head: RequestHeadType,
+    length: BodySize,
+) -> Result<http_1::Request<()>, SendRequestError> {
+    let mut req = http_1::Request::new(());
+
+    *req.uri_mut() = http_1::Uri::try_from(head.as_ref().uri.to_string())
+        .map_err(|err| SendRequestError::Send(io::Error::new(io::ErrorKind::InvalidInput, err)))?;
+    *req.method_mut() = http_1::Method::from_bytes(head.as_ref().method.as_str().as_bytes())
+        .map_err(|err| SendRequestError::Send(io::Error::new(io::ErrorKind::InvalidInput, err)))?;
+    *req.version_mut() = http_1::Version::HTTP_3;
+
+    let skip_len = length != BodySize::Stream;
+
+    if let BodySize::Sized(len) = length {
+        let mut buf = itoa::Buffer::new();
+
+        req.headers_mut().insert(
+            http_1::header::CONTENT_LENGTH,
+            http_1::HeaderValue::from_str(buf.format(len)).unwrap(),
+        );
+    }
+
+    // Extracting extra headers from RequestHeadType. HeaderMap::new() does not allocate.
+    let (head, extra_headers) = match head {
+        RequestHeadType::Owned(head) => (RequestHeadType::Owned(head), HeaderMap::new()),
+        RequestHeadType::Rc(head, extra_headers) => (
+            RequestHeadType::Rc(head, None),
+            extra_headers.unwrap_or_else(HeaderMap::new),
+        ),
+    };
+
+    // merging headers from head and extra headers.
+    let headers = head
+        .as_ref()
+        .headers
+        .iter()
+        .filter(|(name, _)| !extra_headers.contains_key(*name))
+        .chain(extra_headers.iter());
+
+    // copy headers
+    for (key, value) in headers {
+        match *key {
+            // omit connection-specific headers according to:
+            // https://datatracker.ietf.org/doc/html/rfc9114#section-4.2
+            // host is carried by the :authority pseudo-header
+            CONNECTION | TRANSFER_ENCODING | UPGRADE | HOST => continue,
+            CONTENT_LENGTH if skip_len => continue,
+            _ => {}
+        }
+
+        let (Ok(key), Ok(value)) = (
+            http_1::HeaderName::from_bytes(key.as_str().as_bytes()),
+            http_1::HeaderValue::from_bytes(value.as_bytes()),
+        ) else {
+            continue;
+        };
+
+        req.headers_mut().append(key, value);
+    }
+
+    Ok(req)
+}
+
+async fn send_body<B>(body: B, stream: &mut H3Stream) -> Result<(), SendRequestError>
+where
+    B: MessageBody,
+    B::Error: Into<BoxError>,
+{
+    actix_rt::pin!(body);
+
+    while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+        let chunk = res.map_err(|err| SendRequestError::Body(err.into()))?;
+
+        if !chunk.is_empty() {
+            stream.send_data(chunk).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Response body received over an HTTP/3 request stream.
+struct H3Payload {
+    stream: H3Stream,
+}
+
+impl Stream for H3Payload {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        match ready!(this.stream.poll_recv_data(cx)) {
+            Ok(Some(mut chunk)) => Poll::Ready(Some(Ok(chunk.copy_to_bytes(chunk.remaining())))),
+            Ok(None) => Poll::Ready(None),
+            Err(err) => Poll::Ready(Some(Err(err.into()))),
+        }
+    }
+}
+
+/// Opens QUIC connections and keeps one multiplexed HTTP/3 connection per origin.
+#[derive(Clone)]
+pub(crate) struct H3Connector(Rc<H3ConnectorInner>);
+
+struct H3ConnectorInner {
+    client_config: quinn::ClientConfig,
+    timeout: Duration,
+    local_address: Option<IpAddr>,
+    alt_svc: Option<AltSvcCache>,
+    endpoint_v4: RefCell<Option<quinn::Endpoint>>,
+    endpoint_v6: RefCell<Option<quinn::Endpoint>>,
+    conns: RefCell<HashMap<Authority, (u64, H3Sender)>>,
+    connecting: RefCell<HashMap<Authority, Rc<Mutex<()>>>>,
+    next_id: Cell<u64>,
+}
+
+impl H3Connector {
+    /// Constructs an HTTP/3 connector using the given TLS config, with its ALPN protocols replaced.
+    ///
+    /// Fails if the TLS config cannot be used for QUIC, which requires TLS v1.3.
+    pub(crate) fn new(
+        tls: &quinn::rustls::ClientConfig,
+        config: &ConnectorConfig,
+    ) -> Result<Self, BoxError> {
+        let mut tls = tls.clone();
+        tls.alpn_protocols = vec![actix_http::h3::ALPN.to_vec()];
+
+        let quic = quinn::crypto::rustls::QuicClientConfig::try_from(tls)?;
+
+        Ok(Self(Rc::new(H3ConnectorInner {
+            client_config: quinn::ClientConfig::new(Arc::new(quic)),
+            timeout: config.timeout,
+            local_address: config.local_address,
+            alt_svc: config.alt_svc.clone(),
+            endpoint_v4: RefCell::new(None),
+            endpoint_v6: RefCell::new(None),
+            conns: RefCell::new(HashMap::new()),
+            connecting: RefCell::new(HashMap::new()),
+            next_id: Cell::new(0),
+        })))
+    }
+
+    /// Returns true if requests to `uri` should be attempted over HTTP/3.
+    ///
+    /// Without Alt-Svc upgrades enabled, this is always the case. Otherwise, the origin must have
+    /// advertised an HTTP/3 endpoint that has not yet expired.
+    pub(crate) fn is_available(&self, uri: &Uri) -> bool {
+        match self.0.alt_svc {
+            None => true,
+            Some(ref cache) => uri.authority().is_some_and(|authority| {
+                self.0.conns.borrow().contains_key(authority) || cache.get(authority).is_some()
+            }),
+        }
+    }
+
+    /// Returns an existing connection to the request's origin or opens a new one.
+    ///
+    /// Connection attempts to the same origin are made one at a time, so concurrent requests share
+    /// the first connection that is opened. A failed connection attempt forgets any Alt-Svc
+    /// advertisement for the origin.
+    pub(crate) async fn connect(&self, req: &Connect) -> Result<H3Connection, ConnectError> {
+        let authority = req.uri.authority().ok_or(ConnectError::Unresolved)?;
+
+        if let Some(sender) = self.existing(authority) {
+            return Ok(H3Connection::new(sender));
+        }
+
+        let lock = Rc::clone(
+            self.0
+                .connecting
+                .borrow_mut()
+                .entry(authority.clone())
+                .or_default(),
+        );
+
+        let res = actix_rt::time::timeout(self.0.timeout, async {
+            let _guard = lock.lock().await;
+
+            // another request may have connected while this one was waiting
+            match self.existing(authority) {
+                Some(sender) => Ok(sender),
+                None => self.open(req, authority).await,
+            }
+        })
+        .await
+        .unwrap_or(Err(ConnectError::Timeout));
+
+        // forget the lock once no other requests are waiting on it
+        let mut connecting = self.0.connecting.borrow_mut();
+        if Rc::strong_count(&lock) <= 2 {
+            connecting.remove(authority);
+        }
+        drop(connecting);
+
+        if res.is_err() {
+            if let Some(ref cache) = self.0.alt_svc {
+                cache.remove(authority);
+            }
+        }
+
+        res.map(H3Connection::new)
+    }
+
+    fn existing(&self, authority: &Authority) -> Option<H3Sender> {
+        self.0
+            .conns
+            .borrow()
+            .get(authority)
+            .map(|(_, sender)| sender.clone())
+    }
+
+    async fn open(&self, req: &Connect, authority: &Authority) -> Result<H3Sender, ConnectError> {
+        // IPv6 literals are bracketed in URIs
+        let host = authority
+            .host()
+            .trim_start_matches('[')
+            .trim_end_matches(']');
+
+        let alt_port = self
+            .0
+            .alt_svc
+            .as_ref()
+            .and_then(|cache| cache.get(authority));
+
+        let addr = match (req.addr, alt_port) {
+            (Some(mut addr), Some(port)) => {
+                addr.set_port(port);
+                addr
+            }
+            (Some(addr), None) => addr,
+            (None, port) => {
+                let port = port.or(authority.port_u16()).unwrap_or(443);
+
+                tokio::net::lookup_host((host, port))
+                    .await
+                    .map_err(|err| ConnectError::Resolver(Box::new(err)))?
+                    .next()
+                    .ok_or(ConnectError::NoRecords)?
+            }
+        };
+
+        let conn = self
+            .endpoint(&addr)?
+            .connect_with(self.0.client_config.clone(), addr, host)
+            .map_err(|err| ConnectError::Io(io::Error::new(io::ErrorKind::InvalidInput, err)))?
+            .await?;
+
+        let (mut driver, sender) = h3::client::new(h3_quinn::Connection::new(conn)).await?;
+
+        let id = self.0.next_id.get();
+        self.0.next_id.set(id + 1);
+
+        self.0
+            .conns
+            .borrow_mut()
+            .insert(authority.clone(), (id, sender.clone()));
+
+        // drive the connection until it is closed, then forget it
+        let inner = Rc::downgrade(&self.0);
+        let authority = authority.clone();
+        actix_rt::spawn(async move {
+            let err = poll_fn(|cx| driver.poll_close(cx)).await;
+
+            if !err.is_h3_no_error() {
+                trace!("HTTP/3 connection to {authority} closed: {err}");
+            }
+
+            if let Some(inner) = inner.upgrade() {
+                let mut conns = inner.conns.borrow_mut();
+
+                if conns
+                    .get(&authority)
+                    .is_some_and(|(conn_id, _)| *conn_id == id)
+                {
+                    conns.remove(&authority);
+                }
+            }
+        });
+
+        Ok(sender)
+    }
+
+    /// Returns the client endpoint for the address family of `remote`, binding it on first use.
+    fn endpoint(&self, remote: &SocketAddr) -> io::Result<quinn::Endpoint> {
+        let slot = if remote.is_ipv6() {
+            &self.0.endpoint_v6
+        } else {
+            &self.0.endpoint_v4
+        };
+
+        if let Some(ref endpoint) = *slot.borrow() {
+            return Ok(endpoint.clone());
+        }
+
+        let ip = match self.0.local_address {
+            Some(ip) => ip,
+            None if remote.is_ipv6() => Ipv6Addr::UNSPECIFIED.into(),
+            None => Ipv4Addr::UNSPECIFIED.into(),
+        };
+
+        let endpoint = quinn::Endpoint::client(SocketAddr::new(ip, 0))?;
+        *slot.borrow_mut() = Some(endpoint.clone());
+
+        Ok(endpoint)
+    }
+}
+
+/// Cache of the HTTP/3 alternative services advertised by origins, keyed by origin authority.
+///
+/// Only alternatives on the same host as the origin are recorded.
+#[derive(Clone, Default)]
+pub(crate) struct AltSvcCache(Rc<RefCell<HashMap<Authority, AltSvcEntry>>>);
+
+struct AltSvcEntry {
+    port: u16,
+    expires: Instant,
+}
+
+impl AltSvcCache {
+    /// Returns the advertised HTTP/3 port for an origin, if the advertisement has not expired.
+    pub(crate) fn get(&self, authority: &Authority) -> Option<u16> {
+        let mut entries = self.0.borrow_mut();
+
+        match entries.get(authority) {
+            Some(entry) if entry.expires > Instant::now() => Some(entry.port),
+            Some(_) => {
+                entries.remove(authority);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn remove(&self, authority: &Authority) {
+        self.0.borrow_mut().remove(authority);
+    }
+
+    /// Records the `Alt-Svc` headers of a response from `uri`'s origin.
+    ///
+    /// Responses without the header leave the cache untouched. Otherwise, the header replaces any
+    /// previous advertisement for the origin.
+    pub(crate) fn update(&self, uri: &Uri, headers: &HeaderMap) {
+        let Some(authority) = uri.authority() else {
+            return;
+        };
+
+        if !headers.contains_key(ALT_SVC) {
+            return;
+        }
+
+        let alt = headers
+            .get_all(ALT_SVC)
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|value| parse_alt_svc(value, authority.host()));
+
+        let alt = alt.and_then(|(port, max_age)| {
+            let max_age = max_age.min(MAX_ALT_SVC_MAX_AGE);
+            let expires = Instant::now().checked_add(max_age)?;
+            (!max_age.is_zero()).then_some(AltSvcEntry { port, expires })
+        });
+
+        match alt {
+            Some(entry) => {
+                self.0.borrow_mut().insert(authority.clone(), entry);
+            }
+
+            // "clear", zero max age, or no usable alternatives
+            _ => self.remove(authority),
+        }
+    }
+}
+
+/// Returns the port and max age of the first same-host `h3` alternative in an `Alt-Svc` value.
+fn parse_alt_svc(value: &str, host: &str) -> Option<(u16, Duration)> {
+    value.split(',').find_map(|alt| {
+        let mut params = alt.split(';').map(str::trim);
+
+        let (protocol, alt_authority) = params.next()?.split_once('=')?;
+        if protocol != "h3" {
+            return None;
+        }
+
+        let (alt_host, port) = alt_authority.trim_matches('"').rsplit_once(':')?;
+        if !alt_host.is_empty() && !alt_host.eq_ignore_ascii_case(host) {
+            return None;
+        }
+        let port = port.parse().ok()?;
+
+        let max_age = params
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| *name == "ma")
+            .and_then(|(_, val)| val.trim_matches('"').parse().ok())
+            .unwrap_or(DEFAULT_ALT_SVC_MAX_AGE);
+
+        Some((port, Duration::from_secs(max_age)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alt_svc_parsing() {
+        assert_eq!(
+            parse_alt_svc(r#"h3=":443""#, "example.com"),
+            Some((443, Duration::from_secs(DEFAULT_ALT_SVC_MAX_AGE)))
+        );
+        assert_eq!(
+            parse_alt_svc(r#"h2=":8443", h3=":8443"; ma=60; persist=1"#, "example.com"),
+            Some((8443, Duration::from_secs(60)))
+        );
+        assert_eq!(
+            parse_alt_svc(r#"h3="EXAMPLE.com:9443"; ma=0"#, "example.com"),
+            Some((9443, Duration::ZERO))
+        );
+        assert_eq!(parse_alt_svc(r#"h3="other.com:443""#, "example.com"), None);
+        assert_eq!(parse_alt_svc(r#"h3-29=":443""#, "example.com"), None);
+        assert_eq!(parse_alt_svc("clear", "example.com"), None);
+        assert_eq!(parse_alt_svc(r#"h3=":not-a-port""#, "example.com"), None);
+    }
+
+    #[test]
+    fn alt_svc_cache() {
+        let cache = AltSvcCache::default();
+        let uri = Uri::from_static("https://example.com/path");
+        let authority = uri.authority().unwrap();
+
+        let mut headers = HeaderMap::new();
+        cache.update(&uri, &headers);
+        assert_eq!(cache.get(authority), None);
+
+        headers.insert(ALT_SVC, HeaderValue::from_static(r#"h3=":8443""#));
+        cache.update(&uri, &headers);
+        assert_eq!(cache.get(authority), Some(8443));
+
+        // responses without the header do not invalidate the entry
+        cache.update(&uri, &HeaderMap::new());
+        assert_eq!(cache.get(authority), Some(8443));
+
+        headers.insert(ALT_SVC, HeaderValue::from_static("clear"));
+        cache.update(&uri, &headers);
+        assert_eq!(cache.get(authority), None);
+
+        headers.insert(ALT_SVC, HeaderValue::from_static(r#"h3=":8443"; ma=0"#));
+        cache.update(&uri, &headers);
+        assert_eq!(cache.get(authority), None);
+
+        // max ages that would overflow are capped
+        headers.insert(
+            ALT_SVC,
+            HeaderValue::from_static(r#"h3=":8443"; ma=18446744073709551615"#),
+        );
+        cache.update(&uri, &headers);
+        assert_eq!(cache.get(authority), Some(8443));
+
+        let expires = cache.0.borrow().get(authority).unwrap().expires;
+        assert!(expires <= Instant::now() + MAX_ALT_SVC_MAX_AGE);
+    }
+}
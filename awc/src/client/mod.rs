@@ -2,7 +2,11 @@
 
 use std::{rc::Rc, time::Duration};
 
-use actix_http::{error::HttpError, header::HeaderMap, Method, RequestHead, Uri};
+use actix_http::{
+    error::HttpError,
+    header::{ContentEncoding, HeaderMap},
+    Method, RequestHead, Uri,
+};
 use actix_rt::net::TcpStream;
 use actix_service::Service;
 pub use actix_tls::connect::{
@@ -17,12 +21,16 @@ mod connector;
 mod error;
 mod h1proto;
 mod h2proto;
+#[cfg(feature = "http3")]
+mod h3proto;
 mod pool;
+mod proxy;
 
 pub use self::{
     connection::{Connection, ConnectionIo},
     connector::{Connector, ConnectorService},
     error::{ConnectError, FreezeRequestError, InvalidUrl, SendRequestError},
+    proxy::Proxy,
 };
 
 #[derive(Clone)]
@@ -60,6 +68,7 @@ pub(crate) struct ClientConfig {
     pub(crate) connector: BoxConnectorService,
     pub(crate) default_headers: Rc<HeaderMap>,
     pub(crate) timeout: Option<Duration>,
+    pub(crate) compress: Option<ContentEncoding>,
 }
 
 impl Default for Client {
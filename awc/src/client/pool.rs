@@ -1,4 +1,5 @@
-//! Client connection pooling keyed on the authority part of the connection URI.
+//! Client connection pooling keyed on the authority part of the connection URI, or of the proxy
+//! requests are forwarded through.
 
 use std::{
     cell::RefCell,
@@ -28,17 +29,24 @@ use super::{
     connection::{ConnectionInnerType, ConnectionIo, ConnectionType, H2ConnectionInner},
     error::ConnectError,
     h2proto::handshake,
+    proxy::{Proxy, Route},
     Connect,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Key {
     authority: Authority,
+
+    /// Connection is to a proxy at `authority` that requests to any host are forwarded through.
+    forward_proxy: bool,
 }
 
 impl From<Authority> for Key {
     fn from(authority: Authority) -> Key {
-        Key { authority }
+        Key {
+            authority,
+            forward_proxy: false,
+        }
     }
 }
 
@@ -152,6 +160,19 @@ where
     }
 }
 
+impl<S, Io> Clone for ConnectionPool<S, Io>
+where
+    S: Clone,
+    Io: AsyncWrite + Unpin + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            connector: self.connector.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
 impl<S, Io> Service<Connect> for ConnectionPool<S, Io>
 where
     S: Service<Connect, Response = (Io, Protocol), Error = ConnectError> + Clone + 'static,
@@ -168,10 +189,17 @@ where
         let inner = self.inner.clone();
 
         Box::pin(async move {
-            let key = if let Some(authority) = req.uri.authority() {
-                authority.clone().into()
-            } else {
-                return Err(ConnectError::Unresolved);
+            let key = match inner.config.proxy.as_deref() {
+                // connections to a forward proxy are shared between all hosts forwarded through it
+                Some(proxy) if proxy.route(&req.uri) == Route::Forward => Key {
+                    authority: proxy.authority().clone(),
+                    forward_proxy: true,
+                },
+
+                _ => match req.uri.authority() {
+                    Some(authority) => authority.clone().into(),
+                    None => return Err(ConnectError::Unresolved),
+                },
             };
 
             // acquire an owned permit and carry it with connection
@@ -353,6 +381,21 @@ where
 }
 
 impl<Io: ConnectionIo> Acquired<Io> {
+    /// Returns the configuration of the connection pool.
+    #[cfg(feature = "http3")]
+    pub(super) fn config(&self) -> &ConnectorConfig {
+        &self.inner.config
+    }
+
+    /// Returns the proxy that requests are forwarded through, if connected to one.
+    pub(super) fn forward_proxy(&self) -> Option<&Proxy> {
+        self.inner
+            .config
+            .proxy
+            .as_deref()
+            .filter(|_| self.key.forward_proxy)
+    }
+
     /// Close the IO.
     pub(super) fn close(&self, conn: ConnectionInnerType<Io>) {
         self.inner.close(conn);
@@ -613,6 +656,61 @@ mod test {
         release(conn);
     }
 
+    #[actix_rt::test]
+    async fn test_pool_forward_proxy_key() {
+        let generated = Rc::new(Cell::new(0));
+        let generated_clone = generated.clone();
+
+        let connector = TestPoolConnector { generated };
+
+        let proxy = Proxy::new("http://proxy.internal:3128")
+            .unwrap()
+            .no_proxy("localhost, proxy.internal");
+
+        let config = ConnectorConfig {
+            proxy: Some(Rc::new(proxy)),
+            ..Default::default()
+        };
+
+        let pool = super::ConnectionPool::new(connector, config);
+
+        let req = |uri| Connect {
+            uri: Uri::from_static(uri),
+            addr: None,
+        };
+
+        // forwarded requests share connections to the proxy
+        let conn = pool.call(req("http://crates.io")).await.unwrap();
+        assert_eq!(1, generated_clone.get());
+        let ConnectionType::H1(ref h1) = conn else {
+            panic!("expected HTTP/1 connection");
+        };
+        assert!(h1.forward_proxy().is_some());
+        release(conn);
+
+        let conn = pool.call(req("http://google.com")).await.unwrap();
+        assert_eq!(1, generated_clone.get());
+        release(conn);
+
+        // tunnelled connections are keyed on the host
+        let conn = pool.call(req("ws://crates.io")).await.unwrap();
+        assert_eq!(2, generated_clone.get());
+        let ConnectionType::H1(ref h1) = conn else {
+            panic!("expected HTTP/1 connection");
+        };
+        assert!(h1.forward_proxy().is_none());
+        release(conn);
+
+        // as are direct connections, including to the proxy itself
+        let conn = pool.call(req("http://localhost")).await.unwrap();
+        assert_eq!(3, generated_clone.get());
+        release(conn);
+
+        let conn = pool.call(req("http://proxy.internal:3128")).await.unwrap();
+        assert_eq!(4, generated_clone.get());
+        release(conn);
+    }
+
     #[actix_rt::test]
     async fn test_pool_drop() {
         let generated = Rc::new(Cell::new(0));
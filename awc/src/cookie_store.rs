@@ -0,0 +1,891 @@
+//! Persistent cookie storage for clients.
+
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    io,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use actix_http::{
+    header::{self, HeaderMap, HeaderValue},
+    Method, Uri,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::cookie::{time::OffsetDateTime, Cookie, SameSite};
+
+/// Stores cookies received in responses and sends them with later requests.
+///
+/// Cookies are stored and matched to requests following [RFC 6265], respecting their `Domain`,
+/// `Path`, `Expires`, `Max-Age`, and `Secure` attributes. `SameSite=Strict` cookies are not sent
+/// after a redirect to a different site and `SameSite=Lax` cookies are only sent after one when
+/// the redirected request uses a safe method.
+///
+/// Cookies whose `Domain` is a public suffix are only accepted as host-only cookies for that exact
+/// host. The full [public suffix list] is not bundled; instead, a built-in list of common
+/// top-level domains and multi-label suffixes (such as `co.uk`) is used to find registrable
+/// domains. A `Domain` whose registrable domain can not be determined this way is ignored, making
+/// the cookie host-only, and hosts without one are only the same site as themselves.
+///
+/// A store is enabled with [`ClientBuilder::cookie_store`](crate::ClientBuilder::cookie_store).
+/// Clones of a store share the same cookies, so a clone can be kept to inspect or save the
+/// cookies the client has received.
+///
+/// # Examples
+/// ```no_run
+/// use std::fs::File;
+///
+/// use awc::{Client, CookieStore};
+///
+/// # #[actix_rt::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = match File::open("cookies.json") {
+///     Ok(file) => CookieStore::load(file)?,
+///     Err(_) => CookieStore::new(),
+/// };
+///
+/// let client = Client::builder().cookie_store(store.clone()).finish();
+/// client.get("https://www.rust-lang.org").send().await?;
+///
+/// store.save(File::create("cookies.json")?)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [RFC 6265]: https://datatracker.ietf.org/doc/html/rfc6265
+/// [public suffix list]: https://publicsuffix.org
+#[derive(Debug, Clone, Default)]
+pub struct CookieStore {
+    cookies: Rc<RefCell<Vec<StoredCookie>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    host_only: bool,
+    path: String,
+    /// Expiry time as a Unix timestamp. Session cookies have no expiry.
+    expires: Option<i64>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<StoredSameSite>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum StoredSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl CookieStore {
+    /// Constructs an empty cookie store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a cookie store previously written by [`save`](Self::save).
+    ///
+    /// Cookies that have expired since being saved are discarded.
+    pub fn load<R: io::Read>(reader: R) -> Result<Self, serde_json::Error> {
+        let now = unix_now();
+
+        let mut cookies: Vec<StoredCookie> = serde_json::from_reader(reader)?;
+        cookies.retain(|cookie| !cookie.is_expired(now));
+
+        Ok(Self {
+            cookies: Rc::new(RefCell::new(cookies)),
+        })
+    }
+
+    /// Writes the persistent cookies in this store as JSON.
+    ///
+    /// Session cookies, which have neither an `Expires` nor a `Max-Age` attribute, are not
+    /// written.
+    pub fn save<W: io::Write>(&self, writer: W) -> Result<(), serde_json::Error> {
+        let now = unix_now();
+
+        let cookies = self.cookies.borrow();
+        let persistent = cookies
+            .iter()
+            .filter(|cookie| cookie.expires.is_some() && !cookie.is_expired(now))
+            .collect::<Vec<_>>();
+
+        serde_json::to_writer(writer, &persistent)
+    }
+
+    /// Returns the unexpired cookies in this store.
+    pub fn cookies(&self) -> Vec<Cookie<'static>> {
+        let now = unix_now();
+
+        self.cookies
+            .borrow()
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now))
+            .map(StoredCookie::to_cookie)
+            .collect()
+    }
+
+    /// Removes all cookies from this store.
+    pub fn clear(&self) {
+        self.cookies.borrow_mut().clear();
+    }
+
+    /// Stores the cookies set by the headers of a response to a request for `uri`.
+    pub(crate) fn store_response_cookies(&self, uri: &Uri, headers: &HeaderMap) {
+        let Some(host) = uri.host() else {
+            return;
+        };
+
+        let host = host.to_ascii_lowercase();
+        let now = unix_now();
+        let mut cookies = self.cookies.borrow_mut();
+
+        for value in headers.get_all(header::SET_COOKIE) {
+            let Some(cookie) = value
+                .to_str()
+                .ok()
+                .and_then(|value| Cookie::parse(value).ok())
+            else {
+                continue;
+            };
+
+            let Some(cookie) = StoredCookie::new(cookie, uri, &host, now) else {
+                continue;
+            };
+
+            // replace any cookie with the same name, domain, and path; already expired cookies
+            // are only used for removing stored cookies
+            cookies.retain(|stored| {
+                !(stored.name == cookie.name
+                    && stored.domain == cookie.domain
+                    && stored.path == cookie.path)
+            });
+
+            if !cookie.is_expired(now) {
+                cookies.push(cookie);
+            }
+        }
+    }
+
+    /// Returns the `Cookie` header value for a request to `uri`.
+    ///
+    /// `site` is the URI of the request that initiated a redirect chain, if the request is made
+    /// by following a redirect.
+    pub(crate) fn request_cookies(
+        &self,
+        uri: &Uri,
+        method: &Method,
+        site: Option<&Uri>,
+    ) -> Option<String> {
+        let host = uri.host()?.to_ascii_lowercase();
+        let path = uri.path();
+        let secure = is_secure(uri);
+        let cross_site = site.is_some_and(|site| !is_same_site(site, uri));
+        let now = unix_now();
+
+        let mut cookies = self.cookies.borrow_mut();
+        cookies.retain(|cookie| !cookie.is_expired(now));
+
+        let mut matching = cookies
+            .iter()
+            .filter(|cookie| {
+                let domain_matches = if cookie.host_only {
+                    host == cookie.domain
+                } else {
+                    domain_match(&host, &cookie.domain)
+                };
+
+                let same_site_allows = match cookie.same_site {
+                    _ if !cross_site => true,
+                    Some(StoredSameSite::Strict) => false,
+                    Some(StoredSameSite::Lax) => matches!(*method, Method::GET | Method::HEAD),
+                    Some(StoredSameSite::None) | None => true,
+                };
+
+                domain_matches
+                    && path_match(path, &cookie.path)
+                    && (secure || !cookie.secure)
+                    && same_site_allows
+            })
+            .collect::<Vec<_>>();
+
+        if matching.is_empty() {
+            return None;
+        }
+
+        // cookies with longer paths are listed first
+        matching.sort_by_key(|cookie| Reverse(cookie.path.len()));
+
+        Some(
+            matching
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+}
+
+impl StoredCookie {
+    /// Validates a cookie received in a response to a request for `uri`.
+    ///
+    /// See <https://datatracker.ietf.org/doc/html/rfc6265#section-5.3>.
+    fn new(cookie: Cookie<'_>, uri: &Uri, host: &str, now: i64) -> Option<Self> {
+        let secure = cookie.secure().unwrap_or(false);
+
+        // secure cookies can only be set by secure origins
+        if secure && !is_secure(uri) {
+            return None;
+        }
+
+        let domain = cookie
+            .domain()
+            .map(|domain| domain.trim_start_matches('.').to_ascii_lowercase())
+            .filter(|domain| !domain.is_empty());
+
+        let (domain, host_only) = match domain {
+            Some(domain) if !domain_match(host, &domain) => return None,
+
+            // a public suffix is only accepted as the domain of its own host
+            Some(domain) if is_public_suffix(&domain) && domain != host => return None,
+
+            Some(domain) if registrable_domain(&domain).is_some() => (domain, false),
+
+            // fail closed when it is unknown whether the domain is a public suffix by only sending
+            // the cookie back to the host that set it
+            Some(_) | None => (host.to_owned(), true),
+        };
+
+        let path = match cookie.path() {
+            Some(path) if path.starts_with('/') => path.to_owned(),
+            _ => default_path(uri.path()).to_owned(),
+        };
+
+        let name = cookie.name();
+
+        if name.starts_with("__Secure-") && !secure {
+            return None;
+        }
+
+        if name.starts_with("__Host-") && !(secure && host_only && path == "/") {
+            return None;
+        }
+
+        let same_site = match cookie.same_site() {
+            Some(SameSite::Strict) => Some(StoredSameSite::Strict),
+            Some(SameSite::Lax) => Some(StoredSameSite::Lax),
+
+            // cookies without the secure attribute must not be sent cross-site
+            Some(SameSite::None) if !secure => return None,
+            Some(SameSite::None) => Some(StoredSameSite::None),
+
+            None => None,
+        };
+
+        // max-age takes precedence over expires
+        let expires = match (cookie.max_age(), cookie.expires_datetime()) {
+            (Some(max_age), _) => Some(now.saturating_add(max_age.whole_seconds())),
+            (None, Some(expires)) => Some(expires.unix_timestamp()),
+            (None, None) => None,
+        };
+
+        Some(Self {
+            name: name.to_owned(),
+            value: cookie.value().to_owned(),
+            domain,
+            host_only,
+            path,
+            expires,
+            secure,
+            http_only: cookie.http_only().unwrap_or(false),
+            same_site,
+        })
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn to_cookie(&self) -> Cookie<'static> {
+        let mut cookie = Cookie::new(self.name.clone(), self.value.clone());
+
+        if !self.host_only {
+            cookie.set_domain(self.domain.clone());
+        }
+
+        cookie.set_path(self.path.clone());
+        cookie.set_secure(self.secure);
+        cookie.set_http_only(self.http_only);
+
+        if let Some(same_site) = self.same_site {
+            cookie.set_same_site(match same_site {
+                StoredSameSite::Strict => SameSite::Strict,
+                StoredSameSite::Lax => SameSite::Lax,
+                StoredSameSite::None => SameSite::None,
+            });
+        }
+
+        if let Some(expires) = self
+            .expires
+            .and_then(|expires| OffsetDateTime::from_unix_timestamp(expires).ok())
+        {
+            cookie.set_expires(expires);
+        }
+
+        cookie
+    }
+}
+
+/// Adds cookies from `store` for a request to `uri` to the request's headers.
+///
+/// Cookies already present in the `Cookie` header take precedence over stored cookies with the
+/// same name.
+pub(crate) fn set_request_cookies(
+    store: &CookieStore,
+    headers: &mut HeaderMap,
+    uri: &Uri,
+    method: &Method,
+    site: Option<&Uri>,
+) {
+    let Some(stored) = store.request_cookies(uri, method, site) else {
+        return;
+    };
+
+    let existing = headers
+        .get(header::COOKIE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    let existing_names = existing
+        .split(';')
+        .filter_map(|pair| pair.split_once('=').map(|(name, _)| name.trim()))
+        .collect::<Vec<_>>();
+
+    let cookies = existing
+        .split(';')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .chain(stored.split("; ").filter(|pair| {
+            let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+            !existing_names.contains(&name)
+        }))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    if let Ok(value) = HeaderValue::from_str(&cookies) {
+        headers.insert(header::COOKIE, value);
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |dur| dur.as_secs() as i64)
+}
+
+fn is_secure(uri: &Uri) -> bool {
+    matches!(uri.scheme_str(), Some("https") | Some("wss"))
+}
+
+/// Top-level domains whose names are all registrable, apart from the shared hosting domains in
+/// [`PUBLIC_SUFFIXES`].
+///
+/// Registrable domains are not known under other top-level domains, many of which have
+/// second-level registries that are not part of [`PUBLIC_SUFFIXES`].
+const KNOWN_TLDS: &[&str] = &["app", "com", "dev", "edu", "gov", "mil", "net", "org", "uk"];
+
+/// Multi-label public suffixes under which anyone can register a name.
+///
+/// This is a small, conservative subset of the [public suffix list](https://publicsuffix.org)
+/// covering common country-code registries and shared hosting domains.
+const PUBLIC_SUFFIXES: &[&str] = &[
+    // country-code second-level registries
+    "ac.jp",
+    "ac.kr",
+    "ac.nz",
+    "ac.uk",
+    "ad.jp",
+    "asn.au",
+    "co.id",
+    "co.il",
+    "co.in",
+    "co.jp",
+    "co.kr",
+    "co.nz",
+    "co.uk",
+    "co.za",
+    "com.ar",
+    "com.au",
+    "com.br",
+    "com.cn",
+    "com.co",
+    "com.eg",
+    "com.hk",
+    "com.mx",
+    "com.my",
+    "com.pe",
+    "com.ph",
+    "com.pk",
+    "com.sa",
+    "com.sg",
+    "com.tr",
+    "com.tw",
+    "com.ua",
+    "com.vn",
+    "ed.jp",
+    "edu.au",
+    "edu.cn",
+    "go.jp",
+    "go.kr",
+    "gov.au",
+    "gov.br",
+    "gov.cn",
+    "gov.in",
+    "gov.uk",
+    "govt.nz",
+    "gr.jp",
+    "id.au",
+    "lg.jp",
+    "ltd.uk",
+    "me.uk",
+    "ne.jp",
+    "ne.kr",
+    "net.au",
+    "net.br",
+    "net.cn",
+    "net.in",
+    "net.nz",
+    "net.uk",
+    "nhs.uk",
+    "or.id",
+    "or.jp",
+    "or.kr",
+    "org.au",
+    "org.br",
+    "org.cn",
+    "org.il",
+    "org.in",
+    "org.nz",
+    "org.uk",
+    "org.za",
+    "plc.uk",
+    "police.uk",
+    "sch.uk",
+    // shared hosting
+    "appspot.com",
+    "azurewebsites.net",
+    "blogspot.com",
+    "cloudfront.net",
+    "firebaseapp.com",
+    "github.io",
+    "gitlab.io",
+    "herokuapp.com",
+    "netlify.app",
+    "pages.dev",
+    "vercel.app",
+    "web.app",
+    "workers.dev",
+];
+
+/// Returns true if `domain` is one of the [`KNOWN_TLDS`] or [`PUBLIC_SUFFIXES`].
+fn is_public_suffix(domain: &str) -> bool {
+    KNOWN_TLDS.contains(&domain) || PUBLIC_SUFFIXES.contains(&domain)
+}
+
+/// Returns the registrable domain of `host`, its longest public suffix plus one more label.
+///
+/// Returns `None` if `host` is itself a public suffix or if its public suffix is not known.
+fn registrable_domain(host: &str) -> Option<&str> {
+    let mut site = None;
+    let mut rest = host;
+
+    loop {
+        if is_public_suffix(rest) {
+            return site;
+        }
+
+        site = Some(rest);
+        rest = rest.split_once('.')?.1;
+    }
+}
+
+/// Approximates whether two URIs belong to the same site by comparing their schemes and the
+/// registrable domains of their hosts.
+///
+/// Hosts without a known registrable domain, including IP addresses, are only the same site as
+/// themselves.
+fn is_same_site(a: &Uri, b: &Uri) -> bool {
+    fn site(host: &str) -> String {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+
+        match registrable_domain(&host) {
+            Some(site) => site.to_owned(),
+            None => host,
+        }
+    }
+
+    is_secure(a) == is_secure(b)
+        && match (a.host(), b.host()) {
+            (Some(a), Some(b)) => site(a) == site(b),
+            _ => false,
+        }
+}
+
+/// See <https://datatracker.ietf.org/doc/html/rfc6265#section-5.1.3>.
+fn domain_match(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+
+    let is_ip = host.starts_with('[') || host.parse::<std::net::Ipv4Addr>().is_ok();
+
+    !is_ip
+        && host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// See <https://datatracker.ietf.org/doc/html/rfc6265#section-5.1.4>.
+fn default_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(idx) => &path[..idx],
+    }
+}
+
+/// See <https://datatracker.ietf.org/doc/html/rfc6265#section-5.1.4>.
+fn path_match(path: &str, cookie_path: &str) -> bool {
+    match path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_cookies(store: &CookieStore, uri: &str, cookies: &[&str]) {
+        let mut headers = HeaderMap::new();
+
+        for cookie in cookies {
+            headers.append(header::SET_COOKIE, HeaderValue::from_str(cookie).unwrap());
+        }
+
+        store.store_response_cookies(&uri.parse().unwrap(), &headers);
+    }
+
+    fn get_cookies(store: &CookieStore, uri: &str) -> Option<String> {
+        store.request_cookies(&uri.parse().unwrap(), &Method::GET, None)
+    }
+
+    #[test]
+    fn domain_and_host_only() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "http://www.example.com/",
+            &[
+                "host=1",
+                "domain=2; Domain=.Example.com",
+                "other=3; Domain=example.org",
+                "sub=4; Domain=api.www.example.com",
+            ],
+        );
+
+        assert_eq!(
+            get_cookies(&store, "http://www.example.com/").as_deref(),
+            Some("host=1; domain=2")
+        );
+        assert_eq!(
+            get_cookies(&store, "http://api.example.com/").as_deref(),
+            Some("domain=2")
+        );
+        assert_eq!(
+            get_cookies(&store, "http://example.com/").as_deref(),
+            Some("domain=2")
+        );
+        assert_eq!(get_cookies(&store, "http://badexample.com/"), None);
+        assert_eq!(get_cookies(&store, "http://example.org/"), None);
+    }
+
+    #[test]
+    fn public_suffix_domain() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "http://www.example.co.uk/",
+            &[
+                "tld=1; Domain=uk",
+                "suffix=2; Domain=co.uk",
+                "site=3; Domain=example.co.uk",
+            ],
+        );
+        set_cookies(
+            &store,
+            "http://user.github.io/",
+            &["hosting=4; Domain=github.io"],
+        );
+        set_cookies(&store, "http://localhost/", &["local=5; Domain=localhost"]);
+        set_cookies(
+            &store,
+            "http://www.example.tokyo.jp/",
+            &["unknown=6; Domain=example.tokyo.jp"],
+        );
+
+        assert_eq!(
+            get_cookies(&store, "http://www.example.co.uk/").as_deref(),
+            Some("site=3")
+        );
+        assert_eq!(get_cookies(&store, "http://other.co.uk/"), None);
+        assert_eq!(get_cookies(&store, "http://other.github.io/"), None);
+
+        // a public suffix is kept as a host-only cookie when it is the request's host
+        assert_eq!(
+            get_cookies(&store, "http://localhost/").as_deref(),
+            Some("local=5")
+        );
+        assert_eq!(get_cookies(&store, "http://sub.localhost/"), None);
+
+        // a domain with an unknown public suffix is ignored, making the cookie host-only
+        assert_eq!(
+            get_cookies(&store, "http://www.example.tokyo.jp/").as_deref(),
+            Some("unknown=6")
+        );
+        assert_eq!(get_cookies(&store, "http://other.example.tokyo.jp/"), None);
+    }
+
+    #[test]
+    fn path() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "http://example.com/docs/page",
+            &["default=1", "root=2; Path=/", "deep=3; Path=/docs/api"],
+        );
+
+        assert_eq!(
+            get_cookies(&store, "http://example.com/docs/api/v1").as_deref(),
+            Some("deep=3; default=1; root=2")
+        );
+        assert_eq!(
+            get_cookies(&store, "http://example.com/docs").as_deref(),
+            Some("default=1; root=2")
+        );
+        assert_eq!(
+            get_cookies(&store, "http://example.com/docsearch").as_deref(),
+            Some("root=2")
+        );
+
+        assert_eq!(default_path(""), "/");
+        assert_eq!(default_path("/"), "/");
+        assert_eq!(default_path("/docs"), "/");
+        assert_eq!(default_path("/docs/page"), "/docs");
+    }
+
+    #[test]
+    fn expiry_and_replacement() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "http://example.com/",
+            &[
+                "session=1",
+                "persistent=2; Max-Age=3600",
+                "expired=3; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+                "future=4; Expires=Fri, 01 Jan 2100 00:00:00 GMT",
+            ],
+        );
+
+        assert_eq!(
+            get_cookies(&store, "http://example.com/").as_deref(),
+            Some("session=1; persistent=2; future=4")
+        );
+
+        // max-age takes precedence over expires and removes existing cookies
+        set_cookies(
+            &store,
+            "http://example.com/",
+            &[
+                "session=updated",
+                "persistent=; Max-Age=0; Expires=Fri, 01 Jan 2100 00:00:00 GMT",
+            ],
+        );
+
+        assert_eq!(
+            get_cookies(&store, "http://example.com/").as_deref(),
+            Some("future=4; session=updated")
+        );
+    }
+
+    #[test]
+    fn secure() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "http://example.com/",
+            &["insecure=1; Secure", "__Secure-a=1", "__Host-b=1; Secure"],
+        );
+        assert!(store.cookies().is_empty());
+
+        set_cookies(
+            &store,
+            "https://example.com/",
+            &[
+                "secure=1; Secure",
+                "__Secure-a=2; Secure; Domain=example.com",
+                "__Host-b=3; Secure; Path=/",
+                "__Host-c=4; Secure; Path=/; Domain=example.com",
+            ],
+        );
+
+        assert_eq!(
+            get_cookies(&store, "https://example.com/").as_deref(),
+            Some("secure=1; __Secure-a=2; __Host-b=3")
+        );
+        assert_eq!(get_cookies(&store, "http://example.com/"), None);
+        assert_eq!(
+            get_cookies(&store, "wss://example.com/").as_deref(),
+            Some("secure=1; __Secure-a=2; __Host-b=3")
+        );
+    }
+
+    #[test]
+    fn same_site() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "https://example.com/",
+            &[
+                "strict=1; SameSite=Strict",
+                "lax=2; SameSite=Lax",
+                "none=3; SameSite=None; Secure",
+                "insecure=4; SameSite=None",
+                "default=5",
+            ],
+        );
+
+        let uri = Uri::from_static("https://example.com/");
+        let same_site = Uri::from_static("https://login.example.com/");
+        let cross_site = Uri::from_static("https://example.org/");
+
+        assert_eq!(
+            store.request_cookies(&uri, &Method::GET, None).as_deref(),
+            Some("strict=1; lax=2; none=3; default=5")
+        );
+        assert_eq!(
+            store
+                .request_cookies(&uri, &Method::POST, Some(&same_site))
+                .as_deref(),
+            Some("strict=1; lax=2; none=3; default=5")
+        );
+        assert_eq!(
+            store
+                .request_cookies(&uri, &Method::GET, Some(&cross_site))
+                .as_deref(),
+            Some("lax=2; none=3; default=5")
+        );
+        assert_eq!(
+            store
+                .request_cookies(&uri, &Method::POST, Some(&cross_site))
+                .as_deref(),
+            Some("none=3; default=5")
+        );
+
+        assert!(is_same_site(
+            &Uri::from_static("https://a.example.co.uk/"),
+            &Uri::from_static("https://b.example.co.uk/"),
+        ));
+        assert!(!is_same_site(
+            &Uri::from_static("https://a.co.uk/"),
+            &Uri::from_static("https://b.co.uk/"),
+        ));
+        assert!(!is_same_site(
+            &Uri::from_static("https://a.github.io/"),
+            &Uri::from_static("https://b.github.io/"),
+        ));
+        assert!(!is_same_site(
+            &Uri::from_static("https://a.example.tokyo.jp/"),
+            &Uri::from_static("https://b.example.tokyo.jp/"),
+        ));
+        assert!(is_same_site(
+            &Uri::from_static("https://a.example.tokyo.jp/"),
+            &Uri::from_static("https://a.example.tokyo.jp/login"),
+        ));
+    }
+
+    #[test]
+    fn existing_cookie_header() {
+        let store = CookieStore::new();
+        set_cookies(&store, "http://example.com/", &["a=stored", "b=stored"]);
+
+        let uri = Uri::from_static("http://example.com/");
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("b=explicit; c=3"));
+
+        set_request_cookies(&store, &mut headers, &uri, &Method::GET, None);
+        assert_eq!(
+            headers.get(header::COOKIE).unwrap(),
+            "b=explicit; c=3; a=stored"
+        );
+
+        let mut headers = HeaderMap::new();
+        set_request_cookies(&store, &mut headers, &uri, &Method::GET, None);
+        assert_eq!(headers.get(header::COOKIE).unwrap(), "a=stored; b=stored");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let store = CookieStore::new();
+
+        set_cookies(
+            &store,
+            "https://www.example.com/docs/",
+            &[
+                "session=1",
+                "persistent=2; Max-Age=3600; Domain=example.com; Secure; HttpOnly; SameSite=Lax",
+            ],
+        );
+
+        let mut buf = Vec::new();
+        store.save(&mut buf).unwrap();
+
+        let loaded = CookieStore::load(buf.as_slice()).unwrap();
+        let cookies = loaded.cookies();
+        assert_eq!(cookies.len(), 1);
+
+        let cookie = &cookies[0];
+        assert_eq!(cookie.name(), "persistent");
+        assert_eq!(cookie.value(), "2");
+        assert_eq!(cookie.domain(), Some("example.com"));
+        assert_eq!(cookie.path(), Some("/docs"));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert!(cookie.expires_datetime().is_some());
+
+        assert_eq!(
+            get_cookies(&loaded, "https://api.example.com/docs/page").as_deref(),
+            Some("persistent=2")
+        );
+
+        // expired cookies are discarded on load
+        let json = r#"[{"name":"old","value":"1","domain":"example.com","host_only":true,
+            "path":"/","expires":0,"secure":false,"http_only":false,"same_site":null}]"#;
+        assert!(CookieStore::load(json.as_bytes())
+            .unwrap()
+            .cookies()
+            .is_empty());
+    }
+}
@@ -79,3 +79,22 @@ pub enum JsonPayloadError {
 }
 
 impl std::error::Error for JsonPayloadError {}
+
+/// A set of errors that can occur while parsing Server-Sent Events streams
+#[derive(Debug, Display, From)]
+#[non_exhaustive]
+pub enum SsePayloadError {
+    /// Content type error
+    #[display("Content type error")]
+    ContentType,
+
+    /// Line or event is larger than the stream's limit
+    #[display("Line or event is larger than the stream's limit")]
+    Overflow,
+
+    /// Payload error
+    #[display("Error that occur during reading payload: {}", _0)]
+    Payload(PayloadError),
+}
+
+impl std::error::Error for SsePayloadError {}
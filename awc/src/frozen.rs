@@ -3,7 +3,7 @@ use std::{net, rc::Rc, time::Duration};
 use actix_http::{
     body::MessageBody,
     error::HttpError,
-    header::{HeaderMap, TryIntoHeaderPair},
+    header::{ContentEncoding, HeaderMap, TryIntoHeaderPair},
     Method, RequestHead, Uri,
 };
 use bytes::Bytes;
@@ -12,6 +12,7 @@ use serde::Serialize;
 
 use crate::{
     client::ClientConfig,
+    multipart::Form,
     sender::{RequestSender, SendClientRequest},
     BoxError,
 };
@@ -24,6 +25,7 @@ pub struct FrozenClientRequest {
     pub(crate) head: Rc<RequestHead>,
     pub(crate) addr: Option<net::SocketAddr>,
     pub(crate) response_decompress: bool,
+    pub(crate) compress: Option<ContentEncoding>,
     pub(crate) timeout: Option<Duration>,
     pub(crate) config: ClientConfig,
 }
@@ -52,6 +54,7 @@ impl FrozenClientRequest {
         RequestSender::Rc(Rc::clone(&self.head), None).send_body(
             self.addr,
             self.response_decompress,
+            self.compress,
             self.timeout,
             &self.config,
             body,
@@ -63,6 +66,7 @@ impl FrozenClientRequest {
         RequestSender::Rc(Rc::clone(&self.head), None).send_json(
             self.addr,
             self.response_decompress,
+            self.compress,
             self.timeout,
             &self.config,
             value,
@@ -74,12 +78,25 @@ impl FrozenClientRequest {
         RequestSender::Rc(Rc::clone(&self.head), None).send_form(
             self.addr,
             self.response_decompress,
+            self.compress,
             self.timeout,
             &self.config,
             value,
         )
     }
 
+    /// Send a `multipart/form-data` body.
+    pub fn send_multipart(&self, form: Form) -> SendClientRequest {
+        RequestSender::Rc(Rc::clone(&self.head), None).send_multipart(
+            self.addr,
+            self.response_decompress,
+            self.compress,
+            self.timeout,
+            &self.config,
+            form,
+        )
+    }
+
     /// Send a streaming body.
     pub fn send_stream<S, E>(&self, stream: S) -> SendClientRequest
     where
@@ -89,6 +106,7 @@ impl FrozenClientRequest {
         RequestSender::Rc(Rc::clone(&self.head), None).send_stream(
             self.addr,
             self.response_decompress,
+            self.compress,
             self.timeout,
             &self.config,
             stream,
@@ -100,6 +118,7 @@ impl FrozenClientRequest {
         RequestSender::Rc(Rc::clone(&self.head), None).send(
             self.addr,
             self.response_decompress,
+            self.compress,
             self.timeout,
             &self.config,
         )
@@ -154,6 +173,7 @@ impl FrozenSendBuilder {
         RequestSender::Rc(self.req.head, Some(self.extra_headers)).send_body(
             self.req.addr,
             self.req.response_decompress,
+            self.req.compress,
             self.req.timeout,
             &self.req.config,
             body,
@@ -169,6 +189,7 @@ impl FrozenSendBuilder {
         RequestSender::Rc(self.req.head, Some(self.extra_headers)).send_json(
             self.req.addr,
             self.req.response_decompress,
+            self.req.compress,
             self.req.timeout,
             &self.req.config,
             value,
@@ -184,12 +205,29 @@ impl FrozenSendBuilder {
         RequestSender::Rc(self.req.head, Some(self.extra_headers)).send_form(
             self.req.addr,
             self.req.response_decompress,
+            self.req.compress,
             self.req.timeout,
             &self.req.config,
             value,
         )
     }
 
+    /// Complete request construction and send a `multipart/form-data` body.
+    pub fn send_multipart(self, form: Form) -> SendClientRequest {
+        if let Some(err) = self.err {
+            return err.into();
+        }
+
+        RequestSender::Rc(self.req.head, Some(self.extra_headers)).send_multipart(
+            self.req.addr,
+            self.req.response_decompress,
+            self.req.compress,
+            self.req.timeout,
+            &self.req.config,
+            form,
+        )
+    }
+
     /// Complete request construction and send a streaming body.
     pub fn send_stream<S, E>(self, stream: S) -> SendClientRequest
     where
@@ -203,6 +241,7 @@ impl FrozenSendBuilder {
         RequestSender::Rc(self.req.head, Some(self.extra_headers)).send_stream(
             self.req.addr,
             self.req.response_decompress,
+            self.req.compress,
             self.req.timeout,
             &self.req.config,
             stream,
@@ -218,6 +257,7 @@ impl FrozenSendBuilder {
         RequestSender::Rc(self.req.head, Some(self.extra_headers)).send(
             self.req.addr,
             self.req.response_decompress,
+            self.req.compress,
             self.req.timeout,
             &self.req.config,
         )
@@ -118,9 +118,12 @@ mod any_body;
 mod builder;
 mod client;
 mod connect;
+#[cfg(feature = "cookies")]
+mod cookie_store;
 pub mod error;
 mod frozen;
 pub mod middleware;
+pub mod multipart;
 mod request;
 mod responses;
 mod sender;
@@ -134,11 +137,15 @@ pub mod http {
     pub use actix_http::{header, uri, ConnectionType, Error, Method, StatusCode, Uri, Version};
 }
 
+#[cfg(feature = "cookies")]
+pub use self::cookie_store::CookieStore;
 #[allow(deprecated)]
-pub use self::responses::{ClientResponse, JsonBody, MessageBody, ResponseBody};
+pub use self::responses::{
+    ClientResponse, JsonBody, MessageBody, ResponseBody, SseEvent, SseStream,
+};
 pub use self::{
     builder::ClientBuilder,
-    client::{Client, Connect, Connector},
+    client::{Client, Connect, Connector, Proxy},
     connect::{BoxConnectorService, BoxedSocket, ConnectRequest, ConnectResponse},
     frozen::{FrozenClientRequest, FrozenSendBuilder},
     request::ClientRequest,
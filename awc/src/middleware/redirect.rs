@@ -1,5 +1,6 @@
 use std::{
     future::Future,
+    mem,
     net::SocketAddr,
     pin::Pin,
     rc::Rc,
@@ -12,6 +13,8 @@ use bytes::Bytes;
 use futures_core::ready;
 
 use super::Transform;
+#[cfg(feature = "cookies")]
+use crate::cookie_store::{self, CookieStore};
 use crate::{
     any_body::AnyBody,
     client::{InvalidUrl, SendRequestError},
@@ -21,6 +24,8 @@ use crate::{
 
 pub struct Redirect {
     max_redirect_times: u8,
+    #[cfg(feature = "cookies")]
+    cookie_store: Option<CookieStore>,
 }
 
 impl Default for Redirect {
@@ -33,6 +38,8 @@ impl Redirect {
     pub fn new() -> Self {
         Self {
             max_redirect_times: 10,
+            #[cfg(feature = "cookies")]
+            cookie_store: None,
         }
     }
 
@@ -40,6 +47,13 @@ impl Redirect {
         self.max_redirect_times = times;
         self
     }
+
+    /// Sends and stores cookies using `store` for every request made, including redirects.
+    #[cfg(feature = "cookies")]
+    pub(crate) fn cookie_store(mut self, store: CookieStore) -> Self {
+        self.cookie_store = Some(store);
+        self
+    }
 }
 
 impl<S> Transform<S, ConnectRequest> for Redirect
@@ -51,6 +65,8 @@ where
     fn new_transform(self, service: S) -> Self::Transform {
         RedirectService {
             max_redirect_times: self.max_redirect_times,
+            #[cfg(feature = "cookies")]
+            cookie_store: self.cookie_store,
             connector: Rc::new(service),
         }
     }
@@ -58,6 +74,8 @@ where
 
 pub struct RedirectService<S> {
     max_redirect_times: u8,
+    #[cfg(feature = "cookies")]
+    cookie_store: Option<CookieStore>,
     connector: Rc<S>,
 }
 
@@ -73,11 +91,33 @@ where
 
     fn call(&self, req: ConnectRequest) -> Self::Future {
         match req {
-            ConnectRequest::Tunnel(head, addr) => {
+            #[cfg_attr(not(feature = "cookies"), allow(unused_mut))]
+            ConnectRequest::Tunnel(mut head, addr) => {
+                let cookies = RedirectCookies {
+                    #[cfg(feature = "cookies")]
+                    chain: self
+                        .cookie_store
+                        .clone()
+                        .map(|store| (store, head.uri.clone())),
+                };
+
+                #[cfg(feature = "cookies")]
+                if let Some((ref store, ref uri)) = cookies.chain {
+                    cookie_store::set_request_cookies(
+                        store,
+                        &mut head.headers,
+                        uri,
+                        &head.method,
+                        None,
+                    );
+                }
+
                 let fut = self.connector.call(ConnectRequest::Tunnel(head, addr));
-                RedirectServiceFuture::Tunnel { fut }
+
+                RedirectServiceFuture::Tunnel { fut, cookies }
             }
-            ConnectRequest::Client(head, body, addr) => {
+            #[cfg_attr(not(feature = "cookies"), allow(unused_mut))]
+            ConnectRequest::Client(mut head, body, addr) => {
                 let connector = Rc::clone(&self.connector);
                 let max_redirect_times = self.max_redirect_times;
 
@@ -86,11 +126,65 @@ where
                     RequestHeadType::Owned(ref head) => {
                         (head.uri.clone(), head.method.clone(), head.headers.clone())
                     }
-                    RequestHeadType::Rc(ref head, ..) => {
-                        (head.uri.clone(), head.method.clone(), head.headers.clone())
+                    RequestHeadType::Rc(ref head, ref extra_headers) => {
+                        let mut headers = head.headers.clone();
+
+                        // extra headers replace the head's headers with the same name
+                        if let Some(extra_headers) = extra_headers {
+                            for name in extra_headers.keys() {
+                                headers.remove(name);
+                            }
+
+                            for (name, value) in extra_headers.iter() {
+                                headers.append(name.clone(), value.clone());
+                            }
+                        }
+
+                        (head.uri.clone(), head.method.clone(), headers)
                     }
                 };
 
+                let cookies = RedirectCookies {
+                    #[cfg(feature = "cookies")]
+                    chain: self.cookie_store.clone().map(|store| (store, uri.clone())),
+                };
+
+                // cookies from the store are added after the backup so each redirect can pick
+                // the cookies that apply to its own uri
+                #[cfg(feature = "cookies")]
+                if let Some((ref store, _)) = cookies.chain {
+                    match head {
+                        RequestHeadType::Owned(ref mut head) => {
+                            cookie_store::set_request_cookies(
+                                store,
+                                &mut head.headers,
+                                &uri,
+                                &method,
+                                None,
+                            );
+                        }
+                        RequestHeadType::Rc(ref head, ref mut extra_headers) => {
+                            let extra_headers =
+                                extra_headers.get_or_insert_with(header::HeaderMap::new);
+
+                            // extra headers replace the head's headers so merge with its cookies
+                            if !extra_headers.contains_key(header::COOKIE) {
+                                if let Some(cookie) = head.headers.get(header::COOKIE) {
+                                    extra_headers.insert(header::COOKIE, cookie.clone());
+                                }
+                            }
+
+                            cookie_store::set_request_cookies(
+                                store,
+                                extra_headers,
+                                &uri,
+                                &method,
+                                None,
+                            );
+                        }
+                    }
+                }
+
                 let body_opt = match body {
                     AnyBody::Bytes { ref body } => Some(body.clone()),
                     _ => None,
@@ -107,6 +201,7 @@ where
                     body: body_opt,
                     addr,
                     connector: Some(connector),
+                    cookies,
                 }
             }
         }
@@ -120,7 +215,11 @@ pin_project_lite::pin_project! {
         S: Service<ConnectRequest, Response = ConnectResponse, Error = SendRequestError>,
         S: 'static
     {
-        Tunnel { #[pin] fut: S::Future },
+        Tunnel {
+            #[pin]
+            fut: S::Future,
+            cookies: RedirectCookies,
+        },
         Client {
             #[pin]
             fut: S::Future,
@@ -131,6 +230,7 @@ pin_project_lite::pin_project! {
             body: Option<Bytes>,
             addr: Option<SocketAddr>,
             connector: Option<Rc<S>>,
+            cookies: RedirectCookies,
         }
     }
 }
@@ -143,7 +243,19 @@ where
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match self.as_mut().project() {
-            RedirectServiceProj::Tunnel { fut } => fut.poll(cx),
+            #[cfg_attr(not(feature = "cookies"), allow(unused_variables))]
+            RedirectServiceProj::Tunnel { fut, cookies } => {
+                let res = ready!(fut.poll(cx))?;
+
+                #[cfg(feature = "cookies")]
+                if let (Some((store, uri)), ConnectResponse::Tunnel(head, _)) =
+                    (&cookies.chain, &res)
+                {
+                    store.store_response_cookies(uri, &head.headers);
+                }
+
+                Poll::Ready(Ok(res))
+            }
             RedirectServiceProj::Client {
                 fut,
                 max_redirect_times,
@@ -153,91 +265,119 @@ where
                 body,
                 addr,
                 connector,
+                cookies,
             } => match ready!(fut.poll(cx))? {
-                ConnectResponse::Client(res) => match res.head().status {
-                    StatusCode::MOVED_PERMANENTLY
-                    | StatusCode::FOUND
-                    | StatusCode::SEE_OTHER
-                    | StatusCode::TEMPORARY_REDIRECT
-                    | StatusCode::PERMANENT_REDIRECT
-                        if *max_redirect_times > 0
-                            && res.headers().contains_key(header::LOCATION) =>
-                    {
-                        let reuse_body = res.head().status == StatusCode::TEMPORARY_REDIRECT
-                            || res.head().status == StatusCode::PERMANENT_REDIRECT;
-
-                        let prev_uri = uri.take().unwrap();
-
-                        // rebuild uri from the location header value.
-                        let next_uri = build_next_uri(&res, &prev_uri)?;
-
-                        // take ownership of states that could be reused
-                        let addr = addr.take();
-                        let connector = connector.take();
-
-                        // reset method
-                        let method = if reuse_body {
-                            method.take().unwrap()
-                        } else {
-                            let method = method.take().unwrap();
-                            match method {
-                                Method::GET | Method::HEAD => method,
-                                _ => Method::GET,
+                ConnectResponse::Client(res) => {
+                    #[cfg(feature = "cookies")]
+                    if let Some((ref store, _)) = cookies.chain {
+                        store.store_response_cookies(uri.as_ref().unwrap(), res.headers());
+                    }
+
+                    match res.head().status {
+                        StatusCode::MOVED_PERMANENTLY
+                        | StatusCode::FOUND
+                        | StatusCode::SEE_OTHER
+                        | StatusCode::TEMPORARY_REDIRECT
+                        | StatusCode::PERMANENT_REDIRECT
+                            if *max_redirect_times > 0
+                                && res.headers().contains_key(header::LOCATION) =>
+                        {
+                            let reuse_body = res.head().status == StatusCode::TEMPORARY_REDIRECT
+                                || res.head().status == StatusCode::PERMANENT_REDIRECT;
+
+                            let prev_uri = uri.take().unwrap();
+
+                            // rebuild uri from the location header value.
+                            let next_uri = build_next_uri(&res, &prev_uri)?;
+
+                            // take ownership of states that could be reused
+                            let addr = addr.take();
+                            let connector = connector.take();
+
+                            // reset method
+                            let method = if reuse_body {
+                                method.take().unwrap()
+                            } else {
+                                let method = method.take().unwrap();
+                                match method {
+                                    Method::GET | Method::HEAD => method,
+                                    _ => Method::GET,
+                                }
+                            };
+
+                            let mut body = body.take();
+                            let body_new = if reuse_body {
+                                // try to reuse saved body
+                                match body {
+                                    Some(ref bytes) => AnyBody::Bytes {
+                                        body: bytes.clone(),
+                                    },
+
+                                    // body was a non-reusable type so send an empty body instead
+                                    _ => AnyBody::empty(),
+                                }
+                            } else {
+                                body = None;
+                                // remove body since we're downgrading to a GET
+                                AnyBody::None
+                            };
+
+                            let mut headers = headers.take().unwrap();
+
+                            // the encoding of a body that is not sent again no longer applies
+                            if body.is_none() {
+                                headers.remove(header::CONTENT_ENCODING);
                             }
-                        };
-
-                        let mut body = body.take();
-                        let body_new = if reuse_body {
-                            // try to reuse saved body
-                            match body {
-                                Some(ref bytes) => AnyBody::Bytes {
-                                    body: bytes.clone(),
-                                },
-
-                                // body was a non-reusable type so send an empty body instead
-                                _ => AnyBody::empty(),
+
+                            remove_sensitive_headers(&mut headers, &prev_uri, &next_uri);
+
+                            let cookies = mem::take(cookies);
+
+                            // use a new request head.
+                            let mut head = RequestHead::default();
+                            head.uri = next_uri.clone();
+                            head.method = method.clone();
+                            head.headers = headers.clone();
+
+                            // the redirect chain is judged by the site of the first request
+                            #[cfg(feature = "cookies")]
+                            if let Some((ref store, ref site)) = cookies.chain {
+                                cookie_store::set_request_cookies(
+                                    store,
+                                    &mut head.headers,
+                                    &next_uri,
+                                    &method,
+                                    Some(site),
+                                );
                             }
-                        } else {
-                            body = None;
-                            // remove body since we're downgrading to a GET
-                            AnyBody::None
-                        };
-
-                        let mut headers = headers.take().unwrap();
-
-                        remove_sensitive_headers(&mut headers, &prev_uri, &next_uri);
-
-                        // use a new request head.
-                        let mut head = RequestHead::default();
-                        head.uri = next_uri.clone();
-                        head.method = method.clone();
-                        head.headers = headers.clone();
-
-                        let head = RequestHeadType::Owned(head);
-
-                        let mut max_redirect_times = *max_redirect_times;
-                        max_redirect_times -= 1;
-
-                        let fut = connector
-                            .as_ref()
-                            .unwrap()
-                            .call(ConnectRequest::Client(head, body_new, addr));
-
-                        self.set(RedirectServiceFuture::Client {
-                            fut,
-                            max_redirect_times,
-                            uri: Some(next_uri),
-                            method: Some(method),
-                            headers: Some(headers),
-                            body,
-                            addr,
-                            connector,
-                        });
-
-                        self.poll(cx)
+
+                            let head = RequestHeadType::Owned(head);
+
+                            let mut max_redirect_times = *max_redirect_times;
+                            max_redirect_times -= 1;
+
+                            let fut = connector
+                                .as_ref()
+                                .unwrap()
+                                .call(ConnectRequest::Client(head, body_new, addr));
+
+                            self.set(RedirectServiceFuture::Client {
+                                fut,
+                                max_redirect_times,
+                                uri: Some(next_uri),
+                                method: Some(method),
+                                headers: Some(headers),
+                                body,
+                                addr,
+                                connector,
+                                cookies,
+                            });
+
+                            self.poll(cx)
+                        }
+                        _ => Poll::Ready(Ok(ConnectResponse::Client(res))),
                     }
-                    _ => Poll::Ready(Ok(ConnectResponse::Client(res))),
-                },
+                }
                 _ => unreachable!("ConnectRequest::Tunnel is not handled by Redirect"),
             },
         }
@@ -285,6 +425,17 @@ fn build_next_uri(res: &ClientResponse, prev_uri: &Uri) -> Result<Uri, SendReque
     Ok(uri)
 }
 
+/// Cookie state carried through a redirect chain.
+///
+/// Kept in its own type because `pin_project_lite` does not support `cfg` on fields.
+#[derive(Default)]
+pub struct RedirectCookies {
+    /// Cookie store paired with the URI of the first request in the chain, which `SameSite`
+    /// rules are judged against.
+    #[cfg(feature = "cookies")]
+    chain: Option<(CookieStore, Uri)>,
+}
+
 fn remove_sensitive_headers(headers: &mut header::HeaderMap, prev_uri: &Uri, next_uri: &Uri) {
     if next_uri.host() != prev_uri.host()
         || next_uri.port() != prev_uri.port()
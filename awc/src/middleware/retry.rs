@@ -0,0 +1,540 @@
+use std::{
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use actix_http::{
+    header::{HttpDate, RETRY_AFTER},
+    Method, RequestHeadType, StatusCode,
+};
+use actix_rt::time::sleep;
+use actix_service::Service;
+use futures_core::future::LocalBoxFuture;
+
+use super::Transform;
+use crate::{
+    any_body::AnyBody,
+    client::SendRequestError,
+    connect::{ConnectRequest, ConnectResponse},
+    ClientResponse,
+};
+
+/// Middleware that retries failed requests with exponential backoff.
+///
+/// A request is retried when connecting to the server fails or when the response has one of the
+/// configured status codes (`429`, `502`, `503`, and `504` by default). Retries are delayed using
+/// exponential backoff with jitter, or by the duration given in the response's `Retry-After`
+/// header.
+///
+/// Only requests using idempotent methods (`GET`, `HEAD`, `OPTIONS`, `TRACE`, `PUT`, and `DELETE`)
+/// are retried by default. Requests with streaming bodies are never retried since their bodies
+/// cannot be replayed; buffered bodies, such as those sent using `send_body`, `send_json`, or
+/// `send_form`, are sent again with each attempt.
+///
+/// When all attempts are exhausted, the last response or error is returned.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use awc::{http::StatusCode, middleware::Retry, Client};
+///
+/// let client = Client::builder()
+///     .wrap(
+///         Retry::new()
+///             .max_retries(5)
+///             .backoff(Duration::from_millis(50), Duration::from_secs(5))
+///             .statuses([StatusCode::SERVICE_UNAVAILABLE]),
+///     )
+///     .finish();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Retry {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    connect_errors: bool,
+    statuses: Vec<StatusCode>,
+    retry_after: bool,
+    methods: Vec<Method>,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Retry {
+    /// Constructs new `Retry` middleware with default settings.
+    pub fn new() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            connect_errors: true,
+            statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_after: true,
+            methods: vec![
+                Method::GET,
+                Method::HEAD,
+                Method::OPTIONS,
+                Method::TRACE,
+                Method::PUT,
+                Method::DELETE,
+            ],
+        }
+    }
+
+    /// Sets the maximum number of retries after the initial attempt.
+    ///
+    /// Defaults to 3.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first retry and the maximum delay between retries.
+    ///
+    /// The delay doubles with each retry until it reaches `max`. Defaults to 100ms and 10s.
+    pub fn backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.base_delay = base;
+        self.max_delay = max;
+        self
+    }
+
+    /// Sets whether backoff delays are randomized.
+    ///
+    /// With jitter, each delay is chosen at random between half and all of the backoff delay so
+    /// that clients retrying at the same time spread out their requests. Defaults to true.
+    pub fn jitter(mut self, enabled: bool) -> Self {
+        self.jitter = enabled;
+        self
+    }
+
+    /// Sets whether requests are retried when connecting to the server fails.
+    ///
+    /// Requests are not retried after errors that occur once the request has been sent. Defaults
+    /// to true.
+    pub fn connect_errors(mut self, enabled: bool) -> Self {
+        self.connect_errors = enabled;
+        self
+    }
+
+    /// Sets the response status codes for which requests are retried.
+    ///
+    /// Defaults to `429 Too Many Requests`, `502 Bad Gateway`, `503 Service Unavailable`, and
+    /// `504 Gateway Timeout`.
+    pub fn statuses(mut self, statuses: impl IntoIterator<Item = StatusCode>) -> Self {
+        self.statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Sets whether the `Retry-After` header of retried responses is honored.
+    ///
+    /// When enabled, the delay given by the header is used instead of the backoff delay. If it is
+    /// longer than the maximum backoff delay, the response is returned without retrying. Defaults
+    /// to true.
+    pub fn retry_after(mut self, enabled: bool) -> Self {
+        self.retry_after = enabled;
+        self
+    }
+
+    /// Sets the request methods that are retried.
+    ///
+    /// Defaults to the idempotent methods `GET`, `HEAD`, `OPTIONS`, `TRACE`, `PUT`, and `DELETE`.
+    /// Only add non-idempotent methods, such as `POST`, if the server can safely handle receiving
+    /// the same request more than once.
+    pub fn methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Returns the delay before retrying a request that has been attempted `attempt + 1` times.
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        if self.jitter {
+            let half = delay / 2;
+            half + half.mul_f64(rand::random::<f64>())
+        } else {
+            delay
+        }
+    }
+
+    /// Returns the delay before retrying a request after `res`, or `None` if it should not be
+    /// retried.
+    fn response_delay(&self, res: &ClientResponse, attempt: u32) -> Option<Duration> {
+        if !self.statuses.contains(&res.status()) {
+            return None;
+        }
+
+        if self.retry_after {
+            if let Some(delay) = retry_after(res) {
+                return (delay <= self.max_delay).then_some(delay);
+            }
+        }
+
+        Some(self.backoff_delay(attempt))
+    }
+}
+
+impl<S> Transform<S, ConnectRequest> for Retry
+where
+    S: Service<ConnectRequest, Response = ConnectResponse, Error = SendRequestError> + 'static,
+{
+    type Transform = RetryService<S>;
+
+    fn new_transform(self, service: S) -> Self::Transform {
+        RetryService {
+            config: Rc::new(self),
+            connector: Rc::new(service),
+        }
+    }
+}
+
+/// Service created by [`Retry`] middleware.
+pub struct RetryService<S> {
+    config: Rc<Retry>,
+    connector: Rc<S>,
+}
+
+impl<S> Service<ConnectRequest> for RetryService<S>
+where
+    S: Service<ConnectRequest, Response = ConnectResponse, Error = SendRequestError> + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = LocalBoxFuture<'static, Result<ConnectResponse, SendRequestError>>;
+
+    actix_service::forward_ready!(connector);
+
+    fn call(&self, req: ConnectRequest) -> Self::Future {
+        let connector = Rc::clone(&self.connector);
+
+        let retryable = |head: &RequestHeadType| {
+            self.config.max_retries > 0 && self.config.methods.contains(&head.as_ref().method)
+        };
+
+        let (head, body, addr) = match req {
+            ConnectRequest::Client(head, AnyBody::None, addr) if retryable(&head) => {
+                (head, None, addr)
+            }
+            ConnectRequest::Client(head, AnyBody::Bytes { body }, addr) if retryable(&head) => {
+                (head, Some(body), addr)
+            }
+
+            // tunnels, non-idempotent methods, and streaming bodies are sent once
+            req => return Box::pin(async move { connector.call(req).await }),
+        };
+
+        let config = Rc::clone(&self.config);
+
+        Box::pin(async move {
+            let mut attempt = 0;
+
+            loop {
+                let body = match body {
+                    Some(ref body) => AnyBody::Bytes { body: body.clone() },
+                    None => AnyBody::None,
+                };
+
+                let req = ConnectRequest::Client(clone_head(&head), body, addr);
+                let res = connector.call(req).await;
+
+                if attempt == config.max_retries {
+                    return res;
+                }
+
+                let delay = match &res {
+                    Ok(ConnectResponse::Client(res)) => config.response_delay(res, attempt),
+                    Err(SendRequestError::Connect(_)) if config.connect_errors => {
+                        Some(config.backoff_delay(attempt))
+                    }
+                    _ => None,
+                };
+
+                let Some(delay) = delay else {
+                    return res;
+                };
+
+                log::debug!("retrying request to {} in {delay:?}", head.as_ref().uri);
+
+                // release the connection before waiting
+                drop(res);
+
+                sleep(delay).await;
+                attempt += 1;
+            }
+        })
+    }
+}
+
+fn clone_head(head: &RequestHeadType) -> RequestHeadType {
+    match head {
+        RequestHeadType::Owned(head) => RequestHeadType::Owned(head.clone()),
+        RequestHeadType::Rc(head, extra_headers) => {
+            RequestHeadType::Rc(Rc::clone(head), extra_headers.clone())
+        }
+    }
+}
+
+/// Parses the `Retry-After` header of a response, given either in seconds or as an HTTP date.
+fn retry_after(res: &ClientResponse) -> Option<Duration> {
+    let value = res.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = SystemTime::from(value.parse::<HttpDate>().ok()?);
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use actix_http::{header::HeaderValue, Payload, RequestHead, ResponseHead};
+    use actix_service::fn_service;
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::client::ConnectError;
+
+    fn client_req(method: Method, body: AnyBody) -> ConnectRequest {
+        let mut head = RequestHead::default();
+        head.method = method;
+        ConnectRequest::Client(RequestHeadType::Owned(head), body, None)
+    }
+
+    /// Builds a retry service whose connector responds with the given results in turn, counting
+    /// the attempts made.
+    fn retry_service(
+        retry: Retry,
+        results: Vec<Result<ClientResponse, SendRequestError>>,
+    ) -> (
+        impl Service<ConnectRequest, Response = ConnectResponse, Error = SendRequestError>,
+        Rc<Cell<usize>>,
+    ) {
+        let attempts = Rc::new(Cell::new(0));
+        let results = Rc::new(RefCell::new(results.into_iter()));
+
+        let svc = retry.new_transform(fn_service({
+            let attempts = Rc::clone(&attempts);
+
+            move |req: ConnectRequest| {
+                attempts.set(attempts.get() + 1);
+                drop(req);
+
+                let res = results.borrow_mut().next().unwrap();
+                async move { res.map(ConnectResponse::Client) }
+            }
+        }));
+
+        (svc, attempts)
+    }
+
+    fn status(status: StatusCode) -> Result<ClientResponse, SendRequestError> {
+        Ok(ClientResponse::new(
+            ResponseHead::new(status),
+            Payload::None,
+        ))
+    }
+
+    fn fast() -> Retry {
+        Retry::new().backoff(Duration::from_millis(1), Duration::from_millis(10))
+    }
+
+    #[actix_rt::test]
+    async fn retries_statuses() {
+        let (svc, attempts) = retry_service(
+            fast(),
+            vec![
+                status(StatusCode::SERVICE_UNAVAILABLE),
+                status(StatusCode::BAD_GATEWAY),
+                status(StatusCode::OK),
+            ],
+        );
+
+        let res = svc
+            .call(client_req(Method::GET, AnyBody::None))
+            .await
+            .unwrap();
+        assert_eq!(res.into_client_response().status(), StatusCode::OK);
+        assert_eq!(attempts.get(), 3);
+
+        // non-retried status
+        let (svc, attempts) =
+            retry_service(fast(), vec![status(StatusCode::INTERNAL_SERVER_ERROR)]);
+
+        let res = svc
+            .call(client_req(Method::GET, AnyBody::None))
+            .await
+            .unwrap();
+        assert_eq!(
+            res.into_client_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn returns_last_result_when_exhausted() {
+        let (svc, attempts) = retry_service(
+            fast().max_retries(2),
+            vec![
+                Err(SendRequestError::Connect(ConnectError::Timeout)),
+                Err(SendRequestError::Connect(ConnectError::Timeout)),
+                status(StatusCode::SERVICE_UNAVAILABLE),
+            ],
+        );
+
+        let res = svc
+            .call(client_req(Method::GET, AnyBody::None))
+            .await
+            .unwrap();
+        assert_eq!(
+            res.into_client_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(attempts.get(), 3);
+
+        let (svc, attempts) = retry_service(
+            fast().max_retries(1),
+            vec![
+                Err(SendRequestError::Connect(ConnectError::Timeout)),
+                Err(SendRequestError::Connect(ConnectError::Disconnected)),
+            ],
+        );
+
+        let res = svc.call(client_req(Method::GET, AnyBody::None)).await;
+        assert!(matches!(
+            res,
+            Err(SendRequestError::Connect(ConnectError::Disconnected))
+        ));
+        assert_eq!(attempts.get(), 2);
+
+        // errors after connecting are not retried
+        let (svc, attempts) = retry_service(fast(), vec![Err(SendRequestError::Timeout)]);
+        let res = svc.call(client_req(Method::GET, AnyBody::None)).await;
+        assert!(matches!(res, Err(SendRequestError::Timeout)));
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn idempotent_methods_and_replayable_bodies() {
+        let body = || AnyBody::Bytes {
+            body: Bytes::from_static(b"data"),
+        };
+
+        let (svc, attempts) = retry_service(
+            fast(),
+            vec![
+                status(StatusCode::SERVICE_UNAVAILABLE),
+                status(StatusCode::OK),
+            ],
+        );
+        svc.call(client_req(Method::PUT, body())).await.unwrap();
+        assert_eq!(attempts.get(), 2);
+
+        let (svc, attempts) = retry_service(
+            fast(),
+            vec![
+                status(StatusCode::SERVICE_UNAVAILABLE),
+                status(StatusCode::OK),
+            ],
+        );
+        svc.call(client_req(Method::POST, body())).await.unwrap();
+        assert_eq!(attempts.get(), 1);
+
+        let (svc, attempts) = retry_service(
+            fast().methods([Method::POST]),
+            vec![
+                status(StatusCode::SERVICE_UNAVAILABLE),
+                status(StatusCode::OK),
+            ],
+        );
+        svc.call(client_req(Method::POST, body())).await.unwrap();
+        assert_eq!(attempts.get(), 2);
+
+        // streaming bodies cannot be replayed
+        let (svc, attempts) = retry_service(
+            fast(),
+            vec![
+                status(StatusCode::SERVICE_UNAVAILABLE),
+                status(StatusCode::OK),
+            ],
+        );
+        svc.call(client_req(
+            Method::PUT,
+            AnyBody::new_boxed(Bytes::from_static(b"data")),
+        ))
+        .await
+        .unwrap();
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn honors_retry_after() {
+        let retry_after = |value: &str| {
+            let mut head = ResponseHead::new(StatusCode::TOO_MANY_REQUESTS);
+            head.headers
+                .insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+            ClientResponse::new(head, Payload::None)
+        };
+
+        assert_eq!(
+            super::retry_after(&retry_after("120")),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            super::retry_after(&retry_after("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(super::retry_after(&retry_after("soon")), None);
+
+        // delays longer than the maximum backoff are not waited for
+        let retry = fast();
+        assert_eq!(retry.response_delay(&retry_after("120"), 0), None);
+        assert_eq!(
+            retry.response_delay(&retry_after("0"), 0),
+            Some(Duration::ZERO)
+        );
+
+        let retry = fast().retry_after(false);
+        assert!(retry.response_delay(&retry_after("120"), 0).is_some());
+    }
+
+    #[test]
+    fn backoff_delays() {
+        let retry = Retry::new()
+            .backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .jitter(false);
+        assert_eq!(retry.backoff_delay(0), Duration::from_millis(100));
+        assert_eq!(retry.backoff_delay(1), Duration::from_millis(200));
+        assert_eq!(retry.backoff_delay(3), Duration::from_millis(800));
+        assert_eq!(retry.backoff_delay(4), Duration::from_secs(1));
+        assert_eq!(retry.backoff_delay(100), Duration::from_secs(1));
+
+        let retry = retry.jitter(true);
+        for attempt in 0..5 {
+            let delay = retry.backoff_delay(attempt);
+            let max = Duration::from_millis(100 << attempt).min(Duration::from_secs(1));
+            assert!(delay >= max / 2 && delay <= max);
+        }
+    }
+}
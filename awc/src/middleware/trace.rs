@@ -0,0 +1,145 @@
+use actix_http::{
+    header::{HeaderMap, TryIntoHeaderValue as _, TRACEPARENT},
+    trace::TraceContext,
+    RequestHeadType,
+};
+use actix_service::Service;
+
+use super::Transform;
+use crate::connect::ConnectRequest;
+
+/// Middleware that propagates the current [`TraceContext`] to outgoing requests.
+///
+/// When a request is sent while a trace context is [current](TraceContext::current), such as from
+/// a handler wrapped in actix-web's `TracingLogger` middleware, a `traceparent` header is added to
+/// the request so that the receiving service can continue the trace. Requests that already have a
+/// `traceparent` header are left unchanged.
+///
+/// # Examples
+/// ```
+/// use awc::{middleware::TracePropagation, Client};
+///
+/// let client = Client::builder().wrap(TracePropagation::new()).finish();
+/// ```
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct TracePropagation;
+
+impl TracePropagation {
+    /// Constructs new `TracePropagation` middleware.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Transform<S, ConnectRequest> for TracePropagation
+where
+    S: Service<ConnectRequest>,
+{
+    type Transform = TracePropagationService<S>;
+
+    fn new_transform(self, service: S) -> Self::Transform {
+        TracePropagationService { service }
+    }
+}
+
+/// Service created by [`TracePropagation`] middleware.
+pub struct TracePropagationService<S> {
+    service: S,
+}
+
+impl<S> Service<ConnectRequest> for TracePropagationService<S>
+where
+    S: Service<ConnectRequest>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ConnectRequest) -> Self::Future {
+        if let Some(ctx) = TraceContext::current() {
+            match req {
+                ConnectRequest::Client(RequestHeadType::Owned(ref mut head), ..)
+                | ConnectRequest::Tunnel(ref mut head, ..) => {
+                    insert_traceparent(&mut head.headers, ctx);
+                }
+
+                ConnectRequest::Client(
+                    RequestHeadType::Rc(ref head, ref mut extra_headers),
+                    ..,
+                ) => {
+                    if !head.headers.contains_key(TRACEPARENT) {
+                        insert_traceparent(extra_headers.get_or_insert_with(HeaderMap::new), ctx);
+                    }
+                }
+            }
+        }
+
+        self.service.call(req)
+    }
+}
+
+fn insert_traceparent(headers: &mut HeaderMap, ctx: TraceContext) {
+    if !headers.contains_key(TRACEPARENT) {
+        let value = ctx.try_into_value().unwrap_or_else(|err| match err {});
+        headers.insert(TRACEPARENT, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_http::{header::HeaderValue, Method, RequestHead};
+    use actix_service::fn_service;
+
+    use super::*;
+    use crate::any_body::AnyBody;
+
+    fn traceparent(req: &ConnectRequest) -> Option<HeaderValue> {
+        match req {
+            ConnectRequest::Client(head, ..) => head
+                .as_ref()
+                .headers
+                .get(TRACEPARENT)
+                .or_else(|| head.extra_headers()?.get(TRACEPARENT))
+                .cloned(),
+            ConnectRequest::Tunnel(head, ..) => head.headers.get(TRACEPARENT).cloned(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn injects_current_context() {
+        let svc =
+            TracePropagation::new().new_transform(fn_service(|req: ConnectRequest| async move {
+                Ok::<_, ()>(traceparent(&req))
+            }));
+
+        let client_req = || {
+            let mut head = RequestHead::default();
+            head.method = Method::GET;
+            ConnectRequest::Client(head.into(), AnyBody::empty(), None)
+        };
+
+        assert_eq!(svc.call(client_req()).await.unwrap(), None);
+
+        let ctx = TraceContext::new_root();
+        let fut = ctx.scope(|| svc.call(client_req()));
+        assert_eq!(fut.await.unwrap().unwrap(), ctx.to_string());
+
+        let rc_req = ConnectRequest::Client(
+            RequestHeadType::Rc(std::rc::Rc::new(RequestHead::default()), None),
+            AnyBody::empty(),
+            None,
+        );
+        let fut = ctx.scope(|| svc.call(rc_req));
+        assert_eq!(fut.await.unwrap().unwrap(), ctx.to_string());
+
+        // existing header is kept
+        let mut head = RequestHead::default();
+        head.headers
+            .insert(TRACEPARENT, HeaderValue::from_static("existing"));
+        let fut = ctx.scope(|| svc.call(ConnectRequest::Tunnel(head, None)));
+        assert_eq!(fut.await.unwrap().unwrap(), "existing");
+    }
+}
@@ -0,0 +1,491 @@
+//! Multipart form request bodies.
+//!
+//! # Examples
+//! ```no_run
+//! use awc::multipart::{Form, Part};
+//! use bytes::Bytes;
+//! use futures_util::stream;
+//!
+//! # #[actix_rt::main]
+//! # async fn main() -> Result<(), awc::error::SendRequestError> {
+//! let chunks = stream::iter([
+//!     Ok::<_, std::io::Error>(Bytes::from_static(b"date,total\n")),
+//!     Ok(Bytes::from_static(b"2024-01-01,42\n")),
+//! ]);
+//!
+//! let form = Form::new()
+//!     .text("title", "Quarterly report")
+//!     .part(
+//!         "attachment",
+//!         Part::stream(chunks)
+//!             .file_name("report.csv")
+//!             .content_type(mime::TEXT_CSV),
+//!     );
+//!
+//! let res = awc::Client::new()
+//!     .post("http://www.rust-lang.org")
+//!     .send_multipart(form)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_http::{
+    body::{BodySize, MessageBody},
+    header::{ContentDisposition, DispositionParam, DispositionType},
+};
+use bytes::{Bytes, BytesMut};
+use futures_core::{ready, Stream};
+use mime::Mime;
+use rand::distr::{Alphanumeric, SampleString as _};
+
+use crate::BoxError;
+
+const CRLF: &[u8] = b"\r\n";
+const HYPHENS: &[u8] = b"--";
+const BOUNDARY_PREFIX: &str = "------------------------";
+
+/// A `multipart/form-data` request body.
+///
+/// Fields are written in the order they are added. The body has a known size when none of its
+/// fields are streams.
+///
+/// Use with [`ClientRequest::send_multipart`](crate::ClientRequest::send_multipart), which sets
+/// the `Content-Type` header, including the form's boundary, if one is not already set.
+pub struct Form {
+    boundary: String,
+    parts: VecDeque<FormPart>,
+    stream: Option<PartStream>,
+    finished: bool,
+}
+
+struct FormPart {
+    head: Bytes,
+    body: PartBody,
+}
+
+type PartStream = Pin<Box<dyn Stream<Item = Result<Bytes, BoxError>>>>;
+
+enum PartBody {
+    Bytes(Bytes),
+    Stream(PartStream),
+}
+
+impl Form {
+    /// Constructs an empty form with a random boundary.
+    pub fn new() -> Self {
+        let boundary = Alphanumeric.sample_string(&mut rand::rng(), 32);
+        Self::with_boundary([BOUNDARY_PREFIX, &boundary].concat())
+    }
+
+    /// Constructs an empty form with the given boundary.
+    ///
+    /// The boundary must not occur in any of the form's fields.
+    pub fn with_boundary(boundary: impl Into<String>) -> Self {
+        Self {
+            boundary: boundary.into(),
+            parts: VecDeque::new(),
+            stream: None,
+            finished: false,
+        }
+    }
+
+    /// Returns the boundary delimiting the form's fields.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Returns the `Content-Type` header value for this form.
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Adds a text field.
+    pub fn text(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.part(name, Part::text(value))
+    }
+
+    /// Adds a field containing bytes.
+    pub fn bytes(self, name: impl Into<String>, value: impl Into<Bytes>) -> Self {
+        self.part(name, Part::bytes(value))
+    }
+
+    /// Adds a field from a [`Part`], which can also specify a file name and content type.
+    pub fn part(mut self, name: impl Into<String>, part: Part) -> Self {
+        let head = part.head(&self.boundary, name.into());
+
+        self.parts.push_back(FormPart {
+            head,
+            body: part.body,
+        });
+
+        self
+    }
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Form {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Form")
+            .field("boundary", &self.boundary)
+            .field("parts", &self.parts.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MessageBody for Form {
+    type Error = BoxError;
+
+    fn size(&self) -> BodySize {
+        if self.stream.is_some() {
+            return BodySize::Stream;
+        }
+
+        let mut size = if self.finished {
+            0
+        } else {
+            (HYPHENS.len() * 2 + self.boundary.len() + CRLF.len()) as u64
+        };
+
+        for part in &self.parts {
+            match part.body {
+                PartBody::Bytes(ref body) => {
+                    size += (part.head.len() + body.len() + CRLF.len()) as u64;
+                }
+                PartBody::Stream(_) => return BodySize::Stream,
+            }
+        }
+
+        BodySize::Sized(size)
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.get_mut();
+
+        if let Some(stream) = this.stream.as_mut() {
+            return match ready!(stream.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => Poll::Ready(Some(Ok(chunk))),
+
+                Some(Err(err)) => {
+                    this.stream = None;
+                    this.parts.clear();
+                    this.finished = true;
+                    Poll::Ready(Some(Err(err)))
+                }
+
+                None => {
+                    this.stream = None;
+                    Poll::Ready(Some(Ok(Bytes::from_static(CRLF))))
+                }
+            };
+        }
+
+        match this.parts.pop_front() {
+            Some(FormPart {
+                head,
+                body: PartBody::Bytes(body),
+            }) => {
+                let mut buf = BytesMut::with_capacity(head.len() + body.len() + CRLF.len());
+                buf.extend_from_slice(&head);
+                buf.extend_from_slice(&body);
+                buf.extend_from_slice(CRLF);
+                Poll::Ready(Some(Ok(buf.freeze())))
+            }
+
+            Some(FormPart {
+                head,
+                body: PartBody::Stream(stream),
+            }) => {
+                this.stream = Some(stream);
+                Poll::Ready(Some(Ok(head)))
+            }
+
+            None if !this.finished => {
+                this.finished = true;
+
+                let mut buf = BytesMut::with_capacity(this.boundary.len() + 6);
+                buf.extend_from_slice(HYPHENS);
+                buf.extend_from_slice(this.boundary.as_bytes());
+                buf.extend_from_slice(HYPHENS);
+                buf.extend_from_slice(CRLF);
+                Poll::Ready(Some(Ok(buf.freeze())))
+            }
+
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+/// A field of a [`Form`].
+pub struct Part {
+    body: PartBody,
+    file_name: Option<String>,
+    content_type: Option<Mime>,
+}
+
+impl Part {
+    /// Constructs a part containing text.
+    pub fn text(value: impl Into<String>) -> Self {
+        Self::new(PartBody::Bytes(Bytes::from(value.into())))
+    }
+
+    /// Constructs a part containing bytes.
+    pub fn bytes(value: impl Into<Bytes>) -> Self {
+        Self::new(PartBody::Bytes(value.into()))
+    }
+
+    /// Constructs a part whose contents are read from a stream, such as the contents of a file.
+    pub fn stream<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + 'static,
+        E: Into<BoxError> + 'static,
+    {
+        Self::new(PartBody::Stream(Box::pin(MapErr { stream })))
+    }
+
+    fn new(body: PartBody) -> Self {
+        Self {
+            body,
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    /// Sets the file name of this part.
+    ///
+    /// Parts with a file name and no content type are sent as `application/octet-stream`.
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Sets the content type of this part.
+    pub fn content_type(mut self, content_type: Mime) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// Serializes the boundary delimiter and headers preceding this part's contents.
+    fn head(&self, boundary: &str, name: String) -> Bytes {
+        let mut parameters = vec![DispositionParam::Name(escape_param(name))];
+
+        if let Some(ref file_name) = self.file_name {
+            parameters.push(DispositionParam::Filename(escape_param(file_name.clone())));
+        }
+
+        let content_disposition = ContentDisposition {
+            disposition: DispositionType::FormData,
+            parameters,
+        };
+
+        let content_type = match self.content_type {
+            Some(ref content_type) => Some(content_type.as_ref()),
+            None if self.file_name.is_some() => Some(mime::APPLICATION_OCTET_STREAM.as_ref()),
+            None => None,
+        };
+
+        let mut buf = BytesMut::with_capacity(boundary.len() + 128);
+        buf.extend_from_slice(HYPHENS);
+        buf.extend_from_slice(boundary.as_bytes());
+        buf.extend_from_slice(CRLF);
+
+        buf.extend_from_slice(b"Content-Disposition: ");
+        buf.extend_from_slice(content_disposition.to_string().as_bytes());
+        buf.extend_from_slice(CRLF);
+
+        if let Some(content_type) = content_type {
+            buf.extend_from_slice(b"Content-Type: ");
+            buf.extend_from_slice(content_type.as_bytes());
+            buf.extend_from_slice(CRLF);
+        }
+
+        buf.extend_from_slice(CRLF);
+        buf.freeze()
+    }
+}
+
+impl fmt::Debug for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Part")
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Percent-encodes line breaks and double quotes in field names and file names the same way
+/// browsers do, so they cannot end the parameter or header early.
+fn escape_param(value: String) -> String {
+    if !value.contains(['\r', '\n', '"']) {
+        return value;
+    }
+
+    value
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+        .replace('"', "%22")
+}
+
+pin_project_lite::pin_project! {
+    struct MapErr<S> {
+        #[pin]
+        stream: S,
+    }
+}
+
+impl<S, E> Stream for MapErr<S>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project()
+            .stream
+            .poll_next(cx)
+            .map(|item| item.map(|res| res.map_err(Into::into)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use actix_http::body;
+    use futures_util::stream;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn sized_form() {
+        let form = Form::with_boundary("boundary")
+            .text("title", "hello")
+            .bytes("data", Bytes::from_static(b"\x00\x01"))
+            .part(
+                "file",
+                Part::text("a,b")
+                    .file_name("a b.csv")
+                    .content_type(mime::TEXT_CSV),
+            )
+            .part("upload", Part::bytes(&b"raw"[..]).file_name("raw.bin"));
+
+        assert_eq!(
+            form.content_type(),
+            "multipart/form-data; boundary=boundary"
+        );
+
+        let size = form.size();
+        let body = body::to_bytes(form).await.unwrap();
+
+        assert_eq!(
+            body,
+            "--boundary\r\n\
+            Content-Disposition: form-data; name=title\r\n\
+            \r\n\
+            hello\r\n\
+            --boundary\r\n\
+            Content-Disposition: form-data; name=data\r\n\
+            \r\n\
+            \x00\x01\r\n\
+            --boundary\r\n\
+            Content-Disposition: form-data; name=file; filename=\"a b.csv\"\r\n\
+            Content-Type: text/csv\r\n\
+            \r\n\
+            a,b\r\n\
+            --boundary\r\n\
+            Content-Disposition: form-data; name=upload; filename=\"raw.bin\"\r\n\
+            Content-Type: application/octet-stream\r\n\
+            \r\n\
+            raw\r\n\
+            --boundary--\r\n"
+        );
+        assert_eq!(size, BodySize::Sized(body.len() as u64));
+    }
+
+    #[actix_rt::test]
+    async fn streaming_form() {
+        let chunks = stream::iter([
+            Ok::<_, io::Error>(Bytes::from_static(b"chunk 1, ")),
+            Ok(Bytes::from_static(b"chunk 2")),
+        ]);
+
+        let form = Form::with_boundary("boundary")
+            .part("file", Part::stream(chunks).file_name("file.txt"))
+            .text("after", "text");
+
+        assert_eq!(form.size(), BodySize::Stream);
+
+        let body = body::to_bytes(form).await.unwrap();
+
+        assert_eq!(
+            body,
+            "--boundary\r\n\
+            Content-Disposition: form-data; name=file; filename=\"file.txt\"\r\n\
+            Content-Type: application/octet-stream\r\n\
+            \r\n\
+            chunk 1, chunk 2\r\n\
+            --boundary\r\n\
+            Content-Disposition: form-data; name=after\r\n\
+            \r\n\
+            text\r\n\
+            --boundary--\r\n"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn stream_error() {
+        let chunks = stream::iter([
+            Ok(Bytes::from_static(b"chunk")),
+            Err(io::Error::other("read failed")),
+        ]);
+
+        let form = Form::new().part("file", Part::stream(chunks));
+        let err = body::to_bytes(form).await.unwrap_err();
+        assert_eq!(err.to_string(), "read failed");
+    }
+
+    #[actix_rt::test]
+    async fn escaped_params() {
+        let form = Form::with_boundary("boundary").part(
+            "field \"name\"",
+            Part::text("").file_name("evil\r\nContent-Type: text/html\".txt"),
+        );
+
+        let body = body::to_bytes(form).await.unwrap();
+
+        assert_eq!(
+            body,
+            "--boundary\r\n\
+            Content-Disposition: form-data; name=\"field %22name%22\"; \
+            filename=\"evil%0D%0AContent-Type: text/html%22.txt\"\r\n\
+            Content-Type: application/octet-stream\r\n\
+            \r\n\
+            \r\n\
+            --boundary--\r\n"
+        );
+    }
+
+    #[test]
+    fn random_boundary() {
+        let form = Form::new();
+        assert!(form.boundary().starts_with(BOUNDARY_PREFIX));
+        assert_eq!(form.boundary().len(), BOUNDARY_PREFIX.len() + 32);
+        assert_ne!(form.boundary(), Form::new().boundary());
+    }
+}
@@ -3,7 +3,7 @@ use std::{fmt, net, rc::Rc, time::Duration};
 use actix_http::{
     body::MessageBody,
     error::HttpError,
-    header::{self, HeaderMap, HeaderValue, TryIntoHeaderPair},
+    header::{self, ContentEncoding, HeaderMap, HeaderValue, TryIntoHeaderPair},
     ConnectionType, Method, RequestHead, Uri, Version,
 };
 use base64::prelude::*;
@@ -17,6 +17,7 @@ use crate::{
     client::ClientConfig,
     error::{FreezeRequestError, InvalidUrl},
     frozen::FrozenClientRequest,
+    multipart::Form,
     sender::{PrepForSendingError, RequestSender, SendClientRequest},
     BoxError,
 };
@@ -46,6 +47,7 @@ pub struct ClientRequest {
     err: Option<HttpError>,
     addr: Option<net::SocketAddr>,
     response_decompress: bool,
+    compress: Option<ContentEncoding>,
     timeout: Option<Duration>,
     config: ClientConfig,
 
@@ -69,6 +71,7 @@ impl ClientRequest {
             cookies: None,
             timeout: None,
             response_decompress: true,
+            compress: None,
         }
         .method(method)
         .uri(uri)
@@ -279,6 +282,33 @@ impl ClientRequest {
         self
     }
 
+    /// Compresses the request body with the given encoding, setting the `Content-Encoding` header.
+    ///
+    /// Overrides the client wide setting; use [`ContentEncoding::Identity`] to send the body
+    /// uncompressed. Empty bodies, requests that already have a `Content-Encoding` header, and
+    /// encodings that are not enabled by crate features are sent uncompressed. Compressed buffered
+    /// bodies are sent with a known `Content-Length`; compressed streamed bodies are sent with
+    /// chunked transfer encoding.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use awc::http::header::ContentEncoding;
+    ///
+    /// # #[actix_rt::main]
+    /// # async fn main() {
+    /// let res = awc::Client::new()
+    ///     .post("http://www.rust-lang.org")
+    ///     .compress(ContentEncoding::Gzip)
+    ///     .send_json(&serde_json::json!({ "events": [] }))
+    ///     .await;
+    /// # }
+    /// ```
+    #[cfg(feature = "__compress")]
+    pub fn compress(mut self, encoding: ContentEncoding) -> Self {
+        self.compress = Some(encoding);
+        self
+    }
+
     /// Set request timeout. Overrides client wide timeout setting.
     ///
     /// Request timeout is the total time before a response must be received.
@@ -318,6 +348,7 @@ impl ClientRequest {
             head: Rc::new(slf.head),
             addr: slf.addr,
             response_decompress: slf.response_decompress,
+            compress: slf.compress,
             timeout: slf.timeout,
             config: slf.config,
         };
@@ -338,6 +369,7 @@ impl ClientRequest {
         RequestSender::Owned(slf.head).send_body(
             slf.addr,
             slf.response_decompress,
+            slf.compress,
             slf.timeout,
             &slf.config,
             body,
@@ -354,6 +386,7 @@ impl ClientRequest {
         RequestSender::Owned(slf.head).send_json(
             slf.addr,
             slf.response_decompress,
+            slf.compress,
             slf.timeout,
             &slf.config,
             value,
@@ -372,12 +405,32 @@ impl ClientRequest {
         RequestSender::Owned(slf.head).send_form(
             slf.addr,
             slf.response_decompress,
+            slf.compress,
             slf.timeout,
             &slf.config,
             value,
         )
     }
 
+    /// Set a `multipart/form-data` body and generate `ClientRequest`.
+    ///
+    /// The `Content-Type` header is set to include the form's boundary unless it is already set.
+    pub fn send_multipart(self, form: Form) -> SendClientRequest {
+        let slf = match self.prep_for_sending() {
+            Ok(slf) => slf,
+            Err(err) => return err.into(),
+        };
+
+        RequestSender::Owned(slf.head).send_multipart(
+            slf.addr,
+            slf.response_decompress,
+            slf.compress,
+            slf.timeout,
+            &slf.config,
+            form,
+        )
+    }
+
     /// Set an streaming body and generate `ClientRequest`.
     pub fn send_stream<S, E>(self, stream: S) -> SendClientRequest
     where
@@ -392,6 +445,7 @@ impl ClientRequest {
         RequestSender::Owned(slf.head).send_stream(
             slf.addr,
             slf.response_decompress,
+            slf.compress,
             slf.timeout,
             &slf.config,
             stream,
@@ -408,6 +462,7 @@ impl ClientRequest {
         RequestSender::Owned(slf.head).send(
             slf.addr,
             slf.response_decompress,
+            slf.compress,
             slf.timeout,
             &slf.config,
         )
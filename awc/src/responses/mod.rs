@@ -7,10 +7,15 @@ mod json_body;
 mod read_body;
 mod response;
 mod response_body;
+mod sse;
 
 #[allow(deprecated)]
 pub use self::response_body::{MessageBody, ResponseBody};
-pub use self::{json_body::JsonBody, response::ClientResponse};
+pub use self::{
+    json_body::JsonBody,
+    response::ClientResponse,
+    sse::{SseEvent, SseStream},
+};
 
 /// Default body size limit: 2 MiB
 const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
@@ -30,7 +35,7 @@ impl Default for ResponseTimeout {
 }
 
 impl ResponseTimeout {
-    fn poll_timeout(&mut self, cx: &mut Context<'_>) -> Result<(), PayloadError> {
+    pub(super) fn poll_timeout(&mut self, cx: &mut Context<'_>) -> Result<(), PayloadError> {
         match *self {
             Self::Enabled(ref mut timeout) => {
                 if timeout.as_mut().poll(cx).is_ready() {
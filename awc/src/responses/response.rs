@@ -16,7 +16,7 @@ use futures_core::Stream;
 use pin_project_lite::pin_project;
 use serde::de::DeserializeOwned;
 
-use super::{JsonBody, ResponseBody, ResponseTimeout};
+use super::{JsonBody, ResponseBody, ResponseTimeout, SseStream};
 #[cfg(feature = "cookies")]
 use crate::cookie::{Cookie, ParseError as CookieParseError};
 
@@ -204,6 +204,34 @@ where
     pub fn json<T: DeserializeOwned>(&mut self) -> JsonBody<S, T> {
         JsonBody::new(self)
     }
+
+    /// Returns a [`Stream`] that consumes the body stream, parsing it as Server-Sent Events.
+    ///
+    /// # Errors
+    /// Stream yields an error if:
+    /// - content type is not `text/event-stream`;
+    /// - a line or event is larger than the [limit](SseStream::limit) (default: 2 MiB).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use awc::Client;
+    /// use futures_util::StreamExt as _;
+    /// # #[actix_rt::main]
+    /// # async fn async_ctx() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::default();
+    /// let mut res = client.get("http://localhost:8080/events").send().await?;
+    ///
+    /// let mut events = res.sse();
+    /// while let Some(event) = events.next().await {
+    ///     let event = event?;
+    ///     println!("{}: {}", event.event(), event.data());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sse(&mut self) -> SseStream<S> {
+        SseStream::new(self)
+    }
 }
 
 impl<S> fmt::Debug for ClientResponse<S> {
@@ -0,0 +1,404 @@
+use std::{
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use actix_http::{error::PayloadError, HttpMessage, Payload};
+use bytes::{Buf as _, Bytes, BytesMut};
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
+
+use super::{ResponseTimeout, DEFAULT_BODY_LIMIT};
+use crate::{error::SsePayloadError, ClientResponse};
+
+/// An event received from a Server-Sent Events stream.
+///
+/// See [`SseStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    event: String,
+    data: String,
+    id: String,
+    retry: Option<Duration>,
+}
+
+impl SseEvent {
+    /// Returns the event type, which is `message` unless set by the server.
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    /// Returns the event data.
+    ///
+    /// Multi-line data is joined with line feeds.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Returns the last event ID set by the server, which should be sent in the `Last-Event-ID`
+    /// header when reconnecting.
+    ///
+    /// The last event ID persists across events; it is empty if the server has not set one.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the reconnection time last set by the server, if any.
+    ///
+    /// Like the last event ID, the reconnection time persists across events.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+}
+
+pin_project! {
+    /// A `Stream` that parses a `text/event-stream` body, yielding [`SseEvent`]s.
+    ///
+    /// Comments and events without data are skipped, as are incomplete events at the end of the
+    /// stream, following the [event stream interpretation] rules.
+    ///
+    /// # Errors
+    /// Stream yields an error if:
+    /// - content type is not `text/event-stream`;
+    /// - a line or event is larger than the [limit](SseStream::limit) (default: 2 MiB);
+    /// - the [response timeout](ClientResponse::timeout), if set, has passed.
+    ///
+    /// [event stream interpretation]: https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation
+    pub struct SseStream<S> {
+        #[pin]
+        payload: Payload<S>,
+        buf: BytesMut,
+        limit: usize,
+        timeout: ResponseTimeout,
+        started: bool,
+        skip_lf: bool,
+        eof: bool,
+        err: Option<SsePayloadError>,
+
+        // fields of the event being parsed
+        event: String,
+        data: String,
+
+        // state that persists across events
+        last_id: String,
+        retry: Option<Duration>,
+    }
+}
+
+impl<S> SseStream<S>
+where
+    S: Stream<Item = Result<Bytes, PayloadError>>,
+{
+    /// Creates an event stream from a response by taking its payload.
+    pub fn new(res: &mut ClientResponse<S>) -> Self {
+        let is_event_stream = matches!(
+            res.mime_type(),
+            Ok(Some(mime)) if mime.type_() == mime::TEXT && mime.subtype() == mime::EVENT_STREAM
+        );
+
+        SseStream {
+            payload: res.take_payload(),
+            buf: BytesMut::new(),
+            limit: DEFAULT_BODY_LIMIT,
+            timeout: mem::take(&mut res.timeout),
+            started: false,
+            skip_lf: false,
+            eof: false,
+            err: (!is_event_stream).then_some(SsePayloadError::ContentType),
+            event: String::new(),
+            data: String::new(),
+            last_id: String::new(),
+            retry: None,
+        }
+    }
+
+    /// Change max size of a single line or event. Default limit is 2 MiB.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the reconnection time last set by the server, if any.
+    ///
+    /// The reconnection time is also updated by `retry` fields in events without data, which are
+    /// not yielded by the stream.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+}
+
+/// Takes the next complete line from the buffer, without its line ending.
+///
+/// Lines end with CRLF, LF, or CR. Since a trailing CR may be followed by an LF that has not been
+/// received yet, the LF is skipped once it arrives.
+fn next_line(buf: &mut BytesMut, skip_lf: &mut bool) -> Option<Bytes> {
+    if *skip_lf && !buf.is_empty() {
+        if buf[0] == b'\n' {
+            buf.advance(1);
+        }
+
+        *skip_lf = false;
+    }
+
+    let pos = buf.iter().position(|&b| b == b'\n' || b == b'\r')?;
+    let line = buf.split_to(pos).freeze();
+
+    *skip_lf = buf[0] == b'\r';
+    buf.advance(1);
+
+    Some(line)
+}
+
+/// Result of processing a single line.
+enum Line {
+    Field,
+    Dispatch,
+}
+
+fn process_line(
+    line: &[u8],
+    event: &mut String,
+    data: &mut String,
+    last_id: &mut String,
+    retry: &mut Option<Duration>,
+) -> Line {
+    if line.is_empty() {
+        return Line::Dispatch;
+    }
+
+    let line = String::from_utf8_lossy(line);
+
+    // comment
+    if line.starts_with(':') {
+        return Line::Field;
+    }
+
+    let (name, value) = match line.split_once(':') {
+        Some((name, value)) => (name, value.strip_prefix(' ').unwrap_or(value)),
+        None => (line.as_ref(), ""),
+    };
+
+    match name {
+        "event" => *event = value.to_owned(),
+
+        "data" => {
+            data.push_str(value);
+            data.push('\n');
+        }
+
+        "id" if !value.contains('\0') => *last_id = value.to_owned(),
+
+        "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+            if let Ok(ms) = value.parse() {
+                *retry = Some(Duration::from_millis(ms));
+            }
+        }
+
+        // unknown fields are ignored
+        _ => {}
+    }
+
+    Line::Field
+}
+
+impl<S> Stream for SseStream<S>
+where
+    S: Stream<Item = Result<Bytes, PayloadError>>,
+{
+    type Item = Result<SseEvent, SsePayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Some(err) = this.err.take() {
+            *this.eof = true;
+            return Poll::Ready(Some(Err(err)));
+        }
+
+        loop {
+            while let Some(line) = next_line(this.buf, this.skip_lf) {
+                let res = process_line(&line, this.event, this.data, this.last_id, this.retry);
+
+                if let Line::Dispatch = res {
+                    let event = mem::take(this.event);
+                    let mut data = mem::take(this.data);
+
+                    if data.is_empty() {
+                        continue;
+                    }
+
+                    data.pop();
+
+                    return Poll::Ready(Some(Ok(SseEvent {
+                        event: if event.is_empty() {
+                            "message".to_owned()
+                        } else {
+                            event
+                        },
+                        data,
+                        id: this.last_id.clone(),
+                        retry: *this.retry,
+                    })));
+                }
+            }
+
+            if this.buf.len() + this.data.len() > *this.limit {
+                *this.eof = true;
+                return Poll::Ready(Some(Err(SsePayloadError::Overflow)));
+            }
+
+            if *this.eof {
+                return Poll::Ready(None);
+            }
+
+            this.timeout.poll_timeout(cx)?;
+
+            match ready!(this.payload.as_mut().poll_next(cx)) {
+                Some(Ok(mut chunk)) => {
+                    // skip byte order mark
+                    if !*this.started && !chunk.is_empty() {
+                        *this.started = true;
+
+                        if chunk.starts_with(b"\xEF\xBB\xBF") {
+                            chunk.advance(3);
+                        }
+                    }
+
+                    this.buf.extend_from_slice(&chunk);
+                }
+
+                Some(Err(err)) => {
+                    *this.eof = true;
+                    return Poll::Ready(Some(Err(err.into())));
+                }
+
+                None => *this.eof = true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_http::header;
+    use futures_util::{stream, StreamExt as _};
+
+    use super::*;
+    use crate::test::TestResponse;
+
+    fn sse_response(
+        chunks: &[&'static str],
+    ) -> ClientResponse<impl Stream<Item = Result<Bytes, PayloadError>>> {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))
+            .collect::<Vec<_>>();
+
+        TestResponse::with_header((header::CONTENT_TYPE, "text/event-stream"))
+            .set_payload(Bytes::new())
+            .finish()
+            .map_body(|_, _| Payload::Stream {
+                payload: stream::iter(chunks),
+            })
+    }
+
+    #[actix_rt::test]
+    async fn parse_events() {
+        let mut res = sse_response(&[
+            "\u{FEFF}: comment\n\ndata: first\n",
+            "\r\n",
+            "event: update\r",
+            "\nid: 7\ndata:multi\ndata: line\nretry: 3000\n\n",
+            "data\n\nid\ndata: no id\r\rdata: incomplete",
+        ]);
+
+        let events = res.sse().collect::<Vec<_>>().await;
+        let events = events
+            .into_iter()
+            .map(Result::unwrap)
+            .map(|ev| {
+                (
+                    ev.event().to_owned(),
+                    ev.data().to_owned(),
+                    ev.id().to_owned(),
+                    ev.retry(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            events,
+            [
+                (
+                    "message".to_owned(),
+                    "first".to_owned(),
+                    String::new(),
+                    None
+                ),
+                (
+                    "update".to_owned(),
+                    "multi\nline".to_owned(),
+                    "7".to_owned(),
+                    Some(Duration::from_secs(3))
+                ),
+                (
+                    "message".to_owned(),
+                    String::new(),
+                    "7".to_owned(),
+                    Some(Duration::from_secs(3))
+                ),
+                (
+                    "message".to_owned(),
+                    "no id".to_owned(),
+                    String::new(),
+                    Some(Duration::from_secs(3))
+                ),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn retry_without_data() {
+        let mut res = sse_response(&[
+            "retry: 3000
+
+",
+            "data: a
+
+",
+            "retry: 500
+
+",
+        ]);
+        let mut events = res.sse();
+
+        assert_eq!(events.retry(), None);
+
+        let ev = events.next().await.unwrap().unwrap();
+        assert_eq!(ev.data(), "a");
+        assert_eq!(ev.retry(), Some(Duration::from_secs(3)));
+
+        assert!(events.next().await.is_none());
+        assert_eq!(events.retry(), Some(Duration::from_millis(500)));
+    }
+
+    #[actix_rt::test]
+    async fn content_type_and_limit() {
+        let mut res = TestResponse::default().finish();
+        let mut events = res.sse();
+        assert!(matches!(
+            events.next().await,
+            Some(Err(SsePayloadError::ContentType))
+        ));
+        assert!(events.next().await.is_none());
+
+        let mut res = sse_response(&["data: 0123456789"]);
+        let mut events = res.sse().limit(8);
+        assert!(matches!(
+            events.next().await,
+            Some(Err(SsePayloadError::Overflow))
+        ));
+    }
+}
@@ -10,11 +10,14 @@ use std::{
 use actix_http::{
     body::{BodyStream, MessageBody},
     error::HttpError,
-    header::{self, HeaderMap, HeaderName, TryIntoHeaderValue},
+    header::{self, ContentEncoding, HeaderMap, HeaderName, TryIntoHeaderValue},
     RequestHead, RequestHeadType,
 };
 #[cfg(feature = "__compress")]
-use actix_http::{encoding::Decoder, header::ContentEncoding, Payload};
+use actix_http::{
+    encoding::{Decoder, Encoder},
+    Payload,
+};
 use actix_rt::time::{sleep, Sleep};
 use bytes::Bytes;
 use derive_more::From;
@@ -25,6 +28,7 @@ use crate::{
     any_body::AnyBody,
     client::ClientConfig,
     error::{FreezeRequestError, InvalidUrl, SendRequestError},
+    multipart::Form,
     BoxError, ClientResponse, ConnectRequest, ConnectResponse,
 };
 
@@ -66,6 +70,16 @@ impl From<PrepForSendingError> for SendRequestError {
     }
 }
 
+/// Request body prepared for sending by [`RequestSender::encode_body`].
+#[cfg(feature = "__compress")]
+enum EncodedBody {
+    /// Body that can be sent as is.
+    Ready(AnyBody),
+
+    /// Buffered body that is compressed on a blocking thread as it is polled.
+    Pending(Encoder<Bytes>),
+}
+
 /// Future that sends request's payload and resolves to a server response.
 #[must_use = "futures do nothing unless polled"]
 pub enum SendClientRequest {
@@ -184,32 +198,69 @@ impl RequestSender {
         self,
         addr: Option<net::SocketAddr>,
         response_decompress: bool,
+        compress: Option<ContentEncoding>,
         timeout: Option<Duration>,
         config: &ClientConfig,
         body: impl MessageBody + 'static,
     ) -> SendClientRequest {
-        let req = match self {
-            RequestSender::Owned(head) => ConnectRequest::Client(
-                RequestHeadType::Owned(head),
-                AnyBody::from_message_body(body).into_boxed(),
-                addr,
-            ),
-            RequestSender::Rc(head, extra_headers) => ConnectRequest::Client(
-                RequestHeadType::Rc(head, extra_headers),
-                AnyBody::from_message_body(body).into_boxed(),
-                addr,
-            ),
+        let compress = compress.or(config.compress);
+
+        #[cfg(feature = "__compress")]
+        let (sender, body) = match self.encode_body(compress, body) {
+            (sender, EncodedBody::Ready(body)) => (sender, body),
+
+            // compress large buffered bodies on a blocking thread before sending them, so they
+            // are still sent with a known length and can be sent again, e.g. on retries
+            (sender, EncodedBody::Pending(body)) => {
+                let connector = config.connector.clone();
+
+                let fut = Box::pin(async move {
+                    let body = actix_http::body::to_bytes(body)
+                        .await
+                        .map_err(|err| SendRequestError::Body(err.into()))?;
+
+                    let req = sender.into_connect_request(AnyBody::Bytes { body }, addr);
+                    connector.call(req).await
+                });
+
+                return SendClientRequest::new(
+                    fut,
+                    response_decompress,
+                    timeout.or(config.timeout),
+                );
+            }
         };
 
-        let fut = config.connector.call(req);
+        // request compression can not be configured without an encoder
+        #[cfg(not(feature = "__compress"))]
+        let (sender, body) = {
+            debug_assert!(compress.is_none());
+            (self, AnyBody::from_message_body(body).into_boxed())
+        };
+
+        let fut = config
+            .connector
+            .call(sender.into_connect_request(body, addr));
 
         SendClientRequest::new(fut, response_decompress, timeout.or(config.timeout))
     }
 
+    fn into_connect_request(self, body: AnyBody, addr: Option<net::SocketAddr>) -> ConnectRequest {
+        match self {
+            RequestSender::Owned(head) => {
+                ConnectRequest::Client(RequestHeadType::Owned(head), body, addr)
+            }
+            RequestSender::Rc(head, extra_headers) => {
+                ConnectRequest::Client(RequestHeadType::Rc(head, extra_headers), body, addr)
+            }
+        }
+    }
+
     pub(crate) fn send_json(
         mut self,
         addr: Option<net::SocketAddr>,
         response_decompress: bool,
+        compress: Option<ContentEncoding>,
         timeout: Option<Duration>,
         config: &ClientConfig,
         value: impl Serialize,
@@ -223,13 +274,14 @@ impl RequestSender {
             return err.into();
         }
 
-        self.send_body(addr, response_decompress, timeout, config, body)
+        self.send_body(addr, response_decompress, compress, timeout, config, body)
     }
 
     pub(crate) fn send_form(
         mut self,
         addr: Option<net::SocketAddr>,
         response_decompress: bool,
+        compress: Option<ContentEncoding>,
         timeout: Option<Duration>,
         config: &ClientConfig,
         value: impl Serialize,
@@ -246,13 +298,31 @@ impl RequestSender {
             return err.into();
         }
 
-        self.send_body(addr, response_decompress, timeout, config, body)
+        self.send_body(addr, response_decompress, compress, timeout, config, body)
+    }
+
+    pub(crate) fn send_multipart(
+        mut self,
+        addr: Option<net::SocketAddr>,
+        response_decompress: bool,
+        compress: Option<ContentEncoding>,
+        timeout: Option<Duration>,
+        config: &ClientConfig,
+        form: Form,
+    ) -> SendClientRequest {
+        // set content-type with the form's boundary
+        if let Err(err) = self.set_header_if_none(header::CONTENT_TYPE, form.content_type()) {
+            return err.into();
+        }
+
+        self.send_body(addr, response_decompress, compress, timeout, config, form)
     }
 
     pub(crate) fn send_stream<S, E>(
         self,
         addr: Option<net::SocketAddr>,
         response_decompress: bool,
+        compress: Option<ContentEncoding>,
         timeout: Option<Duration>,
         config: &ClientConfig,
         stream: S,
@@ -264,6 +334,7 @@ impl RequestSender {
         self.send_body(
             addr,
             response_decompress,
+            compress,
             timeout,
             config,
             BodyStream::new(stream),
@@ -274,10 +345,55 @@ impl RequestSender {
         self,
         addr: Option<net::SocketAddr>,
         response_decompress: bool,
+        compress: Option<ContentEncoding>,
         timeout: Option<Duration>,
         config: &ClientConfig,
     ) -> SendClientRequest {
-        self.send_body(addr, response_decompress, timeout, config, ())
+        self.send_body(addr, response_decompress, compress, timeout, config, ())
+    }
+
+    /// Compresses the request body using `encoding`, setting the `Content-Encoding` header.
+    #[cfg(feature = "__compress")]
+    fn encode_body(
+        mut self,
+        encoding: Option<ContentEncoding>,
+        body: impl MessageBody + 'static,
+    ) -> (Self, EncodedBody) {
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => {
+                let body = AnyBody::from_message_body(body).into_boxed();
+                return (self, EncodedBody::Ready(body));
+            }
+        };
+
+        let headers = match self {
+            RequestSender::Owned(ref mut head) => &mut head.headers,
+
+            // bodies of frozen requests that already declare an encoding are sent as is
+            RequestSender::Rc(ref head, _)
+                if head.headers.contains_key(header::CONTENT_ENCODING) =>
+            {
+                let body = AnyBody::from_message_body(body).into_boxed();
+                return (self, EncodedBody::Ready(body));
+            }
+
+            RequestSender::Rc(_, ref mut extra_headers) => {
+                extra_headers.get_or_insert_with(HeaderMap::new)
+            }
+        };
+
+        let body = match body.try_into_bytes() {
+            Ok(body) => match Encoder::request(encoding, headers, body).try_into_bytes() {
+                Ok(body) => AnyBody::Bytes { body },
+                Err(body) => return (self, EncodedBody::Pending(body)),
+            },
+            Err(body) => {
+                AnyBody::from_message_body(Encoder::request(encoding, headers, body)).into_boxed()
+            }
+        };
+
+        (self, EncodedBody::Ready(body))
     }
 
     fn set_header_if_none<V>(&mut self, key: HeaderName, value: V) -> Result<(), HttpError>
@@ -26,10 +26,10 @@
 //! }
 //! ```
 
-use std::{fmt, net::SocketAddr, str};
+use std::{fmt, io, net::SocketAddr, str};
 
 use actix_codec::Framed;
-pub use actix_http::ws::{CloseCode, CloseReason, Codec, Frame, Message};
+pub use actix_http::ws::{CloseCode, CloseReason, Codec, DeflateConfig, Frame, Message};
 use actix_http::{ws, Payload, RequestHead};
 use actix_rt::time::timeout;
 use actix_service::Service as _;
@@ -40,7 +40,7 @@ use crate::cookie::{Cookie, CookieJar};
 use crate::{
     client::ClientConfig,
     connect::{BoxedSocket, ConnectRequest},
-    error::{HttpError, InvalidUrl, SendRequestError, WsClientError},
+    error::{HttpError, InvalidUrl, SendRequestError, WsClientError, WsProtocolError},
     http::{
         header::{self, HeaderName, HeaderValue, TryIntoHeaderValue, AUTHORIZATION},
         ConnectionType, Method, StatusCode, Uri, Version,
@@ -57,6 +57,7 @@ pub struct WebsocketsRequest {
     addr: Option<SocketAddr>,
     max_size: usize,
     server_mode: bool,
+    deflate: Option<DeflateConfig>,
     config: ClientConfig,
 
     #[cfg(feature = "cookies")]
@@ -94,6 +95,7 @@ impl WebsocketsRequest {
             protocols: None,
             max_size: 65_536,
             server_mode: false,
+            deflate: None,
             #[cfg(feature = "cookies")]
             cookies: None,
         }
@@ -156,6 +158,17 @@ impl WebsocketsRequest {
         self
     }
 
+    /// Offer `permessage-deflate` compression to the server.
+    ///
+    /// If the server accepts the offer, the returned codec compresses and decompresses messages
+    /// using the negotiated parameters. A server response that does not match the offer fails the
+    /// connection with an [`InvalidData`](std::io::ErrorKind::InvalidData) I/O
+    /// [`WsClientError::Protocol`] error.
+    pub fn deflate(mut self, config: DeflateConfig) -> Self {
+        self.deflate = Some(config);
+        self
+    }
+
     /// Disable payload masking. By default ws client masks frame payload.
     pub fn server_mode(mut self) -> Self {
         self.server_mode = true;
@@ -324,6 +337,10 @@ impl WebsocketsRequest {
             );
         }
 
+        if let Some(deflate) = &self.deflate {
+            deflate.insert_offer(&mut self.head.headers);
+        }
+
         // Generate a random key for the `Sec-WebSocket-Key` header which is a base64-encoded
         // (see RFC 4648 §4) value that, when decoded, is 16 bytes in length (RFC 6455 §1.3).
         let sec_key = rand::random::<[u8; 16]>();
@@ -337,6 +354,7 @@ impl WebsocketsRequest {
         let head = self.head;
         let max_size = self.max_size;
         let server_mode = self.server_mode;
+        let deflate = self.deflate;
 
         let req = ConnectRequest::Tunnel(head, self.addr);
 
@@ -409,14 +427,37 @@ impl WebsocketsRequest {
             return Err(WsClientError::MissingWebSocketAcceptHeader);
         };
 
+        // check negotiated extensions; extensions offered through custom headers are left to
+        // the caller
+        let deflate = match (deflate, head.headers.get(&header::SEC_WEBSOCKET_EXTENSIONS)) {
+            (Some(deflate), Some(hdr)) => match deflate.accept(hdr) {
+                Some(agreed) => Some(agreed),
+                None => {
+                    log::trace!("Invalid extensions header: {:?}", hdr);
+                    return Err(WsClientError::Protocol(WsProtocolError::Io(
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "invalid Sec-WebSocket-Extensions header",
+                        ),
+                    )));
+                }
+            },
+            (_, _) => None,
+        };
+
         // response and ws framed
         Ok((
             ClientResponse::new(head, Payload::None),
             framed.into_map_codec(|_| {
-                if server_mode {
+                let codec = if server_mode {
                     ws::Codec::new().max_size(max_size)
                 } else {
                     ws::Codec::new().max_size(max_size).client_mode()
+                };
+
+                match deflate {
+                    Some(deflate) => codec.deflate(deflate),
+                    None => codec,
                 }
             }),
         ))
@@ -10,7 +10,7 @@ use std::{
     time::Duration,
 };
 
-use actix_http::{HttpService, StatusCode};
+use actix_http::{HttpMessage as _, HttpService, StatusCode};
 use actix_http_test::test_server;
 use actix_service::{fn_service, map_config, ServiceFactoryExt as _};
 use actix_utils::future::ok;
@@ -245,6 +245,358 @@ async fn connection_reuse() {
     assert_eq!(num.load(Ordering::Relaxed), 1);
 }
 
+#[actix_rt::test]
+async fn forward_proxy() {
+    let num = Arc::new(AtomicUsize::new(0));
+    let num2 = num.clone();
+
+    // stands in for a forward proxy by echoing the request target and credentials it receives
+    let srv = test_server(move || {
+        let num2 = num2.clone();
+        fn_service(move |io| {
+            num2.fetch_add(1, Ordering::Relaxed);
+            ok(io)
+        })
+        .and_then(
+            HttpService::build()
+                .h1(|req: actix_http::Request| async move {
+                    let auth = req
+                        .headers()
+                        .get(header::PROXY_AUTHORIZATION)
+                        .map(|auth| auth.to_str().unwrap().to_owned())
+                        .unwrap_or_default();
+
+                    Ok::<_, Infallible>(actix_http::Response::build(StatusCode::OK).body(format!(
+                        "{} {}",
+                        req.uri(),
+                        auth
+                    )))
+                })
+                .tcp(),
+        )
+    })
+    .await;
+
+    let proxy = awc::Proxy::new(format!("http://user:pass@{}", srv.addr())).unwrap();
+    let client = awc::Client::builder().proxy(proxy).finish();
+    let auth = format!("Basic {}", BASE64_STANDARD.encode("user:pass"));
+
+    let mut res = client
+        .get("http://example.invalid/path?query")
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+    let body = res.body().await.unwrap();
+    assert_eq!(body, format!("http://example.invalid/path?query {auth}"));
+
+    let mut res = client
+        .post("http://other.invalid:8080")
+        .send()
+        .await
+        .unwrap();
+    let body = res.body().await.unwrap();
+    assert_eq!(body, format!("http://other.invalid:8080/ {auth}"));
+
+    // one connection to the proxy is shared between hosts
+    assert_eq!(num.load(Ordering::Relaxed), 1);
+
+    // hosts excluded from the proxy are connected to directly
+    let proxy = awc::Proxy::new("http://proxy.invalid")
+        .unwrap()
+        .no_proxy("localhost");
+    let client = awc::Client::builder().proxy(proxy).finish();
+
+    let mut res = client.get(srv.url("/path")).send().await.unwrap();
+    let body = res.body().await.unwrap();
+    assert_eq!(body, "/path ");
+}
+
+#[actix_rt::test]
+async fn multipart() {
+    use awc::multipart::{Form, Part};
+    use futures_util::StreamExt as _;
+
+    // echoes the request's content type, length, and body
+    let srv = test_server(|| {
+        HttpService::build()
+            .h1(|mut req: actix_http::Request| async move {
+                let header = |name| {
+                    req.headers()
+                        .get(name)
+                        .map(|val: &header::HeaderValue| val.to_str().unwrap().to_owned())
+                        .unwrap_or_default()
+                };
+                let head = format!(
+                    "{}\n{}\n",
+                    header(header::CONTENT_TYPE),
+                    header(header::CONTENT_LENGTH)
+                );
+
+                let mut body = head.into_bytes();
+                let mut payload = req.take_payload();
+                while let Some(chunk) = payload.next().await {
+                    body.extend_from_slice(&chunk.unwrap());
+                }
+
+                Ok::<_, Infallible>(actix_http::Response::build(StatusCode::OK).body(body))
+            })
+            .tcp()
+    })
+    .await;
+
+    let client = awc::Client::new();
+    let url = format!("http://{}/", srv.addr());
+
+    let form = Form::with_boundary("boundary").text("name", "value");
+    let mut res = client.post(&url).send_multipart(form).await.unwrap();
+    let body = res.body().await.unwrap();
+    assert_eq!(
+        body,
+        "multipart/form-data; boundary=boundary\n\
+        78\n\
+        --boundary\r\n\
+        Content-Disposition: form-data; name=name\r\n\
+        \r\n\
+        value\r\n\
+        --boundary--\r\n"
+    );
+
+    let chunks = stream::iter([Ok::<_, Infallible>(Bytes::from_static(b"file contents"))]);
+    let form = Form::with_boundary("boundary").part(
+        "file",
+        Part::stream(chunks)
+            .file_name("file.txt")
+            .content_type(mime::TEXT_PLAIN),
+    );
+    let mut res = client
+        .post(&url)
+        .freeze()
+        .unwrap()
+        .send_multipart(form)
+        .await
+        .unwrap();
+    let body = res.body().await.unwrap();
+    assert_eq!(
+        body,
+        "multipart/form-data; boundary=boundary\n\
+        \n\
+        --boundary\r\n\
+        Content-Disposition: form-data; name=file; filename=\"file.txt\"\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        file contents\r\n\
+        --boundary--\r\n"
+    );
+}
+
+#[actix_rt::test]
+async fn request_compression() {
+    use actix_http::header::ContentEncoding;
+    use futures_util::StreamExt as _;
+
+    // echoes the request's content encoding and raw body
+    let srv = test_server(|| {
+        HttpService::build()
+            .h1(|mut req: actix_http::Request| async move {
+                let encoding = req
+                    .headers()
+                    .get(header::CONTENT_ENCODING)
+                    .map(|val| val.to_str().unwrap().to_owned())
+                    .unwrap_or_default();
+
+                let mut body = Vec::new();
+                let mut payload = req.take_payload();
+                while let Some(chunk) = payload.next().await {
+                    body.extend_from_slice(&chunk.unwrap());
+                }
+
+                let chunked = req.headers().contains_key(header::TRANSFER_ENCODING);
+
+                Ok::<_, Infallible>(
+                    actix_http::Response::build(StatusCode::OK)
+                        .insert_header(("x-content-encoding", encoding))
+                        .insert_header(("x-chunked", chunked.to_string()))
+                        .body(body),
+                )
+            })
+            .tcp()
+    })
+    .await;
+
+    let url = format!("http://{}/", srv.addr());
+    let client = awc::Client::new();
+
+    let mut res = client
+        .post(&url)
+        .compress(ContentEncoding::Gzip)
+        .send_body(STR)
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "gzip");
+    assert_eq!(res.headers().get("x-chunked").unwrap(), "false");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::gzip::decode(body), STR.as_bytes());
+
+    let chunks = stream::iter([
+        Ok::<_, Infallible>(Bytes::from_static(S.as_bytes())),
+        Ok(Bytes::from_static(STR.as_bytes())),
+    ]);
+    let mut res = client
+        .post(&url)
+        .compress(ContentEncoding::Brotli)
+        .send_stream(chunks)
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "br");
+    assert_eq!(res.headers().get("x-chunked").unwrap(), "true");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::brotli::decode(body), [S, STR].concat().as_bytes());
+
+    // client default can be overridden per request
+    let client = awc::Client::builder()
+        .compress(ContentEncoding::Zstd)
+        .finish();
+    let data = HashMap::from([("key", "value")]);
+
+    let mut res = client.post(&url).send_json(&data).await.unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "zstd");
+    assert_eq!(res.headers().get("x-chunked").unwrap(), "false");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::zstd::decode(body), br#"{"key":"value"}"#);
+
+    let req = client.post(&url).freeze().unwrap();
+    let mut res = req.send_body(STR).await.unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "zstd");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::zstd::decode(body), STR.as_bytes());
+
+    let mut res = client
+        .post(&url)
+        .compress(ContentEncoding::Identity)
+        .send_body(STR)
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "");
+    assert_eq!(res.body().await.unwrap(), STR);
+
+    // existing encodings are not replaced and empty bodies are not compressed
+    let mut res = client
+        .post(&url)
+        .insert_header((header::CONTENT_ENCODING, "custom"))
+        .send_body(S)
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "custom");
+    assert_eq!(res.body().await.unwrap(), S);
+
+    let mut res = client.post(&url).send().await.unwrap();
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "");
+    assert!(res.body().await.unwrap().is_empty());
+}
+
+#[actix_rt::test]
+async fn request_compression_resend() {
+    use actix_http::header::ContentEncoding;
+    use futures_util::StreamExt as _;
+
+    let num = Arc::new(AtomicUsize::new(0));
+    let num2 = num.clone();
+
+    // fails the first request to `/retry`, redirects `/redirect` to `/`, and otherwise echoes the
+    // request's content encoding and raw body
+    let srv = test_server(move || {
+        let num = num2.clone();
+
+        HttpService::build()
+            .h1(move |mut req: actix_http::Request| {
+                let num = num.clone();
+
+                async move {
+                    match req.path() {
+                        "/retry" if num.fetch_add(1, Ordering::Relaxed) == 0 => {
+                            return Ok::<_, Infallible>(
+                                actix_http::Response::build(StatusCode::SERVICE_UNAVAILABLE)
+                                    .body(Vec::new()),
+                            );
+                        }
+
+                        "/redirect" => {
+                            return Ok(actix_http::Response::build(StatusCode::TEMPORARY_REDIRECT)
+                                .insert_header((header::LOCATION, "/"))
+                                .body(Vec::new()));
+                        }
+
+                        _ => {}
+                    }
+
+                    let encoding = req
+                        .headers()
+                        .get(header::CONTENT_ENCODING)
+                        .map(|val| val.to_str().unwrap().to_owned())
+                        .unwrap_or_default();
+
+                    let mut body = Vec::new();
+                    let mut payload = req.take_payload();
+                    while let Some(chunk) = payload.next().await {
+                        body.extend_from_slice(&chunk.unwrap());
+                    }
+
+                    Ok(actix_http::Response::build(StatusCode::OK)
+                        .insert_header(("x-content-encoding", encoding))
+                        .body(body))
+                }
+            })
+            .tcp()
+    })
+    .await;
+
+    let client = awc::Client::builder()
+        .wrap(
+            awc::middleware::Retry::new()
+                .backoff(Duration::from_millis(1), Duration::from_millis(1)),
+        )
+        .compress(ContentEncoding::Gzip)
+        .finish();
+
+    // compressed body is sent again when retrying
+    let mut res = client.put(srv.url("/retry")).send_body(STR).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(num.load(Ordering::Relaxed), 2);
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "gzip");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::gzip::decode(body), STR.as_bytes());
+
+    // and when following a 307 redirect, including from a frozen request
+    let mut res = client
+        .post(srv.url("/redirect"))
+        .send_body(STR)
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "gzip");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::gzip::decode(body), STR.as_bytes());
+
+    let req = client.post(srv.url("/redirect")).freeze().unwrap();
+    let mut res = req.send_body(STR).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "gzip");
+    let body = res.body().await.unwrap();
+    assert_eq!(utils::gzip::decode(body), STR.as_bytes());
+
+    // streamed bodies cannot be sent again so they are not declared as encoded
+    let chunks = stream::iter([Ok::<_, Infallible>(Bytes::from_static(STR.as_bytes()))]);
+    let mut res = client
+        .post(srv.url("/redirect"))
+        .send_stream(chunks)
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("x-content-encoding").unwrap(), "");
+    assert!(res.body().await.unwrap().is_empty());
+}
+
 #[actix_rt::test]
 async fn connection_force_close() {
     let num = Arc::new(AtomicUsize::new(0));
@@ -725,6 +1077,112 @@ async fn client_cookie_handling() {
     assert_eq!(c2, cookie2);
 }
 
+#[actix_rt::test]
+async fn client_cookie_store() {
+    let srv = test_server(|| {
+        HttpService::build()
+            .h1(|req: actix_http::Request| async move {
+                let res = match req.path() {
+                    "/login" => actix_http::Response::build(StatusCode::FOUND)
+                        .insert_header((header::LOCATION, "/home"))
+                        .append_header((header::SET_COOKIE, "session=abc; Path=/"))
+                        .body(String::new()),
+
+                    // echoes the cookies sent by the client
+                    _ => {
+                        let cookies = req
+                            .headers()
+                            .get(header::COOKIE)
+                            .map(|cookie| cookie.to_str().unwrap().to_owned())
+                            .unwrap_or_default();
+
+                        actix_http::Response::build(StatusCode::OK).body(cookies)
+                    }
+                };
+
+                Ok::<_, Infallible>(res)
+            })
+            .tcp()
+    })
+    .await;
+
+    let store = awc::CookieStore::new();
+    let client = awc::Client::builder().cookie_store(store.clone()).finish();
+
+    // cookie set by the redirect response is sent to the redirect target
+    let mut res = client.get(srv.url("/login")).send().await.unwrap();
+    assert!(res.status().is_success());
+    assert_eq!(res.body().await.unwrap(), "session=abc");
+    assert_eq!(store.cookies().len(), 1);
+
+    // stored cookies are merged with a frozen request's own cookies
+    let req = client
+        .get(srv.url("/home"))
+        .insert_header((header::COOKIE, "user=1"))
+        .freeze()
+        .unwrap();
+
+    let mut res = req.send().await.unwrap();
+    assert_eq!(res.body().await.unwrap(), "user=1; session=abc");
+
+    let mut res = req
+        .extra_header((header::ACCEPT, "*/*"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.body().await.unwrap(), "user=1; session=abc");
+
+    // the store is also used when redirects are disabled
+    let client = awc::Client::builder()
+        .cookie_store(store)
+        .disable_redirects()
+        .finish();
+
+    let mut res = client.get(srv.url("/home")).send().await.unwrap();
+    assert_eq!(res.body().await.unwrap(), "session=abc");
+}
+
+#[actix_rt::test]
+async fn sse_round_trip() {
+    use actix_web::sse::{Event, Sse};
+    use futures_util::StreamExt as _;
+
+    let srv = test_server(|| {
+        HttpService::build()
+            .h1(|_| async {
+                let events = stream::iter([
+                    Ok::<_, Infallible>(Event::data("first").id("1")),
+                    Ok(Event::data("second").event("update")),
+                ]);
+
+                Ok::<_, Infallible>(
+                    actix_http::Response::build(StatusCode::OK)
+                        .content_type(mime::TEXT_EVENT_STREAM)
+                        .body(Sse::from_stream(events).with_retry_duration(Duration::from_secs(3))),
+                )
+            })
+            .tcp()
+    })
+    .await;
+
+    let mut res = srv.get("/").send().await.unwrap();
+    let mut events = res.sse();
+
+    let ev = events.next().await.unwrap().unwrap();
+    assert_eq!(ev.data(), "first");
+    assert_eq!(ev.id(), "1");
+    assert_eq!(ev.retry(), Some(Duration::from_secs(3)));
+
+    let ev = events.next().await.unwrap().unwrap();
+    assert_eq!(ev.event(), "update");
+    assert_eq!(ev.data(), "second");
+    assert_eq!(ev.id(), "1");
+    assert_eq!(ev.retry(), Some(Duration::from_secs(3)));
+
+    assert!(events.next().await.is_none());
+    assert_eq!(events.retry(), Some(Duration::from_secs(3)));
+}
+
 #[actix_rt::test]
 async fn client_unread_response() {
     let addr = actix_test::unused_addr();
@@ -0,0 +1,302 @@
+#![cfg(feature = "http3")]
+
+extern crate tls_rustls_0_23 as rustls;
+
+use std::{
+    convert::Infallible,
+    io::BufReader,
+    net::SocketAddr,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use actix_http::{
+    header::ALT_SVC, Error, HttpService, KeepAlive, Request, Response, StatusCode, Version,
+};
+use actix_http_test::test_server;
+use actix_service::{Service, ServiceFactory, ServiceFactoryExt};
+use bytes::BytesMut;
+use futures_util::StreamExt as _;
+use rustls::{
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName},
+    ClientConfig, ServerConfig,
+};
+use rustls_pemfile::{certs, pkcs8_private_keys};
+
+fn tls_config() -> ServerConfig {
+    let rcgen::CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(["localhost".to_owned()]).unwrap();
+    let cert_file = cert.pem();
+    let key_file = key_pair.serialize_pem();
+
+    let cert_file = &mut BufReader::new(cert_file.as_bytes());
+    let key_file = &mut BufReader::new(key_file.as_bytes());
+
+    let cert_chain = certs(cert_file).collect::<Result<Vec<_>, _>>().unwrap();
+    let mut keys = pkcs8_private_keys(key_file)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, PrivateKeyDer::Pkcs8(keys.remove(0)))
+        .unwrap()
+}
+
+mod danger {
+    use rustls::{
+        client::danger::{ServerCertVerified, ServerCertVerifier},
+        pki_types::UnixTime,
+    };
+
+    use super::*;
+
+    #[derive(Debug)]
+    pub struct NoCertificateVerification;
+
+    impl ServerCertVerifier for NoCertificateVerification {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            Ok(rustls::client::danger::ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            _message: &[u8],
+            _cert: &CertificateDer<'_>,
+            _dss: &rustls::DigitallySignedStruct,
+        ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+            Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            _message: &[u8],
+            _cert: &CertificateDer<'_>,
+            _dss: &rustls::DigitallySignedStruct,
+        ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+            Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+            rustls::crypto::aws_lc_rs::default_provider()
+                .signature_verification_algorithms
+                .supported_schemes()
+        }
+    }
+}
+
+fn client_config() -> Arc<ClientConfig> {
+    let mut config = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(danger::NoCertificateVerification))
+        .with_no_client_auth();
+
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Arc::new(config)
+}
+
+/// Starts an in-process HTTP/3 server that echoes request bodies, returning its address and a
+/// counter of accepted QUIC connections.
+async fn start_h3_server() -> (SocketAddr, Arc<AtomicUsize>) {
+    start_h3_server_with_keep_alive(KeepAlive::default()).await
+}
+
+async fn start_h3_server_with_keep_alive(keep_alive: KeepAlive) -> (SocketAddr, Arc<AtomicUsize>) {
+    let mut tls = tls_config();
+    tls.alpn_protocols = vec![actix_http::h3::ALPN.to_vec()];
+
+    let quic = quinn::crypto::rustls::QuicServerConfig::try_from(tls).unwrap();
+    let endpoint = quinn::Endpoint::server(
+        quinn::ServerConfig::with_crypto(Arc::new(quic)),
+        "127.0.0.1:0".parse().unwrap(),
+    )
+    .unwrap();
+    let addr = endpoint.local_addr().unwrap();
+
+    let service = HttpService::build()
+        .keep_alive(keep_alive)
+        .h3(|mut req: Request| async move {
+            let mut body = BytesMut::new();
+            while let Some(chunk) = req.payload().next().await {
+                body.extend_from_slice(&chunk?);
+            }
+
+            Ok::<_, Error>(Response::ok().set_body(body.freeze()))
+        })
+        .new_service(())
+        .await
+        .unwrap();
+    let service = Rc::new(service);
+
+    let conns = Arc::new(AtomicUsize::new(0));
+    let conns2 = Arc::clone(&conns);
+
+    actix_rt::spawn(async move {
+        while let Some(incoming) = endpoint.accept().await {
+            let service = Rc::clone(&service);
+            conns2.fetch_add(1, Ordering::Relaxed);
+
+            actix_rt::spawn(async move {
+                if let Ok(conn) = incoming.await {
+                    let _ = service.call(conn).await;
+                }
+            });
+        }
+    });
+
+    (addr, conns)
+}
+
+#[actix_rt::test]
+async fn h3_prior_knowledge() {
+    let (addr, conns) = start_h3_server().await;
+
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().rustls_0_23(client_config()))
+        .max_http_version(Version::HTTP_3)
+        .finish();
+
+    let url = format!("https://localhost:{}/", addr.port());
+
+    // req 1
+    let mut res = client.get(&url).address(addr).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.version(), Version::HTTP_3);
+    assert!(res.body().await.unwrap().is_empty());
+
+    // req 2
+    let mut res = client
+        .post(&url)
+        .address(addr)
+        .send_body("hello world")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.version(), Version::HTTP_3);
+    assert_eq!(res.body().await.unwrap(), "hello world");
+
+    // one connection should be reused
+    assert_eq!(conns.load(Ordering::Relaxed), 1);
+}
+
+#[actix_rt::test]
+async fn h3_concurrent_requests_share_connection() {
+    let (addr, conns) = start_h3_server().await;
+
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().rustls_0_23(client_config()))
+        .max_http_version(Version::HTTP_3)
+        .finish();
+
+    let url = format!("https://localhost:{}/", addr.port());
+
+    let reqs = (0..4).map(|_| client.get(&url).address(addr).send());
+    for res in futures_util::future::join_all(reqs).await {
+        let res = res.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.version(), Version::HTTP_3);
+    }
+
+    assert_eq!(conns.load(Ordering::Relaxed), 1);
+}
+
+#[actix_rt::test]
+async fn h3_keep_alive_timeout() {
+    let (addr, conns) =
+        start_h3_server_with_keep_alive(KeepAlive::Timeout(Duration::from_millis(100))).await;
+
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().rustls_0_23(client_config()))
+        .max_http_version(Version::HTTP_3)
+        .finish();
+
+    let url = format!("https://localhost:{}/", addr.port());
+
+    let res = client.get(&url).address(addr).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // idle connection is closed by the server once the keep-alive timeout elapses
+    actix_rt::time::sleep(Duration::from_millis(500)).await;
+
+    let res = client.get(&url).address(addr).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.version(), Version::HTTP_3);
+    assert_eq!(conns.load(Ordering::Relaxed), 2);
+}
+
+#[actix_rt::test]
+async fn h3_alt_svc_upgrade() {
+    let (quic_addr, conns) = start_h3_server().await;
+    let quic_port = quic_addr.port();
+
+    let srv = test_server(move || {
+        HttpService::build()
+            .finish(move |_| async move {
+                Ok::<_, Infallible>(
+                    Response::build(StatusCode::OK)
+                        .insert_header((ALT_SVC, format!(r#"h3=":{quic_port}"; ma=60"#)))
+                        .finish(),
+                )
+            })
+            .rustls_0_23(tls_config())
+            .map_err(|_| ())
+    })
+    .await;
+
+    let client = awc::Client::builder()
+        .connector(
+            awc::Connector::new()
+                .rustls_0_23(client_config())
+                .alt_svc_upgrade(true),
+        )
+        .max_http_version(Version::HTTP_3)
+        .finish();
+
+    // first request is sent over TCP and learns about the HTTP/3 endpoint
+    let res = client.get(srv.surl("/")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_ne!(res.version(), Version::HTTP_3);
+    assert_eq!(conns.load(Ordering::Relaxed), 0);
+
+    let res = client.get(srv.surl("/")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.version(), Version::HTTP_3);
+    assert_eq!(conns.load(Ordering::Relaxed), 1);
+}
+
+#[actix_rt::test]
+async fn h3_falls_back_to_tcp() {
+    let srv = test_server(move || {
+        HttpService::build()
+            .finish(|_| async { Ok::<_, Infallible>(Response::ok()) })
+            .rustls_0_23(tls_config())
+            .map_err(|_| ())
+    })
+    .await;
+
+    let client = awc::Client::builder()
+        .connector(
+            awc::Connector::new()
+                .rustls_0_23(client_config())
+                .timeout(Duration::from_millis(500)),
+        )
+        .max_http_version(Version::HTTP_3)
+        .finish();
+
+    // nothing is listening for QUIC on the server's port
+    let res = client.get(srv.surl("/")).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_ne!(res.version(), Version::HTTP_3);
+}
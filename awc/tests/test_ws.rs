@@ -1,9 +1,12 @@
 use std::io;
 
 use actix_codec::Framed;
-use actix_http::{body::BodySize, h1, ws, Error, HttpService, Request, Response};
+use actix_http::{
+    body::BodySize, h1, header::SEC_WEBSOCKET_EXTENSIONS, ws, Error, HttpService, Request, Response,
+};
 use actix_http_test::test_server;
 use actix_utils::future::ok;
+use awc::error::WsClientError;
 use bytes::Bytes;
 use futures_util::{SinkExt as _, StreamExt as _};
 
@@ -66,3 +69,119 @@ async fn test_simple() {
     let item = framed.next().await.unwrap().unwrap();
     assert_eq!(item, ws::Frame::Close(Some(ws::CloseCode::Normal.into())));
 }
+
+#[actix_rt::test]
+async fn test_deflate() {
+    let srv = test_server(|| {
+        HttpService::build()
+            .upgrade(|(req, mut framed): (Request, Framed<_, _>)| {
+                async move {
+                    let config = ws::DeflateConfig::new().client_max_window_bits(10);
+                    let (mut res, codec) = ws::handshake_with_deflate(req.head(), &config).unwrap();
+
+                    // send handshake response
+                    framed
+                        .send(h1::Message::Item((
+                            res.finish().drop_body(),
+                            BodySize::None,
+                        )))
+                        .await?;
+
+                    // start WebSocket service
+                    let framed = framed.replace_codec(codec);
+                    ws::Dispatcher::with(framed, ws_service).await
+                }
+            })
+            .finish(|_| ok::<_, Error>(Response::not_found()))
+            .tcp()
+    })
+    .await;
+
+    let (res, mut framed) = awc::Client::new()
+        .ws(srv.url("/"))
+        .deflate(
+            ws::DeflateConfig::new()
+                .client_no_context_takeover()
+                .server_max_window_bits(9),
+        )
+        .connect()
+        .await
+        .unwrap();
+    assert_eq!(
+        res.headers().get(SEC_WEBSOCKET_EXTENSIONS).unwrap(),
+        "permessage-deflate; client_no_context_takeover; server_max_window_bits=9; \
+         client_max_window_bits=10"
+    );
+
+    let long = "text ".repeat(1000);
+
+    for text in ["text", "text text text text", "", &long] {
+        framed
+            .send(ws::Message::Text(text.to_owned().into()))
+            .await
+            .unwrap();
+        let item = framed.next().await.unwrap().unwrap();
+        assert_eq!(
+            item,
+            ws::Frame::Text(Bytes::copy_from_slice(text.as_bytes()))
+        );
+    }
+
+    framed
+        .send(ws::Message::Close(Some(ws::CloseCode::Normal.into())))
+        .await
+        .unwrap();
+    let item = framed.next().await.unwrap().unwrap();
+    assert_eq!(item, ws::Frame::Close(Some(ws::CloseCode::Normal.into())));
+}
+
+#[actix_rt::test]
+async fn test_extensions_header() {
+    let srv = test_server(|| {
+        HttpService::build()
+            .upgrade(|(req, mut framed): (Request, Framed<_, _>)| async move {
+                // accept offered extensions as-is
+                let mut res = ws::handshake(req.head()).unwrap();
+                if let Some(hdr) = req.head().headers().get(SEC_WEBSOCKET_EXTENSIONS) {
+                    res.insert_header((SEC_WEBSOCKET_EXTENSIONS, hdr.clone()));
+                }
+
+                framed
+                    .send(h1::Message::Item((
+                        res.finish().drop_body(),
+                        BodySize::None,
+                    )))
+                    .await
+                    .unwrap();
+
+                Ok::<_, Error>(())
+            })
+            .finish(|_| ok::<_, Error>(Response::not_found()))
+            .tcp()
+    })
+    .await;
+
+    // extensions offered through custom headers are not validated
+    let (res, _framed) = awc::Client::new()
+        .ws(srv.url("/"))
+        .set_header(SEC_WEBSOCKET_EXTENSIONS, "x-custom-extension")
+        .connect()
+        .await
+        .unwrap();
+    assert_eq!(
+        res.headers().get(SEC_WEBSOCKET_EXTENSIONS).unwrap(),
+        "x-custom-extension"
+    );
+
+    // window bits must have a value in responses
+    let res = awc::Client::new()
+        .ws(srv.url("/"))
+        .deflate(ws::DeflateConfig::new())
+        .connect()
+        .await;
+    assert!(matches!(
+        res,
+        Err(WsClientError::Protocol(ws::ProtocolError::Io(err)))
+            if err.kind() == io::ErrorKind::InvalidData
+    ));
+}